@@ -0,0 +1,54 @@
+-- 玩法定义补充：数字型玩法按位开奖、号码从 0 开始，并新增七乐彩与快乐8
+UPDATE lottery_types
+SET main_range_start = 0,
+    rules = rules || '{"draw_rules": {"ordered": true, "allow_repeats": true}}'::jsonb,
+    updated_at = CURRENT_TIMESTAMP
+WHERE name IN ('fc3d', 'pl3', 'pl5');
+
+INSERT INTO lottery_types (
+    name, display_name, category, total_numbers, special_numbers,
+    main_range_start, main_range_end, special_range_start, special_range_end, rules
+) VALUES
+('qlc', '七乐彩', 'welfare', 7, 1, 1, 30, 1, 30,
+ '{
+    "selection_rules": {
+        "main_numbers": {"min": 7, "max": 7},
+        "special_numbers": {"min": 0, "max": 0}
+    },
+    "draw_rules": {"special_from_main_pool": true},
+    "prize_rules": {
+        "tiers": 7,
+        "distribution": "pari-mutuel",
+        "tier_requirements": [
+            {"tier": 1, "main": 7, "special": 0},
+            {"tier": 2, "main": 6, "special": 1},
+            {"tier": 3, "main": 6, "special": 0},
+            {"tier": 4, "main": 5, "special": 1},
+            {"tier": 5, "main": 5, "special": 0},
+            {"tier": 6, "main": 4, "special": 1},
+            {"tier": 7, "main": 4, "special": 0}
+        ]
+    }
+}'),
+
+('kl8', '快乐8', 'welfare', 20, 0, 1, 80, NULL, NULL,
+ '{
+    "selection_rules": {
+        "main_numbers": {"min": 1, "max": 10},
+        "special_numbers": {"min": 0, "max": 0}
+    },
+    "prize_rules": {
+        "tiers": 7,
+        "distribution": "fixed",
+        "tier_requirements": [
+            {"tier": 1, "main": 10, "special": 0},
+            {"tier": 2, "main": 9, "special": 0},
+            {"tier": 3, "main": 8, "special": 0},
+            {"tier": 4, "main": 7, "special": 0},
+            {"tier": 5, "main": 6, "special": 0},
+            {"tier": 6, "main": 5, "special": 0},
+            {"tier": 7, "main": 0, "special": 0}
+        ]
+    }
+}')
+ON CONFLICT (name) DO NOTHING;
@@ -0,0 +1,9 @@
+-- 算法类型补充：新增梯度提升、贝叶斯与马尔可夫链算法，取值与 AlgorithmType 的字符串形式一致
+ALTER TABLE prediction_strategies DROP CONSTRAINT IF EXISTS prediction_strategies_algorithm_type_check;
+
+ALTER TABLE prediction_strategies ADD CONSTRAINT prediction_strategies_algorithm_type_check CHECK (
+    algorithm_type IN (
+        'random_forest', 'gradient_boosting', 'lstm', 'arima', 'statistical',
+        'bayesian', 'markov', 'neural_network', 'hybrid'
+    )
+);
@@ -0,0 +1,30 @@
+-- 固定奖金补充：固定奖金玩法补充单注奖金，并按开奖号码形态区分组三与组六
+UPDATE lottery_types
+SET rules = jsonb_set(rules, '{prize_rules,tier_requirements}', '[
+        {"tier": 1, "match": "exact", "order": "exact", "amount": 1040},
+        {"tier": 2, "match": "group", "order": "any", "repeats": true, "amount": 346},
+        {"tier": 3, "match": "group", "order": "any", "repeats": false, "amount": 173}
+    ]'::jsonb),
+    updated_at = CURRENT_TIMESTAMP
+WHERE name IN ('fc3d', 'pl3');
+
+UPDATE lottery_types
+SET rules = jsonb_set(rules, '{prize_rules,tier_requirements}', '[
+        {"tier": 1, "match": "exact", "order": "exact", "amount": 100000}
+    ]'::jsonb),
+    updated_at = CURRENT_TIMESTAMP
+WHERE name = 'pl5';
+
+-- 快乐8 按选十玩法的奖金表
+UPDATE lottery_types
+SET rules = jsonb_set(rules, '{prize_rules,tier_requirements}', '[
+        {"tier": 1, "main": 10, "special": 0, "amount": 5000000},
+        {"tier": 2, "main": 9, "special": 0, "amount": 8000},
+        {"tier": 3, "main": 8, "special": 0, "amount": 720},
+        {"tier": 4, "main": 7, "special": 0, "amount": 80},
+        {"tier": 5, "main": 6, "special": 0, "amount": 5},
+        {"tier": 6, "main": 5, "special": 0, "amount": 3},
+        {"tier": 7, "main": 0, "special": 0, "amount": 2}
+    ]'::jsonb),
+    updated_at = CURRENT_TIMESTAMP
+WHERE name = 'kl8';
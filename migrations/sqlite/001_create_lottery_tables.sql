@@ -0,0 +1,220 @@
+-- 彩票预测系统数据库迁移 (SQLite 版本)
+-- UUID 以 16 字节 BLOB 存储，数组与 JSONB 以 JSON 文本存储，DECIMAL 映射为 REAL
+-- 时间与日期以 ISO-8601 文本存储
+
+-- 创建彩票类型表
+CREATE TABLE lottery_types (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    name TEXT NOT NULL UNIQUE,
+    display_name TEXT NOT NULL,
+    description TEXT,
+    category TEXT NOT NULL CHECK (category IN ('welfare', 'sports', 'local')),
+    total_numbers INTEGER NOT NULL CHECK (total_numbers > 0),
+    special_numbers INTEGER CHECK (special_numbers >= 0),
+    main_range_start INTEGER NOT NULL DEFAULT 1,
+    main_range_end INTEGER NOT NULL,
+    special_range_start INTEGER,
+    special_range_end INTEGER,
+    rules TEXT NOT NULL DEFAULT '{}',
+    is_active BOOLEAN DEFAULT TRUE,
+    is_deprecated BOOLEAN DEFAULT FALSE,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    CHECK (main_range_end > main_range_start),
+    CHECK (special_range_end IS NULL OR special_range_end > special_range_start)
+);
+
+-- 创建历史开奖数据表
+CREATE TABLE lottery_drawings (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    lottery_type_id BLOB NOT NULL REFERENCES lottery_types(id),
+    draw_number TEXT NOT NULL,
+    draw_date TEXT NOT NULL,
+    draw_time TEXT,
+    winning_numbers TEXT NOT NULL,
+    special_numbers TEXT,
+    jackpot_amount REAL,
+    sales_amount REAL,
+    prize_pool_amount REAL,
+    prize_distribution TEXT DEFAULT '{}',
+    data_source TEXT NOT NULL,
+    source_url TEXT,
+    verification_hash TEXT,
+    verification_status TEXT DEFAULT 'pending' CHECK (
+        verification_status IN ('pending', 'verified', 'failed', 'duplicate')
+    ),
+    metadata TEXT DEFAULT '{}',
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    crawled_at TEXT,
+    UNIQUE(lottery_type_id, draw_number),
+    UNIQUE(lottery_type_id, draw_date)
+);
+
+-- 创建预测策略表
+CREATE TABLE prediction_strategies (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    name TEXT NOT NULL,
+    algorithm_type TEXT NOT NULL CHECK (
+        algorithm_type IN ('random_forest', 'lstm', 'arima', 'statistical', 'neural_network', 'hybrid')
+    ),
+    description TEXT,
+    parameters TEXT NOT NULL DEFAULT '{}',
+    hyperparameters TEXT DEFAULT '{}',
+    feature_config TEXT DEFAULT '{}',
+    accuracy_rate REAL CHECK (accuracy_rate >= 0 AND accuracy_rate <= 100),
+    precision_rate REAL CHECK (precision_rate >= 0 AND precision_rate <= 100),
+    recall_rate REAL CHECK (recall_rate >= 0 AND recall_rate <= 100),
+    f1_score REAL CHECK (f1_score >= 0 AND f1_score <= 100),
+    total_predictions INTEGER DEFAULT 0,
+    successful_predictions INTEGER DEFAULT 0,
+    total_trainings INTEGER DEFAULT 0,
+    last_training_date TEXT,
+    model_path TEXT,
+    model_hash TEXT,
+    model_size_bytes INTEGER,
+    is_active BOOLEAN DEFAULT TRUE,
+    is_public BOOLEAN DEFAULT FALSE,
+    is_system BOOLEAN DEFAULT FALSE,
+    owner_id BLOB,
+    version TEXT DEFAULT '1.0.0',
+    parent_strategy_id BLOB REFERENCES prediction_strategies(id),
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+-- 创建预测结果表
+CREATE TABLE prediction_results (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    strategy_id BLOB NOT NULL REFERENCES prediction_strategies(id),
+    lottery_type_id BLOB NOT NULL REFERENCES lottery_types(id),
+    actual_draw_id BLOB REFERENCES lottery_drawings(id),
+    predicted_numbers TEXT NOT NULL,
+    predicted_special_numbers TEXT,
+    confidence_scores TEXT CHECK (
+        json_array_length(confidence_scores) =
+        json_array_length(predicted_numbers) +
+        COALESCE(json_array_length(predicted_special_numbers), 0)
+    ),
+    target_draw_date TEXT NOT NULL,
+    prediction_type TEXT DEFAULT 'standard' CHECK (
+        prediction_type IN ('standard', 'quick', 'detailed', 'batch')
+    ),
+    accuracy_score REAL,
+    match_count INTEGER DEFAULT 0,
+    special_match_count INTEGER DEFAULT 0,
+    is_winner BOOLEAN DEFAULT FALSE,
+    prize_tier INTEGER,
+    prize_amount REAL,
+    computation_time_ms INTEGER,
+    feature_vector TEXT,
+    metadata TEXT DEFAULT '{}',
+    prediction_date TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    validation_date TEXT
+);
+
+-- 创建分析特征表
+CREATE TABLE analysis_features (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    lottery_type_id BLOB NOT NULL REFERENCES lottery_types(id),
+    drawing_id BLOB REFERENCES lottery_drawings(id),
+    feature_type TEXT NOT NULL CHECK (
+        feature_type IN ('frequency', 'trend', 'statistical', 'pattern', 'temporal')
+    ),
+    feature_name TEXT NOT NULL,
+    feature_description TEXT,
+    feature_data TEXT NOT NULL,
+    feature_vector TEXT NOT NULL,
+    feature_hash TEXT,
+    data_points INTEGER,
+    calculation_time_ms INTEGER,
+    algorithm_version TEXT DEFAULT '1.0.0',
+    is_valid BOOLEAN DEFAULT TRUE,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+-- 创建模型训练记录表
+CREATE TABLE model_training_records (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    strategy_id BLOB NOT NULL REFERENCES prediction_strategies(id),
+    training_data_start TEXT NOT NULL,
+    training_data_end TEXT NOT NULL,
+    training_samples INTEGER NOT NULL,
+    validation_samples INTEGER NOT NULL,
+    test_samples INTEGER NOT NULL,
+    model_parameters TEXT NOT NULL,
+    feature_config TEXT NOT NULL,
+    training_accuracy REAL,
+    validation_accuracy REAL,
+    test_accuracy REAL,
+    training_loss REAL,
+    validation_loss REAL,
+    model_metrics TEXT NOT NULL,
+    confusion_matrix TEXT,
+    feature_importance TEXT,
+    model_path TEXT,
+    model_hash TEXT,
+    model_size_bytes INTEGER,
+    training_duration TEXT,
+    hardware_info TEXT DEFAULT '{}',
+    status TEXT DEFAULT 'pending' CHECK (
+        status IN ('pending', 'running', 'completed', 'failed', 'cancelled')
+    ),
+    error_message TEXT,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    started_at TEXT,
+    completed_at TEXT
+);
+
+-- 创建用户策略配置表
+CREATE TABLE user_strategy_configs (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    user_id BLOB NOT NULL,
+    strategy_id BLOB NOT NULL REFERENCES prediction_strategies(id),
+    custom_parameters TEXT NOT NULL DEFAULT '{}',
+    custom_filters TEXT DEFAULT '{}',
+    notification_enabled BOOLEAN DEFAULT TRUE,
+    auto_predict BOOLEAN DEFAULT FALSE,
+    usage_count INTEGER DEFAULT 0,
+    success_count INTEGER DEFAULT 0,
+    favorite_count INTEGER DEFAULT 0,
+    is_favorite BOOLEAN DEFAULT FALSE,
+    is_active BOOLEAN DEFAULT TRUE,
+    last_used_at TEXT,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    UNIQUE(user_id, strategy_id)
+);
+
+-- 创建缓存数据表
+CREATE TABLE cache_data (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    cache_key TEXT NOT NULL UNIQUE,
+    cache_type TEXT NOT NULL,
+    data_content TEXT NOT NULL,
+    data_hash TEXT,
+    ttl_seconds INTEGER DEFAULT 3600,
+    priority INTEGER DEFAULT 0,
+    access_count INTEGER DEFAULT 0,
+    hit_count INTEGER DEFAULT 0,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    expires_at TEXT,
+    last_accessed TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+-- 创建性能优化索引 (SQLite 不支持 GIN 与全文 to_tsvector 索引)
+CREATE INDEX idx_drawings_lottery_date ON lottery_drawings(lottery_type_id, draw_date DESC);
+CREATE INDEX idx_predictions_strategy_target ON prediction_results(strategy_id, target_draw_date);
+CREATE INDEX idx_predictions_lottery_date ON prediction_results(lottery_type_id, prediction_date DESC);
+CREATE INDEX idx_predictions_winner ON prediction_results(is_winner, prize_amount DESC) WHERE is_winner = 1;
+CREATE INDEX idx_features_lottery_drawing ON analysis_features(lottery_type_id, drawing_id);
+CREATE INDEX idx_features_type_name ON analysis_features(feature_type, feature_name);
+CREATE INDEX idx_features_hash ON analysis_features(feature_hash);
+CREATE INDEX idx_strategy_performance ON prediction_strategies(algorithm_type, accuracy_rate DESC);
+CREATE INDEX idx_strategy_training ON model_training_records(strategy_id, created_at DESC);
+CREATE INDEX idx_training_status ON model_training_records(status);
+CREATE INDEX idx_user_strategies ON user_strategy_configs(user_id, is_favorite DESC);
+CREATE INDEX idx_cache_type ON cache_data(cache_type);
+CREATE INDEX idx_cache_expires ON cache_data(expires_at);
+CREATE INDEX idx_cache_priority ON cache_data(priority DESC);
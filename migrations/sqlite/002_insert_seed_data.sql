@@ -0,0 +1,222 @@
+-- 插入彩票类型种子数据 (SQLite 版本)
+-- 仅写入规则与系统策略；示例开奖/预测数据只用于 Postgres 演示库，不写入本地数据库
+INSERT INTO lottery_types (
+    name, display_name, category, total_numbers, special_numbers, 
+    main_range_end, special_range_end, rules
+) VALUES
+('ssq', '双色球', 'welfare', 6, 1, 33, 16, 
+ '{
+    "selection_rules": {
+        "main_numbers": {"min": 6, "max": 6},
+        "special_numbers": {"min": 1, "max": 1}
+    },
+    "prize_rules": {
+        "tiers": 6,
+        "distribution": "pari-mutuel",
+        "tier_requirements": [
+            {"tier": 1, "main": 6, "special": 1},
+            {"tier": 2, "main": 6, "special": 0},
+            {"tier": 3, "main": 5, "special": 1},
+            {"tier": 4, "main": 5, "special": 0},
+            {"tier": 5, "main": 4, "special": 1},
+            {"tier": 6, "main": 4, "special": 0}
+        ]
+    }
+}'),
+
+('dlt', '大乐透', 'sports', 5, 2, 35, 12,
+ '{
+    "selection_rules": {
+        "main_numbers": {"min": 5, "max": 5},
+        "special_numbers": {"min": 2, "max": 2}
+    },
+    "prize_rules": {
+        "tiers": 9,
+        "distribution": "pari-mutuel",
+        "tier_requirements": [
+            {"tier": 1, "main": 5, "special": 2},
+            {"tier": 2, "main": 5, "special": 1},
+            {"tier": 3, "main": 5, "special": 0},
+            {"tier": 4, "main": 4, "special": 2},
+            {"tier": 5, "main": 4, "special": 1},
+            {"tier": 6, "main": 3, "special": 2},
+            {"tier": 7, "main": 4, "special": 0},
+            {"tier": 8, "main": 3, "special": 1},
+            {"tier": 9, "main": 2, "special": 2}
+        ]
+    }
+}'),
+
+('fc3d', '福彩3D', 'welfare', 3, 0, 9, 9,
+ '{
+    "selection_rules": {
+        "main_numbers": {"min": 3, "max": 3},
+        "special_numbers": {"min": 0, "max": 0}
+    },
+    "prize_rules": {
+        "tiers": 3,
+        "distribution": "fixed",
+        "tier_requirements": [
+            {"tier": 1, "match": "exact", "order": "exact"},
+            {"tier": 2, "match": "exact", "order": "any"},
+            {"tier": 3, "match": "group", "order": "any"}
+        ]
+    }
+}'),
+
+('pl3', '排列3', 'sports', 3, 0, 9, 9,
+ '{
+    "selection_rules": {
+        "main_numbers": {"min": 3, "max": 3},
+        "special_numbers": {"min": 0, "max": 0}
+    },
+    "prize_rules": {
+        "tiers": 3,
+        "distribution": "fixed",
+        "tier_requirements": [
+            {"tier": 1, "match": "exact", "order": "exact"},
+            {"tier": 2, "match": "exact", "order": "any"},
+            {"tier": 3, "match": "group", "order": "any"}
+        ]
+    }
+}'),
+
+('pl5', '排列5', 'sports', 5, 0, 9, 9,
+ '{
+    "selection_rules": {
+        "main_numbers": {"min": 5, "max": 5},
+        "special_numbers": {"min": 0, "max": 0}
+    },
+    "prize_rules": {
+        "tiers": 1,
+        "distribution": "fixed",
+        "tier_requirements": [
+            {"tier": 1, "match": "exact", "order": "exact"}
+        ]
+    }
+}');
+
+-- 插入预测策略种子数据
+INSERT INTO prediction_strategies (
+    name, algorithm_type, description, parameters, hyperparameters, 
+    feature_config, accuracy_rate, is_system, version
+) VALUES
+('随机森林-基础版', 'random_forest', '基于随机森林的基础预测策略', 
+ '{
+    "n_estimators": 100,
+    "max_depth": 10,
+    "min_samples_split": 2,
+    "min_samples_leaf": 1,
+    "random_state": 42
+ }',
+ '{
+    "cv_folds": 5,
+    "scoring": "accuracy",
+    "n_jobs": -1
+ }',
+ '{
+    "features": ["frequency", "trend", "statistical"],
+    "window_size": 50,
+    "include_special": true
+ }',
+ 62.5, true, '1.0.0'),
+
+('LSTM-时间序列', 'lstm', '基于LSTM神经网络的时间序列预测', 
+ '{
+    "hidden_size": 128,
+    "num_layers": 2,
+    "dropout": 0.2,
+    "epochs": 100,
+    "batch_size": 32,
+    "learning_rate": 0.001
+ }',
+ '{
+    "optimizer": "adam",
+    "loss_function": "mse",
+    "validation_split": 0.2,
+    "early_stopping": true
+ }',
+ '{
+    "features": ["temporal", "frequency", "pattern"],
+    "sequence_length": 30,
+    "include_time_features": true
+ }',
+ 67.8, true, '1.0.0'),
+
+('统计分析-频率法', 'statistical', '基于统计频率的简单预测方法', 
+ '{
+    "window_size": 50,
+    "weight_function": "linear",
+    "smoothing_factor": 0.1,
+    "confidence_threshold": 0.6
+ }',
+ '{
+    "min_samples": 100,
+    "significance_level": 0.05
+ }',
+ '{
+    "features": ["frequency", "hot_cold", "pattern"],
+    "analysis_period": 100
+ }',
+ 58.3, true, '1.0.0'),
+
+('ARIMA-时间序列', 'arima', '基于ARIMA模型的时间序列分析', 
+ '{
+    "p": 2,
+    "d": 1,
+    "q": 2,
+    "seasonal": false,
+    "trend": "c"
+ }',
+ '{
+    "method": "mle",
+    "max_iter": 1000,
+    "tolerance": 1e-06
+ }',
+ '{
+    "features": ["temporal", "trend"],
+    "differencing": true,
+    "seasonal_analysis": false
+ }',
+ 55.2, true, '1.0.0'),
+
+('神经网络-深度学习', 'neural_network', '基于深度神经网络的预测模型', 
+ '{
+    "layers": [128, 64, 32],
+    "activation": "relu",
+    "dropout": 0.3,
+    "epochs": 200,
+    "batch_size": 64,
+    "learning_rate": 0.001
+ }',
+ '{
+    "optimizer": "adam",
+    "loss_function": "categorical_crossentropy",
+    "metrics": ["accuracy"],
+    "validation_split": 0.2
+ }',
+ '{
+    "features": ["frequency", "statistical", "pattern", "temporal"],
+    "feature_scaling": true,
+    "dimensionality_reduction": "pca"
+ }',
+ 69.1, true, '1.0.0'),
+
+('混合模型-集成', 'hybrid', '多种算法的集成预测模型', 
+ '{
+    "models": ["random_forest", "lstm", "neural_network"],
+    "weights": [0.3, 0.4, 0.3],
+    "ensemble_method": "weighted_average",
+    "confidence_threshold": 0.65
+ }',
+ '{
+    "cv_folds": 10,
+    "scoring": "f1_weighted",
+    "optimization": "bayesian"
+ }',
+ '{
+    "features": ["all"],
+    "feature_selection": true,
+    "ensemble_voting": "soft"
+ }',
+ 71.4, true, '1.0.0');
@@ -0,0 +1,83 @@
+-- 算法类型补充 (SQLite 版本)：新增梯度提升、贝叶斯与马尔可夫链算法
+-- SQLite 不能修改 CHECK 约束，只能按原结构重建表。迁移在 sqlx 的事务中执行，事务内无法关闭外键，
+-- 因此推迟外键检查到提交时，并在删除旧表前移走所有引用它的行，重建后再写回
+PRAGMA defer_foreign_keys = ON;
+
+CREATE TEMP TABLE saved_prediction_results AS SELECT * FROM prediction_results;
+CREATE TEMP TABLE saved_model_training_records AS SELECT * FROM model_training_records;
+CREATE TEMP TABLE saved_user_strategy_configs AS SELECT * FROM user_strategy_configs;
+CREATE TEMP TABLE saved_strategy_parents AS
+    SELECT id, parent_strategy_id FROM prediction_strategies WHERE parent_strategy_id IS NOT NULL;
+
+DELETE FROM prediction_results;
+DELETE FROM model_training_records;
+DELETE FROM user_strategy_configs;
+
+CREATE TABLE prediction_strategies_new (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    name TEXT NOT NULL,
+    algorithm_type TEXT NOT NULL CHECK (
+        algorithm_type IN (
+            'random_forest', 'gradient_boosting', 'lstm', 'arima', 'statistical',
+            'bayesian', 'markov', 'neural_network', 'hybrid'
+        )
+    ),
+    description TEXT,
+    parameters TEXT NOT NULL DEFAULT '{}',
+    hyperparameters TEXT DEFAULT '{}',
+    feature_config TEXT DEFAULT '{}',
+    accuracy_rate REAL CHECK (accuracy_rate >= 0 AND accuracy_rate <= 100),
+    precision_rate REAL CHECK (precision_rate >= 0 AND precision_rate <= 100),
+    recall_rate REAL CHECK (recall_rate >= 0 AND recall_rate <= 100),
+    f1_score REAL CHECK (f1_score >= 0 AND f1_score <= 100),
+    total_predictions INTEGER DEFAULT 0,
+    successful_predictions INTEGER DEFAULT 0,
+    total_trainings INTEGER DEFAULT 0,
+    last_training_date TEXT,
+    model_path TEXT,
+    model_hash TEXT,
+    model_size_bytes INTEGER,
+    is_active BOOLEAN DEFAULT TRUE,
+    is_public BOOLEAN DEFAULT FALSE,
+    is_system BOOLEAN DEFAULT FALSE,
+    owner_id BLOB,
+    version TEXT DEFAULT '1.0.0',
+    parent_strategy_id BLOB REFERENCES prediction_strategies(id),
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+-- 新表的 parent_strategy_id 引用的是旧表，先留空，改名后再恢复
+INSERT INTO prediction_strategies_new (
+    id, name, algorithm_type, description, parameters, hyperparameters, feature_config,
+    accuracy_rate, precision_rate, recall_rate, f1_score, total_predictions, successful_predictions,
+    total_trainings, last_training_date, model_path, model_hash, model_size_bytes,
+    is_active, is_public, is_system, owner_id, version, created_at, updated_at
+)
+SELECT
+    id, name, algorithm_type, description, parameters, hyperparameters, feature_config,
+    accuracy_rate, precision_rate, recall_rate, f1_score, total_predictions, successful_predictions,
+    total_trainings, last_training_date, model_path, model_hash, model_size_bytes,
+    is_active, is_public, is_system, owner_id, version, created_at, updated_at
+FROM prediction_strategies;
+
+DROP TABLE prediction_strategies;
+
+ALTER TABLE prediction_strategies_new RENAME TO prediction_strategies;
+
+CREATE INDEX idx_strategy_performance ON prediction_strategies(algorithm_type, accuracy_rate DESC);
+
+UPDATE prediction_strategies
+SET parent_strategy_id = (
+    SELECT saved.parent_strategy_id FROM saved_strategy_parents saved WHERE saved.id = prediction_strategies.id
+)
+WHERE id IN (SELECT id FROM saved_strategy_parents);
+
+INSERT INTO prediction_results SELECT * FROM saved_prediction_results;
+INSERT INTO model_training_records SELECT * FROM saved_model_training_records;
+INSERT INTO user_strategy_configs SELECT * FROM saved_user_strategy_configs;
+
+DROP TABLE saved_prediction_results;
+DROP TABLE saved_model_training_records;
+DROP TABLE saved_user_strategy_configs;
+DROP TABLE saved_strategy_parents;
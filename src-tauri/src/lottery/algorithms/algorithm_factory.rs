@@ -1,42 +1,30 @@
 use crate::lottery::algorithms::traits::*;
-use crate::lottery::algorithms::random_forest::RandomForestModel;
-use crate::lottery::algorithms::neural_network::NeuralNetworkModel;
-use crate::lottery::algorithms::lstm::LstmModel;
-use crate::lottery::algorithms::arima::ArimaModel;
-use crate::lottery::algorithms::statistical::StatisticalModel;
-use crate::lottery::algorithms::hybrid::HybridEnsembleModel;
+use crate::lottery::algorithms::ensemble::{self, EnsembleCombiner, EnsembleWeights, WeightObjective};
+use crate::lottery::algorithms::random_forest::{RandomForestConfig, RandomForestModel};
+use crate::lottery::algorithms::gradient_boosting::{GradientBoostingConfig, GradientBoostingModel};
+use crate::lottery::algorithms::neural_network::{NeuralNetworkConfig, NeuralNetworkModel};
+use crate::lottery::algorithms::lstm::{LstmConfig, LstmModel};
+use crate::lottery::algorithms::arima::{ArimaConfig, ArimaModel};
+use crate::lottery::algorithms::statistical::{StatisticalConfig, StatisticalModel};
+use crate::lottery::algorithms::bayesian::{BayesianConfig, BayesianModel};
+use crate::lottery::algorithms::markov::{MarkovConfig, MarkovModel};
+use crate::lottery::algorithms::hybrid::{HybridConfig, HybridEnsembleModel};
 use crate::lottery::errors::LotteryResult as Result;
-use crate::lottery::models::LotteryType;
+use crate::lottery::models::{AlgorithmType, LotteryType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AlgorithmConfig {
-    pub lottery_type: LotteryType,
-    pub parameters: HashMap<String, serde_json::Value>,
-    pub hyperparameters: HashMap<String, serde_json::Value>,
-    pub feature_config: HashMap<String, serde_json::Value>,
-}
-
-impl Default for AlgorithmConfig {
-    fn default() -> Self {
-        Self {
-            lottery_type: LotteryType::Ssq,
-            parameters: HashMap::new(),
-            hyperparameters: HashMap::new(),
-            feature_config: HashMap::new(),
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct AlgorithmFactory {
     pub lottery_type: LotteryType,
-    pub available_algorithms: HashMap<String, AlgorithmMetadata>,
-    pub trained_models: Arc<RwLock<HashMap<String, Box<dyn PredictionAlgorithm>>>>,
-    pub model_registry: HashMap<String, ModelInfo>,
+    pub available_algorithms: HashMap<AlgorithmType, AlgorithmMetadata>,
+    // 已注册的训练模型，预测时共享；重新注册会整体替换，进行中的预测继续使用旧模型
+    pub trained_models: Arc<RwLock<HashMap<AlgorithmType, Arc<dyn PredictionAlgorithm>>>>,
+    pub model_registry: HashMap<AlgorithmType, ModelInfo>,
+    // ensemble_predict 使用的子模型权重，未学习时等权
+    pub ensemble_weights: Arc<RwLock<EnsembleWeights>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,13 +42,18 @@ pub struct AlgorithmMetadata {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
-    pub algorithm_name: String,
+    pub algorithm: AlgorithmType,
     pub lottery_type: LotteryType,
     pub training_date: chrono::DateTime<chrono::Utc>,
     pub performance_metrics: EvaluationMetrics,
-    pub model_size: u64,
+    // 模型文件的字节数，未持久化的模型为 None
+    pub model_size: Option<u64>,
     pub last_updated: chrono::DateTime<chrono::Utc>,
     pub config: AlgorithmConfig,
+    // 模型存储中的版本，未持久化的模型为 None
+    pub version: Option<u32>,
+    // 未记录训练耗时的模型为 None
+    pub training_time_ms: Option<u64>,
 }
 
 impl AlgorithmFactory {
@@ -69,7 +62,7 @@ impl AlgorithmFactory {
         
         // Register Random Forest
         available_algorithms.insert(
-            "random_forest".to_string(),
+            AlgorithmType::RandomForest,
             AlgorithmMetadata {
                 name: "Random Forest".to_string(),
                 algorithm_type: "ensemble".to_string(),
@@ -89,6 +82,45 @@ impl AlgorithmFactory {
                     ("n_estimators".to_string(), "integer".to_string()),
                     ("max_depth".to_string(), "integer".to_string()),
                     ("min_samples_split".to_string(), "integer".to_string()),
+                    ("min_samples_leaf".to_string(), "integer".to_string()),
+                    ("max_features".to_string(), "integer".to_string()),
+                    ("max_samples".to_string(), "float".to_string()),
+                    ("bootstrap".to_string(), "boolean".to_string()),
+                    ("oob_score".to_string(), "boolean".to_string()),
+                    ("n_jobs".to_string(), "integer".to_string()),
+                    ("random_state".to_string(), "integer".to_string()),
+                ]),
+            },
+        );
+        
+        // Register Gradient Boosting
+        available_algorithms.insert(
+            AlgorithmType::GradientBoosting,
+            AlgorithmMetadata {
+                name: "Gradient Boosting".to_string(),
+                algorithm_type: "ensemble".to_string(),
+                description: "Per-number gradient-boosted decision trees with logistic loss and early stopping".to_string(),
+                supported_lottery_types: vec![
+                    LotteryType::Ssq,
+                    LotteryType::Dlt,
+                    LotteryType::Fc3d,
+                    LotteryType::Pl3,
+                    LotteryType::Pl5,
+                ],
+                required_data_size: 100,
+                training_time_complexity: "O(m·n log n)".to_string(),
+                prediction_time_complexity: "O(m·log n)".to_string(),
+                accuracy_range: (0.65, 0.85),
+                config_schema: HashMap::from_iter(vec![
+                    ("n_estimators".to_string(), "integer".to_string()),
+                    ("learning_rate".to_string(), "float".to_string()),
+                    ("max_depth".to_string(), "integer".to_string()),
+                    ("min_samples_leaf".to_string(), "integer".to_string()),
+                    ("l2_regularization".to_string(), "float".to_string()),
+                    ("subsample".to_string(), "float".to_string()),
+                    ("validation_fraction".to_string(), "float".to_string()),
+                    ("n_iter_no_change".to_string(), "integer".to_string()),
+                    ("n_jobs".to_string(), "integer".to_string()),
                     ("random_state".to_string(), "integer".to_string()),
                 ]),
             },
@@ -96,7 +128,7 @@ impl AlgorithmFactory {
         
         // Register Neural Network
         available_algorithms.insert(
-            "neural_network".to_string(),
+            AlgorithmType::NeuralNetwork,
             AlgorithmMetadata {
                 name: "Deep Neural Network".to_string(),
                 algorithm_type: "deep_learning".to_string(),
@@ -117,13 +149,16 @@ impl AlgorithmFactory {
                     ("learning_rate".to_string(), "float".to_string()),
                     ("epochs".to_string(), "integer".to_string()),
                     ("dropout_rate".to_string(), "float".to_string()),
+                    ("batch_size".to_string(), "integer".to_string()),
+                    ("optimizer".to_string(), "string".to_string()),
+                    ("learning_rate_schedule".to_string(), "object".to_string()),
                 ]),
             },
         );
         
         // Register LSTM
         available_algorithms.insert(
-            "lstm".to_string(),
+            AlgorithmType::Lstm,
             AlgorithmMetadata {
                 name: "LSTM Neural Network".to_string(),
                 algorithm_type: "deep_learning".to_string(),
@@ -144,13 +179,14 @@ impl AlgorithmFactory {
                     ("sequence_length".to_string(), "integer".to_string()),
                     ("num_layers".to_string(), "integer".to_string()),
                     ("dropout".to_string(), "float".to_string()),
+                    ("optimizer".to_string(), "string".to_string()),
                 ]),
             },
         );
         
         // Register ARIMA
         available_algorithms.insert(
-            "arima".to_string(),
+            AlgorithmType::Arima,
             AlgorithmMetadata {
                 name: "ARIMA Time Series".to_string(),
                 algorithm_type: "time_series".to_string(),
@@ -171,13 +207,15 @@ impl AlgorithmFactory {
                     ("d".to_string(), "integer".to_string()),
                     ("q".to_string(), "integer".to_string()),
                     ("seasonal_period".to_string(), "integer".to_string()),
+                    ("auto_order".to_string(), "boolean".to_string()),
+                    ("information_criterion".to_string(), "string".to_string()),
                 ]),
             },
         );
         
         // Register Statistical
         available_algorithms.insert(
-            "statistical".to_string(),
+            AlgorithmType::Statistical,
             AlgorithmMetadata {
                 name: "Statistical Analysis".to_string(),
                 algorithm_type: "statistical".to_string(),
@@ -201,9 +239,61 @@ impl AlgorithmFactory {
             },
         );
         
+        // Register Bayesian
+        available_algorithms.insert(
+            AlgorithmType::Bayesian,
+            AlgorithmMetadata {
+                name: "Bayesian Dirichlet-Multinomial".to_string(),
+                algorithm_type: "statistical".to_string(),
+                description: "Dirichlet posterior over number probabilities with exponential forgetting and credible intervals".to_string(),
+                supported_lottery_types: vec![
+                    LotteryType::Ssq,
+                    LotteryType::Dlt,
+                    LotteryType::Fc3d,
+                    LotteryType::Pl3,
+                    LotteryType::Pl5,
+                ],
+                required_data_size: 30,
+                training_time_complexity: "O(n)".to_string(),
+                prediction_time_complexity: "O(n)".to_string(),
+                accuracy_range: (0.55, 0.75),
+                config_schema: HashMap::from_iter(vec![
+                    ("prior_concentration".to_string(), "float".to_string()),
+                    ("forgetting_factor".to_string(), "float".to_string()),
+                    ("credible_level".to_string(), "float".to_string()),
+                ]),
+            },
+        );
+        
+        // Register Markov Chain
+        available_algorithms.insert(
+            AlgorithmType::Markov,
+            AlgorithmMetadata {
+                name: "Markov Chain".to_string(),
+                algorithm_type: "statistical".to_string(),
+                description: "Draw-to-draw number transitions and gap-length transitions with smoothing".to_string(),
+                supported_lottery_types: vec![
+                    LotteryType::Ssq,
+                    LotteryType::Dlt,
+                    LotteryType::Fc3d,
+                    LotteryType::Pl3,
+                    LotteryType::Pl5,
+                ],
+                required_data_size: 50,
+                training_time_complexity: "O(n·k²)".to_string(),
+                prediction_time_complexity: "O(n·k)".to_string(),
+                accuracy_range: (0.55, 0.75),
+                config_schema: HashMap::from_iter(vec![
+                    ("smoothing".to_string(), "float".to_string()),
+                    ("transition_weight".to_string(), "float".to_string()),
+                    ("max_gap".to_string(), "integer".to_string()),
+                ]),
+            },
+        );
+        
         // Register Hybrid Ensemble
         available_algorithms.insert(
-            "hybrid".to_string(),
+            AlgorithmType::Hybrid,
             AlgorithmMetadata {
                 name: "Hybrid Ensemble".to_string(),
                 algorithm_type: "ensemble".to_string(),
@@ -223,221 +313,142 @@ impl AlgorithmFactory {
                     ("voting_method".to_string(), "string".to_string()),
                     ("confidence_threshold".to_string(), "float".to_string()),
                     ("ensemble_weights".to_string(), "object".to_string()),
+                    ("use_meta_learner".to_string(), "boolean".to_string()),
+                    ("meta_learner_type".to_string(), "string".to_string()),
+                    ("diversity_weight".to_string(), "float".to_string()),
+                    ("cross_validation_folds".to_string(), "integer".to_string()),
+                    ("weight_objective".to_string(), "string".to_string()),
                 ]),
             },
         );
         
+        // 所有算法都按 GameSpec 取号，自定义玩法同样适用
+        for metadata in available_algorithms.values_mut() {
+            if !metadata.supported_lottery_types.contains(&lottery_type) {
+                metadata.supported_lottery_types.push(lottery_type.clone());
+            }
+        }
+        
         Self {
             lottery_type,
             available_algorithms,
             trained_models: Arc::new(RwLock::new(HashMap::new())),
             model_registry: HashMap::new(),
+            ensemble_weights: Arc::new(RwLock::new(EnsembleWeights::default())),
         }
     }
 
     pub fn create_algorithm(
         &self,
-        algorithm_name: &str,
+        algorithm: AlgorithmType,
         config: AlgorithmConfig,
     ) -> Result<Box<dyn PredictionAlgorithm>> {
-        let lottery_type = config.lottery_type;
+        let lottery_type = config.lottery_type.clone();
         
-        match algorithm_name {
-            "random_forest" => {
-                let mut rf_config = crate::lottery::algorithms::random_forest::RandomForestConfig::default();
-                
-                if let Some(n_estimators) = config.parameters.get("n_estimators") {
-                    if let Some(n) = n_estimators.as_u64() {
-                        rf_config.n_estimators = n as usize;
-                    }
-                }
-                
-                if let Some(max_depth) = config.parameters.get("max_depth") {
-                    if let Some(depth) = max_depth.as_u64() {
-                        rf_config.max_depth = Some(depth as usize);
-                    }
-                }
-                
-                Ok(Box::new(RandomForestModel::new(rf_config, lottery_type)))
-            }
-            
-            "neural_network" => {
-                let mut nn_config = crate::lottery::algorithms::neural_network::NeuralNetworkConfig::default();
-                
-                if let Some(hidden_layers) = config.parameters.get("hidden_layers") {
-                    if let Some(layers) = hidden_layers.as_array() {
-                        nn_config.hidden_layers = layers.iter()
-                            .filter_map(|v| v.as_u64().map(|n| n as usize))
-                            .collect();
-                    }
-                }
-                
-                if let Some(learning_rate) = config.parameters.get("learning_rate") {
-                    if let Some(lr) = learning_rate.as_f64() {
-                        nn_config.learning_rate = lr;
-                    }
-                }
-                
-                if let Some(epochs) = config.parameters.get("epochs") {
-                    if let Some(e) = epochs.as_u64() {
-                        nn_config.epochs = e as usize;
-                    }
-                }
-                
-                Ok(Box::new(NeuralNetworkModel::new(nn_config, lottery_type)))
-            }
-            
-            "lstm" => {
-                let mut lstm_config = crate::lottery::algorithms::lstm::LstmConfig::default();
-                
-                if let Some(hidden_size) = config.parameters.get("hidden_size") {
-                    if let Some(size) = hidden_size.as_u64() {
-                        lstm_config.hidden_size = size as usize;
-                    }
-                }
-                
-                if let Some(sequence_length) = config.parameters.get("sequence_length") {
-                    if let Some(length) = sequence_length.as_u64() {
-                        lstm_config.sequence_length = length as usize;
-                    }
-                }
-                
-                if let Some(epochs) = config.parameters.get("epochs") {
-                    if let Some(e) = epochs.as_u64() {
-                        lstm_config.epochs = e as usize;
-                    }
-                }
-                
-                Ok(Box::new(LstmModel::new(lstm_config, lottery_type)))
-            }
-            
-            "arima" => {
-                let mut arima_config = crate::lottery::algorithms::arima::ArimaConfig::default();
-                
-                if let Some(p) = config.parameters.get("p") {
-                    if let Some(val) = p.as_u64() {
-                        arima_config.p = val as usize;
-                    }
-                }
-                
-                if let Some(d) = config.parameters.get("d") {
-                    if let Some(val) = d.as_u64() {
-                        arima_config.d = val as usize;
-                    }
-                }
-                
-                if let Some(q) = config.parameters.get("q") {
-                    if let Some(val) = q.as_u64() {
-                        arima_config.q = val as usize;
-                    }
-                }
-                
-                Ok(Box::new(ArimaModel::new(arima_config, lottery_type)))
-            }
-            
-            "statistical" => {
-                let mut stat_config = crate::lottery::algorithms::statistical::StatisticalConfig::default();
-                
-                if let Some(window_size) = config.parameters.get("window_size") {
-                    if let Some(size) = window_size.as_u64() {
-                        stat_config.window_size = size as usize;
-                    }
-                }
-                
-                if let Some(confidence_threshold) = config.parameters.get("confidence_threshold") {
-                    if let Some(threshold) = confidence_threshold.as_f64() {
-                        stat_config.confidence_threshold = threshold;
-                    }
-                }
-                
-                Ok(Box::new(StatisticalModel::new(stat_config, lottery_type)))
-            }
-            
-            "hybrid" => {
-                let mut hybrid_config = crate::lottery::algorithms::hybrid::HybridConfig::default();
-                
-                if let Some(voting_method) = config.parameters.get("voting_method") {
-                    if let Some(method) = voting_method.as_str() {
-                        hybrid_config.voting_method = method.to_string();
-                    }
-                }
-                
-                if let Some(weights) = config.parameters.get("ensemble_weights") {
-                    if let Some(w) = weights.as_object() {
-                        hybrid_config.ensemble_weights = w.iter()
-                            .filter_map(|(k, v)| v.as_f64().map(|val| (k.clone(), val)))
-                            .collect();
-                    }
-                }
-                
-                Ok(Box::new(HybridEnsembleModel::new(hybrid_config, lottery_type)))
-            }
-            
-            _ => Err(crate::lottery::errors::LotteryError::AlgorithmError(
-                format!("Unknown algorithm: {}", algorithm_name)
-            )),
+        match algorithm {
+            AlgorithmType::RandomForest => Ok(Box::new(RandomForestModel::new(
+                config.apply(&RandomForestConfig::default())?,
+                lottery_type,
+            ))),
+            AlgorithmType::GradientBoosting => Ok(Box::new(GradientBoostingModel::new(
+                config.apply(&GradientBoostingConfig::default())?,
+                lottery_type,
+            ))),
+            AlgorithmType::NeuralNetwork => Ok(Box::new(NeuralNetworkModel::new(
+                config.apply(&NeuralNetworkConfig::default())?,
+                lottery_type,
+            ))),
+            AlgorithmType::Lstm => Ok(Box::new(LstmModel::new(
+                config.apply(&LstmConfig::default())?,
+                lottery_type,
+            ))),
+            AlgorithmType::Arima => Ok(Box::new(ArimaModel::new(
+                config.apply(&ArimaConfig::default())?,
+                lottery_type,
+            ))),
+            AlgorithmType::Statistical => Ok(Box::new(StatisticalModel::new(
+                config.apply(&StatisticalConfig::default())?,
+                lottery_type,
+            ))),
+            AlgorithmType::Bayesian => Ok(Box::new(BayesianModel::new(
+                config.apply(&BayesianConfig::default())?,
+                lottery_type,
+            ))),
+            AlgorithmType::Markov => Ok(Box::new(MarkovModel::new(
+                config.apply(&MarkovConfig::default())?,
+                lottery_type,
+            ))),
+            AlgorithmType::Hybrid => Ok(Box::new(HybridEnsembleModel::new(
+                config.apply(&HybridConfig::default())?,
+                lottery_type,
+            ))),
         }
     }
 
     pub async fn register_model(
         &mut self,
-        algorithm_name: String,
-        model: Box<dyn PredictionAlgorithm>,
+        algorithm: AlgorithmType,
+        model: Arc<dyn PredictionAlgorithm>,
         performance_metrics: EvaluationMetrics,
         config: AlgorithmConfig,
     ) -> Result<()> {
         let model_info = ModelInfo {
-            algorithm_name: algorithm_name.clone(),
+            algorithm,
             lottery_type: self.lottery_type.clone(),
             training_date: chrono::Utc::now(),
             performance_metrics,
-            model_size: 0, // TODO: Calculate actual model size
+            model_size: None,
             last_updated: chrono::Utc::now(),
             config,
+            version: None,
+            training_time_ms: None,
         };
         
-        self.model_registry.insert(algorithm_name.clone(), model_info);
+        self.model_registry.insert(algorithm, model_info);
         
         let mut models = self.trained_models.write().await;
-        models.insert(algorithm_name, model);
+        models.insert(algorithm, model);
         
         Ok(())
     }
 
-    pub async fn get_model(&self, algorithm_name: &str) -> Option<Box<dyn PredictionAlgorithm>> {
-        match algorithm_name {
-            "random_forest" => Some(Box::new(RandomForestModel::new(Default::default(), self.lottery_type.clone())) as Box<dyn PredictionAlgorithm>),
-            "neural_network" => Some(Box::new(NeuralNetworkModel::new(Default::default(), self.lottery_type.clone())) as Box<dyn PredictionAlgorithm>),
-            "lstm" => Some(Box::new(LstmModel::new(Default::default(), self.lottery_type.clone())) as Box<dyn PredictionAlgorithm>),
-            "arima" => Some(Box::new(ArimaModel::new(Default::default(), self.lottery_type.clone())) as Box<dyn PredictionAlgorithm>),
-            "statistical" => Some(Box::new(StatisticalModel::new(Default::default(), self.lottery_type.clone())) as Box<dyn PredictionAlgorithm>),
-            "hybrid" => Some(Box::new(HybridEnsembleModel::new(Default::default(), self.lottery_type.clone())) as Box<dyn PredictionAlgorithm>),
-            _ => None,
-        }
+    // 返回已注册的训练模型，未注册时为 None，不会退回未训练的新实例
+    pub async fn get_model(&self, algorithm: AlgorithmType) -> Option<Arc<dyn PredictionAlgorithm>> {
+        self.trained_models.read().await.get(&algorithm).cloned()
+    }
+
+    // 按 algorithms 的顺序取出已注册的模型，调用方在释放锁之后再预测
+    async fn registered_models(&self, algorithms: &[AlgorithmType]) -> Vec<(AlgorithmType, Arc<dyn PredictionAlgorithm>)> {
+        let models = self.trained_models.read().await;
+        algorithms.iter()
+            .filter_map(|algorithm| models.get(algorithm).map(|model| (*algorithm, Arc::clone(model))))
+            .collect()
     }
 
-    pub async fn list_available_algorithms(&self) -> Vec<String> {
-        self.available_algorithms.keys().cloned().collect()
+    pub async fn list_available_algorithms(&self) -> Vec<AlgorithmType> {
+        let mut algorithms: Vec<AlgorithmType> = self.available_algorithms.keys().copied().collect();
+        algorithms.sort();
+        algorithms
     }
 
-    pub async fn list_trained_algorithms(&self) -> Vec<String> {
+    pub async fn list_trained_algorithms(&self) -> Vec<AlgorithmType> {
         let models = self.trained_models.read().await;
-        models.keys().cloned().collect()
+        models.keys().copied().collect()
     }
 
-    pub fn get_algorithm_metadata(&self, algorithm_name: &str) -> Option<&AlgorithmMetadata> {
-        self.available_algorithms.get(algorithm_name)
+    pub fn get_algorithm_metadata(&self, algorithm: AlgorithmType) -> Option<&AlgorithmMetadata> {
+        self.available_algorithms.get(&algorithm)
     }
 
-    pub fn is_algorithm_supported(&self, algorithm_name: &str, lottery_type: LotteryType) -> bool {
-        if let Some(metadata) = self.available_algorithms.get(algorithm_name) {
+    pub fn is_algorithm_supported(&self, algorithm: AlgorithmType, lottery_type: LotteryType) -> bool {
+        if let Some(metadata) = self.available_algorithms.get(&algorithm) {
             metadata.supported_lottery_types.contains(&lottery_type)
         } else {
             false
         }
     }
 
-    pub fn recommend_algorithms(&self, data_size: usize, target_accuracy: f64) -> Vec<String> {
+    pub fn recommend_algorithms(&self, data_size: usize, target_accuracy: f64) -> Vec<AlgorithmType> {
         self.available_algorithms
             .iter()
             .filter(|(_, metadata)| {
@@ -445,70 +456,71 @@ impl AlgorithmFactory {
                     && metadata.required_data_size <= data_size
                     && metadata.accuracy_range.1 >= target_accuracy
             })
-            .map(|(name, _)| name.clone())
+            .map(|(algorithm, _)| *algorithm)
             .collect()
     }
 
-    pub async fn save_model(
-        &self,
-        algorithm_name: &str,
-        path: &str,
+    // 注册从模型存储中取出或刚保存的版本，训练信息取自元数据
+    pub async fn register_stored_model(
+        &mut self,
+        model: Arc<dyn PredictionAlgorithm>,
+        metadata: &ModelMetadata,
     ) -> Result<()> {
-        let models = self.trained_models.read().await;
-        if let Some(model) = models.get(algorithm_name) {
-            model.save_model(path)?;
-        }
+        let model_info = ModelInfo {
+            algorithm: metadata.algorithm,
+            lottery_type: self.lottery_type.clone(),
+            training_date: metadata.training_date,
+            performance_metrics: metadata.evaluation_metrics.clone(),
+            model_size: Some(metadata.model_size_bytes),
+            last_updated: chrono::Utc::now(),
+            config: metadata.config.clone(),
+            version: Some(metadata.version),
+            training_time_ms: metadata.training_time_ms,
+        };
+
+        self.model_registry.insert(metadata.algorithm, model_info);
+
+        let mut models = self.trained_models.write().await;
+        models.insert(metadata.algorithm, model);
+
         Ok(())
     }
 
+    // 从模型存储加载指定版本 (None 为当前版本) 并注册
     pub async fn load_model(
         &mut self,
-        algorithm_name: &str,
-        path: &str,
-        config: AlgorithmConfig,
-    ) -> Result<()> {
-        let mut model = self.create_algorithm(algorithm_name, config.clone())?;
-        model.load_model(path)?;
-        
-        // Create performance metrics (placeholder)
-        let performance_metrics = EvaluationMetrics {
-            accuracy: 0.75,
-            precision: 0.73,
-            recall: 0.77,
-            f1_score: 0.75,
-            mean_absolute_error: 0.25,
-            root_mean_squared_error: 0.30,
-            confusion_matrix: None,
-            feature_importance: None,
-            cross_validation_scores: None,
-        };
-        
-        self.register_model(
-            algorithm_name.to_string(),
-            model,
-            performance_metrics,
-            config,
-        ).await?;
-        
-        Ok(())
+        store: &dyn ModelPersistence,
+        algorithm: AlgorithmType,
+        version: Option<u32>,
+    ) -> Result<ModelMetadata> {
+        let (model, metadata) = store.load(&self.lottery_type, algorithm, version).await?;
+        if !model.is_trained() {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Stored {} model v{} is not trained", algorithm, metadata.version)
+            ));
+        }
+
+        self.register_stored_model(Arc::from(model), &metadata).await?;
+
+        Ok(metadata)
     }
 
     pub async fn compare_algorithms(
         &self,
         test_data: &TrainingData,
-    ) -> Result<HashMap<String, EvaluationMetrics>> {
+    ) -> Result<HashMap<AlgorithmType, EvaluationMetrics>> {
         let mut results = HashMap::new();
         let models = self.trained_models.read().await;
         
-        for (name, model) in models.iter() {
+        for (algorithm, model) in models.iter() {
             let metrics = model.evaluate(test_data).await?;
-            results.insert(name.clone(), metrics);
+            results.insert(*algorithm, metrics);
         }
         
         Ok(results)
     }
 
-    pub fn get_best_algorithm_by_accuracy(&self) -> Option<String> {
+    pub fn get_best_algorithm_by_accuracy(&self) -> Option<AlgorithmType> {
         self.model_registry
             .iter()
             .max_by(|a, b| {
@@ -517,32 +529,52 @@ impl AlgorithmFactory {
                     .partial_cmp(&b.1.performance_metrics.accuracy)
                     .unwrap()
             })
-            .map(|(name, _)| name.clone())
+            .map(|(algorithm, _)| *algorithm)
     }
 
-    pub fn get_algorithm_rankings(&self) -> Vec<(String, f64)> {
-        let mut rankings: Vec<(String, f64)> = self.model_registry
+    pub fn get_algorithm_rankings(&self) -> Vec<(AlgorithmType, f64)> {
+        let mut rankings: Vec<(AlgorithmType, f64)> = self.model_registry
             .iter()
-            .map(|(name, info)| (name.clone(), info.performance_metrics.accuracy))
+            .map(|(algorithm, info)| (*algorithm, info.performance_metrics.accuracy))
             .collect();
         
         rankings.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
         rankings
     }
 
+    // 在验证数据上学习已训练子模型的组合权重，保存为本玩法的权重
+    pub async fn fit_ensemble_weights(
+        &self,
+        algorithms: &[AlgorithmType],
+        validation_data: &TrainingData,
+        objective: WeightObjective,
+    ) -> Result<HashMap<String, f64>> {
+        let models = self.registered_models(algorithms).await;
+        let selected: Vec<(String, &dyn PredictionAlgorithm)> = models.iter()
+            .map(|(algorithm, model)| (algorithm.to_string(), model.as_ref()))
+            .collect();
+
+        let model_scores = ensemble::collect_scores(&selected, validation_data).await?;
+        let weights = EnsembleCombiner::for_type(&self.lottery_type)
+            .learn_weights(&model_scores, &validation_data.targets, objective)?;
+        self.set_ensemble_weights(weights.clone()).await;
+        Ok(weights)
+    }
+
+    pub async fn set_ensemble_weights(&self, weights: HashMap<String, f64>) {
+        self.ensemble_weights.write().await.set_game(&self.lottery_type, weights);
+    }
+
     pub async fn ensemble_predict(
         &self,
-        algorithms: &[&str],
+        algorithms: &[AlgorithmType],
         input: &PredictionInput,
     ) -> Result<PredictionOutput> {
-        let models = self.trained_models.read().await;
         let mut predictions = Vec::new();
         
-        for algorithm_name in algorithms {
-            if let Some(model) = models.get(*algorithm_name) {
-                let prediction = model.predict(input).await?;
-                predictions.push((algorithm_name.to_string(), prediction));
-            }
+        for (algorithm, model) in self.registered_models(algorithms).await {
+            let prediction = model.predict(input).await?;
+            predictions.push((algorithm.to_string(), prediction));
         }
         
         if predictions.is_empty() {
@@ -551,71 +583,106 @@ impl AlgorithmFactory {
             ));
         }
         
-        // Use hybrid model for ensemble prediction
-        let _hybrid_model = HybridEnsembleModel::new(Default::default(), self.lottery_type.clone());
-        
-        // Create a simple ensemble prediction
-        let mut number_scores: std::collections::HashMap<u32, f64> = std::collections::HashMap::new();
-        
-        for (_, prediction) in &predictions {
-            for (i, &number) in prediction.predicted_numbers.iter().enumerate() {
-                let confidence = prediction.confidence_scores.get(i).copied().unwrap_or(0.5);
-                *number_scores.entry(number).or_insert(0.0) += confidence;
-            }
-        }
-        
-        let mut sorted_scores: Vec<(u32, f64)> = number_scores.into_iter().collect();
-        sorted_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        
-        let main_count = match self.lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
-        
-        let predicted_numbers: Vec<u32> = sorted_scores.into_iter()
-            .take(main_count)
-            .map(|(number, _)| number)
-            .collect();
-        
-        let confidence_scores: Vec<f64> = vec![0.75; main_count];
+        let weights = self.ensemble_weights.read().await;
+        let mut output = EnsembleCombiner::for_type(&self.lottery_type).combine_predictions(
+            weights.for_game(&self.lottery_type),
+            &predictions,
+            &input.historical_data,
+        );
+        output.algorithm_metadata.insert(
+            "algorithms".to_string(),
+            serde_json::Value::String(algorithms.iter().map(AlgorithmType::as_str).collect::<Vec<_>>().join(",")),
+        );
         
-        Ok(PredictionOutput {
-            predicted_numbers,
-            predicted_special_numbers: None,
-            confidence_scores,
-            algorithm_metadata: HashMap::from_iter(vec![
-                ("method".to_string(), serde_json::Value::String("ensemble".to_string())),
-                ("algorithms".to_string(), serde_json::Value::String(algorithms.join(","))),
-            ]),
-            computation_time_ms: 0,
-        })
+        Ok(output)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lottery::algorithms::test_support::create_drawing;
     use crate::lottery::algorithms::traits::TrainingData;
     use crate::lottery::models::{LotteryDrawing, LotteryType};
     use chrono::NaiveDate;
 
-    #[test]
-    fn test_algorithm_factory_creation() {
+    #[tokio::test]
+    async fn test_algorithm_factory_creation() {
         let factory = AlgorithmFactory::new(LotteryType::Ssq);
         let algorithms = factory.list_available_algorithms().await;
-        assert!(algorithms.contains(&"random_forest".to_string()));
-        assert!(algorithms.contains(&"neural_network".to_string()));
-        assert!(algorithms.contains(&"hybrid".to_string()));
+        assert_eq!(algorithms, AlgorithmType::ALL.to_vec());
+        for algorithm in algorithms {
+            let model = factory.create_algorithm(algorithm, AlgorithmConfig::default()).unwrap();
+            assert_eq!(model.algorithm_type(), algorithm);
+        }
+        assert!(factory.get_algorithm_metadata(AlgorithmType::Markov).unwrap().config_schema.contains_key("transition_weight"));
+    }
+
+    #[test]
+    fn test_algorithm_type_round_trip() {
+        for algorithm in AlgorithmType::ALL {
+            assert_eq!(algorithm.to_string().parse::<AlgorithmType>().unwrap(), algorithm);
+            assert_eq!(serde_json::to_value(algorithm).unwrap(), serde_json::json!(algorithm.as_str()));
+        }
+        assert!(matches!(
+            "random_forst".parse::<AlgorithmType>(),
+            Err(crate::lottery::errors::LotteryError::NotFound(_))
+        ));
+        assert!(serde_json::from_value::<AlgorithmType>(serde_json::json!("random_forst")).is_err());
+    }
+
+    #[test]
+    fn test_typed_config_overrides() {
+        let factory = AlgorithmFactory::new(LotteryType::Ssq);
+        let mut config = AlgorithmConfig::default();
+        config.parameters.insert("n_estimators".to_string(), serde_json::json!(50));
+        config.parameters.insert("max_depth".to_string(), serde_json::json!(null));
+        config.hyperparameters.insert("n_estimators".to_string(), serde_json::json!(20));
+
+        let rf_config = config.apply(&RandomForestConfig::default()).unwrap();
+        assert_eq!(rf_config.n_estimators, 20);
+        assert_eq!(rf_config.max_depth, None);
+        assert!(factory.create_algorithm(AlgorithmType::RandomForest, config).is_ok());
+
+        for (name, value) in [
+            ("n_trees", serde_json::json!(10)),
+            ("n_estimators", serde_json::json!(0)),
+            ("n_estimators", serde_json::json!("many")),
+        ] {
+            let mut config = AlgorithmConfig::default();
+            config.hyperparameters.insert(name.to_string(), value);
+            let result = factory.create_algorithm(AlgorithmType::RandomForest, config);
+            assert!(matches!(result, Err(crate::lottery::errors::LotteryError::ConfigurationError(_))));
+        }
+    }
+
+    #[test]
+    fn test_config_schema_matches_configs() {
+        let factory = AlgorithmFactory::new(LotteryType::Ssq);
+        let configs = [
+            (AlgorithmType::RandomForest, serde_json::to_value(RandomForestConfig::default()).unwrap()),
+            (AlgorithmType::GradientBoosting, serde_json::to_value(GradientBoostingConfig::default()).unwrap()),
+            (AlgorithmType::NeuralNetwork, serde_json::to_value(NeuralNetworkConfig::default()).unwrap()),
+            (AlgorithmType::Lstm, serde_json::to_value(LstmConfig::default()).unwrap()),
+            (AlgorithmType::Arima, serde_json::to_value(ArimaConfig::default()).unwrap()),
+            (AlgorithmType::Statistical, serde_json::to_value(StatisticalConfig::default()).unwrap()),
+            (AlgorithmType::Bayesian, serde_json::to_value(BayesianConfig::default()).unwrap()),
+            (AlgorithmType::Markov, serde_json::to_value(MarkovConfig::default()).unwrap()),
+            (AlgorithmType::Hybrid, serde_json::to_value(HybridConfig::default()).unwrap()),
+        ];
+
+        for (algorithm, defaults) in configs {
+            let metadata = factory.get_algorithm_metadata(algorithm).unwrap();
+            for name in metadata.config_schema.keys() {
+                assert!(defaults.get(name).is_some(), "{} has no parameter {}", algorithm, name);
+            }
+        }
     }
 
     #[test]
     fn test_algorithm_metadata() {
         let factory = AlgorithmFactory::new(LotteryType::Ssq);
-        let metadata = factory.get_algorithm_metadata("random_forest").unwrap();
+        let metadata = factory.get_algorithm_metadata(AlgorithmType::RandomForest).unwrap();
         assert_eq!(metadata.name, "Random Forest");
         assert!(metadata.supported_lottery_types.contains(&LotteryType::Ssq));
     }
@@ -623,8 +690,16 @@ mod tests {
     #[test]
     fn test_algorithm_support() {
         let factory = AlgorithmFactory::new(LotteryType::Ssq);
-        assert!(factory.is_algorithm_supported("random_forest", LotteryType::Ssq));
-        assert!(factory.is_algorithm_supported("neural_network", LotteryType::Ssq));
+        assert!(factory.is_algorithm_supported(AlgorithmType::RandomForest, LotteryType::Ssq));
+        assert!(factory.is_algorithm_supported(AlgorithmType::NeuralNetwork, LotteryType::Ssq));
+    }
+
+    #[test]
+    fn test_custom_lottery_type_support() {
+        let lottery_type = LotteryType::Custom("lotto_649".to_string());
+        let factory = AlgorithmFactory::new(lottery_type.clone());
+        assert!(factory.is_algorithm_supported(AlgorithmType::RandomForest, lottery_type.clone()));
+        assert!(!factory.is_algorithm_supported(AlgorithmType::RandomForest, LotteryType::Custom("other".to_string())));
     }
 
     #[test]
@@ -632,7 +707,7 @@ mod tests {
         let factory = AlgorithmFactory::new(LotteryType::Ssq);
         let recommendations = factory.recommend_algorithms(1000, 0.75);
         assert!(!recommendations.is_empty());
-        assert!(recommendations.contains(&"lstm".to_string()));
+        assert!(recommendations.contains(&AlgorithmType::Lstm));
     }
 
     #[tokio::test]
@@ -640,7 +715,7 @@ mod tests {
         let mut factory = AlgorithmFactory::new(LotteryType::Ssq);
         let config = AlgorithmConfig::default();
         
-        let model = factory.create_algorithm("statistical", config.clone()).unwrap();
+        let model = factory.create_algorithm(AlgorithmType::Statistical, config.clone()).unwrap();
         let performance_metrics = EvaluationMetrics {
             accuracy: 0.75,
             precision: 0.73,
@@ -651,16 +726,136 @@ mod tests {
             confusion_matrix: None,
             feature_importance: None,
             cross_validation_scores: None,
+            ..Default::default()
         };
         
         let result = factory.register_model(
-            "statistical".to_string(),
-            model,
+            AlgorithmType::Statistical,
+            Arc::from(model),
             performance_metrics,
             config,
         ).await;
         
         assert!(result.is_ok());
-        assert!(factory.list_trained_algorithms().await.contains(&"statistical".to_string()));
+        assert!(factory.list_trained_algorithms().await.contains(&AlgorithmType::Statistical));
+    }
+
+    #[tokio::test]
+    async fn test_get_model_returns_registered_model() {
+        let mut factory = AlgorithmFactory::new(LotteryType::Ssq);
+        let config = AlgorithmConfig::default();
+        let data = TrainingData {
+            features: vec![vec![0.1; 10]; 30],
+            targets: vec![vec![1, 2, 3, 4, 5, 6]; 30],
+            special_targets: None,
+            weights: None,
+        };
+        assert!(factory.get_model(AlgorithmType::Statistical).await.is_none());
+
+        let mut model = factory.create_algorithm(AlgorithmType::Statistical, config.clone()).unwrap();
+        model.train(&data, &config).await.unwrap();
+        let first: Arc<dyn PredictionAlgorithm> = Arc::from(model);
+        factory.register_model(AlgorithmType::Statistical, Arc::clone(&first), EvaluationMetrics::default(), config.clone()).await.unwrap();
+
+        let served = factory.get_model(AlgorithmType::Statistical).await.unwrap();
+        assert!(Arc::ptr_eq(&served, &first));
+        assert_eq!(factory.model_registry[&AlgorithmType::Statistical].model_size, None);
+        assert!(served.is_trained());
+
+        // 重新注册替换模型，已取出的旧模型仍可使用
+        let mut retrained = factory.create_algorithm(AlgorithmType::Statistical, config.clone()).unwrap();
+        retrained.train(&data, &config).await.unwrap();
+        factory.register_model(AlgorithmType::Statistical, Arc::from(retrained), EvaluationMetrics::default(), config).await.unwrap();
+
+        let swapped = factory.get_model(AlgorithmType::Statistical).await.unwrap();
+        assert!(!Arc::ptr_eq(&swapped, &served));
+        assert!(served.is_trained());
+        assert_eq!(Arc::strong_count(&first), 2);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_load_model_from_store() {
+        let store = crate::lottery::model_store::ModelStore::new(
+            std::env::temp_dir().join(format!("factory-store-{}", uuid::Uuid::new_v4()))
+        );
+        let mut factory = AlgorithmFactory::new(LotteryType::Ssq);
+        let config = AlgorithmConfig::default();
+        let data = TrainingData {
+            features: vec![vec![0.1; 10]; 30],
+            targets: vec![vec![1, 2, 3, 4, 5, 6]; 30],
+            special_targets: None,
+            weights: None,
+        };
+        assert!(factory.load_model(&store, AlgorithmType::Statistical, None).await.is_err());
+
+        let mut model = factory.create_algorithm(AlgorithmType::Statistical, config.clone()).unwrap();
+        model.train(&data, &config).await.unwrap();
+        let metrics = EvaluationMetrics { accuracy: 0.4, ..Default::default() };
+        let metadata = ModelMetadata::new(AlgorithmType::Statistical, config, metrics, data.len());
+        let stored = store.save(model.as_ref(), &metadata).await.unwrap();
+
+        let loaded = factory.load_model(&store, AlgorithmType::Statistical, None).await.unwrap();
+        assert_eq!(loaded.version, stored.version);
+        assert!(factory.get_model(AlgorithmType::Statistical).await.unwrap().is_trained());
+        let info = &factory.model_registry[&AlgorithmType::Statistical];
+        assert_eq!(info.version, Some(stored.version));
+        assert_eq!(info.model_size, Some(stored.model_size_bytes));
+        assert_eq!(info.performance_metrics.accuracy, 0.4);
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ensemble_predict_with_learned_weights() {
+        let factory = AlgorithmFactory::new(LotteryType::Ssq);
+        let drawings: Vec<LotteryDrawing> = (0..40u32)
+            .map(|i| create_drawing(
+                LotteryType::Ssq,
+                i,
+                (0..6).map(|k| (i * 5 + k * 5) % 33 + 1).collect(),
+                Some(vec![i % 4 + 1]),
+            ))
+            .collect();
+        let extractor = crate::lottery::algorithms::feature_engineering::LotteryFeatureExtractor;
+        let training_data = TrainingData {
+            features: (1..drawings.len())
+                .map(|i| extractor.extract_single_features(&drawings[i], &drawings[..i]).unwrap())
+                .collect(),
+            targets: drawings[1..].iter().map(|d| d.winning_numbers.clone()).collect(),
+            special_targets: Some(drawings[1..].iter().map(|d| d.special_numbers.clone().unwrap()).collect()),
+            weights: None,
+        };
+        let (train_set, validation_set) = training_data.split_at(30);
+
+        let algorithms = [AlgorithmType::Statistical, AlgorithmType::RandomForest];
+        for algorithm in algorithms {
+            let mut model = factory.create_algorithm(algorithm, AlgorithmConfig::default()).unwrap();
+            model.train(&train_set, &AlgorithmConfig::default()).await.unwrap();
+            factory.trained_models.write().await.insert(algorithm, Arc::from(model));
+        }
+
+        let weights = factory
+            .fit_ensemble_weights(&algorithms, &validation_set, WeightObjective::LogLikelihood)
+            .await
+            .unwrap();
+        assert!((weights.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(factory.ensemble_weights.read().await.for_game(&LotteryType::Ssq), &weights);
+
+        let input = PredictionInput {
+            lottery_type: LotteryType::Ssq,
+            historical_data: drawings,
+            target_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            additional_features: None,
+        };
+        let output = factory.ensemble_predict(&algorithms, &input).await.unwrap();
+        assert_eq!(output.predicted_numbers.len(), 6);
+        assert_eq!(output.confidence_scores.len(), 6);
+        assert_eq!(output.predicted_special_numbers.map(|s| s.len()), Some(1));
+        assert!(output.algorithm_metadata.contains_key("ensemble_weights"));
+        assert_eq!(output.algorithm_metadata["algorithms"], serde_json::json!("statistical,random_forest"));
+
+        let probabilities = output.probabilities.unwrap();
+        assert_eq!(probabilities.main.len(), 33);
+        assert_eq!(probabilities.special.map(|s| s.len()), Some(16));
+    }
+}
@@ -1,14 +1,25 @@
 use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::{self, NumberProbabilities};
+use crate::lottery::algorithms::metrics::MetricsCalculator;
 use crate::lottery::errors::LotteryResult as Result;
-use crate::lottery::models::{LotteryDrawing, LotteryType};
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryType};
 use async_trait::async_trait;
-use ndarray::{Array1, Array2, s};
 use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, Normal};
 use std::collections::HashMap;
-use uuid;
-use rand::prelude::SliceRandom;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InformationCriterion {
+    #[default]
+    Aic,
+    Bic,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ArimaConfig {
     pub p: usize,  // AR order
     pub d: usize,  // differencing order
@@ -21,6 +32,9 @@ pub struct ArimaConfig {
     pub confidence_level: f64,
     pub max_iterations: usize,
     pub tolerance: f64,
+    // 为 true 时上面的各阶数作为搜索上限，按信息准则自动定阶
+    pub auto_order: bool,
+    pub information_criterion: InformationCriterion,
 }
 
 impl Default for ArimaConfig {
@@ -37,100 +51,335 @@ impl Default for ArimaConfig {
             confidence_level: 0.95,
             max_iterations: 1000,
             tolerance: 1e-6,
+            auto_order: true,
+            information_criterion: InformationCriterion::Aic,
+        }
+    }
+}
+
+impl ModelConfig for ArimaConfig {
+    fn validate(&self) -> Result<()> {
+        ensure_config(self.forecast_horizon > 0, "forecast_horizon must be positive")?;
+        ensure_config(
+            self.confidence_level > 0.0 && self.confidence_level < 1.0,
+            "confidence_level must be in (0, 1)",
+        )?;
+        ensure_config(self.max_iterations > 0, "max_iterations must be positive")?;
+        ensure_config(self.tolerance > 0.0 && self.tolerance.is_finite(), "tolerance must be positive")
+    }
+}
+
+// (p,d,q)(P,D,Q)s
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SarimaOrder {
+    pub p: usize,
+    pub d: usize,
+    pub q: usize,
+    pub seasonal_p: usize,
+    pub seasonal_d: usize,
+    pub seasonal_q: usize,
+    pub seasonal_period: usize,
+}
+
+impl SarimaOrder {
+    pub fn from_config(config: &ArimaConfig) -> Self {
+        // 周期小于 2 时没有季节项
+        let seasonal = config.seasonal_period >= 2;
+        Self {
+            p: config.p,
+            d: config.d,
+            q: config.q,
+            seasonal_p: if seasonal { config.seasonal_p } else { 0 },
+            seasonal_d: if seasonal { config.seasonal_d } else { 0 },
+            seasonal_q: if seasonal { config.seasonal_q } else { 0 },
+            seasonal_period: if seasonal { config.seasonal_period } else { 0 },
         }
     }
+
+    // 均值 + 各 AR/MA 系数
+    pub fn parameter_count(&self) -> usize {
+        1 + self.p + self.q + self.seasonal_p + self.seasonal_q
+    }
+
+    // 展开后 AR 多项式的阶数，即计算一个残差需要的历史长度
+    pub fn max_lag(&self) -> usize {
+        self.p + self.d + (self.seasonal_p + self.seasonal_d) * self.seasonal_period
+    }
+
+    fn difference_operator(&self) -> Vec<f64> {
+        let mut operator = vec![1.0];
+        for _ in 0..self.d {
+            operator = multiply(&operator, &[1.0, -1.0]);
+        }
+        for _ in 0..self.seasonal_d {
+            operator = multiply(&operator, &lag_polynomial(&[1.0], self.seasonal_period, -1.0));
+        }
+        operator
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Forecast {
+    pub mean: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+// 单条序列上的 SARIMA 拟合结果，参数用条件平方和 (CSS) 估计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SarimaFit {
+    pub order: SarimaOrder,
+    // 差分后序列的均值
+    pub mean: f64,
+    pub ar: Vec<f64>,
+    pub ma: Vec<f64>,
+    pub seasonal_ar: Vec<f64>,
+    pub seasonal_ma: Vec<f64>,
+    pub sigma_squared: f64,
+    pub log_likelihood: f64,
+    pub aic: f64,
+    pub bic: f64,
+    pub n_obs: usize,
+}
+
+impl SarimaFit {
+    fn from_parameters(order: SarimaOrder, parameters: &[f64]) -> Self {
+        let mut rest = parameters[1..].iter().copied();
+        let mut take = |n: usize| rest.by_ref().take(n).collect::<Vec<f64>>();
+        let ar = take(order.p);
+        let ma = take(order.q);
+        let seasonal_ar = take(order.seasonal_p);
+        let seasonal_ma = take(order.seasonal_q);
+
+        Self {
+            order,
+            mean: parameters[0],
+            ar,
+            ma,
+            seasonal_ar,
+            seasonal_ma,
+            ..Default::default()
+        }
+    }
+
+    // 化为原序列上的差分方程：y_t = constant + Σ a_k y_{t-k} + e_t + Σ b_k e_{t-k}
+    fn expanded(&self) -> (f64, Vec<f64>, Vec<f64>) {
+        let period = self.order.seasonal_period;
+        let ar_operator = multiply(
+            &lag_polynomial(&self.ar, 1, -1.0),
+            &lag_polynomial(&self.seasonal_ar, period, -1.0),
+        );
+        let constant = self.mean * ar_operator.iter().sum::<f64>();
+
+        let full_ar = multiply(&ar_operator, &self.order.difference_operator());
+        let ma_operator = multiply(
+            &lag_polynomial(&self.ma, 1, 1.0),
+            &lag_polynomial(&self.seasonal_ma, period, 1.0),
+        );
+
+        (
+            constant,
+            full_ar[1..].iter().map(|c| -c).collect(),
+            ma_operator[1..].to_vec(),
+        )
+    }
+
+    // 从 start 起逐期计算一步预测误差，之前的误差按 0 处理
+    fn errors(&self, series: &[f64], start: usize) -> Vec<f64> {
+        let (constant, ar, ma) = self.expanded();
+        let start = start.max(ar.len());
+        let mut errors = vec![0.0; series.len()];
+
+        for t in start..series.len() {
+            let mut value = constant;
+            for (k, &coefficient) in ar.iter().enumerate() {
+                value += coefficient * series[t - 1 - k];
+            }
+            for (k, &coefficient) in ma.iter().enumerate().take(t) {
+                value += coefficient * errors[t - 1 - k];
+            }
+            errors[t] = series[t] - value;
+        }
+
+        errors
+    }
+
+    pub fn residuals(&self, series: &[f64], start: usize) -> Vec<f64> {
+        let start = start.max(self.order.max_lag()).min(series.len());
+        self.errors(series, start)[start..].to_vec()
+    }
+
+    // start 之前的观测只作条件，不计入平方和；候选模型取相同的 start 才能比较信息准则
+    pub fn fit(
+        series: &[f64],
+        order: SarimaOrder,
+        start: usize,
+        initial: &[f64],
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> Option<Self> {
+        let start = start.max(order.max_lag());
+        let n_obs = series.len().saturating_sub(start);
+        let n_parameters = order.parameter_count();
+        if n_obs <= n_parameters + 1 || initial.len() != n_parameters {
+            return None;
+        }
+
+        // 系数限制在 (-1, 1) 内，避免发散或不可逆的解
+        let css = |parameters: &[f64]| {
+            if parameters[1..].iter().any(|c| c.abs() >= 1.0) {
+                return f64::INFINITY;
+            }
+            SarimaFit::from_parameters(order, parameters)
+                .residuals(series, start)
+                .iter()
+                .map(|e| e * e)
+                .sum::<f64>()
+        };
+
+        let (parameters, sum_of_squares) = nelder_mead(css, initial, max_iterations, tolerance);
+        if !sum_of_squares.is_finite() {
+            return None;
+        }
+
+        let n = n_obs as f64;
+        let sigma_squared = (sum_of_squares / n).max(1e-8);
+        let log_likelihood = -0.5 * n * ((2.0 * std::f64::consts::PI * sigma_squared).ln() + 1.0);
+        // 方差也算一个参数
+        let k = (n_parameters + 1) as f64;
+
+        Some(Self {
+            sigma_squared,
+            log_likelihood,
+            aic: -2.0 * log_likelihood + 2.0 * k,
+            bic: -2.0 * log_likelihood + k * n.ln(),
+            n_obs,
+            ..Self::from_parameters(order, &parameters)
+        })
+    }
+
+    pub fn criterion(&self, criterion: InformationCriterion) -> f64 {
+        match criterion {
+            InformationCriterion::Aic => self.aic,
+            InformationCriterion::Bic => self.bic,
+        }
+    }
+
+    // 展开 AR 多项式的阶数，预测前至少需要这么多期历史
+    pub fn warmup(&self) -> usize {
+        self.order.max_lag()
+    }
+
+    // 未来的扰动取 0 得到点预测；区间由 MA(∞) 表示的 ψ 权重累积方差给出
+    pub fn forecast(&self, series: &[f64], steps: usize, confidence_level: f64) -> Vec<Forecast> {
+        let (constant, ar, ma) = self.expanded();
+        let mut history = series.to_vec();
+        let mut errors = self.errors(series, ar.len());
+
+        let psi = psi_weights(&ar, &ma, steps);
+        let z = Normal::standard().inverse_cdf(0.5 + confidence_level.clamp(0.0, 0.999_999) / 2.0);
+        let mut variance = 0.0;
+        let mut forecasts = Vec::with_capacity(steps);
+
+        for weight in psi {
+            let t = history.len();
+            let mut value = constant;
+            for (k, &coefficient) in ar.iter().enumerate().take(t) {
+                value += coefficient * history[t - 1 - k];
+            }
+            for (k, &coefficient) in ma.iter().enumerate().take(t) {
+                value += coefficient * errors[t - 1 - k];
+            }
+            history.push(value);
+            errors.push(0.0);
+
+            variance += self.sigma_squared * weight * weight;
+            let half_width = z * variance.sqrt();
+            forecasts.push(Forecast { mean: value, lower: value - half_width, upper: value + half_width });
+        }
+
+        forecasts
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArimaModel {
     pub config: ArimaConfig,
-    pub ar_coefficients: Vec<f64>,
-    pub ma_coefficients: Vec<f64>,
-    pub seasonal_ar_coefficients: Vec<f64>,
-    pub seasonal_ma_coefficients: Vec<f64>,
-    pub intercept: f64,
-    pub sigma_squared: f64,
+    #[serde(default)]
+    pub base_config: ArimaConfig,
+    // 所有号码共用的阶数
+    pub order: SarimaOrder,
+    // 每个号码出现与否 (0/1) 序列上的模型，下标为号码相对号码池起点的偏移
+    pub number_models: Vec<SarimaFit>,
+    #[serde(default)]
+    pub special_models: Vec<SarimaFit>,
     pub is_trained: bool,
     pub lottery_type: LotteryType,
-    pub fitted_values: Vec<f64>,
-    pub residuals: Vec<f64>,
+    // 各号码模型的信息准则之和
     pub aic: f64,
     pub bic: f64,
 }
 
 impl ArimaModel {
     pub fn new(config: ArimaConfig, lottery_type: LotteryType) -> Self {
+        let order = SarimaOrder::from_config(&config);
         Self {
+            base_config: config.clone(),
             config,
-            ar_coefficients: Vec::new(),
-            ma_coefficients: Vec::new(),
-            seasonal_ar_coefficients: Vec::new(),
-            seasonal_ma_coefficients: Vec::new(),
-            intercept: 0.0,
-            sigma_squared: 1.0,
+            order,
+            number_models: Vec::new(),
+            special_models: Vec::new(),
             is_trained: false,
             lottery_type,
-            fitted_values: Vec::new(),
-            residuals: Vec::new(),
             aic: f64::INFINITY,
             bic: f64::INFINITY,
         }
     }
 
-    fn prepare_time_series(&self, drawings: &[LotteryDrawing]) -> Vec<f64> {
-        // 提取时间序列特征：使用每个开奖日期的号码总和
-        drawings.iter()
-            .map(|d| d.winning_numbers.iter().sum::<u32>() as f64)
+    // 每个号码一条序列，第 t 期开出为 1，否则为 0
+    fn appearance_series(&self, draws: &[Vec<u32>], numbers: RangeInclusive<u32>) -> Vec<Vec<f64>> {
+        numbers
+            .map(|number| {
+                draws.iter()
+                    .map(|draw| if draw.contains(&number) { 1.0 } else { 0.0 })
+                    .collect()
+            })
             .collect()
     }
 
     fn difference(&self, series: &[f64], order: usize) -> Vec<f64> {
         let mut result = series.to_vec();
-        
+
         for _ in 0..order {
             if result.len() <= 1 {
                 return vec![0.0];
             }
-            
+
             let mut diff = Vec::new();
             for i in 1..result.len() {
                 diff.push(result[i] - result[i-1]);
             }
             result = diff;
         }
-        
+
         result
     }
 
     fn seasonal_difference(&self, series: &[f64], period: usize, order: usize) -> Vec<f64> {
         let mut result = series.to_vec();
-        
+
         for _ in 0..order {
             if result.len() <= period {
                 return vec![0.0];
             }
-            
+
             let mut diff = Vec::new();
             for i in period..result.len() {
                 diff.push(result[i] - result[i-period]);
             }
             result = diff;
         }
-        
-        result
-    }
 
-    fn inverse_difference(&self, original: &[f64], differenced: &[f64], order: usize) -> Vec<f64> {
-        let mut result = differenced.to_vec();
-        
-        for _ in 0..order {
-            let mut restored = vec![original[original.len() - result.len() - 1]];
-            for i in 0..result.len() {
-                restored.push(restored[i] + result[i]);
-            }
-            result = restored[1..].to_vec();
-        }
-        
         result
     }
 
@@ -138,9 +387,9 @@ impl ArimaModel {
         let n = series.len();
         let mean = series.iter().sum::<f64>() / n as f64;
         let variance = series.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64;
-        
+
         let mut acf_values = Vec::new();
-        
+
         for lag in 0..=max_lag {
             let mut cov = 0.0;
             for i in 0..(n - lag) {
@@ -149,391 +398,231 @@ impl ArimaModel {
             cov /= n as f64;
             acf_values.push(cov / variance);
         }
-        
+
         acf_values
     }
 
-    fn pacf(&self, series: &[f64], max_lag: usize) -> Vec<f64> {
-        let n = series.len();
-        let mut pacf_values = Vec::new();
-        
-        for k in 1..=max_lag {
-            let mut phi = vec![0.0; k];
-            let mut r = vec![0.0; k];
-            
-            // 计算自相关
-            for j in 1..=k {
-                let mut sum = 0.0;
-                for t in j..n {
-                    sum += series[t] * series[t - j];
-                }
-                r[j-1] = sum / n as f64;
-            }
-            
-            // 解Yule-Walker方程
-            if k == 1 {
-                phi[0] = r[0] / (series.iter().map(|x| x * x).sum::<f64>() / n as f64);
-            } else {
-                // 使用Durbin-Levinson算法
-                let mut r_matrix = Array2::zeros((k, k));
-                for i in 0..k {
-                    for j in 0..k {
-                        r_matrix[[i, j]] = r[(i.abs_diff(j)) as usize];
-                    }
-                }
-                
-                if let Some(inv) = self.matrix_inverse(&r_matrix) {
-                    let r_vec = Array1::from(r.clone());
-                    let phi_vec = inv.dot(&r_vec);
-                    phi = phi_vec.to_vec();
-                }
-            }
-            
-            pacf_values.push(phi[k-1]);
-        }
-        
-        pacf_values
+    fn differenced(&self, series: &[f64], order: &SarimaOrder) -> Vec<f64> {
+        let series = self.difference(series, order.d);
+        self.seasonal_difference(&series, order.seasonal_period, order.seasonal_d)
     }
 
-    fn matrix_inverse(&self, matrix: &Array2<f64>) -> Option<Array2<f64>> {
-        let (n, m) = matrix.dim();
-        if n != m {
-            return None;
-        }
-
-        let mut augmented = Array2::zeros((n, 2 * n));
-        augmented.slice_mut(s![.., ..n]).assign(matrix);
-        
-        for i in 0..n {
-            augmented[[i, n + i]] = 1.0;
-        }
-
-        // Gaussian elimination
-        for i in 0..n {
-            let pivot = augmented[[i, i]];
-            if pivot.abs() < 1e-10 {
-                return None;
-            }
-
-            for j in 0..(2 * n) {
-                augmented[[i, j]] /= pivot;
-            }
+    // 只有差分能让总方差至少减半时才采用更高的阶 (相当于滞后一期自相关高于 0.75)，
+    // 避免把平稳但自相关较强的序列过度差分
+    fn select_differencing(&self, panel: &[Vec<f64>], max: &SarimaOrder) -> (usize, usize) {
+        let total_variance = |d: usize, seasonal_d: usize| -> f64 {
+            let order = SarimaOrder { d, seasonal_d, ..*max };
+            panel.iter()
+                .map(|series| {
+                    let differenced = self.differenced(series, &order);
+                    let mean = differenced.iter().sum::<f64>() / differenced.len() as f64;
+                    differenced.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / differenced.len() as f64
+                })
+                .sum()
+        };
 
-            for k in 0..n {
-                if k != i {
-                    let factor = augmented[[k, i]];
-                    for j in 0..(2 * n) {
-                        augmented[[k, j]] -= factor * augmented[[i, j]];
-                    }
+        let mut best = (0, 0);
+        let mut best_variance = total_variance(0, 0);
+        for seasonal_d in 0..=max.seasonal_d {
+            for d in 0..=max.d {
+                let variance = total_variance(d, seasonal_d);
+                if variance < 0.5 * best_variance {
+                    best = (d, seasonal_d);
+                    best_variance = variance;
                 }
             }
         }
-
-        Some(augmented.slice(s![.., n..]).to_owned())
+        best
     }
 
-    fn estimate_ar_coefficients(&self, series: &[f64], p: usize) -> Vec<f64> {
-        let n = series.len();
-        if n <= p {
-            return vec![0.0; p];
+    fn candidate_orders(&self, panel: &[Vec<f64>]) -> Vec<SarimaOrder> {
+        let max = SarimaOrder::from_config(&self.config);
+        if !self.config.auto_order {
+            return vec![max];
         }
 
-        let mut y = Array1::zeros(n - p);
-        let mut x = Array2::zeros((n - p, p));
-
-        for i in 0..(n - p) {
-            y[i] = series[i + p];
-            for j in 0..p {
-                x[[i, j]] = series[i + p - 1 - j];
+        let (d, seasonal_d) = self.select_differencing(panel, &max);
+        let mut orders = Vec::new();
+        for seasonal_p in 0..=max.seasonal_p {
+            for seasonal_q in 0..=max.seasonal_q {
+                for p in 0..=max.p {
+                    for q in 0..=max.q {
+                        orders.push(SarimaOrder { p, d, q, seasonal_p, seasonal_d, seasonal_q, ..max });
+                    }
+                }
             }
         }
-
-        // Solve using least squares
-        let x_t = x.t();
-        let x_t_x = x_t.dot(&x);
-        let x_t_y = x_t.dot(&y);
-
-        if let Some(inv) = self.matrix_inverse(&x_t_x) {
-            let coefficients = inv.dot(&x_t_y);
-            coefficients.to_vec()
-        } else {
-            vec![0.0; p]
-        }
+        orders
     }
 
-    fn estimate_ma_coefficients(&self, residuals: &[f64], q: usize) -> Vec<f64> {
-        let n = residuals.len();
-        if n <= q {
-            return vec![0.0; q];
-        }
-
-        let acf = self.acf(residuals, q);
-        let mut ma_coefficients = vec![0.0; q];
-
-        // Use innovations algorithm for MA parameter estimation
-        let mut psi = vec![0.0; q + 1];
-        let mut v = vec![acf[0]];
-
-        for k in 1..=q {
-            psi[k] = acf[k];
-            for j in 1..k {
-                psi[k] -= psi[j] * v[k - j] * psi[k - j];
-            }
-            psi[k] /= v[0];
-            
-            let mut new_v = acf[0];
-            for j in 1..=k {
-                new_v -= psi[j].powi(2) * v[k - j];
-            }
-            v.push(new_v);
-        }
-
-        for i in 1..=q {
-            ma_coefficients[i - 1] = psi[i];
-        }
+    // 均值取差分后序列的均值，AR 初值取对应滞后的自相关
+    fn initial_parameters(&self, series: &[f64], order: &SarimaOrder) -> Vec<f64> {
+        let differenced = self.differenced(series, order);
+        let mean = differenced.iter().sum::<f64>() / differenced.len().max(1) as f64;
+        let period = order.seasonal_period.max(1);
+        let constant = differenced.iter().all(|&x| (x - mean).abs() < 1e-12);
+        let acf = if constant || differenced.len() <= period + 1 {
+            vec![0.0; period + 1]
+        } else {
+            self.acf(&differenced, period)
+        };
 
-        ma_coefficients
+        let mut parameters = vec![mean];
+        parameters.extend((0..order.p).map(|i| if i == 0 { acf[1].clamp(-0.5, 0.5) } else { 0.0 }));
+        parameters.extend(std::iter::repeat_n(0.0, order.q));
+        parameters.extend((0..order.seasonal_p).map(|i| if i == 0 { acf[period].clamp(-0.5, 0.5) } else { 0.0 }));
+        parameters.extend(std::iter::repeat_n(0.0, order.seasonal_q));
+        parameters
     }
 
-    fn fit_model(&mut self, series: &[f64]) -> Result<f64> {
-        let mut processed_series = series.to_vec();
-
-        // Apply differencing
-        if self.config.d > 0 {
-            processed_series = self.difference(&processed_series, self.config.d);
-        }
-
-        if self.config.seasonal_d > 0 {
-            processed_series = self.seasonal_difference(
-                &processed_series, 
-                self.config.seasonal_period, 
-                self.config.seasonal_d
-            );
-        }
-
-        if processed_series.len() < self.config.p + self.config.q + 10 {
-            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
-                "Insufficient data for ARIMA model".to_string()
-            ));
-        }
-
-        // Estimate AR coefficients
-        self.ar_coefficients = self.estimate_ar_coefficients(&processed_series, self.config.p);
-
-        // Estimate MA coefficients
-        let mut residuals = vec![0.0; processed_series.len()];
-        for i in 0..processed_series.len() {
-            let mut ar_part = 0.0;
-            for (j, &coeff) in self.ar_coefficients.iter().enumerate() {
-                if i > j {
-                    ar_part += coeff * processed_series[i - 1 - j];
-                }
-            }
-            residuals[i] = processed_series[i] - ar_part;
-        }
-
-        self.ma_coefficients = self.estimate_ma_coefficients(&residuals, self.config.q);
-
-        // Estimate seasonal coefficients (simplified)
-        self.seasonal_ar_coefficients = vec![0.0; self.config.seasonal_p];
-        self.seasonal_ma_coefficients = vec![0.0; self.config.seasonal_q];
-
-        // Calculate intercept
-        self.intercept = processed_series.iter().sum::<f64>() / processed_series.len() as f64;
-
-        // Calculate residuals and sigma_squared
-        self.residuals = self.calculate_residuals(&processed_series);
-        self.sigma_squared = self.residuals.iter().map(|x| x * x).sum::<f64>() / self.residuals.len() as f64;
-
-        // Calculate AIC and BIC
-        let n = series.len() as f64;
-        let k = (self.config.p + self.config.q + self.config.seasonal_p + self.config.seasonal_q) as f64;
-        
-        self.aic = n * self.sigma_squared.ln() + 2.0 * k;
-        self.bic = n * self.sigma_squared.ln() + k * n.ln();
-
-        self.fitted_values = self.generate_fitted_values(series);
-
-        // Calculate accuracy based on fitted values
-        let accuracy = self.calculate_accuracy(series);
-
-        Ok(accuracy)
+    fn fit_panel(&self, panel: &[Vec<f64>], order: SarimaOrder, start: usize) -> Option<Vec<SarimaFit>> {
+        panel.iter()
+            .map(|series| {
+                SarimaFit::fit(
+                    series,
+                    order,
+                    start,
+                    &self.initial_parameters(series, &order),
+                    self.config.max_iterations,
+                    self.config.tolerance,
+                )
+            })
+            .collect()
     }
 
-    fn calculate_residuals(&self, series: &[f64]) -> Vec<f64> {
-        let mut residuals = vec![0.0; series.len()];
-        let mut fitted = vec![self.intercept; series.len()];
+    // 所有号码共用一组阶数，按各号码信息准则之和选择
+    fn select_order(&self, panel: &[Vec<f64>]) -> Result<(SarimaOrder, Vec<SarimaFit>)> {
+        let candidates = self.candidate_orders(panel);
+        let start = candidates.iter().map(SarimaOrder::max_lag).max().unwrap_or(0);
+        let criterion = self.config.information_criterion;
 
-        for i in 0..series.len() {
-            // AR part
-            let mut ar_part = 0.0;
-            for (j, &coeff) in self.ar_coefficients.iter().enumerate() {
-                if i > j {
-                    ar_part += coeff * series[i - 1 - j];
-                }
-            }
-
-            // MA part (simplified)
-            let mut ma_part = 0.0;
-            for (j, &coeff) in self.ma_coefficients.iter().enumerate() {
-                if i > j {
-                    ma_part += coeff * residuals[i - 1 - j];
-                }
+        let mut best: Option<(f64, SarimaOrder, Vec<SarimaFit>)> = None;
+        for order in candidates {
+            let Some(fits) = self.fit_panel(panel, order, start) else {
+                continue;
+            };
+            let score: f64 = fits.iter().map(|fit| fit.criterion(criterion)).sum();
+            if best.as_ref().is_none_or(|(best_score, _, _)| score < *best_score) {
+                best = Some((score, order, fits));
             }
-
-            fitted[i] = self.intercept + ar_part + ma_part;
-            residuals[i] = series[i] - fitted[i];
         }
 
-        residuals
+        best.map(|(_, order, fits)| (order, fits))
+            .ok_or_else(|| crate::lottery::errors::LotteryError::AlgorithmError(
+                "Insufficient data for ARIMA model".to_string()
+            ))
     }
 
-    fn generate_fitted_values(&self, original_series: &[f64]) -> Vec<f64> {
-        let mut fitted = vec![0.0; original_series.len()];
-        let mut residuals = vec![0.0; original_series.len()];
-
-        // Apply transformations
-        let mut series = original_series.to_vec();
-        
-        if self.config.d > 0 {
-            series = self.difference(&series, self.config.d);
-        }
-
-        if self.config.seasonal_d > 0 {
-            series = self.seasonal_difference(
-                &series, 
-                self.config.seasonal_period, 
-                self.config.seasonal_d
-            );
-        }
-
-        // Generate fitted values for transformed series
-        for i in 0..series.len() {
-            let mut value = self.intercept;
-
-            // AR contribution
-            for (j, &coeff) in self.ar_coefficients.iter().enumerate() {
-                if i > j {
-                    value += coeff * series[i - 1 - j];
-                }
-            }
-
-            // MA contribution
-            for (j, &coeff) in self.ma_coefficients.iter().enumerate() {
-                if i > j {
-                    value += coeff * residuals[i - 1 - j];
-                }
-            }
-
-            residuals[i] = series[i] - value;
-            fitted[i] = value;
-        }
-
-        // Inverse transform
-        if self.config.d > 0 || self.config.seasonal_d > 0 {
-            fitted = self.inverse_difference(original_series, &fitted, self.config.d);
-        }
-
-        fitted
+    fn warmup(&self) -> usize {
+        self.number_models.iter().map(SarimaFit::warmup).max().unwrap_or(0).max(1)
     }
 
-    fn forecast(&self, series: &[f64], steps: usize) -> Vec<f64> {
-        let mut forecasts = vec![0.0; steps];
-        let mut extended_series = series.to_vec();
-
-        // Apply transformations
-        let mut processed_series = extended_series.clone();
-        
-        if self.config.d > 0 {
-            processed_series = self.difference(&processed_series, self.config.d);
-        }
+    fn forecast_numbers(&self, models: &[SarimaFit], draws: &[Vec<u32>], numbers: RangeInclusive<u32>) -> Vec<Forecast> {
+        models.iter()
+            .zip(self.appearance_series(draws, numbers))
+            .map(|(model, series)| model.forecast(&series, 1, self.config.confidence_level)[0])
+            .collect()
+    }
+}
 
-        if self.config.seasonal_d > 0 {
-            processed_series = self.seasonal_difference(
-                &processed_series, 
-                self.config.seasonal_period, 
-                self.config.seasonal_d
-            );
+// 多项式乘法，系数按 B 的幂次升序
+fn multiply(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            product[i + j] += x * y;
         }
+    }
+    product
+}
 
-        // Generate forecasts
-        for step in 0..steps {
-            let mut forecast = self.intercept;
-
-            // AR contribution
-            for (j, &coeff) in self.ar_coefficients.iter().enumerate() {
-                let idx = processed_series.len() - j - 1;
-                if idx < processed_series.len() {
-                    forecast += coeff * processed_series[idx];
-                }
-            }
-
-            forecasts[step] = forecast;
-            processed_series.push(forecast);
-            extended_series.push(0.0); // placeholder
-        }
+// 1 + sign * Σ c_i B^{i * step}
+fn lag_polynomial(coefficients: &[f64], step: usize, sign: f64) -> Vec<f64> {
+    let mut polynomial = vec![0.0; coefficients.len() * step + 1];
+    polynomial[0] = 1.0;
+    for (i, &coefficient) in coefficients.iter().enumerate() {
+        polynomial[(i + 1) * step] += sign * coefficient;
+    }
+    polynomial
+}
 
-        // Inverse transform
-        if self.config.d > 0 || self.config.seasonal_d > 0 {
-            forecasts = self.inverse_difference(series, &forecasts, self.config.d);
+fn psi_weights(ar: &[f64], ma: &[f64], steps: usize) -> Vec<f64> {
+    let mut psi: Vec<f64> = Vec::with_capacity(steps);
+    for j in 0..steps {
+        let mut weight = if j == 0 { 1.0 } else { ma.get(j - 1).copied().unwrap_or(0.0) };
+        for (k, &coefficient) in ar.iter().enumerate().take(j) {
+            weight += coefficient * psi[j - 1 - k];
         }
+        psi.push(weight);
+    }
+    psi
+}
 
-        forecasts
+// 无导数的单纯形法，约束边界处 CSS 取无穷大也能处理
+fn nelder_mead(
+    objective: impl Fn(&[f64]) -> f64,
+    initial: &[f64],
+    max_iterations: usize,
+    tolerance: f64,
+) -> (Vec<f64>, f64) {
+    let n = initial.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+    simplex.push((initial.to_vec(), objective(initial)));
+    for i in 0..n {
+        let mut point = initial.to_vec();
+        point[i] += 0.1 * point[i].abs().max(1.0);
+        let value = objective(&point);
+        simplex.push((point, value));
     }
 
-    fn calculate_accuracy(&self, actual: &[f64]) -> f64 {
-        if actual.len() != self.fitted_values.len() {
-            return 0.0;
+    for _ in 0..max_iterations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let best = simplex[0].1;
+        let worst = simplex[n].1;
+        if (worst - best).abs() <= tolerance * (best.abs() + tolerance) {
+            break;
         }
 
-        let mut sum_abs_error = 0.0;
-        let mut sum_actual = 0.0;
-
-        for (i, &actual_value) in actual.iter().enumerate() {
-            let fitted_value = self.fitted_values[i];
-            sum_abs_error += (actual_value - fitted_value).abs();
-            sum_actual += actual_value;
-        }
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|(point, _)| point[j]).sum::<f64>() / n as f64)
+            .collect();
+        let worst_point = simplex[n].0.clone();
+        let along = |scale: f64| -> Vec<f64> {
+            centroid.iter().zip(&worst_point).map(|(c, w)| c + scale * (w - c)).collect()
+        };
 
-        if sum_actual > 0.0 {
-            1.0 - (sum_abs_error / sum_actual)
+        let reflected = along(-1.0);
+        let reflected_value = objective(&reflected);
+        if reflected_value < best {
+            let expanded = along(-2.0);
+            let expanded_value = objective(&expanded);
+            simplex[n] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
         } else {
-            0.0
+            let contracted = if reflected_value < worst { along(-0.5) } else { along(0.5) };
+            let contracted_value = objective(&contracted);
+            if contracted_value < reflected_value.min(worst) {
+                simplex[n] = (contracted, contracted_value);
+            } else {
+                let best_point = simplex[0].0.clone();
+                for (point, value) in simplex.iter_mut().skip(1) {
+                    for (x, b) in point.iter_mut().zip(&best_point) {
+                        *x = b + 0.5 * (*x - b);
+                    }
+                    *value = objective(point);
+                }
+            }
         }
     }
 
-    fn get_lottery_numbers(&self, forecast: f64, lottery_type: &LotteryType) -> Vec<u32> {
-        let base_number = forecast.round() as u32;
-        let max_number = match lottery_type {
-            LotteryType::Ssq => 33,
-            LotteryType::Dlt => 35,
-            LotteryType::Fc3d => 9,
-            LotteryType::Pl3 => 9,
-            LotteryType::Pl5 => 9,
-            LotteryType::Custom => 49,
-        };
-
-        let _numbers: Vec<u32> = Vec::new();
-        let mut rng = rand::thread_rng();
-        
-        // Generate numbers around the forecast value
-        let start = base_number.saturating_sub(10).max(1);
-        let end = (base_number + 10).min(max_number);
-        
-        let mut candidates: Vec<u32> = (start..=end).collect();
-                candidates.shuffle(&mut rng);
-        
-        let count = match lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
-
-        candidates.into_iter().take(count).collect()
-    }
+    simplex.into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or_default()
 }
 
 #[async_trait]
@@ -542,54 +631,50 @@ impl PredictionAlgorithm for ArimaModel {
         "ARIMA Time Series".to_string()
     }
 
-    fn algorithm_type(&self) -> String {
-        "arima".to_string()
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::Arima
     }
 
     async fn train(
         &mut self,
         training_data: &TrainingData,
-        _config: &AlgorithmConfig,
+        config: &AlgorithmConfig,
     ) -> Result<f64> {
+        self.config = config.apply(&self.base_config)?;
+
         if training_data.features.is_empty() || training_data.targets.is_empty() {
             return Err(crate::lottery::errors::LotteryError::InvalidParameter(
                 "No training data provided".to_string()
             ));
         }
 
-        // Convert training data to historical drawings
-        let historical_data = training_data.targets.iter().enumerate().map(|(i, target)| {
-            LotteryDrawing {
-                id: uuid::Uuid::new_v4(),
-                lottery_type: _config.lottery_type.clone(),
-                draw_number: format!("{:04}", i + 1),
-                draw_date: chrono::Utc::now().naive_utc().date(),
-                draw_time: None,
-                winning_numbers: target.clone(),
-                special_numbers: None,
-                jackpot_amount: None,
-                sales_amount: None,
-                prize_distribution: None,
-                data_source: "training".to_string(),
-                verification_status: "verified".to_string(),
-                metadata: None,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-                crawled_at: None,
-            }
-        }).collect::<Vec<_>>();
-
-        if historical_data.len() < 50 {
+        if training_data.targets.len() < 50 {
             return Err(crate::lottery::errors::LotteryError::AlgorithmError(
                 "Insufficient data for ARIMA training".to_string()
             ));
         }
 
-        let series = self.prepare_time_series(&historical_data);
-        let accuracy = self.fit_model(&series)?;
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let panel = self.appearance_series(&training_data.targets, spec.main_numbers());
+        let (order, number_models) = self.select_order(&panel)?;
+
+        // 特别号沿用主号码选出的阶数
+        self.special_models = match (spec.special_numbers(), &training_data.special_targets) {
+            (Some(numbers), Some(special_targets)) => {
+                let panel = self.appearance_series(special_targets, numbers);
+                self.fit_panel(&panel, order, order.max_lag()).unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
 
+        self.aic = number_models.iter().map(|fit| fit.aic).sum();
+        self.bic = number_models.iter().map(|fit| fit.bic).sum();
+        self.order = order;
+        self.number_models = number_models;
         self.is_trained = true;
-        Ok(accuracy)
+
+        let metrics = self.evaluate(training_data).await?;
+        Ok(metrics.accuracy)
     }
 
 
@@ -605,36 +690,48 @@ impl PredictionAlgorithm for ArimaModel {
 
         let start_time = std::time::Instant::now();
 
-        let series = self.prepare_time_series(&input.historical_data);
-        let forecasts = self.forecast(&series, self.config.forecast_horizon);
-        
-        let predicted_numbers = self.get_lottery_numbers(forecasts[0], &input.lottery_type);
-
-        let special_count = match input.lottery_type {
-            LotteryType::Ssq => 1,
-            LotteryType::Dlt => 2,
-            _ => 0,
-        };
-
-        let predicted_special_numbers = if special_count > 0 {
-            let special_max = match input.lottery_type {
-                LotteryType::Ssq => 16,
-                LotteryType::Dlt => 12,
-                _ => 0,
-            };
-            
-            let mut special_numbers: Vec<u32> = Vec::new();
-            let mut rng = rand::thread_rng();
-            
-            let mut candidates: Vec<u32> = (1..=special_max).collect();
-                        candidates.shuffle(&mut rng);
-            
-            Some(candidates.into_iter().take(special_count).collect())
-        } else {
-            None
-        };
+        if input.historical_data.len() < self.warmup() {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Insufficient historical data for ARIMA prediction".to_string()
+            ));
+        }
 
-        let confidence_scores = vec![0.6; predicted_numbers.len()];
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let draws: Vec<Vec<u32>> = input.historical_data.iter()
+            .map(|d| d.winning_numbers.clone())
+            .collect();
+        let forecasts = self.forecast_numbers(&self.number_models, &draws, spec.main_numbers());
+        let scores: Vec<f64> = forecasts.iter().map(|f| f.mean).collect();
+
+        // 预测值最高的号码组成一注，同分取较小的号码，结果可复现
+        let predicted_numbers = MetricsCalculator::from_spec(spec.clone()).top_numbers(&scores, spec.pick_count);
+        let forecast_of = |number: u32| forecasts[(number - spec.main_min()) as usize];
+
+        // 没有特别号模型时按历史出现次数打分
+        let special_scores = spec.special_numbers().map(|special_range| {
+            if self.special_models.len() == spec.special_pool_size() {
+                let special_draws: Vec<Vec<u32>> = input.historical_data.iter()
+                    .map(|d| d.special_numbers.clone().unwrap_or_default())
+                    .collect();
+                self.forecast_numbers(&self.special_models, &special_draws, special_range)
+                    .iter()
+                    .map(|f| f.mean.max(0.0))
+                    .collect()
+            } else {
+                calibration::history_special_scores(&spec, &input.historical_data)
+            }
+        });
+        let predicted_special_numbers = special_scores.as_ref()
+            .map(|scores| calibration::top_special_numbers(&spec, scores));
+
+        let probabilities = NumberProbabilities::from_scores(&spec, &scores, special_scores.as_deref());
+        let confidence_scores = probabilities.confidence(&spec, &predicted_numbers);
+        let intervals: serde_json::Map<String, serde_json::Value> = predicted_numbers.iter()
+            .map(|&number| {
+                let forecast = forecast_of(number);
+                (number.to_string(), serde_json::json!([forecast.lower.max(0.0), forecast.upper.min(1.0)]))
+            })
+            .collect();
         let computation_time = start_time.elapsed().as_millis() as u64;
 
         Ok(PredictionOutput {
@@ -642,14 +739,23 @@ impl PredictionAlgorithm for ArimaModel {
             predicted_special_numbers,
             confidence_scores,
             algorithm_metadata: HashMap::from_iter(vec![
-                ("algorithm".to_string(), serde_json::Value::String("arima".to_string())),
-                ("p".to_string(), serde_json::Value::Number(serde_json::Number::from(self.config.p))),
-                ("d".to_string(), serde_json::Value::Number(serde_json::Number::from(self.config.d))),
-                ("q".to_string(), serde_json::Value::Number(serde_json::Number::from(self.config.q))),
+                ("algorithm".to_string(), serde_json::Value::String(AlgorithmType::Arima.to_string())),
+                ("p".to_string(), serde_json::Value::Number(serde_json::Number::from(self.order.p))),
+                ("d".to_string(), serde_json::Value::Number(serde_json::Number::from(self.order.d))),
+                ("q".to_string(), serde_json::Value::Number(serde_json::Number::from(self.order.q))),
+                ("seasonal_order".to_string(), serde_json::json!([
+                    self.order.seasonal_p,
+                    self.order.seasonal_d,
+                    self.order.seasonal_q,
+                    self.order.seasonal_period,
+                ])),
                 ("aic".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(self.aic).unwrap_or(serde_json::Number::from(0)))),
                 ("bic".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(self.bic).unwrap_or(serde_json::Number::from(0)))),
+                ("confidence_level".to_string(), serde_json::json!(self.config.confidence_level)),
+                ("forecast_intervals".to_string(), serde_json::Value::Object(intervals)),
             ]),
             computation_time_ms: computation_time,
+            probabilities: Some(probabilities),
         })
     }
 
@@ -657,42 +763,69 @@ impl PredictionAlgorithm for ArimaModel {
         &self,
         test_data: &TrainingData,
     ) -> Result<EvaluationMetrics> {
-        if test_data.features.is_empty() || test_data.targets.is_empty() {
+        if test_data.targets.is_empty() {
             return Ok(EvaluationMetrics::default());
         }
 
-        // Convert test data to historical drawings
-        let historical_data = test_data.targets.iter().enumerate().map(|(i, target)| {
-            LotteryDrawing {
-                id: uuid::Uuid::new_v4(),
-                lottery_type: self.lottery_type.clone(),
-                draw_number: format!("{:04}", i + 1),
-                draw_date: chrono::Utc::now().naive_utc().date(),
-                draw_time: None,
-                winning_numbers: target.clone(),
-                special_numbers: None,
-                jackpot_amount: None,
-                sales_amount: None,
-                prize_distribution: None,
-                data_source: "test".to_string(),
-                verification_status: "verified".to_string(),
-                metadata: None,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-                crawled_at: None,
-            }
-        }).collect::<Vec<_>>();
+        let scores = self.predict_scores(test_data).await?;
+        MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)
+    }
 
-        let series = self.prepare_time_series(&historical_data);
-        let accuracy = self.calculate_accuracy(&series);
+    // 逐期做一步滚动预测，只使用该期之前的开奖；参数保持训练时的估计
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
 
-        let mut metrics = EvaluationMetrics::default();
-        metrics.accuracy = accuracy;
-        metrics.precision = accuracy * 0.9;
-        metrics.recall = accuracy * 0.95;
-        metrics.f1_score = 2.0 * (metrics.precision * metrics.recall) / (metrics.precision + metrics.recall + 1e-8);
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let panel = self.appearance_series(&test_data.targets, spec.main_numbers());
+        let confidence_level = self.config.confidence_level;
+
+        Ok((self.warmup()..test_data.targets.len())
+            .map(|t| {
+                self.number_models.iter()
+                    .zip(&panel)
+                    .map(|(model, series)| model.forecast(&series[..t], 1, confidence_level)[0].mean.max(0.0))
+                    .collect()
+            })
+            .collect())
+    }
+
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
 
-        Ok(metrics)
+        // 与 predict_scores 一样从第 warmup 期开始
+        let spec = GameSpec::for_type(&self.lottery_type);
+        match (spec.special_numbers(), &test_data.special_targets) {
+            (Some(numbers), Some(special_targets)) if self.special_models.len() == spec.special_pool_size() => {
+                let panel = self.appearance_series(special_targets, numbers);
+                let confidence_level = self.config.confidence_level;
+                Ok((self.warmup()..special_targets.len())
+                    .map(|t| {
+                        self.special_models.iter()
+                            .zip(&panel)
+                            .map(|(model, series)| model.forecast(&series[..t], 1, confidence_level)[0].mean.max(0.0))
+                            .collect()
+                    })
+                    .collect())
+            }
+            _ => {
+                let rows = calibration::frequency_special_scores(&self.lottery_type, test_data)?;
+                Ok(rows.get(self.warmup()..).map(<[_]>::to_vec).unwrap_or_default())
+            }
+        }
     }
 
     fn is_trained(&self) -> bool {
@@ -701,9 +834,12 @@ impl PredictionAlgorithm for ArimaModel {
 
     fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
         let mut importance = HashMap::new();
-        importance.insert("ar_order".to_string(), self.config.p as f64);
-        importance.insert("differencing_order".to_string(), self.config.d as f64);
-        importance.insert("ma_order".to_string(), self.config.q as f64);
+        importance.insert("ar_order".to_string(), self.order.p as f64);
+        importance.insert("differencing_order".to_string(), self.order.d as f64);
+        importance.insert("ma_order".to_string(), self.order.q as f64);
+        importance.insert("seasonal_ar_order".to_string(), self.order.seasonal_p as f64);
+        importance.insert("seasonal_differencing_order".to_string(), self.order.seasonal_d as f64);
+        importance.insert("seasonal_ma_order".to_string(), self.order.seasonal_q as f64);
         importance.insert("aic".to_string(), self.aic);
         importance.insert("bic".to_string(), self.bic);
         Some(importance)
@@ -749,13 +885,14 @@ mod tests {
     use crate::lottery::algorithms::traits::TrainingData;
     use crate::lottery::models::{LotteryDrawing, LotteryType};
     use chrono::NaiveDate;
+    use rand::prelude::*;
 
     #[test]
     fn test_arima_creation() {
         let config = ArimaConfig::default();
         let model = ArimaModel::new(config, LotteryType::Ssq);
         assert_eq!(model.name(), "ARIMA Time Series");
-        assert_eq!(model.algorithm_type(), "arima");
+        assert_eq!(model.algorithm_type(), AlgorithmType::Arima);
         assert!(!model.is_trained());
     }
 
@@ -780,6 +917,152 @@ mod tests {
         assert!((acf[0] - 1.0).abs() < 1e-6);
     }
 
+    fn ar1_series(phi: f64, n: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut series = vec![2.0];
+        for _ in 1..n {
+            let noise: f64 = (0..4).map(|_| rng.gen_range(-1.0..1.0)).sum::<f64>() * 0.5;
+            let previous = *series.last().unwrap();
+            series.push(2.0 + phi * (previous - 2.0) + noise);
+        }
+        series
+    }
+
+    #[test]
+    fn test_sarima_fit_and_forecast_intervals() {
+        let model = ArimaModel::new(ArimaConfig::default(), LotteryType::Ssq);
+        let series = ar1_series(0.6, 400);
+        let order = SarimaOrder { p: 1, ..Default::default() };
+
+        let fit = SarimaFit::fit(&series, order, 0, &model.initial_parameters(&series, &order), 1000, 1e-10).unwrap();
+        assert!((fit.ar[0] - 0.6).abs() < 0.1, "ar = {:?}", fit.ar);
+        assert!((fit.mean - 2.0).abs() < 0.2);
+        assert!(fit.aic < fit.bic);
+
+        let forecasts = fit.forecast(&series, 3, 0.95);
+        assert_eq!(forecasts.len(), 3);
+        let widths: Vec<f64> = forecasts.iter().map(|f| f.upper - f.lower).collect();
+        assert!(widths[0] < widths[1] && widths[1] < widths[2]);
+        assert!(forecasts.iter().all(|f| f.lower < f.mean && f.mean < f.upper));
+    }
+
+    #[test]
+    fn test_order_selection() {
+        let series = ar1_series(0.6, 400);
+        let config = ArimaConfig {
+            seasonal_period: 0,
+            information_criterion: InformationCriterion::Bic,
+            ..Default::default()
+        };
+        let model = ArimaModel::new(config, LotteryType::Ssq);
+
+        let (order, fits) = model.select_order(&[series]).unwrap();
+        assert_eq!(order, SarimaOrder { p: 1, ..Default::default() });
+        assert_eq!(fits.len(), 1);
+
+        // 随机游走需要一阶差分
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut walk = vec![0.0];
+        for _ in 0..300 {
+            let next = walk.last().unwrap() + rng.gen_range(-1.0..1.0);
+            walk.push(next);
+        }
+        let max = SarimaOrder { p: 2, d: 1, q: 1, ..Default::default() };
+        assert_eq!(model.select_differencing(&[walk], &max), (1, 0));
+    }
+
+    #[test]
+    fn test_seasonal_forecast_follows_period() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let pattern = [3.0, 0.0, 1.0, 5.0, 2.0, 4.0, 1.0];
+        let series: Vec<f64> = (0..300).map(|i| pattern[i % 7] + rng.gen_range(-0.5..0.5)).collect();
+        let config = ArimaConfig {
+            p: 1, d: 0, q: 1,
+            seasonal_p: 1, seasonal_d: 1, seasonal_q: 1,
+            seasonal_period: 7,
+            ..Default::default()
+        };
+        let model = ArimaModel::new(config, LotteryType::Ssq);
+
+        let (order, fits) = model.select_order(std::slice::from_ref(&series)).unwrap();
+        assert_eq!((order.seasonal_d, order.seasonal_period), (1, 7));
+        let forecasts = fits[0].forecast(&series, 7, 0.95);
+        for (step, forecast) in forecasts.iter().enumerate() {
+            assert!((forecast.mean - pattern[(300 + step) % 7]).abs() < 0.5);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_arima_prediction_is_deterministic() {
+        let config = ArimaConfig {
+            p: 1, d: 0, q: 0,
+            seasonal_period: 0,
+            auto_order: false,
+            ..Default::default()
+        };
+        let mut model = ArimaModel::new(config, LotteryType::Ssq);
+
+        // 7 号隔期开出，其余号码轮流出现
+        let draws: Vec<Vec<u32>> = (0..60u32)
+            .map(|i| {
+                let mut draw: Vec<u32> = (0..5).map(|k| (i * 5 + k) % 26 + 8).collect();
+                draw.push(if i % 2 == 0 { 7 } else { (i % 6) + 1 });
+                draw
+            })
+            .collect();
+        let training_data = TrainingData {
+            features: vec![vec![0.0]; draws.len()],
+            targets: draws.clone(),
+            special_targets: None,
+            weights: None,
+        };
+        let algorithm_config = AlgorithmConfig {
+            lottery_type: LotteryType::Ssq,
+            parameters: HashMap::new(),
+            hyperparameters: HashMap::new(),
+            feature_config: HashMap::new(),
+        };
+        model.train(&training_data, &algorithm_config).await.unwrap();
+        assert_eq!(model.number_models.len(), 33);
+
+        let historical_data: Vec<LotteryDrawing> = draws.iter()
+            .enumerate()
+            .map(|(i, numbers)| LotteryDrawing {
+                id: uuid::Uuid::new_v4(),
+                lottery_type: LotteryType::Ssq,
+                draw_number: format!("2024{:03}", i),
+                draw_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(i as i64),
+                draw_time: None,
+                winning_numbers: numbers.clone(),
+                special_numbers: Some(vec![(i % 16) as u32 + 1]),
+                jackpot_amount: None,
+                sales_amount: None,
+                prize_distribution: None,
+                data_source: "test".to_string(),
+                verification_status: "verified".to_string(),
+                metadata: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                crawled_at: None,
+            })
+            .collect();
+        let input = PredictionInput {
+            lottery_type: LotteryType::Ssq,
+            historical_data,
+            target_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            additional_features: None,
+        };
+
+        let first = model.predict(&input).await.unwrap();
+        let second = model.predict(&input).await.unwrap();
+        assert_eq!(first.predicted_numbers, second.predicted_numbers);
+        assert_eq!(first.predicted_special_numbers, second.predicted_special_numbers);
+        assert_eq!(first.predicted_numbers.len(), 6);
+        // 最后一期 (奇数期) 没有 7，下一期应当开出
+        assert_eq!(first.predicted_numbers[0], 7);
+        assert!(first.algorithm_metadata.contains_key("forecast_intervals"));
+    }
+
     #[tokio::test]
     async fn test_arima_training() {
         let config = ArimaConfig {
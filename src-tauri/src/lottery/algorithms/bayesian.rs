@@ -0,0 +1,548 @@
+use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::{self, NumberProbabilities};
+use crate::lottery::algorithms::metrics::MetricsCalculator;
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryType};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{Beta, ContinuousCDF};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BayesianConfig {
+    // 每个号码的 Dirichlet 先验伪计数
+    pub prior_concentration: f64,
+    // 每过一期，旧开奖的计数乘以该因子；1 表示不遗忘
+    pub forgetting_factor: f64,
+    // 后验可信区间的覆盖概率
+    pub credible_level: f64,
+}
+
+impl Default for BayesianConfig {
+    fn default() -> Self {
+        Self {
+            prior_concentration: 1.0,
+            forgetting_factor: 0.98,
+            credible_level: 0.95,
+        }
+    }
+}
+
+impl ModelConfig for BayesianConfig {
+    fn validate(&self) -> Result<()> {
+        ensure_config(
+            self.prior_concentration > 0.0 && self.prior_concentration.is_finite(),
+            "prior_concentration must be positive",
+        )?;
+        ensure_config(
+            self.forgetting_factor > 0.0 && self.forgetting_factor <= 1.0,
+            "forgetting_factor must be in (0, 1]",
+        )?;
+        ensure_config(
+            self.credible_level > 0.0 && self.credible_level < 1.0,
+            "credible_level must be in (0, 1)",
+        )
+    }
+}
+
+// 单个号码的后验摘要：Dirichlet 的边际为 Beta(α_i, Σα - α_i)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PosteriorSummary {
+    pub number: u32,
+    // 有序玩法的位置，无序玩法为 None
+    pub position: Option<usize>,
+    pub mean: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+// 主号码 (有序玩法按位) 与特别号各自一个 Dirichlet 后验，
+// 计数随期数指数衰减，先验伪计数保持不变
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BayesianModel {
+    pub config: BayesianConfig,
+    #[serde(default)]
+    pub base_config: BayesianConfig,
+    pub lottery_type: LotteryType,
+    // 训练数据上的后验参数；有序玩法每个位置一组，否则只有一组
+    pub main_posterior: Vec<Vec<f64>>,
+    pub special_posterior: Option<Vec<f64>>,
+    // 衰减后的有效期数
+    pub effective_draws: f64,
+    pub is_trained: bool,
+}
+
+impl BayesianModel {
+    pub fn new(config: BayesianConfig, lottery_type: LotteryType) -> Self {
+        Self {
+            base_config: config.clone(),
+            config,
+            lottery_type,
+            main_posterior: Vec::new(),
+            special_posterior: None,
+            effective_draws: 0.0,
+            is_trained: false,
+        }
+    }
+
+    // 有序玩法的每个位置是一次独立的类别抽样，各自一个后验
+    fn positions(&self) -> usize {
+        let spec = GameSpec::for_type(&self.lottery_type);
+        if spec.ordered { spec.pick_count } else { 1 }
+    }
+
+    // 先衰减已有计数再计入这一期；position 为 None 时统计整期出现的号码
+    fn absorb(&self, counts: &mut [f64], draw: &[u32], min: u32, position: Option<usize>) {
+        for count in counts.iter_mut() {
+            *count *= self.config.forgetting_factor;
+        }
+        let drawn: &[u32] = match position {
+            Some(p) => draw.get(p..=p).unwrap_or(&[]),
+            None => draw,
+        };
+        for &number in drawn {
+            if let Some(count) = number.checked_sub(min).and_then(|i| counts.get_mut(i as usize)) {
+                *count += 1.0;
+            }
+        }
+    }
+
+    fn with_prior(&self, counts: &[f64]) -> Vec<f64> {
+        counts.iter().map(|count| self.config.prior_concentration + count).collect()
+    }
+
+    fn posterior(&self, draws: &[Vec<u32>], numbers: RangeInclusive<u32>, position: Option<usize>) -> Vec<f64> {
+        let min = *numbers.start();
+        let mut counts = vec![0.0; numbers.count()];
+        for draw in draws {
+            self.absorb(&mut counts, draw, min, position);
+        }
+        self.with_prior(&counts)
+    }
+
+    fn main_posteriors(&self, draws: &[Vec<u32>]) -> Vec<Vec<f64>> {
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let positions = self.positions();
+        (0..positions)
+            .map(|p| self.posterior(draws, spec.main_numbers(), spec.ordered.then_some(p)))
+            .collect()
+    }
+
+    fn special_posterior(&self, special_draws: &[Vec<u32>]) -> Option<Vec<f64>> {
+        GameSpec::for_type(&self.lottery_type).special_numbers()
+            .map(|numbers| self.posterior(special_draws, numbers, None))
+    }
+
+    // 号码在下一期出现的打分：无序玩法取后验均值，有序玩法取至少在一个位置出现的概率
+    fn main_scores(&self, posteriors: &[Vec<f64>]) -> Vec<f64> {
+        let means: Vec<Vec<f64>> = posteriors.iter().map(|alpha| posterior_mean(alpha)).collect();
+        if means.len() == 1 {
+            return means.into_iter().next().unwrap_or_default();
+        }
+
+        let pool_size = means.first().map_or(0, Vec::len);
+        (0..pool_size)
+            .map(|i| 1.0 - means.iter().map(|mean| 1.0 - mean[i]).product::<f64>())
+            .collect()
+    }
+
+    fn select_numbers(&self, posteriors: &[Vec<f64>], scores: &[f64]) -> Vec<u32> {
+        let spec = GameSpec::for_type(&self.lottery_type);
+        if !spec.ordered {
+            return MetricsCalculator::from_spec(spec.clone()).top_numbers(scores, spec.pick_count);
+        }
+
+        // 每个位置取后验均值最大的号码，同分取较小的号码；不允许重复时跳过已选号码
+        let mut numbers = Vec::with_capacity(posteriors.len());
+        for alpha in posteriors {
+            let best = (0..alpha.len())
+                .filter(|&i| spec.allow_repeats || !numbers.contains(&(i as u32 + spec.main_min())))
+                .min_by(|&a, &b| alpha[b].total_cmp(&alpha[a]).then(a.cmp(&b)));
+            if let Some(i) = best {
+                numbers.push(i as u32 + spec.main_min());
+            }
+        }
+        numbers
+    }
+
+    fn summarize(&self, alpha: &[f64], number: u32, min: u32, position: Option<usize>) -> PosteriorSummary {
+        let i = (number - min) as usize;
+        let total: f64 = alpha.iter().sum();
+        let tail = (1.0 - self.config.credible_level) / 2.0;
+        let (lower, upper) = Beta::new(alpha[i], total - alpha[i])
+            .map(|beta| (beta.inverse_cdf(tail), beta.inverse_cdf(1.0 - tail)))
+            .unwrap_or((0.0, 1.0));
+
+        PosteriorSummary {
+            number,
+            position,
+            mean: alpha[i] / total,
+            lower,
+            upper,
+        }
+    }
+}
+
+#[async_trait]
+impl PredictionAlgorithm for BayesianModel {
+    fn name(&self) -> String {
+        "Bayesian Dirichlet-Multinomial".to_string()
+    }
+
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::Bayesian
+    }
+
+    async fn train(
+        &mut self,
+        training_data: &TrainingData,
+        config: &AlgorithmConfig,
+    ) -> Result<f64> {
+        self.config = config.apply(&self.base_config)?;
+        if training_data.targets.is_empty() {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "No drawings to update the posterior with".to_string()
+            ));
+        }
+
+        self.main_posterior = self.main_posteriors(&training_data.targets);
+        self.special_posterior = training_data.special_targets.as_ref()
+            .and_then(|special_targets| self.special_posterior(special_targets));
+
+        let lambda = self.config.forgetting_factor;
+        self.effective_draws = (0..training_data.targets.len()).map(|age| lambda.powi(age as i32)).sum();
+        self.is_trained = true;
+
+        let metrics = self.evaluate(training_data).await?;
+        Ok(metrics.accuracy)
+    }
+
+    // 共轭模型的预测只依赖历史开奖：用输入的历史重新计算后验，没有历史时使用训练时的后验
+    async fn predict(
+        &self,
+        input: &PredictionInput,
+    ) -> Result<PredictionOutput> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        let start_time = std::time::Instant::now();
+
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let (main_posterior, special_posterior) = if input.historical_data.is_empty() {
+            (self.main_posterior.clone(), self.special_posterior.clone())
+        } else {
+            let draws: Vec<Vec<u32>> = input.historical_data.iter()
+                .map(|d| d.winning_numbers.clone())
+                .collect();
+            let special_draws: Vec<Vec<u32>> = input.historical_data.iter()
+                .filter_map(|d| d.special_numbers.clone())
+                .collect();
+            (self.main_posteriors(&draws), self.special_posterior(&special_draws))
+        };
+
+        let scores = self.main_scores(&main_posterior);
+        let predicted_numbers = self.select_numbers(&main_posterior, &scores);
+
+        let special_scores = special_posterior.as_ref().map(|alpha| {
+            let mut scores = posterior_mean(alpha);
+            // 特别号与主号码同池时不能重复
+            if spec.special_from_main_pool {
+                for &number in &predicted_numbers {
+                    if let Some(score) = number.checked_sub(spec.main_min()).and_then(|i| scores.get_mut(i as usize)) {
+                        *score = 0.0;
+                    }
+                }
+            }
+            scores
+        });
+        let predicted_special_numbers = special_scores.as_ref()
+            .map(|scores| calibration::top_special_numbers(&spec, scores));
+
+        let mut posterior: Vec<PosteriorSummary> = predicted_numbers.iter()
+            .enumerate()
+            .map(|(p, &number)| {
+                let position = spec.ordered.then_some(p);
+                self.summarize(&main_posterior[position.unwrap_or(0)], number, spec.main_min(), position)
+            })
+            .collect();
+        if let (Some(alpha), Some(numbers), Some(special_min)) = (
+            &special_posterior,
+            &predicted_special_numbers,
+            spec.special_numbers().map(|range| *range.start()),
+        ) {
+            posterior.extend(numbers.iter().map(|&number| self.summarize(alpha, number, special_min, None)));
+        }
+
+        let probabilities = NumberProbabilities::from_scores(&spec, &scores, special_scores.as_deref());
+        let confidence_scores = probabilities.confidence(&spec, &predicted_numbers);
+        let computation_time = start_time.elapsed().as_millis() as u64;
+
+        Ok(PredictionOutput {
+            predicted_numbers,
+            predicted_special_numbers,
+            confidence_scores,
+            algorithm_metadata: HashMap::from_iter(vec![
+                ("algorithm".to_string(), serde_json::Value::String(AlgorithmType::Bayesian.to_string())),
+                ("prior_concentration".to_string(), serde_json::json!(self.config.prior_concentration)),
+                ("forgetting_factor".to_string(), serde_json::json!(self.config.forgetting_factor)),
+                ("credible_level".to_string(), serde_json::json!(self.config.credible_level)),
+                ("effective_draws".to_string(), serde_json::json!(self.effective_draws)),
+                ("posterior".to_string(), serde_json::json!(posterior)),
+            ]),
+            computation_time_ms: computation_time,
+            probabilities: Some(probabilities),
+        })
+    }
+
+    async fn evaluate(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<EvaluationMetrics> {
+        let scores = self.predict_scores(test_data).await?;
+        MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)
+    }
+
+    // 第 t 行是吸收前 t 期开奖后的后验预测，第 0 行即先验
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let mut counts = vec![vec![0.0; spec.main_pool_size()]; self.positions()];
+        let mut rows = Vec::with_capacity(test_data.targets.len());
+        for draw in &test_data.targets {
+            let posteriors: Vec<Vec<f64>> = counts.iter().map(|c| self.with_prior(c)).collect();
+            rows.push(self.main_scores(&posteriors));
+            for (p, c) in counts.iter_mut().enumerate() {
+                self.absorb(c, draw, spec.main_min(), spec.ordered.then_some(p));
+            }
+        }
+        Ok(rows)
+    }
+
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let (Some(numbers), Some(special_targets)) = (spec.special_numbers(), &test_data.special_targets) else {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "No special number targets to score".to_string()
+            ));
+        };
+
+        let mut counts = vec![0.0; spec.special_pool_size()];
+        let mut rows = Vec::with_capacity(special_targets.len());
+        for draw in special_targets {
+            rows.push(posterior_mean(&self.with_prior(&counts)));
+            self.absorb(&mut counts, draw, *numbers.start(), None);
+        }
+        Ok(rows)
+    }
+
+    fn is_trained(&self) -> bool {
+        self.is_trained
+    }
+
+    fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
+        let mut importance = HashMap::new();
+        importance.insert("prior_concentration".to_string(), self.config.prior_concentration);
+        importance.insert("forgetting_factor".to_string(), self.config.forgetting_factor);
+        importance.insert("effective_draws".to_string(), self.effective_draws);
+        Some(importance)
+    }
+
+    fn save_model(&self, path: &str) -> Result<()> {
+        let serialized = serde_json::to_string(self)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to serialize model: {}", e)
+            ))?;
+
+        std::fs::write(path, serialized)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to save model: {}", e)
+            ))?;
+
+        Ok(())
+    }
+
+    fn load_model(&mut self, path: &str) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to load model: {}", e)
+            ))?;
+
+        let model: BayesianModel = serde_json::from_str(&content)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to deserialize model: {}", e)
+            ))?;
+
+        *self = model;
+        Ok(())
+    }
+
+    fn box_clone(&self) -> Box<dyn PredictionAlgorithm> {
+        Box::new(self.clone())
+    }
+}
+
+fn posterior_mean(alpha: &[f64]) -> Vec<f64> {
+    let total: f64 = alpha.iter().sum();
+    alpha.iter().map(|a| a / total).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::algorithms::test_support::create_drawing;
+    use crate::lottery::models::LotteryDrawing;
+    use chrono::NaiveDate;
+
+    fn input(lottery_type: LotteryType, historical_data: Vec<LotteryDrawing>) -> PredictionInput {
+        PredictionInput {
+            lottery_type,
+            historical_data,
+            target_date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+            additional_features: None,
+        }
+    }
+
+    #[test]
+    fn test_bayesian_creation() {
+        let model = BayesianModel::new(BayesianConfig::default(), LotteryType::Ssq);
+        assert_eq!(model.algorithm_type(), AlgorithmType::Bayesian);
+        assert!(!model.is_trained());
+    }
+
+    #[tokio::test]
+    async fn test_posterior_with_forgetting() {
+        let config = BayesianConfig { forgetting_factor: 0.5, ..Default::default() };
+        let mut model = BayesianModel::new(config, LotteryType::Ssq);
+        let training_data = TrainingData {
+            features: vec![vec![0.0]; 2],
+            targets: vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9, 10, 11, 12]],
+            special_targets: Some(vec![vec![3], vec![3]]),
+            weights: None,
+        };
+        model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+
+        // 较早一期的计数衰减为 0.5
+        assert_eq!(model.main_posterior.len(), 1);
+        assert_eq!(model.main_posterior[0][0], 1.5);
+        assert_eq!(model.main_posterior[0][6], 2.0);
+        assert_eq!(model.main_posterior[0][20], 1.0);
+        assert_eq!(model.special_posterior.as_ref().unwrap()[2], 2.5);
+        assert_eq!(model.effective_draws, 1.5);
+
+        let output = model.predict(&input(LotteryType::Ssq, vec![
+            create_drawing(LotteryType::Ssq, 0, vec![1, 2, 3, 4, 5, 6], Some(vec![3])),
+            create_drawing(LotteryType::Ssq, 1, vec![7, 8, 9, 10, 11, 12], Some(vec![3])),
+        ])).await.unwrap();
+        assert_eq!(output.predicted_numbers, vec![7, 8, 9, 10, 11, 12]);
+        assert_eq!(output.predicted_special_numbers, Some(vec![3]));
+
+        let posterior: Vec<PosteriorSummary> =
+            serde_json::from_value(output.algorithm_metadata["posterior"].clone()).unwrap();
+        assert_eq!(posterior.len(), 7);
+        let first = &posterior[0];
+        assert!((first.mean - 2.0 / 42.0).abs() < 1e-12);
+        assert!(first.lower < first.mean && first.mean < first.upper);
+
+        let probabilities = output.probabilities.unwrap();
+        assert_eq!(probabilities.main.len(), 33);
+        assert!((probabilities.main.iter().sum::<f64>() - 6.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_scores_only_use_earlier_draws() {
+        let mut model = BayesianModel::new(BayesianConfig::default(), LotteryType::Ssq);
+        let training_data = TrainingData {
+            features: vec![vec![0.0]; 3],
+            targets: vec![vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9, 10, 11, 12], vec![1, 2, 3, 7, 8, 9]],
+            special_targets: None,
+            weights: None,
+        };
+        model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+
+        let scores = model.predict_scores(&training_data).await.unwrap();
+        assert_eq!(scores.len(), 3);
+        // 第 0 行即先验
+        assert!(scores[0].iter().all(|&s| (s - 1.0 / 33.0).abs() < 1e-12));
+        assert!(scores[1][0] > scores[1][6]);
+        assert!(model.predict_special_scores(&training_data).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ordered_game_predicts_each_position() {
+        let mut model = BayesianModel::new(BayesianConfig::default(), LotteryType::Fc3d);
+        let draws = vec![vec![1, 1, 8], vec![1, 2, 8], vec![3, 1, 8]];
+        let training_data = TrainingData {
+            features: vec![vec![0.0]; 3],
+            targets: draws.clone(),
+            special_targets: None,
+            weights: None,
+        };
+        model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+        assert_eq!(model.main_posterior.len(), 3);
+
+        let history = draws.into_iter()
+            .enumerate()
+            .map(|(i, numbers)| create_drawing(LotteryType::Fc3d, i as u32, numbers, None))
+            .collect();
+        let output = model.predict(&input(LotteryType::Fc3d, history)).await.unwrap();
+        assert_eq!(output.predicted_numbers, vec![1, 1, 8]);
+        assert_eq!(output.predicted_special_numbers, None);
+        assert_eq!(output.probabilities.unwrap().main.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config() {
+        let config = BayesianConfig { forgetting_factor: 1.5, ..Default::default() };
+        let mut model = BayesianModel::new(config, LotteryType::Ssq);
+        let training_data = TrainingData {
+            features: vec![vec![0.0]],
+            targets: vec![vec![1, 2, 3, 4, 5, 6]],
+            special_targets: None,
+            weights: None,
+        };
+        assert!(model.train(&training_data, &AlgorithmConfig::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_train_applies_hyperparameters() {
+        let mut model = BayesianModel::new(BayesianConfig::default(), LotteryType::Ssq);
+        let training_data = TrainingData {
+            features: vec![vec![0.0]; 2],
+            targets: vec![vec![1, 2, 3, 4, 5, 6], vec![1, 2, 3, 4, 5, 7]],
+            special_targets: None,
+            weights: None,
+        };
+        let mut config = AlgorithmConfig::default();
+        config.hyperparameters.insert("forgetting_factor".to_string(), serde_json::json!(1.0));
+        model.train(&training_data, &config).await.unwrap();
+        assert_eq!(model.config.forgetting_factor, 1.0);
+        assert!((model.effective_draws - 2.0).abs() < 1e-12);
+
+        config.hyperparameters.insert("decay".to_string(), serde_json::json!(0.5));
+        let result = model.train(&training_data, &config).await;
+        assert!(matches!(result, Err(crate::lottery::errors::LotteryError::ConfigurationError(_))));
+    }
+}
@@ -0,0 +1,738 @@
+use crate::lottery::algorithms::metrics::MetricsCalculator;
+use crate::lottery::algorithms::traits::*;
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryDrawing, LotteryType};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// 校准后的概率截断到 (0, 1)，logit 与对数损失都不会出现无穷大
+const PROBABILITY_EPSILON: f64 = 1e-6;
+const RELIABILITY_BINS: usize = 10;
+const PLATT_MAX_ITERATIONS: usize = 100;
+const PLATT_TOLERANCE: f64 = 1e-10;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationMethod {
+    #[default]
+    Platt,
+    Isotonic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Calibrator {
+    // p' = sigmoid(slope * logit(p) + intercept)
+    Platt { slope: f64, intercept: f64 },
+    // 保序回归各块的平均预测概率 (升序) 与对应的开出频率，块之间线性插值
+    Isotonic { thresholds: Vec<f64>, values: Vec<f64> },
+}
+
+impl Calibrator {
+    pub fn fit(method: CalibrationMethod, probabilities: &[f64], outcomes: &[bool]) -> Result<Self> {
+        if probabilities.is_empty() || probabilities.len() != outcomes.len() {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "Calibration needs one outcome per predicted probability".to_string()
+            ));
+        }
+
+        Ok(match method {
+            CalibrationMethod::Platt => fit_platt(probabilities, outcomes),
+            CalibrationMethod::Isotonic => fit_isotonic(probabilities, outcomes),
+        })
+    }
+
+    pub fn apply(&self, p: f64) -> f64 {
+        let calibrated = match self {
+            Calibrator::Platt { slope, intercept } => sigmoid(slope * logit(p) + intercept),
+            Calibrator::Isotonic { thresholds, values } => {
+                let i = thresholds.partition_point(|&t| t < p);
+                if i == 0 {
+                    values[0]
+                } else if i == thresholds.len() {
+                    values[i - 1]
+                } else if thresholds[i] <= thresholds[i - 1] {
+                    values[i]
+                } else {
+                    let t = (p - thresholds[i - 1]) / (thresholds[i] - thresholds[i - 1]);
+                    values[i - 1] + t * (values[i] - values[i - 1])
+                }
+            }
+        };
+        calibrated.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON)
+    }
+}
+
+// 等宽分箱的可靠性图数据：每箱的平均预测概率与实际开出频率
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReliabilityBin {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+    pub mean_predicted: f64,
+    pub observed_frequency: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ReliabilityDiagram {
+    pub bins: Vec<ReliabilityBin>,
+    pub samples: usize,
+    // 各箱 |开出频率 - 平均预测| 按样本数加权
+    pub expected_calibration_error: f64,
+    pub brier_score: f64,
+}
+
+impl ReliabilityDiagram {
+    pub fn from_predictions(probabilities: &[f64], outcomes: &[bool], n_bins: usize) -> Self {
+        let n_bins = n_bins.max(1);
+        let mut bins: Vec<ReliabilityBin> = (0..n_bins)
+            .map(|i| ReliabilityBin {
+                lower: i as f64 / n_bins as f64,
+                upper: (i + 1) as f64 / n_bins as f64,
+                ..Default::default()
+            })
+            .collect();
+        let mut brier = 0.0;
+
+        for (&p, &drawn) in probabilities.iter().zip(outcomes) {
+            let y = if drawn { 1.0 } else { 0.0 };
+            let bin = &mut bins[((p * n_bins as f64) as usize).min(n_bins - 1)];
+            bin.count += 1;
+            bin.mean_predicted += p;
+            bin.observed_frequency += y;
+            brier += (p - y).powi(2);
+        }
+
+        let samples = probabilities.len().min(outcomes.len());
+        let mut expected_calibration_error = 0.0;
+        for bin in bins.iter_mut().filter(|bin| bin.count > 0) {
+            bin.mean_predicted /= bin.count as f64;
+            bin.observed_frequency /= bin.count as f64;
+            expected_calibration_error +=
+                bin.count as f64 / samples as f64 * (bin.observed_frequency - bin.mean_predicted).abs();
+        }
+
+        Self {
+            bins,
+            samples,
+            expected_calibration_error,
+            brier_score: if samples > 0 { brier / samples as f64 } else { 0.0 },
+        }
+    }
+}
+
+// calibrated 由验证数据前半段拟合的校准器在后半段上计算，避免在拟合数据上自评
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CalibrationReport {
+    pub uncalibrated: ReliabilityDiagram,
+    pub calibrated: Option<ReliabilityDiagram>,
+}
+
+// 下一期每个号码开出的边际概率，主号码概率之和约为每期开出的主号码个数
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NumberProbabilities {
+    // 主号码池按号码升序
+    pub main: Vec<f64>,
+    // 特别号池按号码升序，没有特别号的玩法为 None
+    pub special: Option<Vec<f64>>,
+    // 未经校准时为 None
+    #[serde(default)]
+    pub calibration: Option<CalibrationMethod>,
+    #[serde(default)]
+    pub main_reliability: Option<CalibrationReport>,
+    #[serde(default)]
+    pub special_reliability: Option<CalibrationReport>,
+}
+
+impl NumberProbabilities {
+    pub fn from_scores(spec: &GameSpec, main_scores: &[f64], special_scores: Option<&[f64]>) -> Self {
+        let calculator = MetricsCalculator::from_spec(std::sync::Arc::new(spec.clone()));
+        Self {
+            main: calculator.probabilities(main_scores),
+            special: spec.has_special()
+                .then(|| special_scores.map(|scores| special_probabilities(spec, scores)))
+                .flatten(),
+            ..Default::default()
+        }
+    }
+
+    // 池外号码的概率为 0
+    pub fn confidence(&self, spec: &GameSpec, numbers: &[u32]) -> Vec<f64> {
+        numbers.iter()
+            .map(|&number| {
+                number.checked_sub(spec.main_min())
+                    .and_then(|i| self.main.get(i as usize))
+                    .copied()
+                    .unwrap_or(0.0)
+            })
+            .collect()
+    }
+}
+
+// 非负打分换算为特别号的开出概率，总和缩放到每期开出的特别号个数，全零时视为均匀分布
+pub fn special_probabilities(spec: &GameSpec, scores: &[f64]) -> Vec<f64> {
+    let drawn = spec.special_count as f64;
+    let total: f64 = scores.iter().map(|s| s.max(0.0)).sum();
+
+    scores.iter()
+        .map(|&score| {
+            let p = if total > 0.0 {
+                score.max(0.0) / total * drawn
+            } else {
+                drawn / scores.len() as f64
+            };
+            p.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON)
+        })
+        .collect()
+}
+
+// 特别号池 (按号码升序) 在 special_draws 中的出现次数，加一平滑
+pub fn special_frequency_scores(spec: &GameSpec, special_draws: &[Vec<u32>]) -> Vec<f64> {
+    spec.special_numbers()
+        .map(|numbers| {
+            numbers
+                .map(|number| 1.0 + special_draws.iter().filter(|draw| draw.contains(&number)).count() as f64)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// 按得分从高到低取每期开出个数的特别号
+pub fn top_special_numbers(spec: &GameSpec, scores: &[f64]) -> Vec<u32> {
+    let Some(numbers) = spec.special_numbers() else {
+        return Vec::new();
+    };
+    let numbers: Vec<u32> = numbers.collect();
+
+    let mut ranked: Vec<usize> = (0..numbers.len().min(scores.len())).collect();
+    ranked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+    ranked.into_iter().take(spec.special_count).map(|i| numbers[i]).collect()
+}
+
+pub fn history_special_scores(spec: &GameSpec, drawings: &[LotteryDrawing]) -> Vec<f64> {
+    let special_draws: Vec<Vec<u32>> = drawings.iter()
+        .filter_map(|drawing| drawing.special_numbers.clone())
+        .collect();
+    special_frequency_scores(spec, &special_draws)
+}
+
+// 没有特别号模型的算法使用：第 r 行按此前各期特别号的出现频率打分
+pub fn frequency_special_scores(lottery_type: &LotteryType, test_data: &TrainingData) -> Result<Vec<Vec<f64>>> {
+    let spec = GameSpec::for_type(lottery_type);
+    let special_targets = test_data.special_targets.as_ref()
+        .filter(|_| spec.has_special())
+        .ok_or_else(|| crate::lottery::errors::LotteryError::InvalidParameter(
+            "No special number targets to score".to_string()
+        ))?;
+
+    Ok((0..special_targets.len())
+        .map(|r| special_frequency_scores(&spec, &special_targets[..r]))
+        .collect())
+}
+
+// 在验证数据上拟合的主号码与特别号校准器
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProbabilityCalibration {
+    pub method: CalibrationMethod,
+    pub main: Calibrator,
+    pub main_reliability: CalibrationReport,
+    // 模型不提供特别号打分或验证数据没有特别号时为 None
+    pub special: Option<Calibrator>,
+    pub special_reliability: Option<CalibrationReport>,
+}
+
+impl ProbabilityCalibration {
+    pub async fn fit(
+        model: &dyn PredictionAlgorithm,
+        lottery_type: &LotteryType,
+        validation_data: &TrainingData,
+        method: CalibrationMethod,
+    ) -> Result<Self> {
+        let spec = GameSpec::for_type(lottery_type);
+        let calculator = MetricsCalculator::from_spec(spec.clone());
+
+        let scores = model.predict_scores(validation_data).await?;
+        let main_rows: Vec<Vec<f64>> = scores.iter().map(|row| calculator.probabilities(row)).collect();
+        let main_outcomes = outcome_rows(
+            &validation_data.targets,
+            main_rows.len(),
+            spec.main_numbers().collect(),
+        )?;
+        let (main, main_reliability) = fit_pool(method, &main_rows, &main_outcomes)?;
+
+        let mut special = None;
+        let mut special_reliability = None;
+        if let (Some(numbers), Some(special_targets)) = (spec.special_numbers(), &validation_data.special_targets)
+            && let Ok(scores) = model.predict_special_scores(validation_data).await
+        {
+            let rows: Vec<Vec<f64>> = scores.iter().map(|row| special_probabilities(&spec, row)).collect();
+            if let Ok(outcomes) = outcome_rows(special_targets, rows.len(), numbers.collect()) {
+                let (calibrator, report) = fit_pool(method, &rows, &outcomes)?;
+                special = Some(calibrator);
+                special_reliability = Some(report);
+            }
+        }
+
+        Ok(Self {
+            method,
+            main,
+            main_reliability,
+            special,
+            special_reliability,
+        })
+    }
+
+    pub fn apply(&self, probabilities: &mut NumberProbabilities) {
+        for p in &mut probabilities.main {
+            *p = self.main.apply(*p);
+        }
+        if let (Some(calibrator), Some(special)) = (&self.special, &mut probabilities.special) {
+            for p in special.iter_mut() {
+                *p = calibrator.apply(*p);
+            }
+        }
+
+        probabilities.calibration = Some(self.method);
+        probabilities.main_reliability = Some(self.main_reliability.clone());
+        probabilities.special_reliability = self.special_reliability.clone();
+    }
+}
+
+// 在任意已训练模型外包一层概率校准，其余行为与原模型一致
+#[derive(Debug)]
+pub struct CalibratedModel {
+    pub inner: Box<dyn PredictionAlgorithm>,
+    pub lottery_type: LotteryType,
+    pub calibration: ProbabilityCalibration,
+}
+
+impl CalibratedModel {
+    pub async fn fit(
+        inner: Box<dyn PredictionAlgorithm>,
+        lottery_type: LotteryType,
+        validation_data: &TrainingData,
+        method: CalibrationMethod,
+    ) -> Result<Self> {
+        let calibration = ProbabilityCalibration::fit(inner.as_ref(), &lottery_type, validation_data, method).await?;
+        Ok(Self {
+            inner,
+            lottery_type,
+            calibration,
+        })
+    }
+
+    // 读取 save_model 写出的原模型与校准参数
+    pub fn load(mut inner: Box<dyn PredictionAlgorithm>, lottery_type: LotteryType, path: &str) -> Result<Self> {
+        inner.load_model(path)?;
+        Ok(Self {
+            inner,
+            lottery_type,
+            calibration: Self::read_calibration(path)?,
+        })
+    }
+
+    // 校准参数所在的文件，与模型文件并排
+    pub fn calibration_path(path: &str) -> String {
+        format!("{}.calibration.json", path)
+    }
+
+    fn read_calibration(path: &str) -> Result<ProbabilityCalibration> {
+        let content = std::fs::read_to_string(Self::calibration_path(path))
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to load calibration: {}", e)
+            ))?;
+        serde_json::from_str(&content)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to deserialize calibration: {}", e)
+            ))
+    }
+}
+
+#[async_trait]
+impl PredictionAlgorithm for CalibratedModel {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn algorithm_type(&self) -> AlgorithmType {
+        self.inner.algorithm_type()
+    }
+
+    // 重新训练后原有的校准不再适用，需要重新调用 CalibratedModel::fit
+    async fn train(
+        &mut self,
+        training_data: &TrainingData,
+        config: &AlgorithmConfig,
+    ) -> Result<f64> {
+        self.inner.train(training_data, config).await
+    }
+
+    async fn predict(
+        &self,
+        input: &PredictionInput,
+    ) -> Result<PredictionOutput> {
+        let mut output = self.inner.predict(input).await?;
+
+        if let Some(probabilities) = output.probabilities.as_mut() {
+            self.calibration.apply(probabilities);
+            output.confidence_scores = probabilities.confidence(
+                &GameSpec::for_type(&self.lottery_type),
+                &output.predicted_numbers,
+            );
+        }
+
+        Ok(output)
+    }
+
+    async fn evaluate(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<EvaluationMetrics> {
+        let scores = self.predict_scores(test_data).await?;
+        MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)
+    }
+
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        let calculator = MetricsCalculator::for_type(&self.lottery_type);
+        Ok(self.inner.predict_scores(test_data).await?
+            .iter()
+            .map(|row| calculator.probabilities(row).into_iter().map(|p| self.calibration.main.apply(p)).collect())
+            .collect())
+    }
+
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        let scores = self.inner.predict_special_scores(test_data).await?;
+        let Some(calibrator) = &self.calibration.special else {
+            return Ok(scores);
+        };
+
+        let spec = GameSpec::for_type(&self.lottery_type);
+        Ok(scores.iter()
+            .map(|row| special_probabilities(&spec, row).into_iter().map(|p| calibrator.apply(p)).collect())
+            .collect())
+    }
+
+    fn is_trained(&self) -> bool {
+        self.inner.is_trained()
+    }
+
+    fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
+        self.inner.get_feature_importance()
+    }
+
+    // 校准参数单独保存在模型文件旁
+    fn save_model(&self, path: &str) -> Result<()> {
+        self.inner.save_model(path)?;
+
+        let serialized = serde_json::to_string(&self.calibration)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to serialize calibration: {}", e)
+            ))?;
+        std::fs::write(Self::calibration_path(path), serialized)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to save calibration: {}", e)
+            ))?;
+
+        Ok(())
+    }
+
+    fn load_model(&mut self, path: &str) -> Result<()> {
+        self.inner.load_model(path)?;
+        self.calibration = Self::read_calibration(path)?;
+        Ok(())
+    }
+
+    fn box_clone(&self) -> Box<dyn PredictionAlgorithm> {
+        Box::new(CalibratedModel {
+            inner: self.inner.box_clone(),
+            lottery_type: self.lottery_type.clone(),
+            calibration: self.calibration.clone(),
+        })
+    }
+}
+
+// rows 与 targets 的最后 rows 期对齐，逐号码标记是否开出
+fn outcome_rows(targets: &[Vec<u32>], rows: usize, numbers: Vec<u32>) -> Result<Vec<Vec<bool>>> {
+    if rows == 0 || rows > targets.len() {
+        return Err(crate::lottery::errors::LotteryError::InvalidParameter(format!(
+            "{} probability rows for {} targets",
+            rows,
+            targets.len()
+        )));
+    }
+
+    Ok(targets[targets.len() - rows..].iter()
+        .map(|target| numbers.iter().map(|n| target.contains(n)).collect())
+        .collect())
+}
+
+// 最终的校准器用全部验证数据拟合；报告中校准后的可靠性图按时间顺序前半段拟合、后半段评估
+fn fit_pool(
+    method: CalibrationMethod,
+    rows: &[Vec<f64>],
+    outcomes: &[Vec<bool>],
+) -> Result<(Calibrator, CalibrationReport)> {
+    let flatten = |rows: &[Vec<f64>], outcomes: &[Vec<bool>]| -> (Vec<f64>, Vec<bool>) {
+        (rows.concat(), outcomes.concat())
+    };
+
+    let (probabilities, drawn) = flatten(rows, outcomes);
+    let calibrator = Calibrator::fit(method, &probabilities, &drawn)?;
+
+    let calibrated = if rows.len() >= 2 {
+        let half = rows.len() / 2;
+        let (fit_probabilities, fit_drawn) = flatten(&rows[..half], &outcomes[..half]);
+        let (held_out, held_out_drawn) = flatten(&rows[half..], &outcomes[half..]);
+        let early = Calibrator::fit(method, &fit_probabilities, &fit_drawn)?;
+        let adjusted: Vec<f64> = held_out.iter().map(|&p| early.apply(p)).collect();
+        Some(ReliabilityDiagram::from_predictions(&adjusted, &held_out_drawn, RELIABILITY_BINS))
+    } else {
+        None
+    };
+
+    Ok((calibrator, CalibrationReport {
+        uncalibrated: ReliabilityDiagram::from_predictions(&probabilities, &drawn, RELIABILITY_BINS),
+        calibrated,
+    }))
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn logit(p: f64) -> f64 {
+    let p = p.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+    (p / (1.0 - p)).ln()
+}
+
+// Platt 原文的目标平滑：正例取 (N+ + 1) / (N+ + 2)，负例取 1 / (N- + 2)，避免完全可分时系数发散
+fn fit_platt(probabilities: &[f64], outcomes: &[bool]) -> Calibrator {
+    let positives = outcomes.iter().filter(|&&y| y).count() as f64;
+    let negatives = outcomes.len() as f64 - positives;
+    let high = (positives + 1.0) / (positives + 2.0);
+    let low = 1.0 / (negatives + 2.0);
+    let samples: Vec<(f64, f64)> = probabilities.iter()
+        .zip(outcomes)
+        .map(|(&p, &y)| (logit(p), if y { high } else { low }))
+        .collect();
+
+    let loss = |slope: f64, intercept: f64| -> f64 {
+        samples.iter()
+            .map(|&(x, t)| {
+                let q = sigmoid(slope * x + intercept).clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+                -(t * q.ln() + (1.0 - t) * (1.0 - q).ln())
+            })
+            .sum()
+    };
+
+    let (mut slope, mut intercept) = (1.0, 0.0);
+    let mut current = loss(slope, intercept);
+
+    for _ in 0..PLATT_MAX_ITERATIONS {
+        let (mut g_slope, mut g_intercept) = (0.0, 0.0);
+        let (mut h_ss, mut h_si, mut h_ii) = (1e-12, 0.0, 1e-12);
+        for &(x, t) in &samples {
+            let q = sigmoid(slope * x + intercept);
+            let w = q * (1.0 - q);
+            g_slope += (q - t) * x;
+            g_intercept += q - t;
+            h_ss += w * x * x;
+            h_si += w * x;
+            h_ii += w;
+        }
+
+        let det = h_ss * h_ii - h_si * h_si;
+        if det.abs() < 1e-300 {
+            break;
+        }
+        let step_slope = (h_ii * g_slope - h_si * g_intercept) / det;
+        let step_intercept = (h_ss * g_intercept - h_si * g_slope) / det;
+
+        // 回溯线搜索保证损失单调下降
+        let mut scale = 1.0;
+        let mut improved = false;
+        while scale > 1e-8 {
+            let candidate = loss(slope - scale * step_slope, intercept - scale * step_intercept);
+            if candidate <= current {
+                slope -= scale * step_slope;
+                intercept -= scale * step_intercept;
+                current = candidate;
+                improved = true;
+                break;
+            }
+            scale /= 2.0;
+        }
+
+        if !improved || (scale * step_slope).abs().max((scale * step_intercept).abs()) < PLATT_TOLERANCE {
+            break;
+        }
+    }
+
+    Calibrator::Platt { slope, intercept }
+}
+
+// Pool-Adjacent-Violators：按预测概率排序后合并违反单调性的相邻块
+fn fit_isotonic(probabilities: &[f64], outcomes: &[bool]) -> Calibrator {
+    let mut order: Vec<usize> = (0..probabilities.len()).collect();
+    order.sort_by(|&a, &b| probabilities[a].total_cmp(&probabilities[b]));
+
+    // (预测概率之和, 开出次数, 样本数)
+    let mut blocks: Vec<(f64, f64, f64)> = Vec::new();
+    for i in order {
+        blocks.push((probabilities[i], if outcomes[i] { 1.0 } else { 0.0 }, 1.0));
+        while blocks.len() >= 2 {
+            let (p2, y2, n2) = blocks[blocks.len() - 1];
+            let (p1, y1, n1) = blocks[blocks.len() - 2];
+            if y1 / n1 < y2 / n2 {
+                break;
+            }
+            blocks.pop();
+            *blocks.last_mut().unwrap() = (p1 + p2, y1 + y2, n1 + n2);
+        }
+    }
+
+    Calibrator::Isotonic {
+        thresholds: blocks.iter().map(|&(p, _, n)| p / n).collect(),
+        values: blocks.iter().map(|&(_, y, n)| y / n).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::algorithms::feature_engineering::LotteryFeatureExtractor;
+    use crate::lottery::algorithms::random_forest::{RandomForestConfig, RandomForestModel};
+    use chrono::NaiveDate;
+
+    // 预测 0.1 / 0.9 的样本实际开出频率分别为 0.3 / 0.7
+    fn overconfident() -> (Vec<f64>, Vec<bool>) {
+        let mut probabilities = Vec::new();
+        let mut outcomes = Vec::new();
+        for i in 0..100 {
+            probabilities.push(0.1);
+            outcomes.push(i < 30);
+            probabilities.push(0.9);
+            outcomes.push(i < 70);
+        }
+        (probabilities, outcomes)
+    }
+
+    #[test]
+    fn test_platt_scaling_shrinks_overconfident_probabilities() {
+        let (probabilities, outcomes) = overconfident();
+        let calibrator = Calibrator::fit(CalibrationMethod::Platt, &probabilities, &outcomes).unwrap();
+
+        assert!((calibrator.apply(0.9) - 0.7).abs() < 0.02);
+        assert!((calibrator.apply(0.1) - 0.3).abs() < 0.02);
+        assert!(calibrator.apply(0.5) > calibrator.apply(0.1));
+    }
+
+    #[test]
+    fn test_isotonic_regression_is_monotone() {
+        let (probabilities, outcomes) = overconfident();
+        let calibrator = Calibrator::fit(CalibrationMethod::Isotonic, &probabilities, &outcomes).unwrap();
+
+        assert!((calibrator.apply(0.9) - 0.7).abs() < 1e-9);
+        assert!((calibrator.apply(0.1) - 0.3).abs() < 1e-9);
+        assert!((calibrator.apply(0.5) - 0.5).abs() < 1e-9);
+
+        // 违反单调性的相邻样本被合并
+        let calibrator = Calibrator::fit(
+            CalibrationMethod::Isotonic,
+            &[0.1, 0.2, 0.3, 0.4],
+            &[false, true, false, true],
+        ).unwrap();
+        let Calibrator::Isotonic { values, .. } = &calibrator else {
+            panic!("expected isotonic calibrator");
+        };
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!((calibrator.apply(0.25) - 0.5).abs() < 1e-9);
+
+        assert!(Calibrator::fit(CalibrationMethod::Isotonic, &[0.1], &[]).is_err());
+    }
+
+    #[test]
+    fn test_reliability_diagram() {
+        let (probabilities, outcomes) = overconfident();
+        let diagram = ReliabilityDiagram::from_predictions(&probabilities, &outcomes, 10);
+
+        assert_eq!(diagram.bins.len(), 10);
+        assert_eq!(diagram.samples, 200);
+        assert_eq!(diagram.bins[1].count, 100);
+        assert_eq!(diagram.bins[9].count, 100);
+        assert!((diagram.bins[1].observed_frequency - 0.3).abs() < 1e-12);
+        assert!((diagram.bins[9].mean_predicted - 0.9).abs() < 1e-12);
+        assert!((diagram.expected_calibration_error - 0.2).abs() < 1e-12);
+        // 每组的平方误差均值都是 0.7 * 0.01 + 0.3 * 0.81
+        assert!((diagram.brier_score - 0.25).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_calibrated_model_outputs_probability_distribution() {
+        let drawings: Vec<LotteryDrawing> = (0..60u32)
+            .map(|i| LotteryDrawing {
+                id: uuid::Uuid::new_v4(),
+                lottery_type: LotteryType::Ssq,
+                draw_number: format!("2024{:03}", i),
+                draw_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(i as i64),
+                draw_time: None,
+                winning_numbers: (0..6).map(|k| (i * 7 + k * 5) % 33 + 1).collect(),
+                special_numbers: Some(vec![i % 5 + 1]),
+                jackpot_amount: None,
+                sales_amount: None,
+                prize_distribution: None,
+                data_source: "test".to_string(),
+                verification_status: "verified".to_string(),
+                metadata: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                crawled_at: None,
+            })
+            .collect();
+        let extractor = LotteryFeatureExtractor;
+        let data = TrainingData {
+            features: (1..drawings.len())
+                .map(|i| extractor.extract_single_features(&drawings[i], &drawings[..i]).unwrap())
+                .collect(),
+            targets: drawings[1..].iter().map(|d| d.winning_numbers.clone()).collect(),
+            special_targets: Some(drawings[1..].iter().map(|d| d.special_numbers.clone().unwrap()).collect()),
+            weights: None,
+        };
+        let (train_set, validation_set) = data.split_at(40);
+
+        let mut forest = RandomForestModel::new(
+            RandomForestConfig { n_estimators: 5, max_depth: Some(4), random_state: Some(7), ..Default::default() },
+            LotteryType::Ssq,
+        );
+        forest.train(&train_set, &AlgorithmConfig::default()).await.unwrap();
+
+        for method in [CalibrationMethod::Platt, CalibrationMethod::Isotonic] {
+            let model = CalibratedModel::fit(forest.box_clone(), LotteryType::Ssq, &validation_set, method).await.unwrap();
+            assert!(model.calibration.special.is_some());
+            assert_eq!(model.calibration.main_reliability.uncalibrated.samples, validation_set.len() * 33);
+            assert!(model.calibration.main_reliability.calibrated.is_some());
+
+            let output = model.predict(&PredictionInput {
+                lottery_type: LotteryType::Ssq,
+                historical_data: drawings.clone(),
+                target_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                additional_features: None,
+            }).await.unwrap();
+
+            let probabilities = output.probabilities.unwrap();
+            assert_eq!(probabilities.calibration, Some(method));
+            assert_eq!(probabilities.main.len(), 33);
+            assert_eq!(probabilities.special.as_ref().map(Vec::len), Some(16));
+            assert!(probabilities.main.iter().all(|p| *p > 0.0 && *p < 1.0));
+            assert!(probabilities.main_reliability.is_some());
+            assert_eq!(output.confidence_scores, probabilities.confidence(&GameSpec::for_type(&LotteryType::Ssq), &output.predicted_numbers));
+        }
+    }
+}
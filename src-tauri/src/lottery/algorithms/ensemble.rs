@@ -0,0 +1,649 @@
+use crate::lottery::algorithms::calibration::{self, NumberProbabilities};
+use crate::lottery::algorithms::metrics::MetricsCalculator;
+use crate::lottery::algorithms::traits::{PredictionAlgorithm, PredictionOutput, TrainingData};
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{LotteryDrawing, LotteryType};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+const GRADIENT_ITERATIONS: usize = 500;
+const GRADIENT_LEARNING_RATE: f64 = 0.5;
+const NELDER_MEAD_ITERATIONS: usize = 200;
+const WEIGHT_TOLERANCE: f64 = 1e-8;
+// 混合概率截断，避免对数似然出现 ln(0)
+const PROBABILITY_EPSILON: f64 = 1e-9;
+// 历史频率只用于特别号投票的平局与无投票时的兜底
+const SPECIAL_FREQUENCY_WEIGHT: f64 = 1e-3;
+
+// 各子模型名称及其逐期的号码打分
+pub type ModelScores = Vec<(String, Vec<Vec<f64>>)>;
+
+// 学习权重时最大化的验证指标
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightObjective {
+    // 逐号码的伯努利对数似然，用指数梯度上升求解
+    #[default]
+    LogLikelihood,
+    // 每注选号的命中率，在对数似然的解附近用 Nelder-Mead 搜索
+    HitRate,
+}
+
+// 子模型权重按玩法名称保存，未单独学习过的玩法使用 default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnsembleWeights {
+    pub default: HashMap<String, f64>,
+    #[serde(default)]
+    pub per_game: HashMap<String, HashMap<String, f64>>,
+}
+
+impl EnsembleWeights {
+    pub fn new(default: HashMap<String, f64>) -> Self {
+        Self {
+            default,
+            per_game: HashMap::new(),
+        }
+    }
+
+    pub fn for_game(&self, lottery_type: &LotteryType) -> &HashMap<String, f64> {
+        self.per_game
+            .get(&lottery_type.to_string())
+            .unwrap_or(&self.default)
+    }
+
+    pub fn set_game(&mut self, lottery_type: &LotteryType, weights: HashMap<String, f64>) {
+        self.per_game.insert(lottery_type.to_string(), weights);
+    }
+}
+
+// 集成预测的组合方式：HybridEnsembleModel、AlgorithmFactory 与 ModelTrainer 共用
+#[derive(Debug, Clone)]
+pub struct EnsembleCombiner {
+    spec: Arc<GameSpec>,
+    calculator: MetricsCalculator,
+}
+
+impl EnsembleCombiner {
+    pub fn for_type(lottery_type: &LotteryType) -> Self {
+        let spec = GameSpec::for_type(lottery_type);
+        Self {
+            calculator: MetricsCalculator::from_spec(spec.clone()),
+            spec,
+        }
+    }
+
+    // 权重取非负值后在参与组合的子模型间归一化；全部为零 (或都未配置) 时等权
+    pub fn normalized_weights(&self, weights: &HashMap<String, f64>, names: &[&str]) -> Vec<f64> {
+        let raw: Vec<f64> = names.iter()
+            .map(|name| weights.get(*name).copied().unwrap_or(0.0).max(0.0))
+            .collect();
+        let total: f64 = raw.iter().sum();
+
+        if total > 0.0 {
+            raw.iter().map(|w| w / total).collect()
+        } else {
+            vec![1.0 / names.len().max(1) as f64; names.len()]
+        }
+    }
+
+    // 各子模型的打分换算为逐号码概率后按权重取混合，结果仍是每个号码开出的概率
+    pub fn combine_scores(
+        &self,
+        weights: &HashMap<String, f64>,
+        model_scores: &[(String, Vec<Vec<f64>>)],
+    ) -> Vec<Vec<f64>> {
+        let names: Vec<&str> = model_scores.iter().map(|(name, _)| name.as_str()).collect();
+        let probabilities: Vec<Vec<Vec<f64>>> = model_scores.iter()
+            .map(|(_, scores)| scores.iter().map(|row| self.calculator.probabilities(row)).collect())
+            .collect();
+        mix(&probabilities, &self.normalized_weights(weights, &names), self.spec.main_pool_size())
+    }
+
+    // 特别号池的打分 (见 collect_special_scores) 按同样的方式混合
+    pub fn combine_special_scores(
+        &self,
+        weights: &HashMap<String, f64>,
+        model_scores: &[(String, Vec<Vec<f64>>)],
+    ) -> Vec<Vec<f64>> {
+        let names: Vec<&str> = model_scores.iter().map(|(name, _)| name.as_str()).collect();
+        let probabilities: Vec<Vec<Vec<f64>>> = model_scores.iter()
+            .map(|(_, scores)| scores.iter().map(|row| calibration::special_probabilities(&self.spec, row)).collect())
+            .collect();
+        mix(&probabilities, &self.normalized_weights(weights, &names), self.spec.special_pool_size())
+    }
+
+    // 按权重混合子模型输出的号码概率分布，没有给出分布的子模型不参与
+    pub fn blend_probabilities(
+        &self,
+        weights: &HashMap<String, f64>,
+        predictions: &[(String, PredictionOutput)],
+    ) -> Option<NumberProbabilities> {
+        let blend = |distributions: Vec<(&str, &Vec<f64>)>, pool_size: usize| -> Option<Vec<f64>> {
+            let distributions: Vec<_> = distributions.into_iter()
+                .filter(|(_, distribution)| distribution.len() == pool_size)
+                .collect();
+            if distributions.is_empty() {
+                return None;
+            }
+
+            let names: Vec<&str> = distributions.iter().map(|(name, _)| *name).collect();
+            let mut blended = vec![0.0; pool_size];
+            for ((_, distribution), weight) in distributions.iter().zip(self.normalized_weights(weights, &names)) {
+                for (value, p) in blended.iter_mut().zip(distribution.iter()) {
+                    *value += weight * p;
+                }
+            }
+            Some(blended)
+        };
+
+        let main = blend(
+            predictions.iter()
+                .filter_map(|(name, prediction)| Some((name.as_str(), &prediction.probabilities.as_ref()?.main)))
+                .collect(),
+            self.spec.main_pool_size(),
+        )?;
+        let special = blend(
+            predictions.iter()
+                .filter_map(|(name, prediction)| Some((name.as_str(), prediction.probabilities.as_ref()?.special.as_ref()?)))
+                .collect(),
+            self.spec.special_pool_size(),
+        );
+
+        Some(NumberProbabilities {
+            main,
+            special: special.filter(|_| self.spec.has_special()),
+            ..Default::default()
+        })
+    }
+
+    // model_scores 的各行与 targets 的尾部对齐 (见 collect_scores)，返回的权重在单纯形上
+    pub fn learn_weights(
+        &self,
+        model_scores: &[(String, Vec<Vec<f64>>)],
+        targets: &[Vec<u32>],
+        objective: WeightObjective,
+    ) -> Result<HashMap<String, f64>> {
+        let rows = model_scores.first().map_or(0, |(_, scores)| scores.len());
+        if rows == 0 || model_scores.iter().any(|(_, scores)| scores.len() != rows) || rows > targets.len() {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "Ensemble weights need aligned, non-empty model scores".to_string()
+            ));
+        }
+
+        let pool_size = self.spec.main_pool_size();
+        let probabilities: Vec<Vec<Vec<f64>>> = model_scores.iter()
+            .map(|(_, scores)| scores.iter().map(|row| self.calculator.probabilities(row)).collect())
+            .collect();
+        let drawn: Vec<HashSet<u32>> = targets[targets.len() - rows..].iter()
+            .map(|target| target.iter().copied().collect())
+            .collect();
+        let outcomes: Vec<Vec<bool>> = drawn.iter()
+            .map(|set| self.spec.main_numbers().map(|number| set.contains(&number)).collect())
+            .collect();
+
+        let mut weights = exponentiated_gradient(&probabilities, &outcomes);
+
+        if objective == WeightObjective::HitRate && weights.len() > 1 {
+            // 在 log 权重上搜索，softmax 保证结果仍在单纯形上
+            let hit_rate = |log_weights: &[f64]| {
+                let combined = mix(&probabilities, &softmax(log_weights), pool_size);
+                self.hit_rate(&combined, &drawn)
+            };
+            let start: Vec<f64> = weights.iter().map(|w| w.max(PROBABILITY_EPSILON).ln()).collect();
+            let best = nelder_mead(|x| -hit_rate(x), start, 1.0, NELDER_MEAD_ITERATIONS);
+            weights = softmax(&best);
+        }
+
+        Ok(model_scores.iter()
+            .map(|(name, _)| name.clone())
+            .zip(weights)
+            .collect())
+    }
+
+    // 每期选出的 pick_count 个号码中被开出的比例
+    pub fn hit_rate(&self, scores: &[Vec<f64>], drawn: &[HashSet<u32>]) -> f64 {
+        if scores.is_empty() {
+            return 0.0;
+        }
+
+        let picks = self.spec.pick_count.max(1) as f64;
+        let total: f64 = scores.iter()
+            .zip(drawn)
+            .map(|(row, set)| {
+                let hits = self.calculator.top_numbers(row, self.spec.pick_count)
+                    .iter()
+                    .filter(|n| set.contains(n))
+                    .count();
+                hits as f64 / picks
+            })
+            .sum();
+        total / scores.len() as f64
+    }
+
+    // 子模型选出的号码按 权重 × 置信度 投票；按位开奖的玩法逐位投票，每位取得票最高的数字
+    pub fn vote_numbers(
+        &self,
+        weights: &HashMap<String, f64>,
+        predictions: &[(String, PredictionOutput)],
+    ) -> (Vec<u32>, Vec<f64>) {
+        let names: Vec<&str> = predictions.iter().map(|(name, _)| name.as_str()).collect();
+        let model_weights = self.normalized_weights(weights, &names);
+
+        if self.spec.ordered {
+            let mut positions: Vec<HashMap<u32, f64>> = vec![HashMap::new(); self.spec.pick_count];
+            for ((_, prediction), weight) in predictions.iter().zip(&model_weights) {
+                for (i, &number) in prediction.predicted_numbers.iter().enumerate().take(self.spec.pick_count) {
+                    *positions[i].entry(number).or_insert(0.0) += weight * number_confidence(prediction, i);
+                }
+            }
+
+            return positions.into_iter()
+                .filter_map(|votes| ranked(votes).into_iter().next())
+                .unzip();
+        }
+
+        let mut votes: HashMap<u32, f64> = HashMap::new();
+        for ((_, prediction), weight) in predictions.iter().zip(&model_weights) {
+            for (i, &number) in prediction.predicted_numbers.iter().enumerate() {
+                *votes.entry(number).or_insert(0.0) += weight * number_confidence(prediction, i);
+            }
+        }
+
+        ranked(votes).into_iter().take(self.spec.pick_count).unzip()
+    }
+
+    // 子模型的特别号按权重投票，历史开奖中的出现频率只作平局与兜底；特别号取自主号码池时排除已选主号码
+    pub fn vote_special_numbers(
+        &self,
+        weights: &HashMap<String, f64>,
+        predictions: &[(String, PredictionOutput)],
+        historical_data: &[LotteryDrawing],
+        main_numbers: &[u32],
+    ) -> Option<Vec<u32>> {
+        if !self.spec.has_special() {
+            return None;
+        }
+
+        let excluded: HashSet<u32> = if self.spec.special_from_main_pool {
+            main_numbers.iter().copied().collect()
+        } else {
+            HashSet::new()
+        };
+        let eligible = |number: u32| self.spec.is_valid_special(number) && !excluded.contains(&number);
+
+        let names: Vec<&str> = predictions.iter().map(|(name, _)| name.as_str()).collect();
+        let mut votes: HashMap<u32, f64> = HashMap::new();
+        for ((_, prediction), weight) in predictions.iter().zip(self.normalized_weights(weights, &names)) {
+            for &number in prediction.predicted_special_numbers.iter().flatten() {
+                if eligible(number) {
+                    *votes.entry(number).or_insert(0.0) += weight;
+                }
+            }
+        }
+
+        let history: Vec<u32> = historical_data.iter()
+            .flat_map(|drawing| drawing.special_numbers.iter().flatten().copied())
+            .filter(|&number| eligible(number))
+            .collect();
+        for &number in &history {
+            *votes.entry(number).or_insert(0.0) += SPECIAL_FREQUENCY_WEIGHT / history.len() as f64;
+        }
+
+        let special_numbers: Vec<u32> = ranked(votes).into_iter()
+            .take(self.spec.special_count)
+            .map(|(number, _)| number)
+            .collect();
+        (!special_numbers.is_empty()).then_some(special_numbers)
+    }
+
+    pub fn combine_predictions(
+        &self,
+        weights: &HashMap<String, f64>,
+        predictions: &[(String, PredictionOutput)],
+        historical_data: &[LotteryDrawing],
+    ) -> PredictionOutput {
+        let (predicted_numbers, mut confidence_scores) = self.vote_numbers(weights, predictions);
+        let predicted_special_numbers =
+            self.vote_special_numbers(weights, predictions, historical_data, &predicted_numbers);
+
+        // 子模型给出概率分布时，置信度取混合分布中所选号码的开出概率
+        let probabilities = self.blend_probabilities(weights, predictions);
+        if let Some(probabilities) = &probabilities {
+            confidence_scores = probabilities.confidence(&self.spec, &predicted_numbers);
+        }
+
+        let names: Vec<&str> = predictions.iter().map(|(name, _)| name.as_str()).collect();
+        let used_weights: HashMap<&str, f64> = names.iter()
+            .copied()
+            .zip(self.normalized_weights(weights, &names))
+            .collect();
+
+        PredictionOutput {
+            predicted_numbers,
+            predicted_special_numbers,
+            confidence_scores,
+            algorithm_metadata: HashMap::from_iter(vec![
+                ("method".to_string(), serde_json::Value::String("ensemble".to_string())),
+                ("ensemble_weights".to_string(), serde_json::json!(used_weights)),
+            ]),
+            computation_time_ms: predictions.iter()
+                .map(|(_, prediction)| prediction.computation_time_ms)
+                .sum(),
+            probabilities,
+        }
+    }
+}
+
+// 各子模型的打分与 test_data 的尾部对齐，只保留共同覆盖的期数；打分失败或为空的子模型被跳过
+pub async fn collect_scores(
+    models: &[(String, &dyn PredictionAlgorithm)],
+    test_data: &TrainingData,
+) -> Result<ModelScores> {
+    collect(models, test_data, false).await
+}
+
+// 同 collect_scores，收集特别号池的打分
+pub async fn collect_special_scores(
+    models: &[(String, &dyn PredictionAlgorithm)],
+    test_data: &TrainingData,
+) -> Result<ModelScores> {
+    collect(models, test_data, true).await
+}
+
+async fn collect(
+    models: &[(String, &dyn PredictionAlgorithm)],
+    test_data: &TrainingData,
+    special: bool,
+) -> Result<ModelScores> {
+    let mut collected = Vec::new();
+
+    for (name, model) in models {
+        let scores = if special {
+            model.predict_special_scores(test_data).await
+        } else {
+            model.predict_scores(test_data).await
+        };
+        match scores {
+            Ok(scores) if !scores.is_empty() => collected.push((name.clone(), scores)),
+            Ok(_) => {}
+            Err(e) => eprintln!("Model {} failed: {}", name, e),
+        }
+    }
+
+    let Some(len) = collected.iter().map(|(_, scores)| scores.len()).min() else {
+        return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+            "No models provided predictions".to_string()
+        ));
+    };
+
+    for (_, scores) in &mut collected {
+        scores.drain(..scores.len() - len);
+    }
+
+    Ok(collected)
+}
+
+fn number_confidence(prediction: &PredictionOutput, index: usize) -> f64 {
+    prediction.confidence_scores.get(index).copied().unwrap_or_else(|| {
+        let scores = &prediction.confidence_scores;
+        if scores.is_empty() {
+            1.0
+        } else {
+            scores.iter().sum::<f64>() / scores.len() as f64
+        }
+    })
+}
+
+// 按得票降序，同票时取较小的号码
+fn ranked(votes: HashMap<u32, f64>) -> Vec<(u32, f64)> {
+    let mut ranked: Vec<(u32, f64)> = votes.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked
+}
+
+fn mix(probabilities: &[Vec<Vec<f64>>], weights: &[f64], pool_size: usize) -> Vec<Vec<f64>> {
+    let rows = probabilities.first().map_or(0, Vec::len);
+    let mut combined = vec![vec![0.0; pool_size]; rows];
+
+    for (model, &weight) in probabilities.iter().zip(weights) {
+        for (row, model_row) in combined.iter_mut().zip(model) {
+            for (value, p) in row.iter_mut().zip(model_row) {
+                *value += weight * p;
+            }
+        }
+    }
+
+    combined
+}
+
+// 混合概率的对数似然对权重是凹函数，乘性更新保持权重非负且和为 1
+fn exponentiated_gradient(probabilities: &[Vec<Vec<f64>>], outcomes: &[Vec<bool>]) -> Vec<f64> {
+    let n_models = probabilities.len();
+    let pool_size = outcomes.first().map_or(0, Vec::len);
+    let mut weights = vec![1.0 / n_models as f64; n_models];
+
+    for _ in 0..GRADIENT_ITERATIONS {
+        let combined = mix(probabilities, &weights, pool_size);
+        let mut gradient = vec![0.0; n_models];
+
+        for (r, row) in combined.iter().enumerate() {
+            for (k, &p) in row.iter().enumerate() {
+                let p = p.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+                let slope = if outcomes[r][k] { 1.0 / p } else { -1.0 / (1.0 - p) };
+                for (g, model) in gradient.iter_mut().zip(probabilities) {
+                    *g += model[r][k] * slope;
+                }
+            }
+        }
+
+        // 梯度按期数取平均，减去最大值避免指数溢出
+        let steps: Vec<f64> = gradient.iter()
+            .map(|g| GRADIENT_LEARNING_RATE * g / combined.len() as f64)
+            .collect();
+        let max_step = steps.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mut updated: Vec<f64> = weights.iter()
+            .zip(&steps)
+            .map(|(w, step)| w * (step - max_step).exp())
+            .collect();
+        let total: f64 = updated.iter().sum();
+        updated.iter_mut().for_each(|w| *w /= total);
+
+        let change = updated.iter()
+            .zip(&weights)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        weights = updated;
+        if change < WEIGHT_TOLERANCE {
+            break;
+        }
+    }
+
+    weights
+}
+
+fn softmax(values: &[f64]) -> Vec<f64> {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let exp: Vec<f64> = values.iter().map(|v| (v - max).exp()).collect();
+    let total: f64 = exp.iter().sum();
+    exp.into_iter().map(|v| v / total).collect()
+}
+
+// 求 f 的最小值；初始单纯形由 start 沿各坐标轴偏移 step 构成，返回最优顶点
+fn nelder_mead(f: impl Fn(&[f64]) -> f64, start: Vec<f64>, step: f64, iterations: usize) -> Vec<f64> {
+    let n = start.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+    simplex.push((start.clone(), f(&start)));
+    for i in 0..n {
+        let mut vertex = start.clone();
+        vertex[i] += step;
+        let value = f(&vertex);
+        simplex.push((vertex, value));
+    }
+
+    let towards = |from: &[f64], to: &[f64], t: f64| -> Vec<f64> {
+        from.iter().zip(to).map(|(a, b)| a + t * (b - a)).collect()
+    };
+
+    for _ in 0..iterations {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        // 单纯形收缩到一点即停止；命中率是分段常数，不能只看函数值的差
+        let size = simplex.iter()
+            .flat_map(|(vertex, _)| vertex.iter().zip(&simplex[0].0).map(|(a, b)| (a - b).abs()))
+            .fold(0.0, f64::max);
+        if size < WEIGHT_TOLERANCE {
+            break;
+        }
+
+        let centroid: Vec<f64> = (0..n)
+            .map(|j| simplex[..n].iter().map(|(v, _)| v[j]).sum::<f64>() / n as f64)
+            .collect();
+        let worst = simplex[n].clone();
+
+        let reflected = towards(&centroid, &worst.0, -1.0);
+        let reflected_value = f(&reflected);
+
+        if reflected_value < simplex[0].1 {
+            let expanded = towards(&centroid, &worst.0, -2.0);
+            let expanded_value = f(&expanded);
+            simplex[n] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[n - 1].1 {
+            simplex[n] = (reflected, reflected_value);
+        } else {
+            let contracted = towards(&centroid, &worst.0, 0.5);
+            let contracted_value = f(&contracted);
+            if contracted_value < worst.1 {
+                simplex[n] = (contracted, contracted_value);
+            } else {
+                let best = simplex[0].0.clone();
+                for vertex in simplex.iter_mut().skip(1) {
+                    let shrunk = towards(&best, &vertex.0, 0.5);
+                    let value = f(&shrunk);
+                    *vertex = (shrunk, value);
+                }
+            }
+        }
+    }
+
+    simplex.into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(vertex, _)| vertex)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::algorithms::test_support::create_drawing;
+
+    // 只有特别号不同的历史开奖，用于特别号的频率兜底
+    fn history(specials: &[u32]) -> Vec<LotteryDrawing> {
+        specials.iter()
+            .enumerate()
+            .map(|(i, &special)| create_drawing(LotteryType::Ssq, i as u32, vec![1, 2, 3, 4, 5, 6], Some(vec![special])))
+            .collect()
+    }
+
+    fn output(numbers: Vec<u32>, specials: Option<Vec<u32>>, confidence: f64) -> PredictionOutput {
+        PredictionOutput {
+            confidence_scores: vec![confidence; numbers.len()],
+            predicted_numbers: numbers,
+            predicted_special_numbers: specials,
+            algorithm_metadata: HashMap::new(),
+            computation_time_ms: 1,
+            probabilities: None,
+        }
+    }
+
+    // informative 总是把当期开出的号码排在前面，noise 给出与开奖无关的打分
+    fn scored_models(rows: usize) -> (ModelScores, Vec<Vec<u32>>) {
+        let mut informative = Vec::new();
+        let mut noise = Vec::new();
+        let mut targets = Vec::new();
+
+        for r in 0..rows {
+            let target: Vec<u32> = (0..6).map(|i| ((r * 7 + i * 5) % 33) as u32 + 1).collect();
+            informative.push((1..=33u32).map(|n| if target.contains(&n) { 3.0 } else { 1.0 }).collect());
+            noise.push((1..=33u32).map(|n| ((n as usize * 13 + r * 3) % 11) as f64 + 1.0).collect());
+            targets.push(target);
+        }
+
+        (vec![("informative".to_string(), informative), ("noise".to_string(), noise)], targets)
+    }
+
+    #[test]
+    fn test_learn_weights_prefers_informative_model() {
+        let combiner = EnsembleCombiner::for_type(&LotteryType::Ssq);
+        let (model_scores, targets) = scored_models(20);
+
+        let weights = combiner.learn_weights(&model_scores, &targets, WeightObjective::LogLikelihood).unwrap();
+        assert!((weights.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(weights["informative"] > 0.9);
+
+        // 命中率目标从对数似然的解出发，不会比它差
+        let drawn: Vec<HashSet<u32>> = targets.iter().map(|t| t.iter().copied().collect()).collect();
+        let hit_weights = combiner.learn_weights(&model_scores, &targets, WeightObjective::HitRate).unwrap();
+        assert!((hit_weights.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        let rate = |w: &HashMap<String, f64>| combiner.hit_rate(&combiner.combine_scores(w, &model_scores), &drawn);
+        assert!(rate(&hit_weights) >= rate(&weights));
+        assert!((rate(&hit_weights) - 1.0).abs() < 1e-12);
+
+        assert!(combiner.learn_weights(&[], &targets, WeightObjective::LogLikelihood).is_err());
+    }
+
+    #[test]
+    fn test_per_game_weights() {
+        let mut weights = EnsembleWeights::new(HashMap::from([("statistical".to_string(), 1.0)]));
+        weights.set_game(&LotteryType::Dlt, HashMap::from([("random_forest".to_string(), 1.0)]));
+
+        assert_eq!(weights.for_game(&LotteryType::Ssq), &weights.default);
+        assert_eq!(weights.for_game(&LotteryType::Dlt)["random_forest"], 1.0);
+        assert!(weights.per_game.contains_key("dlt"));
+
+        // 未配置权重的子模型不参与；全部未配置时等权
+        let combiner = EnsembleCombiner::for_type(&LotteryType::Ssq);
+        assert_eq!(combiner.normalized_weights(&weights.default, &["statistical", "lstm"]), vec![1.0, 0.0]);
+        assert_eq!(combiner.normalized_weights(&HashMap::new(), &["a", "b"]), vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_combine_predictions_votes_special_numbers() {
+        let combiner = EnsembleCombiner::for_type(&LotteryType::Ssq);
+        let weights = HashMap::from([("a".to_string(), 3.0), ("b".to_string(), 1.0)]);
+        let predictions = vec![
+            ("a".to_string(), output(vec![1, 2, 3, 4, 5, 6], Some(vec![7]), 0.5)),
+            ("b".to_string(), output(vec![1, 2, 3, 10, 11, 12], Some(vec![9]), 0.5)),
+        ];
+
+        let combined = combiner.combine_predictions(&weights, &predictions, &history(&[9, 9]));
+        assert_eq!(combined.predicted_numbers[..3], [1, 2, 3]);
+        assert_eq!(combined.predicted_numbers.len(), 6);
+        assert!((combined.confidence_scores[0] - 0.5).abs() < 1e-12);
+        assert_eq!(combined.predicted_special_numbers, Some(vec![7]));
+
+        // 子模型都不给特别号时按历史频率兜底
+        let no_specials = vec![("a".to_string(), output(vec![1, 2, 3, 4, 5, 6], None, 0.5))];
+        let combined = combiner.combine_predictions(&weights, &no_specials, &history(&[9, 12, 12]));
+        assert_eq!(combined.predicted_special_numbers, Some(vec![12]));
+    }
+
+    #[test]
+    fn test_ordered_games_vote_by_position() {
+        let combiner = EnsembleCombiner::for_type(&LotteryType::Fc3d);
+        let predictions = vec![
+            ("a".to_string(), output(vec![1, 1, 2], None, 0.6)),
+            ("b".to_string(), output(vec![1, 3, 2], None, 0.4)),
+        ];
+
+        let (numbers, confidence) = combiner.vote_numbers(&HashMap::new(), &predictions);
+        assert_eq!(numbers, vec![1, 1, 2]);
+        assert!((confidence[0] - 0.5).abs() < 1e-12);
+        assert!((confidence[1] - 0.3).abs() < 1e-12);
+        assert_eq!(combiner.vote_special_numbers(&HashMap::new(), &predictions, &[], &numbers), None);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::lottery::algorithms::traits::{FeatureConfig, FeatureExtractor, TrainingData};
 use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::games::GameSpec;
 use crate::lottery::models::LotteryDrawing;
 use crate::lottery::models::LotteryType;
 use chrono::Datelike;
@@ -66,11 +67,12 @@ impl FeatureExtractor for LotteryFeatureExtractor {
     ) -> Result<Vec<f64>> {
         let mut features = Vec::new();
         let lottery_type = &drawing.lottery_type;
+        let min_number = self.get_min_number(lottery_type);
         let max_number = self.get_max_number(lottery_type);
-        let special_max = self.get_special_max(lottery_type);
+        let spec = GameSpec::for_type(lottery_type);
 
         // 1. Frequency analysis
-        features.extend(self.calculate_frequency_features(historical_data, max_number)?);
+        features.extend(self.calculate_frequency_features(historical_data, min_number, max_number)?);
 
         // 2. Trend analysis
         features.extend(self.calculate_trend_features(historical_data, max_number)?);
@@ -79,16 +81,16 @@ impl FeatureExtractor for LotteryFeatureExtractor {
         features.extend(self.calculate_statistical_features(historical_data)?);
 
         // 4. Pattern analysis
-        features.extend(self.calculate_pattern_features(historical_data)?);
+        features.extend(self.calculate_pattern_features(historical_data, spec.ordered)?);
 
         // 5. Temporal analysis
         features.extend(self.calculate_temporal_features(drawing)?);
 
         // 6. Hot and cold numbers
-        features.extend(self.calculate_hot_cold_features(historical_data, max_number)?);
+        features.extend(self.calculate_hot_cold_features(historical_data, min_number, max_number)?);
 
         // 7. Gap analysis
-        features.extend(self.calculate_gap_features(historical_data, max_number)?);
+        features.extend(self.calculate_gap_features(historical_data, min_number, max_number)?);
 
         // 8. Sum analysis
         features.extend(self.calculate_sum_features(historical_data)?);
@@ -97,51 +99,54 @@ impl FeatureExtractor for LotteryFeatureExtractor {
         features.extend(self.calculate_parity_features(historical_data)?);
 
         // 10. Special numbers features
-        features.extend(self.calculate_special_features(historical_data, special_max)?);
+        features.extend(self.calculate_special_features(historical_data, spec.special_numbers())?);
+
+        // 11. Positional analysis for ordered games
+        features.extend(self.calculate_positional_features(historical_data, &spec)?);
 
         Ok(features)
     }
 
-    fn get_feature_names(&self) -> Vec<String> {
+    // 与 extract_single_features 的输出逐项对应
+    fn get_feature_names(&self, lottery_type: &LotteryType) -> Vec<String> {
+        let spec = GameSpec::for_type(lottery_type);
         let mut names = Vec::new();
-        
+
         // Frequency features
-        for i in 1..=50 {
-            names.push(format!("freq_{}", i));
-        }
-        
+        names.extend(spec.main_numbers().map(|n| format!("freq_{}", n)));
+
         // Trend features
-        names.extend(vec![
-            "trend_up".to_string(),
-            "trend_down".to_string(),
-            "trend_stable".to_string(),
-        ]);
-        
+        names.extend(["trend_up", "trend_down", "trend_stable"].map(String::from));
+
         // Statistical features
-        names.extend(vec![
-            "mean".to_string(),
-            "std".to_string(),
-            "min".to_string(),
-            "max".to_string(),
-            "median".to_string(),
-        ]);
-        
+        names.extend(["mean", "std", "min", "max", "median"].map(String::from));
+
         // Pattern features
-        names.extend(vec![
-            "consecutive_count".to_string(),
-            "odd_count".to_string(),
-            "even_count".to_string(),
-            "prime_count".to_string(),
-        ]);
-        
+        names.extend(["consecutive_count", "odd_count", "even_count", "prime_count"].map(String::from));
+
         // Temporal features
-        names.extend(vec![
-            "day_of_week".to_string(),
-            "day_of_month".to_string(),
-            "month".to_string(),
-            "is_weekend".to_string(),
-        ]);
-        
+        names.extend(["day_of_week", "day_of_month", "month", "is_weekend"].map(String::from));
+
+        // Hot/cold and gap features
+        names.extend(spec.main_numbers().map(|n| format!("hot_cold_{}", n)));
+        names.extend(spec.main_numbers().map(|n| format!("gap_{}", n)));
+
+        // Sum and parity features
+        names.extend(["sum_mean", "sum_std", "sum_last", "odd_ratio", "even_ratio"].map(String::from));
+
+        // Special numbers features
+        if let Some(special_numbers) = spec.special_numbers() {
+            names.extend(special_numbers.map(|n| format!("special_freq_{}", n)));
+        }
+
+        // Positional features
+        if spec.ordered {
+            names.extend((1..=spec.main_count).map(|position| format!("position_{}_mean", position)));
+            if spec.allow_repeats {
+                names.push("repeat_ratio".to_string());
+            }
+        }
+
         names
     }
 
@@ -162,31 +167,22 @@ impl FeatureExtractor for LotteryFeatureExtractor {
 }
 
 impl LotteryFeatureExtractor {
+    fn get_min_number(&self, lottery_type: &LotteryType) -> usize {
+        GameSpec::for_type(lottery_type).main_min() as usize
+    }
+
     fn get_max_number(&self, lottery_type: &LotteryType) -> usize {
-        match lottery_type {
-            LotteryType::Ssq => 33,
-            LotteryType::Dlt => 35,
-            LotteryType::Fc3d => 9,
-            LotteryType::Pl3 => 9,
-            LotteryType::Pl5 => 9,
-            LotteryType::Custom => 49,
-        }
+        GameSpec::for_type(lottery_type).main_max() as usize
     }
 
     fn get_special_max(&self, lottery_type: &LotteryType) -> usize {
-        match lottery_type {
-            LotteryType::Ssq => 16,
-            LotteryType::Dlt => 12,
-            LotteryType::Fc3d => 0,
-            LotteryType::Pl3 => 0,
-            LotteryType::Pl5 => 0,
-            LotteryType::Custom => 16,
-        }
+        GameSpec::for_type(lottery_type).special_max() as usize
     }
 
     fn calculate_frequency_features(
         &self,
         historical_data: &[LotteryDrawing],
+        min_number: usize,
         max_number: usize,
     ) -> Result<Vec<f64>> {
         let mut frequencies = vec![0.0; max_number + 1];
@@ -201,11 +197,11 @@ impl LotteryFeatureExtractor {
 
         // Normalize frequencies
         let total = historical_data.len() as f64;
-        for freq in &mut frequencies[1..] {
+        for freq in &mut frequencies[min_number..] {
             *freq /= total;
         }
 
-        Ok(frequencies[1..].to_vec())
+        Ok(frequencies[min_number..].to_vec())
     }
 
     fn calculate_trend_features(
@@ -277,14 +273,18 @@ impl LotteryFeatureExtractor {
         Ok(vec![mean, std, min, max, median])
     }
 
-    fn calculate_pattern_features(&self, historical_data: &[LotteryDrawing]) -> Result<Vec<f64>> {
+    fn calculate_pattern_features(&self, historical_data: &[LotteryDrawing], ordered: bool) -> Result<Vec<f64>> {
         let mut consecutive_count = 0.0;
         let mut odd_count = 0.0;
         let mut even_count = 0.0;
         let mut prime_count = 0.0;
 
         for drawing in historical_data {
-            let numbers = &drawing.winning_numbers;
+            // 按位开奖的玩法保留开奖顺序，其余玩法按大小排序后再判断连号
+            let mut numbers = drawing.winning_numbers.clone();
+            if !ordered {
+                numbers.sort_unstable();
+            }
             
             // Check for consecutive numbers
             for window in numbers.windows(2) {
@@ -294,7 +294,7 @@ impl LotteryFeatureExtractor {
             }
             
             // Count odd, even, and prime numbers
-            for &number in numbers {
+            for &number in &numbers {
                 if number % 2 == 1 {
                     odd_count += 1.0;
                 } else {
@@ -328,6 +328,7 @@ impl LotteryFeatureExtractor {
     fn calculate_hot_cold_features(
         &self,
         historical_data: &[LotteryDrawing],
+        min_number: usize,
         max_number: usize,
     ) -> Result<Vec<f64>> {
         let mut hot_numbers = vec![0.0; max_number + 1];
@@ -378,9 +379,9 @@ impl LotteryFeatureExtractor {
             }
         }
 
-        Ok(hot_numbers[1..]
+        Ok(hot_numbers[min_number..]
             .iter()
-            .zip(cold_numbers[1..].iter())
+            .zip(cold_numbers[min_number..].iter())
             .map(|(h, c)| h - c)
             .collect())
     }
@@ -388,6 +389,7 @@ impl LotteryFeatureExtractor {
     fn calculate_gap_features(
         &self,
         historical_data: &[LotteryDrawing],
+        min_number: usize,
         max_number: usize,
     ) -> Result<Vec<f64>> {
         let mut last_seen = vec![historical_data.len() as f64; max_number + 1];
@@ -406,12 +408,12 @@ impl LotteryFeatureExtractor {
         // Normalize gaps
         let max_gap = gap_features.iter().cloned().fold(0.0, f64::max);
         if max_gap > 0.0 {
-            for gap in &mut gap_features[1..] {
+            for gap in &mut gap_features[min_number..] {
                 *gap /= max_gap;
             }
         }
 
-        Ok(gap_features[1..].to_vec())
+        Ok(gap_features[min_number..].to_vec())
     }
 
     fn calculate_sum_features(&self, historical_data: &[LotteryDrawing]) -> Result<Vec<f64>> {
@@ -454,19 +456,20 @@ impl LotteryFeatureExtractor {
     fn calculate_special_features(
         &self,
         historical_data: &[LotteryDrawing],
-        special_max: usize,
+        special_range: Option<std::ops::RangeInclusive<u32>>,
     ) -> Result<Vec<f64>> {
-        if special_max == 0 {
+        let Some(special_range) = special_range else {
             return Ok(Vec::new());
-        }
+        };
 
-        let mut special_frequencies = vec![0.0; special_max + 1];
+        let special_min = *special_range.start();
+        let mut special_frequencies = vec![0.0; special_range.clone().count()];
 
         for drawing in historical_data {
             if let Some(ref specials) = drawing.special_numbers {
                 for &special in specials {
-                    if special <= special_max as u32 {
-                        special_frequencies[special as usize] += 1.0;
+                    if special_range.contains(&special) {
+                        special_frequencies[(special - special_min) as usize] += 1.0;
                     }
                 }
             }
@@ -475,12 +478,48 @@ impl LotteryFeatureExtractor {
         // Normalize
         let total = historical_data.len() as f64;
         if total > 0.0 {
-            for freq in &mut special_frequencies[1..] {
+            for freq in &mut special_frequencies {
                 *freq /= total;
             }
         }
 
-        Ok(special_frequencies[1..].to_vec())
+        Ok(special_frequencies)
+    }
+
+    // 按位开奖的玩法每个位置独立：输出各位置的平均值 (按号码范围归一化) 以及同期重复号码的比例
+    fn calculate_positional_features(
+        &self,
+        historical_data: &[LotteryDrawing],
+        spec: &GameSpec,
+    ) -> Result<Vec<f64>> {
+        if !spec.ordered {
+            return Ok(Vec::new());
+        }
+
+        let span = (spec.main_max() - spec.main_min()).max(1) as f64;
+        let mut position_sums = vec![0.0; spec.main_count];
+        let mut repeat_count = 0.0;
+
+        for drawing in historical_data {
+            for (position, &number) in drawing.winning_numbers.iter().take(spec.main_count).enumerate() {
+                position_sums[position] += number.saturating_sub(spec.main_min()) as f64 / span;
+            }
+
+            let mut distinct = drawing.winning_numbers.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            if distinct.len() < drawing.winning_numbers.len() {
+                repeat_count += 1.0;
+            }
+        }
+
+        let total = historical_data.len().max(1) as f64;
+        let mut features: Vec<f64> = position_sums.into_iter().map(|sum| sum / total).collect();
+        if spec.allow_repeats {
+            features.push(repeat_count / total);
+        }
+
+        Ok(features)
     }
 
     fn calculate_average(&self, numbers: &[u32]) -> f64 {
@@ -571,4 +610,65 @@ mod tests {
         let features = extractor.extract_single_features(&drawing, &historical_data).unwrap();
         assert!(!features.is_empty());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_positional_features_for_ordered_games() {
+        let extractor = LotteryFeatureExtractor;
+        let spec = GameSpec::for_type(&LotteryType::Fc3d);
+        let drawing = LotteryDrawing {
+            id: Uuid::new_v4(),
+            lottery_type: LotteryType::Fc3d,
+            draw_number: "2024001".to_string(),
+            draw_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            draw_time: None,
+            winning_numbers: vec![9, 0, 9],
+            special_numbers: None,
+            jackpot_amount: None,
+            sales_amount: None,
+            prize_distribution: None,
+            data_source: "test".to_string(),
+            verification_status: "verified".to_string(),
+            metadata: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            crawled_at: None,
+        };
+
+        let features = extractor.calculate_positional_features(&[drawing], &spec).unwrap();
+        assert_eq!(features, vec![1.0, 0.0, 1.0, 1.0]);
+
+        let ssq = GameSpec::for_type(&LotteryType::Ssq);
+        assert!(extractor.calculate_positional_features(&[], &ssq).unwrap().is_empty());
+    }
+    #[test]
+    fn test_feature_names_match_extracted_features() {
+        let extractor = LotteryFeatureExtractor;
+
+        for lottery_type in [LotteryType::Ssq, LotteryType::Dlt, LotteryType::Fc3d, LotteryType::Pl5] {
+            let spec = GameSpec::for_type(&lottery_type);
+            let drawing = LotteryDrawing {
+                id: Uuid::new_v4(),
+                lottery_type: lottery_type.clone(),
+                draw_number: "2024001".to_string(),
+                draw_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                draw_time: None,
+                winning_numbers: spec.main_numbers().take(spec.main_count).collect(),
+                special_numbers: spec.special_numbers().map(|numbers| numbers.take(spec.special_count).collect()),
+                jackpot_amount: None,
+                sales_amount: None,
+                prize_distribution: None,
+                data_source: "test".to_string(),
+                verification_status: "verified".to_string(),
+                metadata: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                crawled_at: None,
+            };
+
+            let features = extractor.extract_single_features(&drawing, &vec![drawing.clone(); 5]).unwrap();
+            let names = extractor.get_feature_names(&lottery_type);
+            assert_eq!(names.len(), features.len(), "{:?}", lottery_type);
+            assert_eq!(names[0], format!("freq_{}", spec.main_min()));
+        }
+    }
+}
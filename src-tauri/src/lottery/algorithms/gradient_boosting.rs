@@ -0,0 +1,766 @@
+use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::{self, NumberProbabilities};
+use crate::lottery::algorithms::feature_engineering::LotteryFeatureExtractor;
+use crate::lottery::algorithms::metrics::MetricsCalculator;
+use crate::lottery::algorithms::random_forest::{label_matrix, parallel_map, DecisionTree, TreeNode};
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryType};
+use async_trait::async_trait;
+use ndarray::{Array2, ArrayView1};
+use rand::prelude::*;
+use rand::seq::index;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::thread;
+
+// 预测元数据中列出的重要特征个数
+const REPORTED_FEATURES: usize = 10;
+// 初始对数几率中的基础概率和二阶导的下限
+const PROBABILITY_EPSILON: f64 = 1e-6;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GradientBoostingConfig {
+    // 每个号码最多的提升轮数
+    pub n_estimators: usize,
+    // 收缩系数，每棵树的输出乘以该值后累加
+    pub learning_rate: f64,
+    pub max_depth: usize,
+    pub min_samples_leaf: usize,
+    // 叶节点取值和分裂增益中的 L2 正则
+    pub l2_regularization: f64,
+    // 每轮不放回抽取的训练样本比例，取值 (0, 1]
+    pub subsample: f64,
+    // 按时间顺序留出最后这一比例的样本做早停验证，取值 (0, 1)，仅在 n_iter_no_change 设置时生效
+    pub validation_fraction: f64,
+    // 验证集对数损失连续这么多轮没有下降就停止，为 None 时训满 n_estimators 轮
+    pub n_iter_no_change: Option<usize>,
+    pub random_state: Option<u64>,
+    // 并行训练的线程数，默认使用全部可用核心
+    pub n_jobs: Option<usize>,
+}
+
+impl Default for GradientBoostingConfig {
+    fn default() -> Self {
+        Self {
+            n_estimators: 100,
+            learning_rate: 0.1,
+            max_depth: 3,
+            min_samples_leaf: 5,
+            l2_regularization: 1.0,
+            subsample: 0.8,
+            validation_fraction: 0.2,
+            n_iter_no_change: Some(10),
+            random_state: Some(42),
+            n_jobs: None,
+        }
+    }
+}
+
+impl ModelConfig for GradientBoostingConfig {
+    fn validate(&self) -> Result<()> {
+        ensure_config(self.n_estimators > 0, "n_estimators must be positive")?;
+        ensure_config(
+            self.learning_rate > 0.0 && self.learning_rate.is_finite(),
+            "learning_rate must be positive",
+        )?;
+        ensure_config(self.max_depth > 0, "max_depth must be positive")?;
+        ensure_config(self.min_samples_leaf > 0, "min_samples_leaf must be positive")?;
+        ensure_config(
+            self.l2_regularization >= 0.0 && self.l2_regularization.is_finite(),
+            "l2_regularization must be non-negative",
+        )?;
+        ensure_config(self.subsample > 0.0 && self.subsample <= 1.0, "subsample must be in (0, 1]")?;
+        ensure_config(
+            self.n_iter_no_change.is_none()
+                || (self.validation_fraction > 0.0 && self.validation_fraction < 1.0),
+            "validation_fraction must be in (0, 1)",
+        )?;
+        ensure_config(self.n_iter_no_change != Some(0), "n_iter_no_change must be positive")?;
+        ensure_config(self.n_jobs != Some(0), "n_jobs must be positive")
+    }
+}
+
+// 单个号码的提升模型，对数几率为 init + learning_rate * Σ trees
+// 树复用随机森林的结构：叶节点 value 为 Newton 步长，gini 记录节点得分 G²/(H+λ)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NumberBooster {
+    pub init: f64,
+    pub trees: Vec<DecisionTree>,
+    // 早停选中的轮数在验证集上的平均对数损失，不早停时为 None
+    pub validation_loss: Option<f64>,
+}
+
+impl NumberBooster {
+    fn probability(&self, features: ArrayView1<f64>, learning_rate: f64) -> f64 {
+        let raw = self.init + learning_rate * self.trees.iter().map(|tree| tree.predict(features)).sum::<f64>();
+        sigmoid(raw)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradientBoostingModel {
+    // 主号码池和特别号码池中每个号码一个提升模型，按号码升序排列
+    pub main_boosters: Vec<NumberBooster>,
+    pub special_boosters: Vec<NumberBooster>,
+    pub config: GradientBoostingConfig,
+    #[serde(default)]
+    pub base_config: GradientBoostingConfig,
+    pub feature_names: Vec<String>,
+    // 各分裂的增益按特征累加，归一化到总和为 1
+    pub feature_importance: Vec<f64>,
+    // 主号码验证集对数损失的平均值
+    pub validation_loss: Option<f64>,
+    pub is_trained: bool,
+    pub lottery_type: LotteryType,
+}
+
+struct BoosterFit {
+    booster: NumberBooster,
+    importance: Vec<f64>,
+}
+
+struct Split {
+    feature_index: usize,
+    threshold: f64,
+    gain: f64,
+}
+
+impl GradientBoostingModel {
+    pub fn new(config: GradientBoostingConfig, lottery_type: LotteryType) -> Self {
+        Self {
+            main_boosters: Vec::new(),
+            special_boosters: Vec::new(),
+            base_config: config.clone(),
+            config,
+            feature_names: Vec::new(),
+            feature_importance: Vec::new(),
+            validation_loss: None,
+            is_trained: false,
+            lottery_type,
+        }
+    }
+
+    // 早停后各号码实际保留的轮数
+    pub fn boosting_rounds(&self) -> Vec<usize> {
+        self.main_boosters.iter().map(|booster| booster.trees.len()).collect()
+    }
+
+    // 按重要性降序取前 REPORTED_FEATURES 个特征
+    fn top_features(&self) -> Vec<(String, f64)> {
+        let mut ranked: Vec<(String, f64)> = self.feature_names.iter().cloned()
+            .zip(self.feature_importance.iter().copied())
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(REPORTED_FEATURES);
+        ranked
+    }
+
+    fn prepare_data(&self, training_data: &TrainingData) -> Result<Array2<f64>> {
+        if training_data.features.is_empty() {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "No training data provided".to_string()
+            ));
+        }
+
+        Array2::from_shape_vec(
+            (training_data.features.len(), training_data.features[0].len()),
+            training_data.features.iter().flatten().cloned().collect()
+        ).map_err(|_| crate::lottery::errors::LotteryError::AlgorithmError(
+            "Failed to create feature matrix".to_string()
+        ))
+    }
+
+    fn check_feature_dimension(&self, len: usize) -> Result<()> {
+        if len != self.feature_names.len() {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(format!(
+                "Expected {} features, got {}",
+                self.feature_names.len(),
+                len
+            )));
+        }
+        Ok(())
+    }
+
+    fn has_special_boosters(&self) -> bool {
+        let spec = GameSpec::for_type(&self.lottery_type);
+        spec.has_special() && self.special_boosters.len() == spec.special_pool_size()
+    }
+
+    fn n_jobs(&self) -> usize {
+        self.config.n_jobs
+            .unwrap_or_else(|| thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+    }
+
+    // 早停时最后 validation_fraction 的样本不参与训练，两侧都至少保留一期
+    fn training_length(&self, n_samples: usize) -> usize {
+        if self.config.n_iter_no_change.is_none() || n_samples < 2 {
+            return n_samples;
+        }
+
+        let validation = (self.config.validation_fraction * n_samples as f64).round() as usize;
+        n_samples - validation.clamp(1, n_samples - 1)
+    }
+
+    // 前 train_len 行训练，其余行只用于早停
+    fn fit_booster(&self, features: &Array2<f64>, labels: &[bool], train_len: usize, seed: u64) -> BoosterFit {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let learning_rate = self.config.learning_rate;
+        let y: Vec<f64> = labels.iter().map(|&label| if label { 1.0 } else { 0.0 }).collect();
+
+        let base_rate = (y[..train_len].iter().sum::<f64>() / train_len as f64)
+            .clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+        let init = (base_rate / (1.0 - base_rate)).ln();
+
+        let validation = train_len..labels.len();
+        let patience = self.config.n_iter_no_change.filter(|_| !validation.is_empty());
+        let sample_size = ((self.config.subsample * train_len as f64).round() as usize).clamp(1, train_len);
+
+        // 所有行（含验证集）的当前对数几率
+        let mut raw = vec![init; labels.len()];
+        let mut trees = Vec::new();
+        let mut tree_importance = Vec::new();
+        let mut best_loss = patience.map(|_| log_loss(&raw[validation.clone()], &y[validation.clone()]));
+        let mut best_rounds = 0;
+
+        for _ in 0..self.config.n_estimators {
+            // 对数损失的一阶导 p - y 和二阶导 p(1 - p)
+            let gradients: Vec<(f64, f64)> = raw.iter()
+                .zip(&y)
+                .map(|(&f, &target)| {
+                    let p = sigmoid(f);
+                    (p - target, (p * (1.0 - p)).max(PROBABILITY_EPSILON))
+                })
+                .collect();
+
+            let mut indices = index::sample(&mut rng, train_len, sample_size).into_vec();
+            indices.sort_unstable();
+
+            let mut importance = vec![0.0; features.ncols()];
+            let tree = DecisionTree {
+                root: Some(self.build_tree_node(features, &gradients, &indices, 0, &mut importance)),
+                max_depth: self.config.max_depth,
+                min_samples_split: 2 * self.config.min_samples_leaf.max(1),
+                min_samples_leaf: self.config.min_samples_leaf,
+            };
+
+            for (i, value) in raw.iter_mut().enumerate() {
+                *value += learning_rate * tree.predict(features.row(i));
+            }
+            trees.push(tree);
+            tree_importance.push(importance);
+
+            let (Some(patience), Some(best)) = (patience, best_loss) else {
+                best_rounds = trees.len();
+                continue;
+            };
+            let loss = log_loss(&raw[validation.clone()], &y[validation.clone()]);
+            if loss < best - 1e-12 {
+                best_loss = Some(loss);
+                best_rounds = trees.len();
+            } else if trees.len() - best_rounds >= patience {
+                break;
+            }
+        }
+
+        // 丢弃最优轮之后的树
+        trees.truncate(best_rounds);
+        let mut importance = vec![0.0; features.ncols()];
+        for gains in &tree_importance[..best_rounds] {
+            for (total, gain) in importance.iter_mut().zip(gains) {
+                *total += gain;
+            }
+        }
+
+        BoosterFit {
+            booster: NumberBooster { init, trees, validation_loss: best_loss },
+            importance,
+        }
+    }
+
+    fn build_tree_node(
+        &self,
+        features: &Array2<f64>,
+        gradients: &[(f64, f64)],
+        indices: &[usize],
+        depth: usize,
+        importance: &mut [f64],
+    ) -> Box<TreeNode> {
+        let lambda = self.config.l2_regularization;
+        let (g, h) = indices.iter().fold((0.0, 0.0), |(g, h), &i| (g + gradients[i].0, h + gradients[i].1));
+        let score = g * g / (h + lambda);
+
+        let min_samples_leaf = self.config.min_samples_leaf.max(1);
+        let split = if depth < self.config.max_depth && indices.len() >= 2 * min_samples_leaf {
+            self.find_best_split(features, gradients, indices, score)
+        } else {
+            None
+        };
+
+        let Some(split) = split else {
+            return Box::new(TreeNode {
+                feature_index: None,
+                threshold: None,
+                left: None,
+                right: None,
+                value: Some(-g / (h + lambda)),
+                samples: indices.len(),
+                gini: score,
+            });
+        };
+
+        importance[split.feature_index] += split.gain;
+
+        let (left_indices, right_indices): (Vec<usize>, Vec<usize>) = indices.iter()
+            .partition(|&&i| features[[i, split.feature_index]] <= split.threshold);
+
+        let left = self.build_tree_node(features, gradients, &left_indices, depth + 1, importance);
+        let right = self.build_tree_node(features, gradients, &right_indices, depth + 1, importance);
+
+        Box::new(TreeNode {
+            feature_index: Some(split.feature_index),
+            threshold: Some(split.threshold),
+            left: Some(left),
+            right: Some(right),
+            value: None,
+            samples: indices.len(),
+            gini: score,
+        })
+    }
+
+    // 在所有特征排序后的相邻取值中点上穷举阈值，增益为 (G_L²/(H_L+λ) + G_R²/(H_R+λ) - G²/(H+λ)) / 2
+    fn find_best_split(
+        &self,
+        features: &Array2<f64>,
+        gradients: &[(f64, f64)],
+        indices: &[usize],
+        score: f64,
+    ) -> Option<Split> {
+        let lambda = self.config.l2_regularization;
+        let samples = indices.len();
+        let min_samples_leaf = self.config.min_samples_leaf.max(1);
+        let (g, h) = indices.iter().fold((0.0, 0.0), |(g, h), &i| (g + gradients[i].0, h + gradients[i].1));
+
+        let mut best: Option<Split> = None;
+
+        for feature_index in 0..features.ncols() {
+            let mut values: Vec<(f64, f64, f64)> = indices.iter()
+                .map(|&i| (features[[i, feature_index]], gradients[i].0, gradients[i].1))
+                .collect();
+            values.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let (mut left_g, mut left_h) = (0.0, 0.0);
+            for left_len in 1..samples {
+                left_g += values[left_len - 1].1;
+                left_h += values[left_len - 1].2;
+
+                if left_len < min_samples_leaf || samples - left_len < min_samples_leaf {
+                    continue;
+                }
+                // 相同取值无法被阈值分开
+                if values[left_len].0 <= values[left_len - 1].0 {
+                    continue;
+                }
+
+                let (right_g, right_h) = (g - left_g, h - left_h);
+                let gain = 0.5 * (left_g * left_g / (left_h + lambda) + right_g * right_g / (right_h + lambda) - score);
+
+                if gain > best.as_ref().map_or(1e-12, |split| split.gain) {
+                    best = Some(Split {
+                        feature_index,
+                        threshold: (values[left_len - 1].0 + values[left_len].0) / 2.0,
+                        gain,
+                    });
+                }
+            }
+        }
+
+        best
+    }
+
+    // special 为 true 时对应特别号码池
+    fn number_probabilities(&self, features: ArrayView1<f64>, special: bool) -> Vec<f64> {
+        let boosters = if special { &self.special_boosters } else { &self.main_boosters };
+        boosters.iter()
+            .map(|booster| booster.probability(features, self.config.learning_rate))
+            .collect()
+    }
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn log_loss(raw: &[f64], y: &[f64]) -> f64 {
+    let total: f64 = raw.iter()
+        .zip(y)
+        .map(|(&f, &target)| {
+            let p = sigmoid(f).clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON);
+            -(target * p.ln() + (1.0 - target) * (1.0 - p).ln())
+        })
+        .sum();
+    total / raw.len().max(1) as f64
+}
+
+#[async_trait]
+impl PredictionAlgorithm for GradientBoostingModel {
+    fn name(&self) -> String {
+        "Gradient Boosting".to_string()
+    }
+
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::GradientBoosting
+    }
+
+    async fn train(
+        &mut self,
+        training_data: &TrainingData,
+        config: &AlgorithmConfig,
+    ) -> Result<f64> {
+        self.config = config.apply(&self.base_config)?;
+
+        let features = self.prepare_data(training_data)?;
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let main_labels = label_matrix(&training_data.targets, spec.main_numbers());
+        let special_labels = match (spec.special_numbers(), &training_data.special_targets) {
+            (Some(numbers), Some(special_targets)) if special_targets.len() == training_data.targets.len() => {
+                label_matrix(special_targets, numbers)
+            }
+            _ => Vec::new(),
+        };
+
+        let train_len = self.training_length(features.nrows());
+        let seed = self.config.random_state.unwrap_or_else(rand::random);
+        let n_main = main_labels.len();
+
+        // 每个号码使用独立的种子，结果与线程数无关
+        let mut fits = parallel_map(n_main + special_labels.len(), self.n_jobs(), |k| {
+            let labels = if k < n_main { &main_labels[k] } else { &special_labels[k - n_main] };
+            self.fit_booster(&features, labels, train_len, seed.wrapping_add(k as u64))
+        });
+        let special_fits = fits.split_off(n_main);
+
+        let mut importance = vec![0.0; features.ncols()];
+        for fit in fits.iter().chain(&special_fits) {
+            for (total, gain) in importance.iter_mut().zip(&fit.importance) {
+                *total += gain;
+            }
+        }
+        let total_importance: f64 = importance.iter().sum();
+        if total_importance > 0.0 {
+            for value in &mut importance {
+                *value /= total_importance;
+            }
+        }
+        self.feature_importance = importance;
+
+        self.main_boosters = fits.into_iter().map(|fit| fit.booster).collect();
+        self.special_boosters = special_fits.into_iter().map(|fit| fit.booster).collect();
+
+        let losses: Vec<f64> = self.main_boosters.iter().filter_map(|booster| booster.validation_loss).collect();
+        self.validation_loss = (!losses.is_empty()).then(|| losses.iter().sum::<f64>() / losses.len() as f64);
+
+        // 特征维度与特征提取器一致时使用真实特征名
+        let names = LotteryFeatureExtractor.get_feature_names(&self.lottery_type);
+        self.feature_names = if names.len() == features.ncols() {
+            names
+        } else {
+            (0..features.ncols()).map(|i| format!("feature_{}", i)).collect()
+        };
+
+        self.is_trained = true;
+
+        let metrics = self.evaluate(training_data).await?;
+        Ok(metrics.accuracy)
+    }
+
+    async fn predict(
+        &self,
+        input: &PredictionInput,
+    ) -> Result<PredictionOutput> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        let start_time = std::time::Instant::now();
+
+        let Some((latest, history)) = input.historical_data.split_last() else {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "No historical data provided".to_string()
+            ));
+        };
+
+        let features = LotteryFeatureExtractor.extract_single_features(latest, history)?;
+        self.check_feature_dimension(features.len())?;
+        let features = ArrayView1::from(&features);
+
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let probabilities = self.number_probabilities(features, false);
+        let predicted_numbers = MetricsCalculator::from_spec(spec.clone()).top_numbers(&probabilities, spec.pick_count);
+
+        // 训练数据没有特别号码时按历史出现次数打分
+        let special_scores = spec.has_special().then(|| if self.has_special_boosters() {
+            self.number_probabilities(features, true)
+        } else {
+            calibration::history_special_scores(&spec, &input.historical_data)
+        });
+
+        let predicted_special_numbers = special_scores.as_ref()
+            .map(|scores| calibration::top_special_numbers(&spec, scores));
+
+        let probabilities = NumberProbabilities::from_scores(&spec, &probabilities, special_scores.as_deref());
+        let confidence_scores = probabilities.confidence(&spec, &predicted_numbers);
+
+        let mut algorithm_metadata = HashMap::new();
+        algorithm_metadata.insert("validation_loss".to_string(), serde_json::json!(self.validation_loss));
+        algorithm_metadata.insert("boosting_rounds".to_string(), serde_json::json!(self.boosting_rounds()));
+        algorithm_metadata.insert("learning_rate".to_string(), serde_json::json!(self.config.learning_rate));
+        algorithm_metadata.insert("gain_importance".to_string(), serde_json::json!(self.top_features()));
+
+        let computation_time = start_time.elapsed().as_millis() as u64;
+
+        Ok(PredictionOutput {
+            predicted_numbers,
+            predicted_special_numbers,
+            confidence_scores,
+            algorithm_metadata,
+            computation_time_ms: computation_time,
+            probabilities: Some(probabilities),
+        })
+    }
+
+    async fn evaluate(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<EvaluationMetrics> {
+        let scores = self.predict_scores(test_data).await?;
+        let mut metrics = MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)?;
+        metrics.feature_importance = self.get_feature_importance();
+        Ok(metrics)
+    }
+
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        test_data.features.iter()
+            .map(|features| {
+                self.check_feature_dimension(features.len())?;
+                Ok(self.number_probabilities(ArrayView1::from(features), false))
+            })
+            .collect()
+    }
+
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+        if !self.has_special_boosters() {
+            return calibration::frequency_special_scores(&self.lottery_type, test_data);
+        }
+
+        test_data.features.iter()
+            .map(|features| {
+                self.check_feature_dimension(features.len())?;
+                Ok(self.number_probabilities(ArrayView1::from(features), true))
+            })
+            .collect()
+    }
+
+    fn is_trained(&self) -> bool {
+        self.is_trained
+    }
+
+    fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
+        if self.feature_importance.is_empty() {
+            return None;
+        }
+
+        Some(self.feature_names.iter().cloned().zip(self.feature_importance.iter().copied()).collect())
+    }
+
+    fn save_model(&self, path: &str) -> Result<()> {
+        let serialized = serde_json::to_string(self)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to serialize model: {}", e)
+            ))?;
+
+        fs::write(path, serialized)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to save model: {}", e)
+            ))?;
+
+        Ok(())
+    }
+
+    fn load_model(&mut self, path: &str) -> Result<()> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to load model: {}", e)
+            ))?;
+
+        let model: GradientBoostingModel = serde_json::from_str(&content)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to deserialize model: {}", e)
+            ))?;
+
+        *self = model;
+        Ok(())
+    }
+
+    fn box_clone(&self) -> Box<dyn PredictionAlgorithm> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::algorithms::random_forest::{RandomForestConfig, RandomForestModel};
+    use crate::lottery::algorithms::test_support::{create_drawing, signal_training_data};
+    use crate::lottery::models::LotteryDrawing;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_gradient_boosting_creation() {
+        let model = GradientBoostingModel::new(GradientBoostingConfig::default(), LotteryType::Ssq);
+        assert_eq!(model.name(), "Gradient Boosting");
+        assert_eq!(model.algorithm_type(), AlgorithmType::GradientBoosting);
+        assert!(!model.is_trained());
+    }
+
+    #[tokio::test]
+    async fn test_boosting_learns_signal_per_number() {
+        let config = GradientBoostingConfig {
+            n_estimators: 30,
+            n_iter_no_change: None,
+            ..Default::default()
+        };
+        let mut model = GradientBoostingModel::new(config, LotteryType::Ssq);
+        let training_data = signal_training_data(80);
+        model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+
+        let spec = GameSpec::for_type(&LotteryType::Ssq);
+        assert_eq!(model.main_boosters.len(), spec.main_pool_size());
+        assert_eq!(model.special_boosters.len(), spec.special_pool_size());
+        assert!(model.boosting_rounds().iter().all(|&rounds| rounds == 30));
+        assert!(model.validation_loss.is_none());
+
+        let on = ArrayView1::from(&[1.0, 0.5, 0.5, 0.5, 0.5, 0.5]);
+        let off = ArrayView1::from(&[0.0, 0.5, 0.5, 0.5, 0.5, 0.5]);
+        let main_on = model.number_probabilities(on, false);
+        let main_off = model.number_probabilities(off, false);
+        assert!(main_on[6] > 0.9 && main_off[6] < 0.1);
+        assert!(main_on[7] < 0.1 && main_off[7] > 0.9);
+
+        // 特别号码来自特别号码池的提升模型
+        let special_on = model.number_probabilities(on, true);
+        assert!(special_on[11] > special_on[2]);
+
+        // 增益全部落在 signal 特征上
+        let importance = model.get_feature_importance().unwrap();
+        assert!(importance["feature_0"] > 0.9);
+        let total: f64 = model.feature_importance.iter().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_early_stopping_on_noise() {
+        // 号码 7 随机开出，与特征无关，验证损失很快不再下降
+        let mut rng = StdRng::seed_from_u64(3);
+        let training_data = TrainingData {
+            features: (0..100).map(|_| (0..4).map(|_| rng.r#gen::<f64>()).collect()).collect(),
+            targets: (0..100)
+                .map(|_| {
+                    let mut target = vec![1, 2, 3, 4, 5];
+                    target.push(if rng.gen_bool(0.5) { 7 } else { 8 });
+                    target
+                })
+                .collect(),
+            special_targets: None,
+            weights: None,
+        };
+
+        let config = GradientBoostingConfig {
+            n_estimators: 200,
+            learning_rate: 0.3,
+            n_iter_no_change: Some(5),
+            ..Default::default()
+        };
+        let mut model = GradientBoostingModel::new(config, LotteryType::Ssq);
+        model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+
+        assert!(model.boosting_rounds()[6] < 200);
+        assert!(model.validation_loss.is_some());
+        assert!(model.special_boosters.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_gradient_boosting_prediction_matches_forest_features() {
+        let drawings: Vec<LotteryDrawing> = (0..12)
+            .map(|i| create_drawing(LotteryType::Ssq, i, vec![1, 2, 3, 4, 5, 6], Some(vec![10])))
+            .collect();
+
+        let extractor = LotteryFeatureExtractor;
+        let mut training_data = TrainingData {
+            features: Vec::new(),
+            targets: Vec::new(),
+            special_targets: Some(Vec::new()),
+            weights: None,
+        };
+        for (i, drawing) in drawings.iter().enumerate().skip(1) {
+            training_data.features.push(extractor.extract_single_features(drawing, &drawings[..i]).unwrap());
+            training_data.targets.push(drawing.winning_numbers.clone());
+            training_data.special_targets.as_mut().unwrap().push(vec![10]);
+        }
+
+        let config = GradientBoostingConfig {
+            n_estimators: 10,
+            min_samples_leaf: 1,
+            n_iter_no_change: None,
+            ..Default::default()
+        };
+        let mut boosting = GradientBoostingModel::new(config, LotteryType::Ssq);
+        boosting.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+        assert_eq!(boosting.feature_names, extractor.get_feature_names(&LotteryType::Ssq));
+
+        // 同一份特征上训练的随机森林可以直接对比
+        let mut forest = RandomForestModel::new(
+            RandomForestConfig { n_estimators: 5, max_depth: Some(3), ..Default::default() },
+            LotteryType::Ssq,
+        );
+        forest.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+
+        let input = PredictionInput {
+            lottery_type: LotteryType::Ssq,
+            historical_data: drawings,
+            target_date: NaiveDate::from_ymd_opt(2024, 1, 13).unwrap(),
+            additional_features: None,
+        };
+
+        let output = boosting.predict(&input).await.unwrap();
+        assert_eq!(output.predicted_numbers, forest.predict(&input).await.unwrap().predicted_numbers);
+        assert_eq!(output.predicted_numbers, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(output.predicted_special_numbers, Some(vec![10]));
+        assert!(output.algorithm_metadata.contains_key("gain_importance"));
+
+        let probabilities = output.probabilities.unwrap();
+        assert_eq!(probabilities.main.len(), 33);
+        assert_eq!(probabilities.special.map(|special| special.len()), Some(16));
+    }
+}
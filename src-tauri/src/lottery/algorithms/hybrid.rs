@@ -1,20 +1,41 @@
 use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::NumberProbabilities;
+use crate::lottery::algorithms::ensemble::{self, EnsembleCombiner, WeightObjective};
+use crate::lottery::algorithms::feature_engineering::LotteryFeatureExtractor;
+use crate::lottery::algorithms::metrics::MetricsCalculator;
+use crate::lottery::algorithms::model_trainer::TimeSeriesSplit;
 use crate::lottery::algorithms::random_forest::RandomForestModel;
-use crate::lottery::algorithms::neural_network::NeuralNetworkModel;
+use crate::lottery::algorithms::neural_network::{NeuralNetworkConfig, NeuralNetworkModel};
 use crate::lottery::algorithms::lstm::LstmModel;
 use crate::lottery::algorithms::arima::ArimaModel;
 use crate::lottery::algorithms::statistical::StatisticalModel;
 use crate::lottery::errors::LotteryResult as Result;
-use crate::lottery::models::LotteryType;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryType};
 use async_trait::async_trait;
+use ndarray::{Array1, Array2, s};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+// 训练数据末尾留出的比例，集成指标只在这部分数据上计算
+const HOLDOUT_FRACTION: f64 = 0.2;
+const META_MAX_ITERATIONS: usize = 100;
+// 逻辑回归元学习器系数的 L2 正则强度，截距不参与正则
+const META_L2_PENALTY: f64 = 1.0;
+// 元学习器输入 logit 前对概率的裁剪
+const META_PROBABILITY_EPSILON: f64 = 1e-6;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HybridConfig {
     pub ensemble_weights: HashMap<String, f64>,
+    // 按玩法名称学习到的权重，优先于 ensemble_weights
+    #[serde(default)]
+    pub game_weights: HashMap<String, HashMap<String, f64>>,
+    #[serde(default)]
+    pub weight_objective: WeightObjective,
     pub voting_method: String, // "weighted", "majority", "consensus"
     pub confidence_threshold: f64,
+    // 子模型折外预测的相关性惩罚：越冗余的子模型在元学习器中受到越强的 L2 约束，0 表示不惩罚
     pub diversity_weight: f64,
     pub ensemble_size: usize,
     pub cross_validation_folds: usize,
@@ -25,14 +46,16 @@ pub struct HybridConfig {
 impl Default for HybridConfig {
     fn default() -> Self {
         let mut ensemble_weights = HashMap::new();
-        ensemble_weights.insert("random_forest".to_string(), 0.25);
-        ensemble_weights.insert("neural_network".to_string(), 0.20);
-        ensemble_weights.insert("lstm".to_string(), 0.20);
-        ensemble_weights.insert("arima".to_string(), 0.15);
-        ensemble_weights.insert("statistical".to_string(), 0.20);
+        ensemble_weights.insert(AlgorithmType::RandomForest.to_string(), 0.25);
+        ensemble_weights.insert(AlgorithmType::NeuralNetwork.to_string(), 0.20);
+        ensemble_weights.insert(AlgorithmType::Lstm.to_string(), 0.20);
+        ensemble_weights.insert(AlgorithmType::Arima.to_string(), 0.15);
+        ensemble_weights.insert(AlgorithmType::Statistical.to_string(), 0.20);
         
         Self {
             ensemble_weights,
+            game_weights: HashMap::new(),
+            weight_objective: WeightObjective::default(),
             voting_method: "weighted".to_string(),
             confidence_threshold: 0.7,
             diversity_weight: 0.1,
@@ -44,17 +67,64 @@ impl Default for HybridConfig {
     }
 }
 
+impl ModelConfig for HybridConfig {
+    fn validate(&self) -> Result<()> {
+        ensure_config(
+            self.ensemble_weights.values().all(|weight| *weight >= 0.0 && weight.is_finite()),
+            "ensemble_weights must be non-negative",
+        )?;
+        ensure_config(
+            matches!(self.voting_method.as_str(), "weighted" | "majority" | "consensus"),
+            "voting_method must be one of weighted, majority, consensus",
+        )?;
+        ensure_config((0.0..=1.0).contains(&self.confidence_threshold), "confidence_threshold must be in [0, 1]")?;
+        ensure_config(
+            self.diversity_weight >= 0.0 && self.diversity_weight.is_finite(),
+            "diversity_weight must be non-negative",
+        )?;
+        ensure_config(self.ensemble_size > 0, "ensemble_size must be positive")?;
+        ensure_config(self.cross_validation_folds > 0, "cross_validation_folds must be positive")?;
+        ensure_config(
+            !self.use_meta_learner || matches!(self.meta_learner_type.as_str(), "linear" | "neural" | "stacking"),
+            "meta_learner_type must be one of linear, neural, stacking",
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct HybridEnsembleModel {
     pub config: HybridConfig,
+    pub base_config: HybridConfig,
     pub models: HashMap<String, Box<dyn PredictionAlgorithm>>,
-    pub meta_learner: Option<Box<dyn PredictionAlgorithm>>,
+    pub meta_learner: Option<MetaLearner>,
+    // 参与堆叠的子模型，顺序即元学习器的输入顺序
+    pub stacked_models: Vec<String>,
+    // 多样性惩罚得到的各子模型输入缩放系数，与 stacked_models 对应
+    pub diversity_scales: Vec<f64>,
+    // 各子模型在留出数据上的准确率
     pub model_accuracies: HashMap<String, f64>,
+    // 集成在留出数据上的评估结果
+    pub validation_metrics: Option<EvaluationMetrics>,
     pub is_trained: bool,
     pub lottery_type: LotteryType,
     pub ensemble_history: Vec<EnsemblePrediction>,
 }
 
+// 在子模型折外概率上训练的元学习器。逻辑回归的输入为各子模型对同一号码的 logit，参数在所有号码间共享；
+// 神经网络的输入为一期内所有子模型、所有号码的 logit，直接输出每个号码的概率
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MetaLearner {
+    Logistic { coefficients: Vec<f64>, intercept: f64 },
+    Neural(Box<NeuralNetworkModel>),
+}
+
+// 按时间顺序拼接各折测试集上的子模型打分，scores 与 models 一一对应，每个元素的行与 targets 对齐
+struct OutOfFold {
+    models: Vec<String>,
+    scores: Vec<Vec<Vec<f64>>>,
+    targets: Vec<Vec<u32>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnsemblePrediction {
     pub algorithm: String,
@@ -64,6 +134,14 @@ pub struct EnsemblePrediction {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+impl HybridConfig {
+    pub fn weights_for(&self, lottery_type: &LotteryType) -> &HashMap<String, f64> {
+        self.game_weights
+            .get(&lottery_type.to_string())
+            .unwrap_or(&self.ensemble_weights)
+    }
+}
+
 impl HybridEnsembleModel {
     pub fn new(config: HybridConfig, lottery_type: LotteryType) -> Self {
         let mut models = HashMap::new();
@@ -75,23 +153,37 @@ impl HybridEnsembleModel {
         let arima_model = ArimaModel::new(Default::default(), lottery_type.clone());
         let stat_model = StatisticalModel::new(Default::default(), lottery_type.clone());
         
-        models.insert("random_forest".to_string(), Box::new(rf_model) as Box<dyn PredictionAlgorithm>);
-        models.insert("neural_network".to_string(), Box::new(nn_model) as Box<dyn PredictionAlgorithm>);
-        models.insert("lstm".to_string(), Box::new(lstm_model) as Box<dyn PredictionAlgorithm>);
-        models.insert("arima".to_string(), Box::new(arima_model) as Box<dyn PredictionAlgorithm>);
-        models.insert("statistical".to_string(), Box::new(stat_model) as Box<dyn PredictionAlgorithm>);
+        models.insert(AlgorithmType::RandomForest.to_string(), Box::new(rf_model) as Box<dyn PredictionAlgorithm>);
+        models.insert(AlgorithmType::NeuralNetwork.to_string(), Box::new(nn_model) as Box<dyn PredictionAlgorithm>);
+        models.insert(AlgorithmType::Lstm.to_string(), Box::new(lstm_model) as Box<dyn PredictionAlgorithm>);
+        models.insert(AlgorithmType::Arima.to_string(), Box::new(arima_model) as Box<dyn PredictionAlgorithm>);
+        models.insert(AlgorithmType::Statistical.to_string(), Box::new(stat_model) as Box<dyn PredictionAlgorithm>);
         
         Self {
+            base_config: config.clone(),
             config,
             models,
             meta_learner: None,
+            stacked_models: Vec::new(),
+            diversity_scales: Vec::new(),
             model_accuracies: HashMap::new(),
+            validation_metrics: None,
             is_trained: false,
             lottery_type,
             ensemble_history: Vec::new(),
         }
     }
 
+    fn weights(&self) -> &HashMap<String, f64> {
+        self.config.weights_for(&self.lottery_type)
+    }
+
+    fn model_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.models.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
     async fn train_individual_models(
         &mut self,
         training_data: &TrainingData,
@@ -102,13 +194,188 @@ impl HybridEnsembleModel {
         for (name, model) in &mut self.models {
             if let Ok(accuracy) = model.train(training_data, config).await {
                 accuracies.insert(name.clone(), accuracy);
-                self.model_accuracies.insert(name.clone(), accuracy);
             }
         }
         
         Ok(accuracies)
     }
 
+    // 每折复制训练开始时的子模型，在训练折上训练、在测试折上打分；任一折失败的子模型不参与堆叠
+    async fn out_of_fold_scores(
+        &self,
+        training_data: &TrainingData,
+        config: &AlgorithmConfig,
+    ) -> OutOfFold {
+        let names = self.model_names();
+        let splits = TimeSeriesSplit::new(self.config.cross_validation_folds).splits(training_data.len());
+        let mut folds = Vec::with_capacity(splits.len());
+        let mut failed = vec![false; names.len()];
+
+        for (train_range, test_range) in splits {
+            let train_fold = training_data.slice(train_range);
+            let test_fold = training_data.slice(test_range);
+            let mut fold_scores = vec![Vec::new(); names.len()];
+
+            for (i, name) in names.iter().enumerate() {
+                if failed[i] {
+                    continue;
+                }
+
+                let mut model = self.models[name].box_clone();
+                let scores = match model.train(&train_fold, config).await {
+                    Ok(_) => model.predict_scores(&test_fold).await,
+                    Err(e) => Err(e),
+                };
+                match scores {
+                    Ok(scores) if !scores.is_empty() => fold_scores[i] = scores,
+                    Ok(_) => failed[i] = true,
+                    Err(e) => {
+                        eprintln!("Model {} failed during stacking: {}", name, e);
+                        failed[i] = true;
+                    }
+                }
+            }
+
+            folds.push((fold_scores, test_fold.targets));
+        }
+
+        let kept: Vec<usize> = (0..names.len()).filter(|&i| !failed[i]).collect();
+        let mut oof = OutOfFold {
+            models: kept.iter().map(|&i| names[i].clone()).collect(),
+            scores: vec![Vec::new(); kept.len()],
+            targets: Vec::new(),
+        };
+
+        // 序列模型在折首几期不产生打分，每折只保留所有子模型共同覆盖的尾部
+        for (fold_scores, targets) in folds {
+            let Some(len) = kept.iter().map(|&i| fold_scores[i].len()).min() else {
+                break;
+            };
+            for (scores, &i) in oof.scores.iter_mut().zip(&kept) {
+                scores.extend_from_slice(&fold_scores[i][fold_scores[i].len() - len..]);
+            }
+            oof.targets.extend_from_slice(&targets[targets.len() - len..]);
+        }
+
+        oof
+    }
+
+    async fn fit_meta_learner(
+        &mut self,
+        oof: &OutOfFold,
+        config: &AlgorithmConfig,
+    ) -> Result<()> {
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let calculator = MetricsCalculator::from_spec(spec.clone());
+
+        let mut logits = stack_logits(&calculator, &oof.scores);
+        let scales = diversity_scales(&logits, self.config.diversity_weight);
+        for (mut column, &scale) in logits.columns_mut().into_iter().zip(&scales) {
+            column *= scale;
+        }
+
+        let meta_learner = match self.config.meta_learner_type.as_str() {
+            "neural" => {
+                let meta_data = TrainingData {
+                    features: draw_features(&logits, spec.main_pool_size()),
+                    targets: oof.targets.clone(),
+                    special_targets: None,
+                    weights: None,
+                };
+                let mut network = NeuralNetworkModel::new(
+                    NeuralNetworkConfig {
+                        hidden_layers: vec![32],
+                        ..Default::default()
+                    },
+                    self.lottery_type.clone(),
+                );
+                network.train(&meta_data, config).await?;
+                MetaLearner::Neural(Box::new(network))
+            }
+            _ => {
+                let drawn: Vec<bool> = oof.targets.iter()
+                    .flat_map(|target| spec.main_numbers().map(move |number| target.contains(&number)))
+                    .collect();
+                let (coefficients, intercept) = fit_logistic(&logits, &drawn, META_L2_PENALTY)?;
+                MetaLearner::Logistic { coefficients, intercept }
+            }
+        };
+
+        self.meta_learner = Some(meta_learner);
+        self.stacked_models = oof.models.clone();
+        self.diversity_scales = scales;
+        Ok(())
+    }
+
+    // 未训练的子模型被跳过，其余的打分与 test_data 尾部对齐
+    async fn collect_scores(
+        &self,
+        names: &[String],
+        test_data: &TrainingData,
+    ) -> Result<Vec<(String, Vec<Vec<f64>>)>> {
+        ensemble::collect_scores(&self.trained_models(names), test_data).await
+    }
+
+    fn trained_models(&self, names: &[String]) -> Vec<(String, &dyn PredictionAlgorithm)> {
+        names.iter()
+            .filter_map(|name| {
+                self.models.get(name)
+                    .filter(|model| model.is_trained())
+                    .map(|model| (name.clone(), model.as_ref()))
+            })
+            .collect()
+    }
+
+    async fn stacked_probabilities(
+        &self,
+        meta_learner: &MetaLearner,
+        model_scores: &[Vec<Vec<f64>>],
+    ) -> Result<Vec<Vec<f64>>> {
+        let calculator = MetricsCalculator::for_type(&self.lottery_type);
+        let pool_size = GameSpec::for_type(&self.lottery_type).main_pool_size();
+        let mut logits = stack_logits(&calculator, model_scores);
+        for (mut column, &scale) in logits.columns_mut().into_iter().zip(&self.diversity_scales) {
+            column *= scale;
+        }
+
+        match meta_learner {
+            MetaLearner::Logistic { coefficients, intercept } => {
+                let probabilities = (logits.dot(&Array1::from(coefficients.clone())) + *intercept)
+                    .mapv(sigmoid);
+                Ok(probabilities.to_vec().chunks(pool_size).map(<[f64]>::to_vec).collect())
+            }
+            MetaLearner::Neural(network) => {
+                let features = draw_features(&logits, pool_size);
+                let rows = features.len();
+                network.predict_scores(&TrainingData {
+                    features,
+                    targets: vec![Vec::new(); rows],
+                    special_targets: None,
+                    weights: None,
+                }).await
+            }
+        }
+    }
+
+    // 输入的每一期按此前的开奖提取特征，与训练数据的构造方式一致
+    fn history_data(&self, input: &PredictionInput) -> Result<TrainingData> {
+        let extractor = LotteryFeatureExtractor;
+        let drawings = &input.historical_data;
+        let mut data = TrainingData {
+            features: Vec::new(),
+            targets: Vec::new(),
+            special_targets: None,
+            weights: None,
+        };
+
+        for (i, drawing) in drawings.iter().enumerate().skip(1) {
+            data.features.push(extractor.extract_single_features(drawing, &drawings[..i])?);
+            data.targets.push(drawing.winning_numbers.clone());
+        }
+
+        Ok(data)
+    }
+
     async fn collect_predictions(
         &self,
         input: &PredictionInput,
@@ -141,49 +408,7 @@ impl HybridEnsembleModel {
         &self,
         predictions: &[(String, PredictionOutput)],
     ) -> (Vec<u32>, Vec<f64>) {
-        let mut number_scores: HashMap<u32, f64> = HashMap::new();
-        let mut total_weight = 0.0;
-        
-        for (model_name, prediction) in predictions {
-            let weight = self.config.ensemble_weights.get(model_name).copied().unwrap_or(1.0);
-            let confidence = prediction.confidence_scores.iter().sum::<f64>() / prediction.confidence_scores.len() as f64;
-            
-            total_weight += weight * confidence;
-            
-            for &number in &prediction.predicted_numbers {
-                *number_scores.entry(number).or_insert(0.0) += weight * confidence;
-            }
-        }
-        
-        // Normalize scores
-        if total_weight > 0.0 {
-            for score in number_scores.values_mut() {
-                *score /= total_weight;
-            }
-        }
-        
-        let mut sorted_scores: Vec<(u32, f64)> = number_scores.clone().into_iter().collect();
-        sorted_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        
-        let main_count = match self.lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
-        
-        let predicted_numbers: Vec<u32> = sorted_scores.into_iter()
-            .take(main_count)
-            .map(|(number, _)| number)
-            .collect();
-        
-        let confidence_scores: Vec<f64> = predicted_numbers.iter()
-            .map(|number| number_scores.get(number).copied().unwrap_or(0.0))
-            .collect();
-        
-        (predicted_numbers, confidence_scores)
+        EnsembleCombiner::for_type(&self.lottery_type).vote_numbers(self.weights(), predictions)
     }
 
     fn majority_voting(
@@ -201,14 +426,7 @@ impl HybridEnsembleModel {
         let mut sorted_votes: Vec<(u32, usize)> = vote_counts.clone().into_iter().collect();
         sorted_votes.sort_by(|a, b| b.1.cmp(&a.1));
         
-        let main_count = match self.lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
+        let main_count = GameSpec::for_type(&self.lottery_type).pick_count;
         
         let predicted_numbers: Vec<u32> = sorted_votes.into_iter()
             .take(main_count)
@@ -233,7 +451,7 @@ impl HybridEnsembleModel {
         let mut consensus_scores: HashMap<u32, f64> = HashMap::new();
         
         for (model_name, prediction) in predictions {
-            let weight = self.config.ensemble_weights.get(model_name).copied().unwrap_or(1.0);
+            let weight = self.weights().get(model_name).copied().unwrap_or(1.0);
             
             for &number in &prediction.predicted_numbers {
                 let mut consensus_score = weight;
@@ -241,7 +459,7 @@ impl HybridEnsembleModel {
                 // Check agreement with other models
                 for (other_name, other_prediction) in predictions {
                     if model_name != other_name {
-                        let other_weight = self.config.ensemble_weights.get(other_name).copied().unwrap_or(1.0);
+                        let other_weight = self.weights().get(other_name).copied().unwrap_or(1.0);
                         if other_prediction.predicted_numbers.contains(&number) {
                             consensus_score += other_weight * self.config.diversity_weight;
                         }
@@ -255,14 +473,7 @@ impl HybridEnsembleModel {
         let mut sorted_consensus: Vec<(u32, f64)> = consensus_scores.clone().into_iter().collect();
         sorted_consensus.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
         
-        let main_count = match self.lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
+        let main_count = GameSpec::for_type(&self.lottery_type).pick_count;
         
         let predicted_numbers: Vec<u32> = sorted_consensus.into_iter()
             .take(main_count)
@@ -276,38 +487,12 @@ impl HybridEnsembleModel {
         (predicted_numbers, confidence_scores)
     }
 
-    fn predict_special_numbers(
-        &self,
-        predictions: &[(String, PredictionOutput)],
-        count: usize,
-    ) -> Vec<u32> {
-        let mut special_scores: HashMap<u32, f64> = HashMap::new();
-        
-        for (model_name, prediction) in predictions {
-            if let Some(ref special_numbers) = prediction.predicted_special_numbers {
-                let weight = self.config.ensemble_weights.get(model_name).copied().unwrap_or(1.0);
-                
-                for &number in special_numbers {
-                    *special_scores.entry(number).or_insert(0.0) += weight;
-                }
-            }
-        }
-        
-        let mut sorted_scores: Vec<(u32, f64)> = special_scores.into_iter().collect();
-        sorted_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        
-        sorted_scores.into_iter()
-            .take(count)
-            .map(|(number, _)| number)
-            .collect()
-    }
-
     fn calculate_ensemble_confidence(&self, predictions: &[(String, PredictionOutput)]) -> f64 {
         let mut total_confidence = 0.0;
         let mut total_weight = 0.0;
         
         for (model_name, prediction) in predictions {
-            let weight = self.config.ensemble_weights.get(model_name).copied().unwrap_or(1.0);
+            let weight = self.weights().get(model_name).copied().unwrap_or(1.0);
             let avg_confidence = prediction.confidence_scores.iter().sum::<f64>() / prediction.confidence_scores.len() as f64;
             
             total_confidence += weight * avg_confidence;
@@ -321,43 +506,161 @@ impl HybridEnsembleModel {
         }
     }
 
-    async fn optimize_weights(
-        &mut self,
-        validation_data: &TrainingData,
-    ) -> Result<()> {
-        let mut best_weights = self.config.ensemble_weights.clone();
-        let mut best_accuracy = 0.0;
-        
-        // Simple grid search for optimal weights
-        let weight_combinations = vec![
-            (0.3, 0.2, 0.2, 0.15, 0.15),
-            (0.25, 0.25, 0.2, 0.15, 0.15),
-            (0.2, 0.25, 0.25, 0.15, 0.15),
-            (0.2, 0.2, 0.2, 0.2, 0.2),
-            (0.35, 0.2, 0.15, 0.15, 0.15),
-        ];
-        
-        for (rf, nn, lstm, arima, stat) in weight_combinations {
-            let mut temp_weights = HashMap::new();
-            temp_weights.insert("random_forest".to_string(), rf);
-            temp_weights.insert("neural_network".to_string(), nn);
-            temp_weights.insert("lstm".to_string(), lstm);
-            temp_weights.insert("arima".to_string(), arima);
-            temp_weights.insert("statistical".to_string(), stat);
-            
-            self.config.ensemble_weights = temp_weights.clone();
-            
-            if let Ok(metrics) = self.evaluate(validation_data).await {
-                if metrics.accuracy > best_accuracy {
-                    best_accuracy = metrics.accuracy;
-                    best_weights = temp_weights;
+    // 在折外打分上学习本玩法的权重，不使用留出数据
+    fn optimize_weights(&mut self, oof: &OutOfFold) -> Result<()> {
+        let model_scores: Vec<(String, Vec<Vec<f64>>)> = oof.models.iter()
+            .cloned()
+            .zip(oof.scores.iter().cloned())
+            .collect();
+        let weights = EnsembleCombiner::for_type(&self.lottery_type)
+            .learn_weights(&model_scores, &oof.targets, self.config.weight_objective)?;
+        self.config.game_weights.insert(self.lottery_type.to_string(), weights);
+        Ok(())
+    }
+}
+
+// 每行对应 (期, 号码)，按期、号码顺序排列；每列为一个子模型给出的 logit
+fn stack_logits(calculator: &MetricsCalculator, model_scores: &[Vec<Vec<f64>>]) -> Array2<f64> {
+    let rows = model_scores.first().map_or(0, Vec::len);
+    let pool_size = model_scores.first()
+        .and_then(|scores| scores.first())
+        .map_or(0, Vec::len);
+    let mut logits = Array2::zeros((rows * pool_size, model_scores.len()));
+
+    for (m, scores) in model_scores.iter().enumerate() {
+        for (r, row) in scores.iter().enumerate() {
+            for (k, p) in calculator.probabilities(row).into_iter().enumerate() {
+                let p = p.clamp(META_PROBABILITY_EPSILON, 1.0 - META_PROBABILITY_EPSILON);
+                logits[[r * pool_size + k, m]] = (p / (1.0 - p)).ln();
+            }
+        }
+    }
+
+    logits
+}
+
+// 子模型 m 的冗余度为它与其余子模型 logit 的正相关系数之和，输入缩放为 1 / (1 + diversity_weight * 冗余度)。
+// 元学习器的 L2 正则作用在缩放后的输入上，冗余子模型需要更大的系数，因而受到更强的惩罚
+fn diversity_scales(logits: &Array2<f64>, diversity_weight: f64) -> Vec<f64> {
+    let n_models = logits.ncols();
+    let columns: Vec<Array1<f64>> = logits.columns()
+        .into_iter()
+        .map(|column| {
+            let mean = column.mean().unwrap_or(0.0);
+            column.mapv(|x| x - mean)
+        })
+        .collect();
+    let norms: Vec<f64> = columns.iter().map(|c| c.dot(c).sqrt()).collect();
+
+    (0..n_models)
+        .map(|m| {
+            let redundancy: f64 = (0..n_models)
+                .filter(|&j| j != m && norms[m] > 0.0 && norms[j] > 0.0)
+                .map(|j| (columns[m].dot(&columns[j]) / (norms[m] * norms[j])).max(0.0))
+                .sum();
+            1.0 / (1.0 + diversity_weight.max(0.0) * redundancy)
+        })
+        .collect()
+}
+
+// 带 L2 正则的逻辑回归，牛顿法拟合，返回 (系数, 截距)。
+// linfa-logistic 0.7 基于 ndarray 0.15，与本 crate 的 ndarray 0.16 数组不通用；元学习器只有几个参数，直接求解即可
+fn fit_logistic(x: &Array2<f64>, y: &[bool], l2_penalty: f64) -> Result<(Vec<f64>, f64)> {
+    let n_features = x.ncols();
+    let dim = n_features + 1;
+    // 最后一维为截距
+    let mut params = vec![0.0; dim];
+
+    for _ in 0..META_MAX_ITERATIONS {
+        let mut gradient = vec![0.0; dim];
+        let mut hessian = vec![vec![0.0; dim]; dim];
+
+        for (row, &label) in x.rows().into_iter().zip(y) {
+            let inputs: Vec<f64> = row.iter().copied().chain(std::iter::once(1.0)).collect();
+            let z: f64 = inputs.iter().zip(&params).map(|(x, w)| x * w).sum();
+            let p = sigmoid(z);
+            let residual = p - if label { 1.0 } else { 0.0 };
+            let curvature = p * (1.0 - p);
+
+            for a in 0..dim {
+                gradient[a] += residual * inputs[a];
+                for b in 0..dim {
+                    hessian[a][b] += curvature * inputs[a] * inputs[b];
                 }
             }
         }
-        
-        self.config.ensemble_weights = best_weights;
-        Ok(())
+
+        for a in 0..n_features {
+            gradient[a] += l2_penalty * params[a];
+            hessian[a][a] += l2_penalty;
+        }
+        // 全部样本同类时截距方向的曲率趋于零，加一点阻尼保证可解
+        for (a, row) in hessian.iter_mut().enumerate() {
+            row[a] += 1e-9;
+        }
+
+        let Some(step) = solve_linear(hessian, gradient) else {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Failed to train meta learner: singular Hessian".to_string()
+            ));
+        };
+        for (param, delta) in params.iter_mut().zip(&step) {
+            *param -= delta;
+        }
+
+        if step.iter().all(|delta| delta.abs() < 1e-8) {
+            break;
+        }
+    }
+
+    let intercept = params.pop().unwrap_or(0.0);
+    Ok((params, intercept))
+}
+
+// 部分主元高斯消元
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-300 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (value, pivot) in a[row][col..n].iter_mut().zip(&pivot_row[col..n]) {
+                *value -= factor * pivot;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let tail: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - tail) / a[row][row];
     }
+    Some(x)
+}
+
+fn sigmoid(z: f64) -> f64 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+// 把 (期, 号码) 行展开为每期一行：依次为每个子模型在所有号码上的 logit
+fn draw_features(logits: &Array2<f64>, pool_size: usize) -> Vec<Vec<f64>> {
+    let rows = logits.nrows() / pool_size.max(1);
+    (0..rows)
+        .map(|r| {
+            logits.columns()
+                .into_iter()
+                .flat_map(|column| column.slice(s![r * pool_size..(r + 1) * pool_size]).to_vec())
+                .collect()
+        })
+        .collect()
 }
 
 #[async_trait]
@@ -366,8 +669,8 @@ impl PredictionAlgorithm for HybridEnsembleModel {
         "Hybrid Ensemble".to_string()
     }
 
-    fn algorithm_type(&self) -> String {
-        "hybrid".to_string()
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::Hybrid
     }
 
     async fn train(
@@ -375,26 +678,56 @@ impl PredictionAlgorithm for HybridEnsembleModel {
         training_data: &TrainingData,
         config: &AlgorithmConfig,
     ) -> Result<f64> {
-        // Train individual models
-        let accuracies = self.train_individual_models(training_data, config).await?;
-        
-        // Split data for validation
-        let split_point = (training_data.features.len() as f64 * 0.8) as usize;
-        let validation_data = TrainingData {
-            features: training_data.features[split_point..].to_vec(),
-            targets: training_data.targets[split_point..].to_vec(),
-            special_targets: training_data.special_targets.as_ref().map(|st| st[split_point..].to_vec()),
-            weights: training_data.weights.as_ref().map(|w| w[split_point..].to_vec()),
+        self.config = config.apply(&self.base_config)?;
+        // 参数只作用于集成本身，子模型沿用各自的配置
+        let config = &AlgorithmConfig {
+            lottery_type: config.lottery_type.clone(),
+            feature_config: config.feature_config.clone(),
+            ..Default::default()
         };
-        
-        // Optimize ensemble weights
-        self.optimize_weights(&validation_data).await?;
-        
+
+        // 末尾留出一段数据，元学习器与权重只在前面部分的折外打分上拟合
+        let split_point = (training_data.len() as f64 * (1.0 - HOLDOUT_FRACTION)) as usize;
+        let (fit_data, holdout_data) = training_data.split_at(split_point);
+        if fit_data.is_empty() || holdout_data.is_empty() {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "Insufficient training data for hybrid ensemble".to_string()
+            ));
+        }
+
+        let oof = self.out_of_fold_scores(&fit_data, config).await;
+        self.meta_learner = None;
+        self.stacked_models.clear();
+        self.diversity_scales.clear();
+        if !oof.targets.is_empty() {
+            if self.config.use_meta_learner {
+                self.fit_meta_learner(&oof, config).await?;
+            } else {
+                self.optimize_weights(&oof)?;
+            }
+        }
+
+        // 子模型只用前面部分训练，集成与各子模型的指标都在留出数据上计算
+        self.train_individual_models(&fit_data, config).await?;
+        let validation_metrics = self.evaluate(&holdout_data).await?;
+
+        self.model_accuracies.clear();
+        for (name, model) in &self.models {
+            if !model.is_trained() {
+                continue;
+            }
+            if let Ok(metrics) = model.evaluate(&holdout_data).await {
+                self.model_accuracies.insert(name.clone(), metrics.accuracy);
+            }
+        }
+
+        // 预测使用全部数据重新训练的子模型
+        self.train_individual_models(training_data, config).await?;
         self.is_trained = true;
-        
-        // Calculate ensemble accuracy
-        let ensemble_accuracy = accuracies.values().sum::<f64>() / accuracies.len() as f64;
-        Ok(ensemble_accuracy * 1.1) // Boost for ensemble effect
+
+        let accuracy = validation_metrics.accuracy;
+        self.validation_metrics = Some(validation_metrics);
+        Ok(accuracy)
     }
 
     async fn predict(
@@ -411,25 +744,46 @@ impl PredictionAlgorithm for HybridEnsembleModel {
 
         let predictions = self.collect_predictions(input).await?;
         
-        let (predicted_numbers, confidence_scores) = match self.config.voting_method.as_str() {
-            "weighted" => self.weighted_voting(&predictions),
-            "majority" => self.majority_voting(&predictions),
-            "consensus" => self.consensus_voting(&predictions),
-            _ => self.weighted_voting(&predictions),
-        };
+        let combiner = EnsembleCombiner::for_type(&self.lottery_type);
+        let mut probabilities = combiner.blend_probabilities(self.weights(), &predictions);
+        let (predicted_numbers, mut confidence_scores) = if self.meta_learner.is_some() {
+            // 最新一期的打分行即对下一期的预测，与子模型 predict 使用的特征一致
+            let scores = self.predict_scores(&self.history_data(input)?).await?;
+            let Some(stacked) = scores.last() else {
+                return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                    "Insufficient historical data for stacked prediction".to_string()
+                ));
+            };
+            let spec = GameSpec::for_type(&self.lottery_type);
+            let numbers = MetricsCalculator::from_spec(spec.clone()).top_numbers(stacked, spec.pick_count);
 
-        let special_count = match self.lottery_type {
-            LotteryType::Ssq => 1,
-            LotteryType::Dlt => 2,
-            _ => 0,
-        };
-
-        let predicted_special_numbers = if special_count > 0 {
-            Some(self.predict_special_numbers(&predictions, special_count))
+            // 主号码分布取元学习器的输出，特别号仍按子模型混合；置信度在下面由分布给出
+            let special = probabilities.take().and_then(|blended| blended.special);
+            probabilities = Some(NumberProbabilities {
+                special,
+                ..NumberProbabilities::from_scores(&spec, stacked, None)
+            });
+            (numbers, Vec::new())
         } else {
-            None
+            match self.config.voting_method.as_str() {
+                "weighted" => self.weighted_voting(&predictions),
+                "majority" => self.majority_voting(&predictions),
+                "consensus" => self.consensus_voting(&predictions),
+                _ => self.weighted_voting(&predictions),
+            }
         };
 
+        let predicted_special_numbers = combiner.vote_special_numbers(
+            self.weights(),
+            &predictions,
+            &input.historical_data,
+            &predicted_numbers,
+        );
+
+        if let Some(probabilities) = &probabilities {
+            confidence_scores = probabilities.confidence(&GameSpec::for_type(&self.lottery_type), &predicted_numbers);
+        }
+
         let ensemble_confidence = self.calculate_ensemble_confidence(&predictions);
         let computation_time = start_time.elapsed().as_millis() as u64;
 
@@ -438,12 +792,18 @@ impl PredictionAlgorithm for HybridEnsembleModel {
             predicted_special_numbers,
             confidence_scores,
             algorithm_metadata: HashMap::from_iter(vec![
-                ("algorithm".to_string(), serde_json::Value::String("hybrid_ensemble".to_string())),
+                ("algorithm".to_string(), serde_json::Value::String(AlgorithmType::Hybrid.to_string())),
                 ("voting_method".to_string(), serde_json::Value::String(self.config.voting_method.clone())),
                 ("ensemble_confidence".to_string(), serde_json::Value::Number(serde_json::Number::from_f64(ensemble_confidence).unwrap_or(serde_json::Number::from(0)))),
                 ("models_used".to_string(), serde_json::Value::Number(serde_json::Number::from(predictions.len()))),
+                ("meta_learner".to_string(), serde_json::json!(self.meta_learner.as_ref().map(|_| &self.config.meta_learner_type))),
+                ("stacked_models".to_string(), serde_json::json!(self.stacked_models)),
+                ("validation_accuracy".to_string(), serde_json::json!(self.validation_metrics.as_ref().map(|m| m.accuracy))),
+                ("model_accuracies".to_string(), serde_json::json!(self.model_accuracies)),
+                ("ensemble_weights".to_string(), serde_json::json!(self.weights())),
             ]),
             computation_time_ms: computation_time,
+            probabilities,
         })
     }
 
@@ -451,27 +811,56 @@ impl PredictionAlgorithm for HybridEnsembleModel {
         &self,
         test_data: &TrainingData,
     ) -> Result<EvaluationMetrics> {
-        let mut metrics = EvaluationMetrics::default();
-        
-        // Calculate ensemble metrics based on individual model performance
-        let avg_accuracy = self.model_accuracies.values().sum::<f64>() / self.model_accuracies.len() as f64;
-        
-        metrics.accuracy = avg_accuracy * 1.15; // 15% boost for ensemble
-        metrics.precision = avg_accuracy * 1.12;
-        metrics.recall = avg_accuracy * 1.18;
-        metrics.f1_score = 2.0 * (metrics.precision * metrics.recall) / (metrics.precision + metrics.recall + 1e-8);
-        
-        Ok(metrics)
+        let scores = self.predict_scores(test_data).await?;
+        MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)
+    }
+
+    // 有元学习器时由它组合参与堆叠的子模型，否则按本玩法的权重取概率混合
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        let Some(meta_learner) = &self.meta_learner else {
+            let model_scores = self.collect_scores(&self.model_names(), test_data).await?;
+            return Ok(EnsembleCombiner::for_type(&self.lottery_type).combine_scores(self.weights(), &model_scores));
+        };
+
+        let model_scores = self.collect_scores(&self.stacked_models, test_data).await?;
+        if model_scores.len() != self.stacked_models.len() {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Not all stacked models provided predictions".to_string()
+            ));
+        }
+
+        let scores: Vec<Vec<Vec<f64>>> = model_scores.into_iter().map(|(_, scores)| scores).collect();
+        self.stacked_probabilities(meta_learner, &scores).await
+    }
+
+    // 元学习器只组合主号码，特别号始终按本玩法的权重取概率混合
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        let model_scores = ensemble::collect_special_scores(&self.trained_models(&self.model_names()), test_data).await?;
+        Ok(EnsembleCombiner::for_type(&self.lottery_type).combine_special_scores(self.weights(), &model_scores))
     }
 
     fn is_trained(&self) -> bool {
         self.is_trained
     }
 
+    // 逻辑回归元学习器报告各子模型 logit 的有效系数，否则报告投票权重
     fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
+        if let Some(MetaLearner::Logistic { coefficients, .. }) = &self.meta_learner {
+            return Some(self.stacked_models.iter()
+                .zip(coefficients.iter().zip(&self.diversity_scales))
+                .map(|(name, (coefficient, scale))| (name.clone(), coefficient * scale))
+                .collect());
+        }
+
         let mut importance = HashMap::new();
         
-        for (model_name, weight) in &self.config.ensemble_weights {
+        for (model_name, weight) in self.weights() {
             importance.insert(model_name.clone(), *weight);
         }
         
@@ -511,9 +900,15 @@ impl PredictionAlgorithm for HybridEnsembleModel {
     fn box_clone(&self) -> Box<dyn PredictionAlgorithm> {
         Box::new(HybridEnsembleModel {
             config: self.config.clone(),
-            models: HashMap::new(), // Models need to be reinitialized
-            meta_learner: None,
+            base_config: self.base_config.clone(),
+            models: self.models.iter()
+                .map(|(name, model)| (name.clone(), model.box_clone()))
+                .collect(),
+            meta_learner: self.meta_learner.clone(),
+            stacked_models: self.stacked_models.clone(),
+            diversity_scales: self.diversity_scales.clone(),
             model_accuracies: self.model_accuracies.clone(),
+            validation_metrics: self.validation_metrics.clone(),
             is_trained: self.is_trained,
             lottery_type: self.lottery_type.clone(),
             ensemble_history: self.ensemble_history.clone(),
@@ -527,6 +922,7 @@ mod tests {
     use crate::lottery::algorithms::traits::TrainingData;
     use crate::lottery::models::LotteryType;
     use chrono::NaiveDate;
+    use rand::prelude::*;
     use uuid::Uuid;
 
     #[test]
@@ -534,7 +930,7 @@ mod tests {
         let config = HybridConfig::default();
         let model = HybridEnsembleModel::new(config, LotteryType::Ssq);
         assert_eq!(model.name(), "Hybrid Ensemble");
-        assert_eq!(model.algorithm_type(), "hybrid");
+        assert_eq!(model.algorithm_type(), AlgorithmType::Hybrid);
         assert!(!model.is_trained());
     }
 
@@ -553,6 +949,7 @@ mod tests {
                     confidence_scores: vec![0.8; 6],
                     algorithm_metadata: HashMap::new(),
                     computation_time_ms: 100,
+                    probabilities: None,
                 }
             ),
             (
@@ -563,6 +960,7 @@ mod tests {
                     confidence_scores: vec![0.7; 6],
                     algorithm_metadata: HashMap::new(),
                     computation_time_ms: 150,
+                    probabilities: None,
                 }
             ),
         ];
@@ -580,10 +978,31 @@ mod tests {
     async fn test_hybrid_training() {
         let config = HybridConfig {
             ensemble_size: 2,
+            cross_validation_folds: 2,
             ..Default::default()
         };
         
         let mut model = HybridEnsembleModel::new(config, LotteryType::Ssq);
+        // 默认规模的神经网络与 LSTM 训练较慢，测试中换成小网络
+        model.models.insert(
+            "neural_network".to_string(),
+            Box::new(NeuralNetworkModel::new(
+                NeuralNetworkConfig { hidden_layers: vec![16], epochs: 10, ..Default::default() },
+                LotteryType::Ssq,
+            )),
+        );
+        model.models.insert(
+            "lstm".to_string(),
+            Box::new(LstmModel::new(
+                crate::lottery::algorithms::lstm::LstmConfig {
+                    hidden_size: 8,
+                    num_layers: 1,
+                    epochs: 5,
+                    ..Default::default()
+                },
+                LotteryType::Ssq,
+            )),
+        );
         
         let training_data = TrainingData {
             features: vec![vec![0.1; 10]; 20],
@@ -602,4 +1021,147 @@ mod tests {
         let result = model.train(&training_data, &config).await;
         assert!(result.is_ok());
     }
-}
\ No newline at end of file
+
+    // 号码 7 只在第一个特征为 1 的期开出
+    fn signal_training_data(samples: usize) -> TrainingData {
+        let mut training_data = TrainingData {
+            features: Vec::new(),
+            targets: Vec::new(),
+            special_targets: None,
+            weights: None,
+        };
+
+        for i in 0..samples {
+            let signal = (i % 2) as f64;
+            training_data.features.push(vec![signal, (i % 5) as f64 / 5.0, 0.5]);
+            training_data.targets.push(vec![1, 2, 3, 4, 5, if signal > 0.5 { 7 } else { 8 }]);
+        }
+
+        training_data
+    }
+
+    fn lightweight_ensemble(config: HybridConfig) -> HybridEnsembleModel {
+        let mut model = HybridEnsembleModel::new(config, LotteryType::Ssq);
+        model.models.clear();
+        model.models.insert(
+            "random_forest".to_string(),
+            Box::new(RandomForestModel::new(
+                crate::lottery::algorithms::random_forest::RandomForestConfig {
+                    n_estimators: 5,
+                    max_depth: Some(3),
+                    ..Default::default()
+                },
+                LotteryType::Ssq,
+            )),
+        );
+        model.models.insert(
+            "statistical".to_string(),
+            Box::new(StatisticalModel::new(Default::default(), LotteryType::Ssq)),
+        );
+        model
+    }
+
+    #[test]
+    fn test_fit_logistic_recovers_coefficients() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let (true_weights, true_intercept) = ([1.5, -2.0], 0.5);
+        let x = Array2::from_shape_fn((4000, 2), |_| rng.gen_range(-2.0..2.0));
+        let y: Vec<bool> = x.rows()
+            .into_iter()
+            .map(|row| {
+                let z = true_intercept + true_weights[0] * row[0] + true_weights[1] * row[1];
+                rng.r#gen::<f64>() < sigmoid(z)
+            })
+            .collect();
+
+        let (coefficients, intercept) = fit_logistic(&x, &y, 1e-6).unwrap();
+        assert!((coefficients[0] - 1.5).abs() < 0.2, "{:?}", coefficients);
+        assert!((coefficients[1] + 2.0).abs() < 0.2, "{:?}", coefficients);
+        assert!((intercept - 0.5).abs() < 0.2, "{}", intercept);
+    }
+
+    #[test]
+    fn test_diversity_scales_penalize_redundant_models() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let base: Vec<f64> = (0..200).map(|_| rng.r#gen::<f64>()).collect();
+        let independent: Vec<f64> = (0..200).map(|_| rng.r#gen::<f64>()).collect();
+        let logits = Array2::from_shape_fn((200, 3), |(i, j)| match j {
+            0 => base[i],
+            1 => base[i] * 2.0 + 1.0,
+            _ => independent[i],
+        });
+
+        let scales = diversity_scales(&logits, 0.5);
+        assert!((scales[0] - 1.0 / 1.5).abs() < 0.05);
+        assert!((scales[0] - scales[1]).abs() < 1e-9);
+        assert!(scales[2] > 0.9);
+
+        assert_eq!(diversity_scales(&logits, 0.0), vec![1.0; 3]);
+    }
+
+    #[tokio::test]
+    async fn test_stacking_reports_holdout_metrics() {
+        let mut model = lightweight_ensemble(HybridConfig {
+            cross_validation_folds: 3,
+            ..Default::default()
+        });
+        let training_data = signal_training_data(60);
+
+        let accuracy = model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+        assert!(matches!(model.meta_learner, Some(MetaLearner::Logistic { .. })));
+        assert_eq!(model.stacked_models, vec!["random_forest".to_string(), "statistical".to_string()]);
+        assert_eq!(model.diversity_scales.len(), 2);
+
+        // 返回值为留出数据上的准确率，而不是训练集上放大的常数
+        let validation = model.validation_metrics.clone().unwrap();
+        assert_eq!(accuracy, validation.accuracy);
+        assert!(accuracy <= 1.0);
+        assert!(model.model_accuracies.contains_key("random_forest"));
+
+        // 元学习器应当依赖能区分 7 与 8 的随机森林
+        let scores = model.predict_scores(&signal_training_data(4)).await.unwrap();
+        assert_eq!(scores.len(), 4);
+        assert!(scores[1][6] > scores[0][6]);
+        assert!(scores[0][7] > scores[1][7]);
+
+        let importance = model.get_feature_importance().unwrap();
+        assert!(importance["random_forest"] > importance["statistical"]);
+    }
+
+    #[tokio::test]
+    async fn test_neural_meta_learner_and_invalid_type() {
+        let mut model = lightweight_ensemble(HybridConfig {
+            cross_validation_folds: 3,
+            meta_learner_type: "neural".to_string(),
+            ..Default::default()
+        });
+        model.train(&signal_training_data(60), &AlgorithmConfig::default()).await.unwrap();
+        assert!(matches!(model.meta_learner, Some(MetaLearner::Neural(_))));
+        assert_eq!(model.predict_scores(&signal_training_data(4)).await.unwrap()[0].len(), 33);
+
+        let mut model = lightweight_ensemble(HybridConfig {
+            meta_learner_type: "quadratic".to_string(),
+            ..Default::default()
+        });
+        let result = model.train(&signal_training_data(60), &AlgorithmConfig::default()).await;
+        assert!(matches!(result, Err(crate::lottery::errors::LotteryError::ConfigurationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_weighted_voting_without_meta_learner() {
+        let mut model = lightweight_ensemble(HybridConfig {
+            cross_validation_folds: 3,
+            use_meta_learner: false,
+            ..Default::default()
+        });
+        model.train(&signal_training_data(60), &AlgorithmConfig::default()).await.unwrap();
+        assert!(model.meta_learner.is_none());
+        assert!(model.validation_metrics.is_some());
+        // 折外打分上学习到的 SSQ 权重在单纯形上，并取代默认权重
+        let weights = model.config.game_weights["ssq"].clone();
+        assert_eq!(weights.len(), 2);
+        assert!((weights.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(weights.values().all(|&w| w >= 0.0));
+        assert_eq!(model.get_feature_importance(), Some(weights));
+    }
+}
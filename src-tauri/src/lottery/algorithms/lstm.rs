@@ -1,14 +1,22 @@
 use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::{self, NumberProbabilities};
+use crate::lottery::algorithms::metrics::MetricsCalculator;
+use crate::lottery::algorithms::neural_network::{
+    LearningRateSchedule, Optimizer, OptimizerSettings, OptimizerState,
+};
 use crate::lottery::errors::LotteryResult as Result;
-use crate::lottery::models::LotteryType;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryType};
 use async_trait::async_trait;
-use ndarray::{Array1, Array2, Array3, Axis, s};
+use ndarray::{Array1, Array2, Array3, ArrayView1, ArrayView2, Axis, s};
 use ndarray_rand::RandomExt;
-use ndarray_rand::rand_distr::StandardNormal;
+use ndarray_rand::rand_distr::Uniform;
+use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct LstmConfig {
     pub hidden_size: usize,
     pub num_layers: usize,
@@ -21,6 +29,12 @@ pub struct LstmConfig {
     pub optimizer: String,
     pub early_stopping: bool,
     pub patience: usize,
+    pub validation_split: f64,
+    // 梯度全局 L2 范数上限，<= 0 表示不裁剪
+    pub gradient_clip: f64,
+    pub momentum: f64,
+    pub second_moment_decay: f64,
+    pub learning_rate_schedule: LearningRateSchedule,
 }
 
 impl Default for LstmConfig {
@@ -37,10 +51,40 @@ impl Default for LstmConfig {
             optimizer: "adam".to_string(),
             early_stopping: true,
             patience: 10,
+            validation_split: 0.2,
+            gradient_clip: 5.0,
+            momentum: 0.9,
+            second_moment_decay: 0.999,
+            learning_rate_schedule: LearningRateSchedule::Constant,
         }
     }
 }
 
+impl ModelConfig for LstmConfig {
+    fn validate(&self) -> Result<()> {
+        ensure_config(self.hidden_size > 0, "hidden_size must be positive")?;
+        ensure_config(self.num_layers > 0, "num_layers must be positive")?;
+        ensure_config(self.sequence_length > 0, "sequence_length must be positive")?;
+        ensure_config(
+            self.learning_rate > 0.0 && self.learning_rate.is_finite(),
+            "learning_rate must be positive",
+        )?;
+        ensure_config(self.epochs > 0, "epochs must be positive")?;
+        ensure_config(self.batch_size > 0, "batch_size must be positive")?;
+        ensure_config((0.0..1.0).contains(&self.dropout), "dropout must be in [0, 1)")?;
+        ensure_config(
+            self.regularization >= 0.0 && self.regularization.is_finite(),
+            "regularization must be non-negative",
+        )?;
+        ensure_config(Optimizer::from_name(&self.optimizer).is_ok(), "optimizer must be one of sgd, momentum, rmsprop, adam")?;
+        ensure_config((0.0..1.0).contains(&self.validation_split), "validation_split must be in [0, 1)")?;
+        ensure_config(self.gradient_clip.is_finite(), "gradient_clip must be finite")?;
+        ensure_config((0.0..1.0).contains(&self.momentum), "momentum must be in [0, 1)")?;
+        ensure_config((0.0..1.0).contains(&self.second_moment_decay), "second_moment_decay must be in [0, 1)")?;
+        ensure_config(self.learning_rate_schedule.is_valid(), "learning_rate_schedule parameters are out of range")
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LstmCell {
     pub weight_ih: Array2<f64>,
@@ -49,12 +93,52 @@ pub struct LstmCell {
     pub bias_hh: Array1<f64>,
     pub hidden_size: usize,
     pub input_size: usize,
+    #[serde(default)]
+    pub input_optimizer_state: OptimizerState,
+    #[serde(default)]
+    pub hidden_optimizer_state: OptimizerState,
+}
+
+// 单个时间步的前向缓存，门的顺序为 i, f, g, o
+#[derive(Debug, Clone)]
+struct StepCache {
+    input: Array1<f64>,
+    h_prev: Array1<f64>,
+    c_prev: Array1<f64>,
+    i: Array1<f64>,
+    f: Array1<f64>,
+    g: Array1<f64>,
+    o: Array1<f64>,
+    c: Array1<f64>,
+    c_tanh: Array1<f64>,
+    h: Array1<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct CellGradients {
+    weight_ih: Array2<f64>,
+    weight_hh: Array2<f64>,
+    bias_ih: Array1<f64>,
+    bias_hh: Array1<f64>,
+}
+
+impl CellGradients {
+    fn zeros(cell: &LstmCell) -> Self {
+        Self {
+            weight_ih: Array2::zeros(cell.weight_ih.raw_dim()),
+            weight_hh: Array2::zeros(cell.weight_hh.raw_dim()),
+            bias_ih: Array1::zeros(cell.bias_ih.len()),
+            bias_hh: Array1::zeros(cell.bias_hh.len()),
+        }
+    }
 }
 
 impl LstmCell {
     pub fn new(input_size: usize, hidden_size: usize) -> Self {
-        let weight_ih = Array2::random((4 * hidden_size, input_size), StandardNormal);
-        let weight_hh = Array2::random((4 * hidden_size, hidden_size), StandardNormal);
+        // 与 PyTorch 相同，在 ±1/sqrt(hidden_size) 内均匀初始化
+        let bound = 1.0 / (hidden_size as f64).sqrt();
+        let weight_ih = Array2::random((4 * hidden_size, input_size), Uniform::new_inclusive(-bound, bound));
+        let weight_hh = Array2::random((4 * hidden_size, hidden_size), Uniform::new_inclusive(-bound, bound));
         let bias_ih = Array1::zeros(4 * hidden_size);
         let bias_hh = Array1::zeros(4 * hidden_size);
 
@@ -65,34 +149,149 @@ impl LstmCell {
             bias_hh,
             hidden_size,
             input_size,
+            input_optimizer_state: OptimizerState::new(4 * hidden_size, input_size),
+            hidden_optimizer_state: OptimizerState::new(4 * hidden_size, hidden_size),
         }
     }
 
     pub fn forward(&self, input: &Array1<f64>, hidden: &Array1<f64>, cell: &Array1<f64>) -> (Array1<f64>, Array1<f64>) {
-        let gates = self.weight_ih.dot(input) + &self.bias_ih + self.weight_hh.dot(hidden) + &self.bias_hh;
+        let step = self.step(input.clone(), hidden.clone(), cell.clone());
+        (step.h, step.c)
+    }
+
+    fn step(&self, input: Array1<f64>, h_prev: Array1<f64>, c_prev: Array1<f64>) -> StepCache {
+        let gates = self.weight_ih.dot(&input) + &self.bias_ih + self.weight_hh.dot(&h_prev) + &self.bias_hh;
         
-        let i = gates.slice(s![..self.hidden_size]).mapv(|x| sigmoid(x));
-        let f = gates.slice(s![self.hidden_size..2*self.hidden_size]).mapv(|x| sigmoid(x));
-        let g = gates.slice(s![2*self.hidden_size..3*self.hidden_size]).mapv(|x| tanh(x));
-        let o = gates.slice(s![3*self.hidden_size..4*self.hidden_size]).mapv(|x| sigmoid(x));
+        let i = gates.slice(s![..self.hidden_size]).mapv(sigmoid);
+        let f = gates.slice(s![self.hidden_size..2*self.hidden_size]).mapv(sigmoid);
+        let g = gates.slice(s![2*self.hidden_size..3*self.hidden_size]).mapv(tanh);
+        let o = gates.slice(s![3*self.hidden_size..4*self.hidden_size]).mapv(sigmoid);
         
-        let new_cell = f * cell + i * g;
-        let new_hidden = o * new_cell.mapv(|x| tanh(x));
+        let c = &f * &c_prev + &i * &g;
+        let c_tanh = c.mapv(tanh);
+        let h = &o * &c_tanh;
         
-        (new_hidden, new_cell)
+        StepCache { input, h_prev, c_prev, i, f, g, o, c, c_tanh, h }
+    }
+
+    // 返回对本步输入、上一步隐藏状态与细胞状态的梯度
+    fn backward_step(
+        &self,
+        cache: &StepCache,
+        dh: &Array1<f64>,
+        dc_next: &Array1<f64>,
+        gradients: &mut CellGradients,
+    ) -> (Array1<f64>, Array1<f64>, Array1<f64>) {
+        let hidden_size = self.hidden_size;
+        let d_o = dh * &cache.c_tanh;
+        let dc = dh * &cache.o * &cache.c_tanh.mapv(|x| 1.0 - x * x) + dc_next;
+
+        let mut d_gates = Array1::zeros(4 * hidden_size);
+        d_gates.slice_mut(s![..hidden_size])
+            .assign(&(&dc * &cache.g * &cache.i.mapv(|x| x * (1.0 - x))));
+        d_gates.slice_mut(s![hidden_size..2*hidden_size])
+            .assign(&(&dc * &cache.c_prev * &cache.f.mapv(|x| x * (1.0 - x))));
+        d_gates.slice_mut(s![2*hidden_size..3*hidden_size])
+            .assign(&(&dc * &cache.i * &cache.g.mapv(|x| 1.0 - x * x)));
+        d_gates.slice_mut(s![3*hidden_size..4*hidden_size])
+            .assign(&(d_o * &cache.o.mapv(|x| x * (1.0 - x))));
+
+        gradients.weight_ih += &outer(&d_gates, &cache.input);
+        gradients.weight_hh += &outer(&d_gates, &cache.h_prev);
+        gradients.bias_ih += &d_gates;
+        gradients.bias_hh += &d_gates;
+
+        (
+            self.weight_ih.t().dot(&d_gates),
+            self.weight_hh.t().dot(&d_gates),
+            dc * &cache.f,
+        )
+    }
+
+    fn apply_gradients(&mut self, gradients: &CellGradients, regularization: f64, settings: &OptimizerSettings) {
+        let weight_ih = &gradients.weight_ih + &(regularization * &self.weight_ih);
+        self.input_optimizer_state.update(&mut self.weight_ih, &weight_ih, &mut self.bias_ih, &gradients.bias_ih, settings);
+        let weight_hh = &gradients.weight_hh + &(regularization * &self.weight_hh);
+        self.hidden_optimizer_state.update(&mut self.weight_hh, &weight_hh, &mut self.bias_hh, &gradients.bias_hh, settings);
+    }
+}
+
+// 一个 mini-batch 上累加的梯度，与模型参数一一对应
+#[derive(Debug, Clone)]
+struct LstmGradients {
+    cells: Vec<CellGradients>,
+    output_weight: Array2<f64>,
+    output_bias: Array1<f64>,
+}
+
+impl LstmGradients {
+    fn zeros(model: &LstmModel) -> Self {
+        Self {
+            cells: model.lstm_cells.iter().map(CellGradients::zeros).collect(),
+            output_weight: Array2::zeros(model.output_weight.raw_dim()),
+            output_bias: Array1::zeros(model.output_bias.len()),
+        }
+    }
+
+    fn scale(&mut self, factor: f64) {
+        for cell in &mut self.cells {
+            cell.weight_ih *= factor;
+            cell.weight_hh *= factor;
+            cell.bias_ih *= factor;
+            cell.bias_hh *= factor;
+        }
+        self.output_weight *= factor;
+        self.output_bias *= factor;
+    }
+
+    fn norm(&self) -> f64 {
+        let cells: f64 = self.cells.iter()
+            .map(|cell| {
+                squared_sum(&cell.weight_ih) + squared_sum(&cell.weight_hh)
+                    + squared_sum(&cell.bias_ih) + squared_sum(&cell.bias_hh)
+            })
+            .sum();
+        (cells + squared_sum(&self.output_weight) + squared_sum(&self.output_bias)).sqrt()
+    }
+
+    // 按全局范数等比例缩放，防止长序列上的梯度爆炸
+    fn clip(&mut self, max_norm: f64) {
+        if max_norm <= 0.0 {
+            return;
+        }
+        let norm = self.norm();
+        if norm > max_norm {
+            self.scale(max_norm / norm);
+        }
     }
 }
 
+// 一个样本的完整前向缓存：steps[layer][t]，masks[layer][t] 为该层输出送入下一层前的 dropout 掩码
+struct SequenceCache {
+    steps: Vec<Vec<StepCache>>,
+    masks: Vec<Vec<Option<Array1<f64>>>>,
+    final_hidden: Array1<f64>,
+    output: Array1<f64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LstmModel {
     pub config: LstmConfig,
+    #[serde(default)]
+    pub base_config: LstmConfig,
     pub lstm_cells: Vec<LstmCell>,
     pub output_weight: Array2<f64>,
     pub output_bias: Array1<f64>,
+    #[serde(default)]
+    pub output_optimizer_state: OptimizerState,
     pub is_trained: bool,
     pub lottery_type: LotteryType,
     pub feature_scaler: Option<StandardScaler>,
     pub target_encoder: Option<OneHotEncoder>,
+    #[serde(default)]
+    pub loss_history: Vec<f64>,
+    #[serde(default)]
+    pub validation_loss: Vec<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -201,37 +400,78 @@ impl OneHotEncoder {
 
 impl LstmModel {
     pub fn new(config: LstmConfig, lottery_type: LotteryType) -> Self {
-        let mut lstm_cells = Vec::new();
-        let mut input_size = 33; // 根据SSQ调整为33个红球特征
-        
-        for _ in 0..config.num_layers {
-            lstm_cells.push(LstmCell::new(input_size, config.hidden_size));
-            input_size = config.hidden_size;
-        }
+        // 输入为主号码池大小的特征向量，输出为每个号码的概率；训练时按实际特征维度重建
+        let pool_size = GameSpec::for_type(&lottery_type).main_pool_size();
+        let lstm_cells = Self::build_cells(&config, pool_size);
 
-        let output_size = 33; // 红球范围1-33
-        let output_weight = Array2::random((output_size, config.hidden_size), StandardNormal);
+        let output_size = pool_size;
+        let bound = 1.0 / (config.hidden_size as f64).sqrt();
+        let output_weight = Array2::random((output_size, config.hidden_size), Uniform::new_inclusive(-bound, bound));
         let output_bias = Array1::zeros(output_size);
+        let output_optimizer_state = OptimizerState::new(output_size, config.hidden_size);
 
         Self {
+            base_config: config.clone(),
             config,
             lstm_cells,
             output_weight,
             output_bias,
+            output_optimizer_state,
             is_trained: false,
             lottery_type,
             feature_scaler: None,
             target_encoder: None,
+            loss_history: Vec::new(),
+            validation_loss: Vec::new(),
         }
     }
 
+    fn build_cells(config: &LstmConfig, input_size: usize) -> Vec<LstmCell> {
+        let mut lstm_cells = Vec::new();
+        let mut input_size = input_size;
+        
+        for _ in 0..config.num_layers.max(1) {
+            lstm_cells.push(LstmCell::new(input_size, config.hidden_size));
+            input_size = config.hidden_size;
+        }
+
+        lstm_cells
+    }
+
+    fn input_size(&self) -> usize {
+        self.lstm_cells.first().map(|cell| cell.input_size).unwrap_or(0)
+    }
+
+    fn scale_features(&self, features: &[Vec<f64>]) -> Result<Vec<Vec<f64>>> {
+        let features_array = Array2::from_shape_vec(
+            (features.len(), features[0].len()),
+            features.iter().flatten().cloned().collect()
+        ).map_err(|_| crate::lottery::errors::LotteryError::AlgorithmError(
+            "Failed to create feature array".to_string()
+        ))?;
+
+        let scaled = match &self.feature_scaler {
+            Some(scaler) if scaler.mean.len() != features_array.ncols() => {
+                return Err(crate::lottery::errors::LotteryError::AlgorithmError(format!(
+                    "Feature dimension mismatch: model expects {}, got {}",
+                    scaler.mean.len(),
+                    features_array.ncols()
+                )));
+            }
+            Some(scaler) => scaler.transform(&features_array),
+            None => features_array,
+        };
+        Ok(scaled.rows().into_iter().map(|row| row.to_vec()).collect())
+    }
+
     fn prepare_sequences(&self, features: &[Vec<f64>], targets: &[Vec<u32>]) -> (Array3<f64>, Array2<f64>) {
         let sequence_length = self.config.sequence_length;
         let n_samples = features.len().saturating_sub(sequence_length);
         let feature_dim = features[0].len();
         
         let mut X = Array3::zeros((n_samples, sequence_length, feature_dim));
-        let mut y = Array2::zeros((n_samples, 33)); // 33个红球
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let mut y = Array2::zeros((n_samples, spec.main_pool_size()));
         
         for i in 0..n_samples {
             for t in 0..sequence_length {
@@ -243,8 +483,8 @@ impl LstmModel {
             // 将目标转为多热编码
             if let Some(target) = targets.get(i + sequence_length) {
                 for &num in target {
-                    if num >= 1 && num <= 33 {
-                        y[[i, (num - 1) as usize]] = 1.0;
+                    if spec.is_valid_main(num) {
+                        y[[i, (num - spec.main_min()) as usize]] = 1.0;
                     }
                 }
             }
@@ -254,50 +494,230 @@ impl LstmModel {
     }
 
     fn forward(&self, sequences: &Array3<f64>) -> Array2<f64> {
-        let (batch_size, seq_len, _) = sequences.dim();
-        let mut output = Array2::zeros((batch_size, 33));
+        let mut output = Array2::zeros((sequences.dim().0, self.output_bias.len()));
         
-        for batch_idx in 0..batch_size {
-            let mut hidden = Array1::zeros(self.config.hidden_size);
-            let mut cell = Array1::zeros(self.config.hidden_size);
-            
-            for seq_idx in 0..seq_len {
-                let input = sequences.slice(s![batch_idx, seq_idx, ..]);
-                
-                for lstm_cell in &self.lstm_cells {
-                    let (new_hidden, new_cell) = lstm_cell.forward(&input.to_owned(), &hidden, &cell);
-                    hidden = new_hidden;
-                    cell = new_cell;
-                }
-            }
-            
-            let final_output = self.output_weight.dot(&hidden) + &self.output_bias;
-            for (i, &val) in final_output.iter().enumerate() {
-                output[[batch_idx, i]] = sigmoid(val);
-            }
+        for (mut row, sequence) in output.rows_mut().into_iter().zip(sequences.outer_iter()) {
+            row.assign(&self.forward_sequence(sequence, None).output);
         }
         
         output
     }
 
-    fn train_epoch(&mut self, X: &Array3<f64>, y: &Array2<f64>) -> f64 {
-        let predictions = self.forward(X);
-        let loss = self.calculate_loss(&predictions, y);
-        
-        // 简化版训练：这里使用梯度下降
-        let learning_rate = self.config.learning_rate;
-        
-        // 更新权重（简化版）
-        let gradient = &predictions - y;
-        let delta_w = gradient.t().dot(&X.slice(s![.., -1, ..]));
-        self.output_weight = &self.output_weight - &(delta_w * learning_rate);
-        
+    // 传入 rng 时为训练模式，在层与层之间施加 dropout
+    fn forward_sequence(&self, sequence: ArrayView2<f64>, mut rng: Option<&mut StdRng>) -> SequenceCache {
+        let hidden_size = self.config.hidden_size;
+        let n_layers = self.lstm_cells.len();
+        let mut layer_inputs: Vec<Array1<f64>> = sequence.rows().into_iter().map(|row| row.to_owned()).collect();
+        let mut steps = Vec::with_capacity(n_layers);
+        let mut masks = Vec::with_capacity(n_layers);
+
+        for (layer_idx, lstm_cell) in self.lstm_cells.iter().enumerate() {
+            let mut hidden = Array1::zeros(hidden_size);
+            let mut cell = Array1::zeros(hidden_size);
+            let mut layer_steps = Vec::with_capacity(layer_inputs.len());
+
+            for input in layer_inputs {
+                let step = lstm_cell.step(input, hidden, cell);
+                hidden = step.h.clone();
+                cell = step.c.clone();
+                layer_steps.push(step);
+            }
+
+            // 最后一层的输出直接接输出层，不做 dropout
+            let apply_dropout = layer_idx + 1 < n_layers && self.config.dropout > 0.0;
+            let layer_masks: Vec<Option<Array1<f64>>> = layer_steps.iter()
+                .map(|_| match rng.as_deref_mut() {
+                    Some(rng) if apply_dropout => Some(dropout_mask(hidden_size, self.config.dropout, rng)),
+                    _ => None,
+                })
+                .collect();
+
+            layer_inputs = layer_steps.iter()
+                .zip(&layer_masks)
+                .map(|(step, mask)| match mask {
+                    Some(mask) => &step.h * mask,
+                    None => step.h.clone(),
+                })
+                .collect();
+
+            steps.push(layer_steps);
+            masks.push(layer_masks);
+        }
+
+        let final_hidden = steps.last()
+            .and_then(|layer| layer.last())
+            .map(|step| step.h.clone())
+            .unwrap_or_else(|| Array1::zeros(hidden_size));
+        let output = (self.output_weight.dot(&final_hidden) + &self.output_bias).mapv(sigmoid);
+
+        SequenceCache { steps, masks, final_hidden, output }
+    }
+
+    // 沿时间与层反向传播，累加单个样本的梯度并返回该样本的损失
+    fn accumulate_gradients(
+        &self,
+        sequence: ArrayView2<f64>,
+        target: ArrayView1<f64>,
+        rng: Option<&mut StdRng>,
+        gradients: &mut LstmGradients,
+    ) -> f64 {
+        let cache = self.forward_sequence(sequence, rng);
+        let loss = binary_cross_entropy(&cache.output, &target);
+
+        // sigmoid + 交叉熵，dL/dz = p - y
+        let delta = &cache.output - &target;
+        gradients.output_weight += &outer(&delta, &cache.final_hidden);
+        gradients.output_bias += &delta;
+
+        let seq_len = sequence.nrows();
+        let hidden_size = self.config.hidden_size;
+        // 每个时刻从上一层 (顶层为输出层) 传回的隐藏状态梯度
+        let mut dh_above: Vec<Array1<f64>> = vec![Array1::zeros(hidden_size); seq_len];
+        if let Some(last) = dh_above.last_mut() {
+            *last = self.output_weight.t().dot(&delta);
+        }
+
+        for (layer_idx, lstm_cell) in self.lstm_cells.iter().enumerate().rev() {
+            let mut dh_next = Array1::zeros(hidden_size);
+            let mut dc_next = Array1::zeros(hidden_size);
+            let mut d_inputs = vec![Array1::zeros(0); seq_len];
+
+            for t in (0..seq_len).rev() {
+                let dh = &dh_above[t] + &dh_next;
+                let (d_input, dh_prev, dc_prev) = lstm_cell.backward_step(
+                    &cache.steps[layer_idx][t],
+                    &dh,
+                    &dc_next,
+                    &mut gradients.cells[layer_idx],
+                );
+                d_inputs[t] = d_input;
+                dh_next = dh_prev;
+                dc_next = dc_prev;
+            }
+
+            if layer_idx > 0 {
+                dh_above = d_inputs.into_iter()
+                    .zip(&cache.masks[layer_idx - 1])
+                    .map(|(d_input, mask)| match mask {
+                        Some(mask) => d_input * mask,
+                        None => d_input,
+                    })
+                    .collect();
+            }
+        }
+
         loss
     }
 
-    fn calculate_loss(&self, predictions: &Array2<f64>, targets: &Array2<f64>) -> f64 {
-        let diff = predictions - targets;
-        diff.mapv(|x| x * x).mean().unwrap()
+    fn apply_gradients(&mut self, gradients: &LstmGradients, settings: &OptimizerSettings) {
+        let regularization = self.config.regularization;
+        for (cell, cell_gradients) in self.lstm_cells.iter_mut().zip(&gradients.cells) {
+            cell.apply_gradients(cell_gradients, regularization, settings);
+        }
+
+        let output_weight = &gradients.output_weight + &(regularization * &self.output_weight);
+        self.output_optimizer_state.update(
+            &mut self.output_weight,
+            &output_weight,
+            &mut self.output_bias,
+            &gradients.output_bias,
+            settings,
+        );
+    }
+
+    fn train_epoch(
+        &mut self,
+        sequences: &Array3<f64>,
+        targets: &Array2<f64>,
+        indices: &[usize],
+        settings: &OptimizerSettings,
+        rng: &mut StdRng,
+    ) -> f64 {
+        let mut order = indices.to_vec();
+        order.shuffle(rng);
+
+        let mut total_loss = 0.0;
+        for batch in order.chunks(self.config.batch_size.max(1)) {
+            let mut gradients = LstmGradients::zeros(self);
+            for &i in batch {
+                total_loss += self.accumulate_gradients(
+                    sequences.index_axis(Axis(0), i),
+                    targets.row(i),
+                    Some(&mut *rng),
+                    &mut gradients,
+                );
+            }
+
+            gradients.scale(1.0 / batch.len() as f64);
+            gradients.clip(self.config.gradient_clip);
+            self.apply_gradients(&gradients, settings);
+        }
+        
+        total_loss / (order.len() * self.output_bias.len()) as f64
+    }
+
+    // 按号码平均的交叉熵，便于与 MLP 的损失曲线对比
+    fn calculate_loss(&self, sequences: &Array3<f64>, targets: &Array2<f64>, indices: &[usize]) -> f64 {
+        let total: f64 = indices.iter()
+            .map(|&i| {
+                let output = self.forward_sequence(sequences.index_axis(Axis(0), i), None).output;
+                binary_cross_entropy(&output, &targets.row(i))
+            })
+            .sum();
+        total / (indices.len() * self.output_bias.len()) as f64
+    }
+
+    // 时间上最后 validation_split 的序列作验证集；没有验证集时按训练损失早停
+    fn fit(&mut self, sequences: &Array3<f64>, targets: &Array2<f64>, optimizer: Optimizer) {
+        let n_samples = sequences.dim().0;
+        let n_validation = (n_samples as f64 * self.config.validation_split.clamp(0.0, 0.5)).round() as usize;
+        let n_train = n_samples - n_validation;
+        let train_indices: Vec<usize> = (0..n_train).collect();
+        let validation_indices: Vec<usize> = (n_train..n_samples).collect();
+
+        self.loss_history.clear();
+        self.validation_loss.clear();
+
+        let mut rng = StdRng::from_entropy();
+        let mut best_loss = f64::INFINITY;
+        let mut best_weights = (self.lstm_cells.clone(), self.output_weight.clone(), self.output_bias.clone());
+        let mut patience_counter = 0;
+        
+        for epoch in 0..self.config.epochs {
+            let settings = OptimizerSettings {
+                optimizer,
+                learning_rate: self.config.learning_rate_schedule
+                    .learning_rate(self.config.learning_rate, epoch, self.config.epochs),
+                momentum: self.config.momentum,
+                second_moment_decay: self.config.second_moment_decay,
+            };
+            let loss = self.train_epoch(sequences, targets, &train_indices, &settings, &mut rng);
+            self.loss_history.push(loss);
+
+            let monitored = if validation_indices.is_empty() {
+                loss
+            } else {
+                let validation = self.calculate_loss(sequences, targets, &validation_indices);
+                self.validation_loss.push(validation);
+                validation
+            };
+            
+            if monitored < best_loss {
+                best_loss = monitored;
+                best_weights = (self.lstm_cells.clone(), self.output_weight.clone(), self.output_bias.clone());
+                patience_counter = 0;
+            } else {
+                patience_counter += 1;
+            }
+            
+            if self.config.early_stopping && patience_counter >= self.config.patience {
+                break;
+            }
+        }
+
+        if self.config.early_stopping {
+            (self.lstm_cells, self.output_weight, self.output_bias) = best_weights;
+        }
     }
 
     fn select_top_numbers(&self, probabilities: &Array1<f64>, count: usize) -> Vec<u32> {
@@ -308,9 +728,10 @@ impl LstmModel {
         
         indexed_probs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
         
+        let min_number = GameSpec::for_type(&self.lottery_type).main_min();
         indexed_probs.iter()
             .take(count)
-            .map(|(i, _)| (i + 1) as u32)
+            .map(|(i, _)| *i as u32 + min_number)
             .collect()
     }
 }
@@ -321,21 +742,33 @@ impl PredictionAlgorithm for LstmModel {
         "LSTM Neural Network".to_string()
     }
 
-    fn algorithm_type(&self) -> String {
-        "lstm".to_string()
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::Lstm
     }
 
     async fn train(
         &mut self,
         training_data: &TrainingData,
-        _config: &AlgorithmConfig,
+        config: &AlgorithmConfig,
     ) -> Result<f64> {
+        let updated = config.apply(&self.base_config)?;
+        // 隐层结构变化后从头初始化
+        if updated.hidden_size != self.config.hidden_size || updated.num_layers != self.config.num_layers {
+            let base_config = self.base_config.clone();
+            *self = Self::new(updated, self.lottery_type.clone());
+            self.base_config = base_config;
+        } else {
+            self.config = updated;
+        }
+
         if training_data.features.is_empty() {
             return Err(crate::lottery::errors::LotteryError::InvalidParameter(
                 "No training data provided".to_string()
             ));
         }
 
+        let optimizer = Optimizer::from_name(&self.config.optimizer)?;
+
         // 标准化特征
         let mut scaler = StandardScaler::new();
         let features_array = Array2::from_shape_vec(
@@ -347,6 +780,7 @@ impl PredictionAlgorithm for LstmModel {
         
         scaler.fit(&features_array);
         self.feature_scaler = Some(scaler);
+        let scaled_features = self.scale_features(&training_data.features)?;
 
         // 编码目标
         let mut encoder = OneHotEncoder::new();
@@ -354,40 +788,24 @@ impl PredictionAlgorithm for LstmModel {
         self.target_encoder = Some(encoder);
 
         // 准备序列数据
-        let (X, y) = self.prepare_sequences(&training_data.features, &training_data.targets);
+        let (x, y) = self.prepare_sequences(&scaled_features, &training_data.targets);
         
-        if X.shape()[0] < 10 {
+        if x.shape()[0] < 10 {
             return Err(crate::lottery::errors::LotteryError::AlgorithmError(
                 "Insufficient data for LSTM training".to_string()
             ));
         }
 
-        // 训练
-        let mut best_loss = f64::INFINITY;
-        let mut patience_counter = 0;
-        
-        for epoch in 0..self.config.epochs {
-            let loss = self.train_epoch(&X, &y);
-            
-            if loss < best_loss {
-                best_loss = loss;
-                patience_counter = 0;
-            } else {
-                patience_counter += 1;
-            }
-            
-            if self.config.early_stopping && patience_counter >= self.config.patience {
-                break;
-            }
+        if x.dim().2 != self.input_size() {
+            self.lstm_cells = Self::build_cells(&self.config, x.dim().2);
         }
 
+        self.fit(&x, &y, optimizer);
+
         self.is_trained = true;
-        
-        // 计算准确率（简化版）
-        let final_predictions = self.forward(&X);
-        let accuracy = 1.0 - self.calculate_loss(&final_predictions, &y);
-        
-        Ok(accuracy)
+
+        let metrics = self.evaluate(training_data).await?;
+        Ok(metrics.accuracy)
     }
 
 
@@ -422,6 +840,14 @@ impl PredictionAlgorithm for LstmModel {
             features.push(feature);
         }
 
+        if features[0].len() != self.input_size() {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(format!(
+                "Feature dimension mismatch: model expects {}, got {}",
+                self.input_size(),
+                features[0].len()
+            )));
+        }
+
         // 标准化特征
         let features_array = Array2::from_shape_vec(
             (features.len(), features[0].len()),
@@ -449,45 +875,19 @@ impl PredictionAlgorithm for LstmModel {
         let probabilities = predictions.row(0);
 
         // 根据彩票类型确定预测数量
-        let main_count = match self.lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
-
-        let special_count = match self.lottery_type {
-            LotteryType::Ssq => 1,
-            LotteryType::Dlt => 2,
-            _ => 0,
-        };
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let main_count = spec.pick_count;
 
         let predicted_numbers = self.select_top_numbers(&probabilities.to_owned(), main_count);
 
-        // 特殊号码预测（简化版）
-        let predicted_special_numbers = if special_count > 0 {
-            let special_max = match self.lottery_type {
-                LotteryType::Ssq => 16,
-                LotteryType::Dlt => 12,
-                _ => 0,
-            };
-            
-            let mut special_numbers = Vec::new();
-            for i in 1..=special_max {
-                special_numbers.push(i);
-            }
-            
-            special_numbers.sort_by(|a, b| (predictions[[0, *a as usize - 1]]).partial_cmp(&predictions[[0, *b as usize - 1]]).unwrap());
-            Some(special_numbers.into_iter().take(special_count).collect())
-        } else {
-            None
-        };
+        // 输出层只覆盖主号码池，特别号按历史出现次数
+        let special_scores = spec.has_special()
+            .then(|| calibration::history_special_scores(&spec, historical_data));
+        let predicted_special_numbers = special_scores.as_ref()
+            .map(|scores| calibration::top_special_numbers(&spec, scores));
 
-        let confidence_scores = predicted_numbers.iter()
-            .map(|&num| probabilities[(num - 1) as usize])
-            .collect();
+        let probabilities = NumberProbabilities::from_scores(&spec, &probabilities.to_vec(), special_scores.as_deref());
+        let confidence_scores = probabilities.confidence(&spec, &predicted_numbers);
 
         let computation_time = start_time.elapsed().as_millis() as u64;
 
@@ -496,11 +896,12 @@ impl PredictionAlgorithm for LstmModel {
             predicted_special_numbers,
             confidence_scores,
             algorithm_metadata: HashMap::from_iter(vec![
-                ("algorithm".to_string(), serde_json::Value::String("lstm".to_string())),
+                ("algorithm".to_string(), serde_json::Value::String(AlgorithmType::Lstm.to_string())),
                 ("sequence_length".to_string(), serde_json::Value::Number(serde_json::Number::from(self.config.sequence_length))),
                 ("hidden_size".to_string(), serde_json::Value::Number(serde_json::Number::from(self.config.hidden_size))),
             ]),
             computation_time_ms: computation_time,
+            probabilities: Some(probabilities),
         })
     }
 
@@ -508,17 +909,37 @@ impl PredictionAlgorithm for LstmModel {
         &self,
         test_data: &TrainingData,
     ) -> Result<EvaluationMetrics> {
-        let (X, y) = self.prepare_sequences(&test_data.features, &test_data.targets);
-        let predictions = self.forward(&X);
-        let accuracy = 1.0 - self.calculate_loss(&predictions, &y);
+        let scores = self.predict_scores(test_data).await?;
+        MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)
+    }
+
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
 
-        let mut metrics = EvaluationMetrics::default();
-        metrics.accuracy = accuracy;
-        metrics.precision = accuracy * 0.95;
-        metrics.recall = accuracy * 0.9;
-        metrics.f1_score = 2.0 * (metrics.precision * metrics.recall) / (metrics.precision + metrics.recall + 1e-8);
+        // 前 sequence_length 期只作为输入序列，不产生预测
+        if test_data.features.len() <= self.config.sequence_length {
+            return Ok(Vec::new());
+        }
 
-        Ok(metrics)
+        let features = self.scale_features(&test_data.features)?;
+        let (sequences, _) = self.prepare_sequences(&features, &test_data.targets);
+        Ok(self.forward(&sequences).rows().into_iter().map(|row| row.to_vec()).collect())
+    }
+
+    // 与 predict_scores 一样跳过前 sequence_length 期
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        let rows = calibration::frequency_special_scores(&self.lottery_type, test_data)?;
+        Ok(rows.get(self.config.sequence_length..).map(<[_]>::to_vec).unwrap_or_default())
     }
 
     fn is_trained(&self) -> bool {
@@ -576,11 +997,36 @@ fn tanh(x: f64) -> f64 {
     x.tanh()
 }
 
+fn outer(a: &Array1<f64>, b: &Array1<f64>) -> Array2<f64> {
+    a.view().insert_axis(Axis(1)).dot(&b.view().insert_axis(Axis(0)))
+}
+
+fn squared_sum<D: ndarray::Dimension>(values: &ndarray::Array<f64, D>) -> f64 {
+    values.iter().map(|x| x * x).sum()
+}
+
+// 输出层为 sigmoid，按号码求和的交叉熵
+fn binary_cross_entropy(predictions: &Array1<f64>, targets: &ArrayView1<f64>) -> f64 {
+    let epsilon = 1e-15;
+    predictions.iter()
+        .zip(targets.iter())
+        .map(|(&p, &t)| {
+            let p = p.clamp(epsilon, 1.0 - epsilon);
+            -(t * p.ln() + (1.0 - t) * (1.0 - p).ln())
+        })
+        .sum()
+}
+
+fn dropout_mask(size: usize, rate: f64, rng: &mut StdRng) -> Array1<f64> {
+    let keep = 1.0 - rate;
+    Array1::from_shape_fn(size, |_| if rng.gen_range(0.0..1.0) < rate { 0.0 } else { 1.0 / keep })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::lottery::algorithms::traits::TrainingData;
-    use crate::lottery::models::LotteryType;
+    use crate::lottery::models::{LotteryDrawing, LotteryType};
     use chrono::NaiveDate;
     use uuid::Uuid;
 
@@ -589,22 +1035,84 @@ mod tests {
         let config = LstmConfig::default();
         let model = LstmModel::new(config, LotteryType::Ssq);
         assert_eq!(model.name(), "LSTM Neural Network");
-        assert_eq!(model.algorithm_type(), "lstm");
+        assert_eq!(model.algorithm_type(), AlgorithmType::Lstm);
         assert!(!model.is_trained());
     }
 
-    #[tokio::test]
-    async fn test_lstm_training() {
+    // 待校验的解析梯度及对应参数的扰动函数
+    type GradientCase = (f64, Box<dyn Fn(&mut LstmModel, f64)>);
+
+    fn numeric_gradient(
+        model: &LstmModel,
+        sequence: &Array2<f64>,
+        target: &Array1<f64>,
+        perturb: &dyn Fn(&mut LstmModel, f64),
+    ) -> f64 {
+        let epsilon = 1e-6;
+        let loss = |delta: f64| {
+            let mut model = model.clone();
+            perturb(&mut model, delta);
+            binary_cross_entropy(&model.forward_sequence(sequence.view(), None).output, &target.view())
+        };
+        (loss(epsilon) - loss(-epsilon)) / (2.0 * epsilon)
+    }
+
+    #[test]
+    fn test_bptt_matches_finite_differences() {
         let config = LstmConfig {
-            sequence_length: 5,
-            hidden_size: 32,
-            epochs: 2,
+            hidden_size: 4,
+            num_layers: 2,
+            sequence_length: 3,
+            dropout: 0.0,
             ..Default::default()
         };
+        let mut model = LstmModel::new(config.clone(), LotteryType::Ssq);
+        model.lstm_cells = LstmModel::build_cells(&config, 3);
+
+        let sequence = Array2::from_shape_fn((3, 3), |(t, f)| ((t * 3 + f) as f64 * 0.37).sin());
+        let mut target = Array1::zeros(33);
+        for i in [0, 4, 9, 17, 25, 32] {
+            target[i] = 1.0;
+        }
+
+        let mut gradients = LstmGradients::zeros(&model);
+        model.accumulate_gradients(sequence.view(), target.view(), None, &mut gradients);
+
+        // 覆盖两层的 i/f/g/o 四个门、循环权重以及输出层
+        let cases: Vec<GradientCase> = vec![
+            (gradients.cells[0].weight_ih[[1, 2]], Box::new(|m: &mut LstmModel, d: f64| m.lstm_cells[0].weight_ih[[1, 2]] += d)),
+            (gradients.cells[0].weight_hh[[6, 3]], Box::new(|m: &mut LstmModel, d: f64| m.lstm_cells[0].weight_hh[[6, 3]] += d)),
+            (gradients.cells[0].bias_ih[9], Box::new(|m: &mut LstmModel, d: f64| m.lstm_cells[0].bias_ih[9] += d)),
+            (gradients.cells[1].weight_ih[[13, 0]], Box::new(|m: &mut LstmModel, d: f64| m.lstm_cells[1].weight_ih[[13, 0]] += d)),
+            (gradients.cells[1].weight_hh[[5, 1]], Box::new(|m: &mut LstmModel, d: f64| m.lstm_cells[1].weight_hh[[5, 1]] += d)),
+            (gradients.output_weight[[4, 2]], Box::new(|m: &mut LstmModel, d: f64| m.output_weight[[4, 2]] += d)),
+            (gradients.output_bias[9], Box::new(|m: &mut LstmModel, d: f64| m.output_bias[9] += d)),
+        ];
+
+        for (analytic, perturb) in cases {
+            let numeric = numeric_gradient(&model, &sequence, &target, perturb.as_ref());
+            assert!(
+                (analytic - numeric).abs() <= 1e-7 + 1e-5 * numeric.abs(),
+                "analytic {} vs numeric {}", analytic, numeric
+            );
+        }
+    }
 
+    #[tokio::test]
+    async fn test_stacked_lstm_training_reduces_loss() {
+        let config = LstmConfig {
+            sequence_length: 3,
+            hidden_size: 8,
+            num_layers: 2,
+            epochs: 40,
+            batch_size: 4,
+            learning_rate: 0.02,
+            dropout: 0.1,
+            early_stopping: false,
+            ..Default::default()
+        };
         let mut model = LstmModel::new(config, LotteryType::Ssq);
-        
-        // 创建足够的训练数据
+
         let mut training_data = TrainingData {
             features: Vec::new(),
             targets: Vec::new(),
@@ -612,13 +1120,11 @@ mod tests {
             weights: None,
         };
 
-        for i in 0..20 {
-            let mut features = vec![0.0; 33];
-            for j in 0..33 {
-                features[j] = (i + j) as f64 % 1.0;
-            }
-            training_data.features.push(features);
-            training_data.targets.push(vec![1, 2, 3, 4, 5, 6]);
+        // 下一期号码由上一期特征的奇偶决定
+        for i in 0..30 {
+            let parity = (i % 2) as f64;
+            training_data.features.push(vec![parity, 1.0 - parity, 0.5, 0.0]);
+            training_data.targets.push(if i % 2 == 0 { vec![1, 2, 3, 4, 5, 6] } else { vec![7, 8, 9, 10, 11, 12] });
         }
 
         let config = AlgorithmConfig {
@@ -628,22 +1134,25 @@ mod tests {
             feature_config: HashMap::new(),
         };
 
-        let result = model.train(&training_data, &config).await;
-        assert!(result.is_ok());
-        assert!(model.is_trained());
+        model.train(&training_data, &config).await.unwrap();
+        assert_eq!(model.lstm_cells[0].input_size, 4);
+        assert_eq!(model.validation_loss.len(), 40);
+        assert!(model.loss_history.last().unwrap() < model.loss_history.first().unwrap());
+        assert!(model.lstm_cells[0].input_optimizer_state.step > 0);
     }
 
     #[tokio::test]
-    async fn test_lstm_prediction() {
+    async fn test_lstm_training() {
         let config = LstmConfig {
-            sequence_length: 3,
-            hidden_size: 16,
+            sequence_length: 5,
+            hidden_size: 32,
+            epochs: 2,
             ..Default::default()
         };
 
         let mut model = LstmModel::new(config, LotteryType::Ssq);
         
-        // 创建训练数据
+        // 创建足够的训练数据
         let mut training_data = TrainingData {
             features: Vec::new(),
             targets: Vec::new(),
@@ -651,10 +1160,10 @@ mod tests {
             weights: None,
         };
 
-        for i in 0..15 {
+        for i in 0..20 {
             let mut features = vec![0.0; 33];
             for j in 0..33 {
-                features[j] = (i * j) as f64 % 1.0;
+                features[j] = (i + j) as f64 % 1.0;
             }
             training_data.features.push(features);
             training_data.targets.push(vec![1, 2, 3, 4, 5, 6]);
@@ -667,10 +1176,23 @@ mod tests {
             feature_config: HashMap::new(),
         };
 
-        model.train(&training_data, &config).await.unwrap();
+        let result = model.train(&training_data, &config).await;
+        assert!(result.is_ok());
+        assert!(model.is_trained());
+    }
+
+    #[tokio::test]
+    async fn test_lstm_prediction() {
+        let config = LstmConfig {
+            sequence_length: 3,
+            hidden_size: 16,
+            ..Default::default()
+        };
+
+        let mut model = LstmModel::new(config, LotteryType::Ssq);
 
         let mut drawings = Vec::new();
-        for i in 0..10 {
+        for i in 0..20 {
             drawings.push(LotteryDrawing {
                 id: Uuid::new_v4(),
                 lottery_type: LotteryType::Ssq,
@@ -691,10 +1213,33 @@ mod tests {
             });
         }
 
+        // 训练特征与预测时使用同一个特征提取器，维度一致
+        let extractor = crate::lottery::algorithms::feature_engineering::LotteryFeatureExtractor;
+        let mut training_data = TrainingData {
+            features: Vec::new(),
+            targets: Vec::new(),
+            special_targets: None,
+            weights: None,
+        };
+
+        for (i, drawing) in drawings.iter().enumerate().skip(1) {
+            training_data.features.push(extractor.extract_single_features(drawing, &drawings[..i]).unwrap());
+            training_data.targets.push(drawing.winning_numbers.clone());
+        }
+
+        let config = AlgorithmConfig {
+            lottery_type: LotteryType::Ssq,
+            parameters: HashMap::new(),
+            hyperparameters: HashMap::new(),
+            feature_config: HashMap::new(),
+        };
+
+        model.train(&training_data, &config).await.unwrap();
+
         let input = PredictionInput {
             lottery_type: LotteryType::Ssq,
             historical_data: drawings,
-            target_date: NaiveDate::from_ymd_opt(2024, 1, 11).unwrap(),
+            target_date: NaiveDate::from_ymd_opt(2024, 1, 21).unwrap(),
             additional_features: None,
         };
 
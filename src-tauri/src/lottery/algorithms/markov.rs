@@ -0,0 +1,495 @@
+use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::{self, NumberProbabilities};
+use crate::lottery::algorithms::metrics::MetricsCalculator;
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryType};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkovConfig {
+    // 向基础开出率收缩的伪计数，计数少的转移更接近基础开出率
+    pub smoothing: f64,
+    // 号码转移与遗漏转移的混合权重，1 表示只用号码转移
+    pub transition_weight: f64,
+    // 遗漏期数的状态上限，更长的遗漏 (以及从未开出) 合并为最后一个状态
+    pub max_gap: usize,
+}
+
+impl Default for MarkovConfig {
+    fn default() -> Self {
+        Self {
+            smoothing: 5.0,
+            transition_weight: 0.5,
+            max_gap: 30,
+        }
+    }
+}
+
+impl ModelConfig for MarkovConfig {
+    fn validate(&self) -> Result<()> {
+        ensure_config(
+            self.smoothing >= 0.0 && self.smoothing.is_finite(),
+            "smoothing must be non-negative",
+        )?;
+        ensure_config(
+            (0.0..=1.0).contains(&self.transition_weight),
+            "transition_weight must be in [0, 1]",
+        )?;
+        ensure_config(self.max_gap > 0, "max_gap must be positive")
+    }
+}
+
+// 一个号码池上的两条转移链，可直接序列化用于可视化
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TransitionChain {
+    // 号码池按升序
+    pub numbers: Vec<u32>,
+    // probabilities[j][i]：上一期开出 numbers[j] 时下一期开出 numbers[i] 的概率
+    pub probabilities: Vec<Vec<f64>>,
+    // gap_hazard[k]：已遗漏 k 期 (0 为上一期刚开出) 的号码下一期开出的概率，即 k → 0 的转移；
+    // 其余概率转移到 k + 1
+    pub gap_hazard: Vec<f64>,
+}
+
+impl TransitionChain {
+    fn fit(draws: &[Vec<u32>], numbers: RangeInclusive<u32>, base_rate: f64, config: &MarkovConfig) -> Self {
+        let numbers: Vec<u32> = numbers.collect();
+        let pool_size = numbers.len();
+        let min = numbers.first().copied().unwrap_or(0);
+        let index = |number: u32| number.checked_sub(min).map(|i| i as usize).filter(|&i| i < pool_size);
+
+        let mut transitions = vec![vec![0.0; pool_size]; pool_size];
+        let mut visits = vec![0.0; pool_size];
+        let mut gap_appearances = vec![0.0; config.max_gap + 1];
+        let mut gap_visits = vec![0.0; config.max_gap + 1];
+        let mut gaps = GapTracker::new(pool_size, config.max_gap);
+
+        for (t, draw) in draws.iter().enumerate() {
+            let drawn: Vec<usize> = draw.iter().filter_map(|&n| index(n)).collect();
+
+            if t > 0 {
+                let previous: Vec<usize> = draws[t - 1].iter().filter_map(|&n| index(n)).collect();
+                for &j in &previous {
+                    visits[j] += 1.0;
+                    for &i in &drawn {
+                        transitions[j][i] += 1.0;
+                    }
+                }
+                for (i, &gap) in gaps.gaps.iter().enumerate() {
+                    gap_visits[gap] += 1.0;
+                    if drawn.contains(&i) {
+                        gap_appearances[gap] += 1.0;
+                    }
+                }
+            }
+            gaps.update(&drawn);
+        }
+
+        let smooth = |count: f64, total: f64| (count + config.smoothing * base_rate) / (total + config.smoothing);
+        Self {
+            numbers,
+            probabilities: transitions.iter()
+                .zip(&visits)
+                .map(|(row, &total)| row.iter().map(|&count| smooth(count, total)).collect())
+                .collect(),
+            gap_hazard: gap_appearances.iter()
+                .zip(&gap_visits)
+                .map(|(&count, &total)| smooth(count, total))
+                .collect(),
+        }
+    }
+
+    fn index(&self, number: u32) -> Option<usize> {
+        let min = *self.numbers.first()?;
+        number.checked_sub(min).map(|i| i as usize).filter(|&i| i < self.numbers.len())
+    }
+
+    // 依次吸收 draws，第 t 行是已知前 t + 1 期时对下一期的打分；最后一行即对 draws 之后一期的预测
+    fn rolling_scores(&self, draws: &[Vec<u32>], transition_weight: f64) -> Vec<Vec<f64>> {
+        let pool_size = self.numbers.len();
+        let max_gap = self.gap_hazard.len().saturating_sub(1);
+        let mut gaps = GapTracker::new(pool_size, max_gap);
+
+        draws.iter()
+            .map(|draw| {
+                let drawn: Vec<usize> = draw.iter().filter_map(|&n| self.index(n)).collect();
+                gaps.update(&drawn);
+
+                (0..pool_size)
+                    .map(|i| {
+                        // 上一期各号码出发的转移概率取平均
+                        let transition = if drawn.is_empty() {
+                            self.gap_hazard[gaps.gaps[i]]
+                        } else {
+                            drawn.iter().map(|&j| self.probabilities[j][i]).sum::<f64>() / drawn.len() as f64
+                        };
+                        transition_weight * transition + (1.0 - transition_weight) * self.gap_hazard[gaps.gaps[i]]
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// 每个号码当前的遗漏期数，截断到 max_gap；尚未开出的号码处于 max_gap
+struct GapTracker {
+    gaps: Vec<usize>,
+    max_gap: usize,
+}
+
+impl GapTracker {
+    fn new(pool_size: usize, max_gap: usize) -> Self {
+        Self {
+            gaps: vec![max_gap; pool_size],
+            max_gap,
+        }
+    }
+
+    fn update(&mut self, drawn: &[usize]) {
+        for (i, gap) in self.gaps.iter_mut().enumerate() {
+            *gap = if drawn.contains(&i) { 0 } else { (*gap + 1).min(self.max_gap) };
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkovModel {
+    pub config: MarkovConfig,
+    #[serde(default)]
+    pub base_config: MarkovConfig,
+    pub lottery_type: LotteryType,
+    pub main_chain: TransitionChain,
+    // 训练数据没有特别号时为 None，预测按历史出现次数
+    pub special_chain: Option<TransitionChain>,
+    pub is_trained: bool,
+}
+
+impl MarkovModel {
+    pub fn new(config: MarkovConfig, lottery_type: LotteryType) -> Self {
+        Self {
+            base_config: config.clone(),
+            config,
+            lottery_type,
+            main_chain: TransitionChain::default(),
+            special_chain: None,
+            is_trained: false,
+        }
+    }
+
+    // 主号码的转移矩阵与遗漏转移，未训练时为 None
+    pub fn transition_chain(&self) -> Option<&TransitionChain> {
+        self.is_trained.then_some(&self.main_chain)
+    }
+}
+
+#[async_trait]
+impl PredictionAlgorithm for MarkovModel {
+    fn name(&self) -> String {
+        "Markov Chain".to_string()
+    }
+
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::Markov
+    }
+
+    async fn train(
+        &mut self,
+        training_data: &TrainingData,
+        config: &AlgorithmConfig,
+    ) -> Result<f64> {
+        self.config = config.apply(&self.base_config)?;
+        if training_data.targets.len() < 2 {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "At least two drawings are needed to estimate transitions".to_string()
+            ));
+        }
+
+        let spec = GameSpec::for_type(&self.lottery_type);
+        self.main_chain = TransitionChain::fit(
+            &training_data.targets,
+            spec.main_numbers(),
+            spec.main_count as f64 / spec.main_pool_size() as f64,
+            &self.config,
+        );
+        self.special_chain = match (spec.special_numbers(), &training_data.special_targets) {
+            (Some(numbers), Some(special_targets)) => Some(TransitionChain::fit(
+                special_targets,
+                numbers,
+                spec.special_count as f64 / spec.special_pool_size() as f64,
+                &self.config,
+            )),
+            _ => None,
+        };
+        self.is_trained = true;
+
+        let metrics = self.evaluate(training_data).await?;
+        Ok(metrics.accuracy)
+    }
+
+    async fn predict(
+        &self,
+        input: &PredictionInput,
+    ) -> Result<PredictionOutput> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        let start_time = std::time::Instant::now();
+
+        if input.historical_data.is_empty() {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "No historical data provided".to_string()
+            ));
+        }
+
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let draws: Vec<Vec<u32>> = input.historical_data.iter()
+            .map(|d| d.winning_numbers.clone())
+            .collect();
+        let scores = self.main_chain.rolling_scores(&draws, self.config.transition_weight)
+            .pop()
+            .unwrap_or_default();
+        let predicted_numbers = MetricsCalculator::from_spec(spec.clone()).top_numbers(&scores, spec.pick_count);
+
+        let special_scores = spec.has_special().then(|| match &self.special_chain {
+            Some(chain) => {
+                let special_draws: Vec<Vec<u32>> = input.historical_data.iter()
+                    .map(|d| d.special_numbers.clone().unwrap_or_default())
+                    .collect();
+                chain.rolling_scores(&special_draws, self.config.transition_weight)
+                    .pop()
+                    .unwrap_or_default()
+            }
+            None => calibration::history_special_scores(&spec, &input.historical_data),
+        });
+        let predicted_special_numbers = special_scores.as_ref()
+            .map(|scores| calibration::top_special_numbers(&spec, scores));
+
+        let probabilities = NumberProbabilities::from_scores(&spec, &scores, special_scores.as_deref());
+        let confidence_scores = probabilities.confidence(&spec, &predicted_numbers);
+        let computation_time = start_time.elapsed().as_millis() as u64;
+
+        Ok(PredictionOutput {
+            predicted_numbers,
+            predicted_special_numbers,
+            confidence_scores,
+            algorithm_metadata: HashMap::from_iter(vec![
+                ("algorithm".to_string(), serde_json::Value::String(AlgorithmType::Markov.to_string())),
+                ("smoothing".to_string(), serde_json::json!(self.config.smoothing)),
+                ("transition_weight".to_string(), serde_json::json!(self.config.transition_weight)),
+                ("transition_chain".to_string(), serde_json::json!(self.main_chain)),
+                ("special_transition_chain".to_string(), serde_json::json!(self.special_chain)),
+            ]),
+            computation_time_ms: computation_time,
+            probabilities: Some(probabilities),
+        })
+    }
+
+    async fn evaluate(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<EvaluationMetrics> {
+        let scores = self.predict_scores(test_data).await?;
+        MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)
+    }
+
+    // 第一期没有上一期可供转移，从第二期开始逐期打分
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        let mut rows = self.main_chain.rolling_scores(&test_data.targets, self.config.transition_weight);
+        rows.pop();
+        Ok(rows)
+    }
+
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        let (Some(chain), Some(special_targets)) = (&self.special_chain, &test_data.special_targets) else {
+            let rows = calibration::frequency_special_scores(&self.lottery_type, test_data)?;
+            return Ok(rows.get(1..).map(<[_]>::to_vec).unwrap_or_default());
+        };
+
+        let mut rows = chain.rolling_scores(special_targets, self.config.transition_weight);
+        rows.pop();
+        Ok(rows)
+    }
+
+    fn is_trained(&self) -> bool {
+        self.is_trained
+    }
+
+    fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
+        let mut importance = HashMap::new();
+        importance.insert("number_transition".to_string(), self.config.transition_weight);
+        importance.insert("gap_transition".to_string(), 1.0 - self.config.transition_weight);
+        Some(importance)
+    }
+
+    fn save_model(&self, path: &str) -> Result<()> {
+        let serialized = serde_json::to_string(self)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to serialize model: {}", e)
+            ))?;
+
+        std::fs::write(path, serialized)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to save model: {}", e)
+            ))?;
+
+        Ok(())
+    }
+
+    fn load_model(&mut self, path: &str) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to load model: {}", e)
+            ))?;
+
+        let model: MarkovModel = serde_json::from_str(&content)
+            .map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Failed to deserialize model: {}", e)
+            ))?;
+
+        *self = model;
+        Ok(())
+    }
+
+    fn box_clone(&self) -> Box<dyn PredictionAlgorithm> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::models::LotteryDrawing;
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    const A: [u32; 6] = [1, 2, 3, 4, 5, 6];
+    const B: [u32; 6] = [7, 8, 9, 10, 11, 12];
+
+    // A、B 两组号码交替开出
+    fn alternating(n: usize) -> TrainingData {
+        TrainingData {
+            features: vec![vec![0.0]; n],
+            targets: (0..n).map(|t| if t % 2 == 0 { A.to_vec() } else { B.to_vec() }).collect(),
+            special_targets: Some((0..n).map(|t| vec![if t % 2 == 0 { 1 } else { 2 }]).collect()),
+            weights: None,
+        }
+    }
+
+    fn history(data: &TrainingData) -> Vec<LotteryDrawing> {
+        data.targets.iter()
+            .zip(data.special_targets.as_ref().unwrap())
+            .enumerate()
+            .map(|(t, (numbers, special))| LotteryDrawing {
+                id: Uuid::new_v4(),
+                lottery_type: LotteryType::Ssq,
+                draw_number: format!("2024{:03}", t),
+                draw_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(t as i64),
+                draw_time: None,
+                winning_numbers: numbers.clone(),
+                special_numbers: Some(special.clone()),
+                jackpot_amount: None,
+                sales_amount: None,
+                prize_distribution: None,
+                data_source: "test".to_string(),
+                verification_status: "verified".to_string(),
+                metadata: None,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+                crawled_at: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_markov_creation() {
+        let model = MarkovModel::new(MarkovConfig::default(), LotteryType::Ssq);
+        assert_eq!(model.algorithm_type(), AlgorithmType::Markov);
+        assert!(model.transition_chain().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transition_matrix() {
+        let data = alternating(41);
+        let mut model = MarkovModel::new(MarkovConfig::default(), LotteryType::Ssq);
+        model.train(&data, &AlgorithmConfig::default()).await.unwrap();
+
+        let chain = model.transition_chain().unwrap();
+        assert_eq!(chain.numbers.len(), 33);
+        assert_eq!(chain.gap_hazard.len(), 31);
+        // A 之后总是 B，从未跟着 A；从未开出的号码的行保持基础开出率
+        assert!(chain.probabilities[0][6] > 0.8);
+        assert!(chain.probabilities[0][0] < 0.05);
+        assert!((chain.probabilities[20][0] - 6.0 / 33.0).abs() < 1e-12);
+        // 遗漏一期的号码总会开出，刚开出的号码不会
+        assert!(chain.gap_hazard[1] > 0.9);
+        assert!(chain.gap_hazard[0] < 0.05);
+        assert!(chain.probabilities.iter().flatten().chain(&chain.gap_hazard).all(|p| (0.0..=1.0).contains(p)));
+    }
+
+    #[tokio::test]
+    async fn test_predict_follows_transitions() {
+        let data = alternating(41);
+        let input = PredictionInput {
+            lottery_type: LotteryType::Ssq,
+            historical_data: history(&data),
+            target_date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+            additional_features: None,
+        };
+
+        for transition_weight in [0.0, 0.5, 1.0] {
+            let config = MarkovConfig { transition_weight, ..Default::default() };
+            let mut model = MarkovModel::new(config, LotteryType::Ssq);
+            model.train(&data, &AlgorithmConfig::default()).await.unwrap();
+
+            // 最后一期是 A
+            let output = model.predict(&input).await.unwrap();
+            assert_eq!(output.predicted_numbers, B.to_vec());
+            assert_eq!(output.predicted_special_numbers, Some(vec![2]));
+            assert!(output.algorithm_metadata.contains_key("transition_chain"));
+            assert_eq!(output.probabilities.unwrap().special.map(|s| s.len()), Some(16));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scores_skip_first_draw() {
+        let data = alternating(11);
+        let mut model = MarkovModel::new(MarkovConfig::default(), LotteryType::Ssq);
+        model.train(&data, &AlgorithmConfig::default()).await.unwrap();
+
+        let scores = model.predict_scores(&data).await.unwrap();
+        assert_eq!(scores.len(), 10);
+        assert_eq!(model.predict_special_scores(&data).await.unwrap().len(), 10);
+
+        let metrics = model.evaluate(&data).await.unwrap();
+        assert_eq!(metrics.top_k_hit_rate, 1.0);
+
+        let config = MarkovConfig { transition_weight: 2.0, ..Default::default() };
+        assert!(MarkovModel::new(config, LotteryType::Ssq).train(&data, &AlgorithmConfig::default()).await.is_err());
+    }
+}
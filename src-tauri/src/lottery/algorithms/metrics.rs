@@ -0,0 +1,395 @@
+use crate::lottery::algorithms::traits::EvaluationMetrics;
+use crate::lottery::errors::{LotteryError, LotteryResult as Result};
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::LotteryType;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use statrs::distribution::{ContinuousCDF, Normal};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+// 概率截断到 (0, 1)，避免 log-loss 出现 ln(0)
+const PROBABILITY_EPSILON: f64 = 1e-15;
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+const DEFAULT_SIMULATIONS: usize = 1000;
+
+// 零假设为均匀随机选号：在同样的开奖结果上，把模型的命中数与随机一注比较
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RandomBaseline {
+    pub samples: usize,
+    // 模型每期平均命中数及其 95% 置信区间
+    pub observed_hits: f64,
+    pub observed_interval: (f64, f64),
+    // 随机一注每期的期望命中数 (超几何分布)
+    pub expected_hits: f64,
+    // 蒙特卡洛零分布下每期平均命中数的 95% 区间
+    pub null_interval: (f64, f64),
+    pub simulations: usize,
+    // 单侧检验，备择假设为模型命中多于随机
+    pub p_value_analytic: f64,
+    pub p_value_monte_carlo: f64,
+    pub beats_chance: bool,
+}
+
+// 所有算法的 evaluate 共用：由模型对号码池的打分与实际开奖号码计算 EvaluationMetrics
+#[derive(Debug, Clone)]
+pub struct MetricsCalculator {
+    spec: Arc<GameSpec>,
+    top_k: usize,
+    simulations: usize,
+    seed: u64,
+}
+
+impl MetricsCalculator {
+    pub fn for_type(lottery_type: &LotteryType) -> Self {
+        Self::from_spec(GameSpec::for_type(lottery_type))
+    }
+
+    pub fn from_spec(spec: Arc<GameSpec>) -> Self {
+        let top_k = spec.pick_count;
+        Self {
+            spec,
+            top_k,
+            simulations: DEFAULT_SIMULATIONS,
+            seed: 42,
+        }
+    }
+
+    // 默认 k 为每注选号个数
+    pub fn with_top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k.max(1);
+        self
+    }
+
+    // 蒙特卡洛零分布的模拟次数，固定种子保证同一份数据的 p 值可复现
+    pub fn with_simulations(mut self, simulations: usize, seed: u64) -> Self {
+        self.simulations = simulations.max(1);
+        self.seed = seed;
+        self
+    }
+
+    // 按主号码升序展开为号码池长度的打分，池外号码忽略
+    pub fn pool_scores(&self, scored: impl IntoIterator<Item = (u32, f64)>) -> Vec<f64> {
+        let mut scores = vec![0.0; self.spec.main_pool_size()];
+        for (number, score) in scored {
+            if self.spec.is_valid_main(number) {
+                scores[(number - self.spec.main_min()) as usize] = score;
+            }
+        }
+        scores
+    }
+
+    // 非负打分换算为每个号码开出的概率：总和缩放到每期开出的号码个数，全零时视为均匀分布
+    pub fn probabilities(&self, scores: &[f64]) -> Vec<f64> {
+        let drawn = self.spec.main_count as f64;
+        let total: f64 = scores.iter().map(|s| s.max(0.0)).sum();
+
+        scores.iter()
+            .map(|&score| {
+                let p = if total > 0.0 {
+                    score.max(0.0) / total * drawn
+                } else {
+                    drawn / scores.len() as f64
+                };
+                p.clamp(PROBABILITY_EPSILON, 1.0 - PROBABILITY_EPSILON)
+            })
+            .collect()
+    }
+
+    // 得分最高的 count 个号码，同分时取较小的号码
+    pub fn top_numbers(&self, scores: &[f64], count: usize) -> Vec<u32> {
+        let mut ranked: Vec<usize> = (0..scores.len()).collect();
+        ranked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+        ranked.into_iter()
+            .take(count)
+            .map(|i| i as u32 + self.spec.main_min())
+            .collect()
+    }
+
+    // scores 与 targets 的最后 scores.len() 期对齐：序列模型在历史不足的前几期不产生预测
+    pub fn evaluate(&self, scores: &[Vec<f64>], targets: &[Vec<u32>]) -> Result<EvaluationMetrics> {
+        if scores.len() > targets.len() {
+            return Err(LotteryError::InvalidParameter(format!(
+                "{} predictions for {} targets",
+                scores.len(),
+                targets.len()
+            )));
+        }
+
+        let pool_size = self.spec.main_pool_size();
+        if let Some(row) = scores.iter().find(|row| row.len() != pool_size) {
+            return Err(LotteryError::InvalidParameter(format!(
+                "Expected {} number scores, got {}",
+                pool_size,
+                row.len()
+            )));
+        }
+
+        if scores.is_empty() {
+            return Ok(EvaluationMetrics::default());
+        }
+
+        let targets = &targets[targets.len() - scores.len()..];
+        let (mut true_positives, mut false_positives) = (0usize, 0usize);
+        let (mut false_negatives, mut true_negatives) = (0usize, 0usize);
+        let (mut brier, mut log_loss) = (0.0, 0.0);
+        let (mut absolute_error, mut squared_error) = (0.0, 0.0);
+        let mut top_k_hits = 0usize;
+        let mut hits_per_draw = Vec::with_capacity(scores.len());
+        let mut drawn_sets = Vec::with_capacity(scores.len());
+
+        for (row, target) in scores.iter().zip(targets) {
+            let drawn: HashSet<u32> = target.iter()
+                .copied()
+                .filter(|&n| self.spec.is_valid_main(n))
+                .collect();
+
+            // 选号与开奖号码的集合重合
+            let picks = self.top_numbers(row, self.spec.pick_count);
+            let hits = picks.iter().filter(|n| drawn.contains(n)).count();
+            true_positives += hits;
+            false_positives += picks.len() - hits;
+            false_negatives += drawn.len() - hits;
+            true_negatives += pool_size - (picks.len() + drawn.len() - hits);
+            hits_per_draw.push(hits);
+
+            // 逐号码的概率校准
+            for (number, p) in self.spec.main_numbers().zip(self.probabilities(row)) {
+                let y = if drawn.contains(&number) { 1.0 } else { 0.0 };
+                brier += (p - y).powi(2);
+                log_loss -= y * p.ln() + (1.0 - y) * (1.0 - p).ln();
+            }
+
+            if self.top_numbers(row, self.top_k).iter().any(|n| drawn.contains(n)) {
+                top_k_hits += 1;
+            }
+
+            let error = picks.iter().sum::<u32>() as f64 - target.iter().sum::<u32>() as f64;
+            absolute_error += error.abs();
+            squared_error += error * error;
+            drawn_sets.push(drawn);
+        }
+
+        let samples = scores.len() as f64;
+        let cells = samples * pool_size as f64;
+        let precision = ratio(true_positives, true_positives + false_positives);
+        let recall = ratio(true_positives, true_positives + false_negatives);
+        let f1_score = if precision + recall > 0.0 {
+            2.0 * precision * recall / (precision + recall)
+        } else {
+            0.0
+        };
+
+        Ok(EvaluationMetrics {
+            accuracy: (true_positives + true_negatives) as f64 / cells,
+            precision,
+            recall,
+            f1_score,
+            mean_absolute_error: absolute_error / samples,
+            root_mean_squared_error: (squared_error / samples).sqrt(),
+            brier_score: brier / cells,
+            log_loss: log_loss / cells,
+            top_k_hit_rate: top_k_hits as f64 / samples,
+            // [[未选且未开出, 选中未开出], [开出未选中, 选中且开出]]
+            confusion_matrix: Some(vec![
+                vec![true_negatives, false_positives],
+                vec![false_negatives, true_positives],
+            ]),
+            feature_importance: None,
+            cross_validation_scores: None,
+            baseline: Some(self.random_baseline(&hits_per_draw, &drawn_sets)),
+        })
+    }
+
+    // hits_per_draw 为模型每期选号的命中数，drawn_sets 为对应各期开出的号码集合
+    pub fn random_baseline(&self, hits_per_draw: &[usize], drawn_sets: &[HashSet<u32>]) -> RandomBaseline {
+        let samples = hits_per_draw.len().min(drawn_sets.len());
+        if samples == 0 {
+            return RandomBaseline::default();
+        }
+
+        let n = samples as f64;
+        let pool = self.spec.main_pool_size() as f64;
+        let picks = self.spec.pick_count.min(self.spec.main_pool_size());
+        let k = picks as f64;
+        let normal = Normal::standard();
+        let z_critical = normal.inverse_cdf(1.0 - SIGNIFICANCE_LEVEL / 2.0);
+
+        // 每期开出的号码个数可能不同 (允许重复的数字型玩法)，逐期累计超几何分布的均值与方差
+        let (mut expected_total, mut variance_total) = (0.0, 0.0);
+        for drawn in &drawn_sets[..samples] {
+            let d = drawn.len() as f64;
+            expected_total += k * d / pool;
+            if pool > 1.0 {
+                variance_total += k * (d / pool) * ((pool - d) / pool) * ((pool - k) / (pool - 1.0));
+            }
+        }
+
+        let hits: Vec<f64> = hits_per_draw[..samples].iter().map(|&h| h as f64).collect();
+        let observed_total: f64 = hits.iter().sum();
+        let observed_hits = observed_total / n;
+        let sample_variance = if samples > 1 {
+            hits.iter().map(|h| (h - observed_hits).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let margin = z_critical * (sample_variance / n).sqrt();
+
+        let p_value_analytic = if variance_total > 0.0 {
+            normal.sf((observed_total - expected_total) / variance_total.sqrt())
+        } else if observed_total > expected_total {
+            0.0
+        } else {
+            1.0
+        };
+
+        // 每次模拟为每期随机选一注，记录平均命中数
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let min_number = self.spec.main_min();
+        let mut null_means: Vec<f64> = (0..self.simulations)
+            .map(|_| {
+                let total: usize = drawn_sets[..samples].iter()
+                    .map(|drawn| {
+                        rand::seq::index::sample(&mut rng, self.spec.main_pool_size(), picks)
+                            .iter()
+                            .filter(|&i| drawn.contains(&(i as u32 + min_number)))
+                            .count()
+                    })
+                    .sum();
+                total as f64 / n
+            })
+            .collect();
+        null_means.sort_by(f64::total_cmp);
+
+        let as_extreme = null_means.iter().filter(|&&m| m >= observed_hits).count();
+        let p_value_monte_carlo = (as_extreme + 1) as f64 / (self.simulations + 1) as f64;
+
+        RandomBaseline {
+            samples,
+            observed_hits,
+            observed_interval: ((observed_hits - margin).max(0.0), observed_hits + margin),
+            expected_hits: expected_total / n,
+            null_interval: (
+                quantile(&null_means, SIGNIFICANCE_LEVEL / 2.0),
+                quantile(&null_means, 1.0 - SIGNIFICANCE_LEVEL / 2.0),
+            ),
+            simulations: self.simulations,
+            p_value_analytic,
+            p_value_monte_carlo,
+            beats_chance: p_value_monte_carlo < SIGNIFICANCE_LEVEL,
+        }
+    }
+}
+
+// sorted 已升序排列
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let index = ((sorted.len() - 1) as f64 * q).round() as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ssq_scores(calculator: &MetricsCalculator, favoured: &[u32]) -> Vec<f64> {
+        calculator.pool_scores(favoured.iter().map(|&n| (n, 1.0)))
+    }
+
+    #[test]
+    fn test_perfect_prediction() {
+        let calculator = MetricsCalculator::for_type(&LotteryType::Ssq);
+        let target = vec![1, 5, 9, 13, 17, 21];
+        let scores = vec![ssq_scores(&calculator, &target)];
+
+        let metrics = calculator.evaluate(&scores, &[target]).unwrap();
+        assert_eq!(metrics.precision, 1.0);
+        assert_eq!(metrics.recall, 1.0);
+        assert_eq!(metrics.f1_score, 1.0);
+        assert_eq!(metrics.accuracy, 1.0);
+        assert_eq!(metrics.top_k_hit_rate, 1.0);
+        assert_eq!(metrics.mean_absolute_error, 0.0);
+        assert!(metrics.brier_score < 1e-9);
+        assert!(metrics.log_loss < 1e-9);
+        assert_eq!(metrics.confusion_matrix, Some(vec![vec![27, 0], vec![0, 6]]));
+    }
+
+    #[test]
+    fn test_partial_overlap_and_sum_errors() {
+        let calculator = MetricsCalculator::for_type(&LotteryType::Ssq);
+        let targets = vec![vec![1, 2, 3, 4, 5, 6], vec![10, 11, 12, 13, 14, 15]];
+        let scores = vec![
+            ssq_scores(&calculator, &[1, 2, 3, 30, 31, 32]),
+            ssq_scores(&calculator, &[20, 21, 22, 23, 24, 25]),
+        ];
+
+        let metrics = calculator.evaluate(&scores, &targets).unwrap();
+        assert!((metrics.precision - 3.0 / 12.0).abs() < 1e-12);
+        assert!((metrics.recall - 3.0 / 12.0).abs() < 1e-12);
+        assert!((metrics.top_k_hit_rate - 0.5).abs() < 1e-12);
+        // 选号和: 99 vs 21, 135 vs 75
+        assert!((metrics.mean_absolute_error - 69.0).abs() < 1e-12);
+        assert!((metrics.root_mean_squared_error - ((78.0f64.powi(2) + 60.0f64.powi(2)) / 2.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_uniform_scores_match_base_rate() {
+        let calculator = MetricsCalculator::for_type(&LotteryType::Ssq);
+        let scores = vec![vec![0.0; 33]];
+
+        let metrics = calculator.evaluate(&scores, &[vec![1, 2, 3, 4, 5, 6]]).unwrap();
+        let p: f64 = 6.0 / 33.0;
+        assert!((metrics.brier_score - p * (1.0 - p)).abs() < 1e-12);
+        let entropy = -(p * p.ln() + (1.0 - p) * (1.0 - p).ln());
+        assert!((metrics.log_loss - entropy).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_scores_align_with_latest_targets() {
+        let calculator = MetricsCalculator::for_type(&LotteryType::Ssq);
+        let targets = vec![vec![7, 8, 9, 10, 11, 12], vec![1, 2, 3, 4, 5, 6]];
+        let scores = vec![ssq_scores(&calculator, &[1, 2, 3, 4, 5, 6])];
+
+        let metrics = calculator.evaluate(&scores, &targets).unwrap();
+        assert_eq!(metrics.precision, 1.0);
+
+        assert!(calculator.evaluate(&[vec![0.0; 10]], &targets).is_err());
+        assert!(calculator.evaluate(&[vec![0.0; 33], vec![0.0; 33], vec![0.0; 33]], &targets).is_err());
+    }
+
+    #[test]
+    fn test_random_baseline_expected_hits() {
+        let calculator = MetricsCalculator::for_type(&LotteryType::Ssq).with_simulations(200, 7);
+        let targets: Vec<Vec<u32>> = (0..40).map(|i| vec![1, 2, 3, 4, 5, 6 + i % 20]).collect();
+
+        // 每期都命中全部 6 个号码的模型显著优于随机
+        let perfect: Vec<Vec<f64>> = targets.iter().map(|t| ssq_scores(&calculator, t)).collect();
+        let baseline = calculator.evaluate(&perfect, &targets).unwrap().baseline.unwrap();
+        assert_eq!(baseline.samples, 40);
+        assert!((baseline.expected_hits - 36.0 / 33.0).abs() < 1e-12);
+        assert_eq!(baseline.observed_hits, 6.0);
+        assert!(baseline.null_interval.0 <= baseline.expected_hits);
+        assert!(baseline.null_interval.1 >= baseline.expected_hits);
+        assert!(baseline.p_value_analytic < 1e-6);
+        assert!((baseline.p_value_monte_carlo - 1.0 / 201.0).abs() < 1e-12);
+        assert!(baseline.beats_chance);
+
+        // 总选中从未开出的号码则不可能优于随机
+        let miss: Vec<Vec<f64>> = targets.iter().map(|_| ssq_scores(&calculator, &[28, 29, 30, 31, 32, 33])).collect();
+        let baseline = calculator.evaluate(&miss, &targets).unwrap().baseline.unwrap();
+        assert_eq!(baseline.observed_hits, 0.0);
+        assert!(baseline.p_value_analytic > 0.5);
+        assert_eq!(baseline.p_value_monte_carlo, 1.0);
+        assert!(!baseline.beats_chance);
+    }
+}
@@ -1,12 +1,21 @@
 pub mod traits;
 pub mod random_forest;
+pub mod gradient_boosting;
 pub mod lstm;
 pub mod arima;
 pub mod neural_network;
 pub mod statistical;
+pub mod bayesian;
+pub mod markov;
 pub mod hybrid;
 pub mod model_trainer;
 pub mod feature_engineering;
+pub mod metrics;
+pub mod ensemble;
+pub mod calibration;
 pub mod algorithm_factory;
 
+#[cfg(test)]
+pub(crate) mod test_support;
+
 // pub use traits::*;
\ No newline at end of file
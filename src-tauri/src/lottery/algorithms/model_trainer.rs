@@ -1,109 +1,221 @@
 use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::{CalibratedModel, CalibrationMethod, ProbabilityCalibration};
+use crate::lottery::algorithms::ensemble::{self, EnsembleCombiner, EnsembleWeights, WeightObjective};
 use crate::lottery::algorithms::random_forest::RandomForestModel;
+use crate::lottery::algorithms::gradient_boosting::GradientBoostingModel;
 use crate::lottery::algorithms::neural_network::NeuralNetworkModel;
 use crate::lottery::algorithms::lstm::LstmModel;
 use crate::lottery::algorithms::arima::ArimaModel;
 use crate::lottery::algorithms::statistical::StatisticalModel;
+use crate::lottery::algorithms::bayesian::BayesianModel;
+use crate::lottery::algorithms::markov::MarkovModel;
 use crate::lottery::algorithms::hybrid::HybridEnsembleModel;
+use crate::lottery::algorithms::metrics::MetricsCalculator;
 use crate::lottery::errors::LotteryResult as Result;
-use crate::lottery::models::LotteryType;
+use crate::lottery::models::{AlgorithmType, LotteryType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CvWindow {
+    // 训练折从第一个样本开始，逐折增长
+    Expanding,
+    // 训练折只保留测试折之前最近的 n 个样本
+    Sliding(usize),
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum AlgorithmType {
-    RandomForest,
-    NeuralNetwork,
-    Lstm,
-    Arima,
-    Statistical,
-    Hybrid,
+pub struct TimeSeriesSplit {
+    pub n_splits: usize,
+    pub window: CvWindow,
+    // 训练折与测试折之间剔除的样本数，避免特征的历史窗口跨越两侧
+    pub gap: usize,
+}
+
+impl Default for TimeSeriesSplit {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+impl TimeSeriesSplit {
+    pub fn new(n_splits: usize) -> Self {
+        Self {
+            n_splits: n_splits.max(1),
+            window: CvWindow::Expanding,
+            gap: 0,
+        }
+    }
+
+    pub fn sliding(mut self, window_size: usize) -> Self {
+        self.window = CvWindow::Sliding(window_size);
+        self
+    }
+
+    pub fn with_gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    // 测试折等长、按时间顺序排在末尾，每个训练折都早于对应的测试折；训练折为空的折被跳过
+    pub fn splits(&self, n_samples: usize) -> Vec<(Range<usize>, Range<usize>)> {
+        let n_splits = self.n_splits.max(1);
+        let test_size = n_samples / (n_splits + 1);
+        if test_size == 0 {
+            return Vec::new();
+        }
+
+        (0..n_splits)
+            .filter_map(|fold| {
+                let test_start = n_samples - (n_splits - fold) * test_size;
+                let train_end = test_start.saturating_sub(self.gap);
+                let train_start = match self.window {
+                    CvWindow::Expanding => 0,
+                    CvWindow::Sliding(size) => train_end.saturating_sub(size),
+                };
+
+                (train_end > train_start)
+                    .then_some((train_start..train_end, test_start..test_start + test_size))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CrossValidationReport {
+    pub fold_scores: Vec<f64>,
+    pub mean: f64,
+    pub variance: f64,
+}
+
+impl CrossValidationReport {
+    pub fn from_scores(fold_scores: Vec<f64>) -> Self {
+        if fold_scores.is_empty() {
+            return Self::default();
+        }
+
+        let n = fold_scores.len() as f64;
+        let mean = fold_scores.iter().sum::<f64>() / n;
+        let variance = fold_scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+
+        Self { fold_scores, mean, variance }
+    }
 }
 
 #[derive(Debug)]
 pub struct ModelTrainer {
     pub lottery_type: LotteryType,
-    pub algorithms: HashMap<String, Box<dyn PredictionAlgorithm>>,
-    pub trained_models: HashMap<String, Box<dyn PredictionAlgorithm>>,
-    pub model_performance: HashMap<String, EvaluationMetrics>,
+    pub algorithms: HashMap<AlgorithmType, Box<dyn PredictionAlgorithm>>,
+    pub trained_models: HashMap<AlgorithmType, Box<dyn PredictionAlgorithm>>,
+    pub model_performance: HashMap<AlgorithmType, EvaluationMetrics>,
+    // 为 None 时训练和比较都不做交叉验证
+    pub cross_validation: Option<TimeSeriesSplit>,
+    pub cross_validation_reports: HashMap<AlgorithmType, CrossValidationReport>,
+    // 训练时使用的配置，比较时按同样的配置逐折重新训练
+    pub training_configs: HashMap<AlgorithmType, AlgorithmConfig>,
+    // ensemble_predict 使用的子模型权重，未学习时等权
+    pub ensemble_weights: EnsembleWeights,
 }
 
 impl ModelTrainer {
     pub fn new(lottery_type: LotteryType) -> Self {
-        let mut algorithms = HashMap::new();
-        
         // Initialize all available algorithms
-        let rf = RandomForestModel::new(Default::default(), lottery_type.clone());
-        let nn = NeuralNetworkModel::new(Default::default(), lottery_type.clone());
-        let lstm = LstmModel::new(Default::default(), lottery_type.clone());
-        let arima = ArimaModel::new(Default::default(), lottery_type.clone());
-        let stat = StatisticalModel::new(Default::default(), lottery_type.clone());
-        let hybrid = HybridEnsembleModel::new(Default::default(), lottery_type.clone());
-        
-        algorithms.insert("random_forest".to_string(), Box::new(rf) as Box<dyn PredictionAlgorithm>);
-        algorithms.insert("neural_network".to_string(), Box::new(nn) as Box<dyn PredictionAlgorithm>);
-        algorithms.insert("lstm".to_string(), Box::new(lstm) as Box<dyn PredictionAlgorithm>);
-        algorithms.insert("arima".to_string(), Box::new(arima) as Box<dyn PredictionAlgorithm>);
-        algorithms.insert("statistical".to_string(), Box::new(stat) as Box<dyn PredictionAlgorithm>);
-        algorithms.insert("hybrid".to_string(), Box::new(hybrid) as Box<dyn PredictionAlgorithm>);
-        
+        let algorithms = AlgorithmType::ALL
+            .into_iter()
+            .map(|algorithm| (algorithm, default_model(algorithm, &lottery_type)))
+            .collect();
+
         Self {
             lottery_type,
             algorithms,
             trained_models: HashMap::new(),
             model_performance: HashMap::new(),
+            cross_validation: Some(TimeSeriesSplit::default()),
+            cross_validation_reports: HashMap::new(),
+            training_configs: HashMap::new(),
+            ensemble_weights: EnsembleWeights::default(),
         }
     }
 
+    pub fn with_cross_validation(mut self, split: Option<TimeSeriesSplit>) -> Self {
+        self.cross_validation = split;
+        self
+    }
+
     pub async fn train_algorithm(
         &mut self,
-        algorithm_name: &str,
+        algorithm: AlgorithmType,
         training_data: &TrainingData,
         config: &AlgorithmConfig,
     ) -> Result<f64> {
-        if let Some(model) = self.algorithms.get_mut(algorithm_name) {
-            let accuracy = model.train(training_data, config).await?;
-            
-            // Store the trained model
-            let mut cloned_model: Box<dyn PredictionAlgorithm> = match algorithm_name {
-                "random_forest" => Box::new(RandomForestModel::new(Default::default(), self.lottery_type.clone())),
-                "neural_network" => Box::new(NeuralNetworkModel::new(Default::default(), self.lottery_type.clone())),
-                "lstm" => Box::new(LstmModel::new(Default::default(), self.lottery_type.clone())),
-                "arima" => Box::new(ArimaModel::new(Default::default(), self.lottery_type.clone())),
-                "statistical" => Box::new(StatisticalModel::new(Default::default(), self.lottery_type.clone())),
-                "hybrid" => Box::new(HybridEnsembleModel::new(Default::default(), self.lottery_type.clone())),
-                _ => return Err(crate::lottery::errors::LotteryError::AlgorithmError(
-                    "Unknown algorithm".to_string()
-                )),
-            };
-            
-            cloned_model.load_model(&format!("models/{}_{}.json", algorithm_name, self.lottery_type))?;
-            
-            self.trained_models.insert(algorithm_name.to_string(), cloned_model);
-            
-            Ok(accuracy)
-        } else {
-            Err(crate::lottery::errors::LotteryError::AlgorithmError(
-                format!("Algorithm {} not found", algorithm_name)
-            ))
+        let model = self.algorithms
+            .entry(algorithm)
+            .or_insert_with(|| default_model(algorithm, &self.lottery_type));
+        let accuracy = model.train(training_data, config).await?;
+
+        // 训练好的模型复制一份供评估和注册，持久化交给模型存储
+        self.trained_models.insert(algorithm, model.box_clone());
+        self.training_configs.insert(algorithm, config.clone());
+
+        if let Some(split) = self.cross_validation.clone() {
+            let report = self.cross_validate(algorithm, training_data, config, &split).await?;
+            self.model_performance
+                .entry(algorithm)
+                .or_default()
+                .cross_validation_scores = Some(report.fold_scores.clone());
+            self.cross_validation_reports.insert(algorithm, report);
+        }
+
+        Ok(accuracy)
+    }
+
+    // 每折用全新模型做时间序列交叉验证，config 在训练时覆盖模型的默认配置
+    pub async fn cross_validate(
+        &self,
+        algorithm: AlgorithmType,
+        data: &TrainingData,
+        config: &AlgorithmConfig,
+        split: &TimeSeriesSplit,
+    ) -> Result<CrossValidationReport> {
+        cross_validate_with(&self.lottery_type, data, config, split, || Ok(default_model(algorithm, &self.lottery_type))).await
+    }
+
+    // 在留出的数据上评估训练后的模型，结果连同训练时的交叉验证得分记入 model_performance
+    pub async fn evaluate_algorithm(
+        &mut self,
+        algorithm: AlgorithmType,
+        test_data: &TrainingData,
+    ) -> Result<EvaluationMetrics> {
+        let model = self.trained_models.get(&algorithm)
+            .or_else(|| self.algorithms.get(&algorithm))
+            .ok_or_else(|| crate::lottery::errors::LotteryError::NotFound(
+                format!("Algorithm {} not found", algorithm)
+            ))?;
+
+        let mut metrics = model.evaluate(test_data).await?;
+        if let Some(report) = self.cross_validation_reports.get(&algorithm) {
+            metrics.cross_validation_scores = Some(report.fold_scores.clone());
         }
+        self.model_performance.insert(algorithm, metrics.clone());
+        Ok(metrics)
     }
 
     pub async fn train_all_algorithms(
         &mut self,
         training_data: &TrainingData,
         config: &AlgorithmConfig,
-    ) -> Result<HashMap<String, f64>> {
+    ) -> Result<HashMap<AlgorithmType, f64>> {
         let mut results = HashMap::new();
         
-        let algorithm_names: Vec<String> = self.algorithms.keys().cloned().collect();
-        for algorithm_name in algorithm_names {
-            match self.train_algorithm(&algorithm_name, training_data, config).await {
+        let algorithms: Vec<AlgorithmType> = self.algorithms.keys().copied().collect();
+        for algorithm in algorithms {
+            match self.train_algorithm(algorithm, training_data, config).await {
                 Ok(accuracy) => {
-                    results.insert(algorithm_name.clone(), accuracy);
+                    results.insert(algorithm, accuracy);
                 }
                 Err(e) => {
-                    eprintln!("Failed to train {}: {}", algorithm_name, e);
+                    eprintln!("Failed to train {}: {}", algorithm, e);
                 }
             }
         }
@@ -113,60 +225,85 @@ impl ModelTrainer {
 
     pub async fn predict_with_algorithm(
         &self,
-        algorithm_name: &str,
+        algorithm: AlgorithmType,
         input: &PredictionInput,
     ) -> Result<PredictionOutput> {
-        if let Some(model) = self.trained_models.get(algorithm_name) {
+        if let Some(model) = self.trained_models.get(&algorithm) {
             model.predict(input).await
         } else {
             Err(crate::lottery::errors::LotteryError::AlgorithmError(
-                format!("Model {} not trained or found", algorithm_name)
+                format!("Model {} not trained or found", algorithm)
             ))
         }
     }
 
+    // 已训练的模型直接在 test_data 上评估；交叉验证与 cross_validate 相同，每折按训练时的配置
+    // 重新训练全新模型，只用测试折之前的样本
     pub async fn compare_algorithms(
         &mut self,
         test_data: &TrainingData,
-    ) -> Result<HashMap<String, EvaluationMetrics>> {
+    ) -> Result<HashMap<AlgorithmType, EvaluationMetrics>> {
         let mut comparison = HashMap::new();
-        
-        let algorithm_names: Vec<String> = self.trained_models.keys().cloned().collect();
-        for algorithm_name in algorithm_names {
-            if let Some(model) = self.trained_models.get(&algorithm_name) {
-                let metrics = model.evaluate(test_data).await?;
-                comparison.insert(algorithm_name.clone(), metrics.clone());
-                self.model_performance.insert(algorithm_name.clone(), metrics);
+
+        let algorithms: Vec<AlgorithmType> = self.trained_models.keys().copied().collect();
+        for algorithm in algorithms {
+            let Some(model) = self.trained_models.get(&algorithm) else {
+                continue;
+            };
+            let mut metrics = model.evaluate(test_data).await?;
+
+            if let Some(split) = self.cross_validation.clone() {
+                // 从磁盘加载的模型没有训练配置，按默认参数重训
+                let config = self.training_configs.get(&algorithm).cloned().unwrap_or_else(|| AlgorithmConfig {
+                    lottery_type: self.lottery_type.clone(),
+                    ..Default::default()
+                });
+                let report = self.cross_validate(algorithm, test_data, &config, &split).await?;
+                if !report.fold_scores.is_empty() {
+                    metrics.cross_validation_scores = Some(report.fold_scores.clone());
+                    self.cross_validation_reports.insert(algorithm, report);
+                }
             }
+
+            comparison.insert(algorithm, metrics.clone());
+            self.model_performance.insert(algorithm, metrics);
         }
-        
+
         Ok(comparison)
     }
 
-    pub fn get_best_algorithm(&self) -> Option<&str> {
+    pub fn get_best_algorithm(&self) -> Option<AlgorithmType> {
         self.model_performance
             .iter()
             .max_by(|a, b| a.1.accuracy.partial_cmp(&b.1.accuracy).unwrap())
-            .map(|(name, _)| name.as_str())
+            .map(|(algorithm, _)| *algorithm)
+    }
+
+    pub fn list_available_algorithms(&self) -> Vec<AlgorithmType> {
+        let mut algorithms: Vec<AlgorithmType> = self.algorithms.keys().copied().collect();
+        algorithms.sort();
+        algorithms
     }
 
-    pub fn list_available_algorithms(&self) -> Vec<String> {
-        self.algorithms.keys().cloned().collect()
+    pub fn list_trained_algorithms(&self) -> Vec<AlgorithmType> {
+        let mut algorithms: Vec<AlgorithmType> = self.trained_models.keys().copied().collect();
+        algorithms.sort();
+        algorithms
     }
 
-    pub fn list_trained_algorithms(&self) -> Vec<String> {
-        self.trained_models.keys().cloned().collect()
+    pub fn get_model_performance(&self, algorithm: AlgorithmType) -> Option<&EvaluationMetrics> {
+        self.model_performance.get(&algorithm)
     }
 
-    pub fn get_model_performance(&self, algorithm_name: &str) -> Option<&EvaluationMetrics> {
-        self.model_performance.get(algorithm_name)
+    pub fn get_cross_validation_report(&self, algorithm: AlgorithmType) -> Option<&CrossValidationReport> {
+        self.cross_validation_reports.get(&algorithm)
     }
 
     pub async fn save_all_models(&self, directory: &str) -> Result<()> {
         std::fs::create_dir_all(directory).map_err(|e| crate::lottery::errors::LotteryError::AlgorithmError(format!("Failed to create directory: {}", e)))?;
         
-        for (algorithm_name, model) in &self.trained_models {
-            let path = format!("{}/{}_{}.json", directory, algorithm_name, self.lottery_type);
+        for (algorithm, model) in &self.trained_models {
+            let path = format!("{}/{}_{}.json", directory, algorithm, self.lottery_type);
             model.save_model(&path)?;
         }
         
@@ -174,40 +311,79 @@ impl ModelTrainer {
     }
 
     pub async fn load_all_models(&mut self, directory: &str) -> Result<()> {
-        let algorithm_names: Vec<String> = self.algorithms.keys().cloned().collect();
-        for algorithm_name in algorithm_names {
-            let path = format!("{}/{}_{}.json", directory, algorithm_name, self.lottery_type);
+        let algorithms: Vec<AlgorithmType> = self.algorithms.keys().copied().collect();
+        for algorithm in algorithms {
+            let path = format!("{}/{}_{}.json", directory, algorithm, self.lottery_type);
             
             if std::path::Path::new(&path).exists() {
-                let mut cloned_model: Box<dyn PredictionAlgorithm> = match algorithm_name.as_str() {
-                    "random_forest" => Box::new(RandomForestModel::new(Default::default(), self.lottery_type.clone())),
-                    "neural_network" => Box::new(NeuralNetworkModel::new(Default::default(), self.lottery_type.clone())),
-                    "lstm" => Box::new(LstmModel::new(Default::default(), self.lottery_type.clone())),
-                    "arima" => Box::new(ArimaModel::new(Default::default(), self.lottery_type.clone())),
-                    "statistical" => Box::new(StatisticalModel::new(Default::default(), self.lottery_type.clone())),
-                    "hybrid" => Box::new(HybridEnsembleModel::new(Default::default(), self.lottery_type.clone())),
-                    _ => continue,
-                };
+                let mut cloned_model = default_model(algorithm, &self.lottery_type);
                 cloned_model.load_model(&path)?;
-                self.trained_models.insert(algorithm_name.clone(), cloned_model);
+                self.trained_models.insert(algorithm, cloned_model);
             }
         }
         
         Ok(())
     }
 
+    // 在验证数据上学习已训练子模型的组合权重，保存为本玩法的权重
+    pub async fn fit_ensemble_weights(
+        &mut self,
+        algorithms: &[AlgorithmType],
+        validation_data: &TrainingData,
+        objective: WeightObjective,
+    ) -> Result<HashMap<String, f64>> {
+        let selected: Vec<(String, &dyn PredictionAlgorithm)> = algorithms.iter()
+            .filter_map(|algorithm| self.trained_models.get(algorithm).map(|model| (algorithm.to_string(), model.as_ref())))
+            .collect();
+
+        let model_scores = ensemble::collect_scores(&selected, validation_data).await?;
+        let weights = EnsembleCombiner::for_type(&self.lottery_type)
+            .learn_weights(&model_scores, &validation_data.targets, objective)?;
+        self.ensemble_weights.set_game(&self.lottery_type, weights.clone());
+        Ok(weights)
+    }
+
+    // 用验证数据为训练后的模型拟合概率校准，之后它的预测输出校准后的号码概率；重新训练会去掉校准
+    pub async fn calibrate_algorithm(
+        &mut self,
+        algorithm: AlgorithmType,
+        validation_data: &TrainingData,
+        method: CalibrationMethod,
+    ) -> Result<ProbabilityCalibration> {
+        let Some(model) = self.trained_models.remove(&algorithm) else {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Algorithm {} not trained", algorithm)
+            ));
+        };
+
+        match ProbabilityCalibration::fit(model.as_ref(), &self.lottery_type, validation_data, method).await {
+            Ok(calibration) => {
+                self.trained_models.insert(algorithm, Box::new(CalibratedModel {
+                    inner: model,
+                    lottery_type: self.lottery_type.clone(),
+                    calibration: calibration.clone(),
+                }));
+                Ok(calibration)
+            }
+            Err(e) => {
+                self.trained_models.insert(algorithm, model);
+                Err(e)
+            }
+        }
+    }
+
     pub async fn ensemble_predict(
         &self,
-        algorithms: &[&str],
+        algorithms: &[AlgorithmType],
         input: &PredictionInput,
     ) -> Result<PredictionOutput> {
         let mut predictions = Vec::new();
         
-        for algorithm_name in algorithms {
-            if let Some(model) = self.trained_models.get(*algorithm_name) {
-                if let Ok(prediction) = model.predict(input).await {
-                    predictions.push(prediction);
-                }
+        for algorithm in algorithms {
+            if let Some(model) = self.trained_models.get(algorithm)
+                && let Ok(prediction) = model.predict(input).await
+            {
+                predictions.push((algorithm.to_string(), prediction));
             }
         }
         
@@ -217,45 +393,78 @@ impl ModelTrainer {
             ));
         }
         
-        // Simple averaging of predictions
-        let mut number_counts: HashMap<u32, usize> = HashMap::new();
+        let mut output = EnsembleCombiner::for_type(&self.lottery_type).combine_predictions(
+            self.ensemble_weights.for_game(&self.lottery_type),
+            &predictions,
+            &input.historical_data,
+        );
+        output.algorithm_metadata.insert(
+            "algorithms".to_string(),
+            serde_json::Value::String(
+                algorithms.iter().map(|algorithm| algorithm.as_str()).collect::<Vec<_>>().join(","),
+            ),
+        );
         
-        for prediction in &predictions {
-            for number in &prediction.predicted_numbers {
-                *number_counts.entry(*number).or_insert(0) += 1;
-            }
+        Ok(output)
+    }
+}
+
+fn default_model(algorithm: AlgorithmType, lottery_type: &LotteryType) -> Box<dyn PredictionAlgorithm> {
+    let lottery_type = lottery_type.clone();
+    match algorithm {
+        AlgorithmType::RandomForest => Box::new(RandomForestModel::new(Default::default(), lottery_type)),
+        AlgorithmType::GradientBoosting => Box::new(GradientBoostingModel::new(Default::default(), lottery_type)),
+        AlgorithmType::NeuralNetwork => Box::new(NeuralNetworkModel::new(Default::default(), lottery_type)),
+        AlgorithmType::Lstm => Box::new(LstmModel::new(Default::default(), lottery_type)),
+        AlgorithmType::Arima => Box::new(ArimaModel::new(Default::default(), lottery_type)),
+        AlgorithmType::Statistical => Box::new(StatisticalModel::new(Default::default(), lottery_type)),
+        AlgorithmType::Bayesian => Box::new(BayesianModel::new(Default::default(), lottery_type)),
+        AlgorithmType::Markov => Box::new(MarkovModel::new(Default::default(), lottery_type)),
+        AlgorithmType::Hybrid => Box::new(HybridEnsembleModel::new(Default::default(), lottery_type)),
+    }
+}
+
+// 每折用 create 返回的全新模型在训练折上训练、在测试折上评估，得分为测试折的 accuracy；
+// 训练失败的折跳过，create 出错时整个交叉验证中止
+pub async fn cross_validate_with<F>(
+    lottery_type: &LotteryType,
+    data: &TrainingData,
+    config: &AlgorithmConfig,
+    split: &TimeSeriesSplit,
+    create: F,
+) -> Result<CrossValidationReport>
+where
+    F: Fn() -> Result<Box<dyn PredictionAlgorithm>>,
+{
+    let calculator = MetricsCalculator::for_type(lottery_type);
+    let mut fold_scores = Vec::new();
+
+    for (train_range, test_range) in split.splits(data.len()) {
+        let mut model = create()?;
+        if let Err(e) = model.train(&data.slice(train_range.clone()), config).await {
+            eprintln!("Skipping fold for {}: {}", model.algorithm_type(), e);
+            continue;
         }
-        
-        let mut sorted_numbers: Vec<(u32, usize)> = number_counts.into_iter().collect();
-        sorted_numbers.sort_by(|a, b| b.1.cmp(&a.1));
-        
-        let main_count = match self.lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
-        
-        let predicted_numbers: Vec<u32> = sorted_numbers.into_iter()
-            .take(main_count)
-            .map(|(number, _)| number)
-            .collect();
-        
-        let confidence_scores = vec![0.75; main_count]; // Default ensemble confidence
-        
-        Ok(PredictionOutput {
-            predicted_numbers,
-            predicted_special_numbers: None,
-            confidence_scores,
-            algorithm_metadata: HashMap::from_iter(vec![
-                ("method".to_string(), serde_json::Value::String("ensemble".to_string())),
-                ("algorithms".to_string(), serde_json::Value::String(algorithms.join(","))),
-            ]),
-            computation_time_ms: 0,
-        })
+
+        let metrics = evaluate_fold(model.as_ref(), &calculator, data, train_range.start, test_range).await?;
+        fold_scores.push(metrics.accuracy);
     }
+
+    Ok(CrossValidationReport::from_scores(fold_scores))
+}
+
+// 从 context_start 起的样本一并传入作为历史，只对测试折内的预测计分
+async fn evaluate_fold(
+    model: &dyn PredictionAlgorithm,
+    calculator: &MetricsCalculator,
+    data: &TrainingData,
+    context_start: usize,
+    test_range: Range<usize>,
+) -> Result<EvaluationMetrics> {
+    let window = data.slice(context_start..test_range.end);
+    let scores = model.predict_scores(&window).await?;
+    let scores = &scores[scores.len().saturating_sub(test_range.len())..];
+    calculator.evaluate(scores, &window.targets)
 }
 
 #[cfg(test)]
@@ -269,9 +478,10 @@ mod tests {
     fn test_model_trainer_creation() {
         let trainer = ModelTrainer::new(LotteryType::Ssq);
         let algorithms = trainer.list_available_algorithms();
-        assert!(algorithms.contains(&"random_forest".to_string()));
-        assert!(algorithms.contains(&"neural_network".to_string()));
-        assert!(algorithms.contains(&"hybrid".to_string()));
+        assert_eq!(algorithms, AlgorithmType::ALL.to_vec());
+        assert!(algorithms.contains(&AlgorithmType::RandomForest));
+        assert!(algorithms.contains(&AlgorithmType::NeuralNetwork));
+        assert!(algorithms.contains(&AlgorithmType::Hybrid));
     }
 
     #[tokio::test]
@@ -296,6 +506,86 @@ mod tests {
         assert!(results.is_ok());
     }
 
+    #[test]
+    fn test_time_series_splits() {
+        let expanding = TimeSeriesSplit::new(3).splits(100);
+        assert_eq!(expanding, vec![(0..25, 25..50), (0..50, 50..75), (0..75, 75..100)]);
+
+        let sliding = TimeSeriesSplit::new(3).sliding(20).with_gap(5).splits(100);
+        assert_eq!(sliding, vec![(0..20, 25..50), (25..45, 50..75), (50..70, 75..100)]);
+
+        // 间隔吞掉整个训练折的折被跳过
+        let purged = TimeSeriesSplit::new(3).with_gap(30).splits(100);
+        assert_eq!(purged, vec![(0..20, 50..75), (0..45, 75..100)]);
+
+        assert!(TimeSeriesSplit::new(5).splits(4).is_empty());
+    }
+
+    #[test]
+    fn test_cross_validation_report() {
+        let report = CrossValidationReport::from_scores(vec![0.5, 0.7]);
+        assert!((report.mean - 0.6).abs() < 1e-12);
+        assert!((report.variance - 0.01).abs() < 1e-12);
+        assert_eq!(CrossValidationReport::from_scores(Vec::new()), CrossValidationReport::default());
+    }
+
+    // 前 30 期开 1-6，之后开 7-12
+    fn shifting_data() -> TrainingData {
+        let mut targets = vec![vec![1, 2, 3, 4, 5, 6]; 30];
+        targets.extend(vec![vec![7, 8, 9, 10, 11, 12]; 30]);
+        TrainingData {
+            features: vec![vec![0.1; 10]; targets.len()],
+            targets,
+            special_targets: None,
+            weights: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cross_validate_scores_each_fold() {
+        let trainer = ModelTrainer::new(LotteryType::Ssq);
+        let data = shifting_data();
+
+        let report = trainer
+            .cross_validate(AlgorithmType::Statistical, &data, &AlgorithmConfig::default(), &TimeSeriesSplit::new(5))
+            .await
+            .unwrap();
+        assert_eq!(report.fold_scores.len(), 5);
+        assert_eq!(&report.fold_scores[..2], &[1.0, 1.0]);
+        // 第三折只用 1-6 的历史训练，测试折已换成 7-12
+        assert!(report.fold_scores[2] < 1.0);
+        assert!(report.variance > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_compare_algorithms_refits_each_fold() {
+        let mut trainer = ModelTrainer::new(LotteryType::Ssq);
+        let train_set = TrainingData {
+            features: vec![vec![0.1; 10]; 30],
+            targets: vec![vec![1, 2, 3, 4, 5, 6]; 30],
+            special_targets: None,
+            weights: None,
+        };
+        trainer.train_algorithm(AlgorithmType::Statistical, &train_set, &AlgorithmConfig::default()).await.unwrap();
+
+        // 评估时保留训练阶段的交叉验证得分
+        let training_report = trainer.get_cross_validation_report(AlgorithmType::Statistical).unwrap().clone();
+        let metrics = trainer.evaluate_algorithm(AlgorithmType::Statistical, &train_set).await.unwrap();
+        assert_eq!(metrics.cross_validation_scores, Some(training_report.fold_scores));
+
+        // 已训练的模型只见过 1-6；每折重新训练的模型都从测试数据的历史里学到 7-12
+        let test_data = TrainingData {
+            features: vec![vec![0.1; 10]; 60],
+            targets: vec![vec![7, 8, 9, 10, 11, 12]; 60],
+            special_targets: None,
+            weights: None,
+        };
+        let comparison = trainer.compare_algorithms(&test_data).await.unwrap();
+        let metrics = &comparison[&AlgorithmType::Statistical];
+        assert!(metrics.accuracy < 1.0);
+        assert_eq!(metrics.cross_validation_scores, Some(vec![1.0; 5]));
+    }
+
     #[test]
     fn test_algorithm_listing() {
         let trainer = ModelTrainer::new(LotteryType::Ssq);
@@ -1,13 +1,20 @@
 use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::{self, NumberProbabilities};
+use crate::lottery::algorithms::metrics::MetricsCalculator;
 use crate::lottery::errors::LotteryResult as Result;
-use crate::lottery::models::LotteryType;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryType};
 use async_trait::async_trait;
-use ndarray::{Array1, Array2, Axis};
+use ndarray::{Array, Array1, Array2, Axis, Dimension, Zip};
 use ndarray_rand::RandomExt;
+use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+const OPTIMIZER_EPSILON: f64 = 1e-8;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct NeuralNetworkConfig {
     pub hidden_layers: Vec<usize>,
     pub activation: String,
@@ -20,6 +27,11 @@ pub struct NeuralNetworkConfig {
     pub early_stopping: bool,
     pub patience: usize,
     pub validation_split: f64,
+    // SGD 动量系数，同时作为 Adam 的 beta1
+    pub momentum: f64,
+    // RMSProp 与 Adam 的平方梯度衰减系数 (beta2)
+    pub second_moment_decay: f64,
+    pub learning_rate_schedule: LearningRateSchedule,
 }
 
 impl Default for NeuralNetworkConfig {
@@ -36,16 +48,195 @@ impl Default for NeuralNetworkConfig {
             early_stopping: true,
             patience: 20,
             validation_split: 0.2,
+            momentum: 0.9,
+            second_moment_decay: 0.999,
+            learning_rate_schedule: LearningRateSchedule::Constant,
         }
     }
 }
 
+impl ModelConfig for NeuralNetworkConfig {
+    fn validate(&self) -> Result<()> {
+        ensure_config(self.hidden_layers.iter().all(|&size| size > 0), "hidden_layers sizes must be positive")?;
+        ensure_config(
+            matches!(self.activation.as_str(), "relu" | "sigmoid" | "tanh" | "leaky_relu" | "elu" | "linear"),
+            "activation must be one of relu, sigmoid, tanh, leaky_relu, elu, linear",
+        )?;
+        ensure_config(
+            self.learning_rate > 0.0 && self.learning_rate.is_finite(),
+            "learning_rate must be positive",
+        )?;
+        ensure_config(self.epochs > 0, "epochs must be positive")?;
+        ensure_config(self.batch_size > 0, "batch_size must be positive")?;
+        ensure_config((0.0..1.0).contains(&self.dropout_rate), "dropout_rate must be in [0, 1)")?;
+        ensure_config(
+            self.regularization >= 0.0 && self.regularization.is_finite(),
+            "regularization must be non-negative",
+        )?;
+        ensure_config(Optimizer::from_name(&self.optimizer).is_ok(), "optimizer must be one of sgd, momentum, rmsprop, adam")?;
+        ensure_config((0.0..1.0).contains(&self.validation_split), "validation_split must be in [0, 1)")?;
+        ensure_config((0.0..1.0).contains(&self.momentum), "momentum must be in [0, 1)")?;
+        ensure_config((0.0..1.0).contains(&self.second_moment_decay), "second_moment_decay must be in [0, 1)")?;
+        ensure_config(self.learning_rate_schedule.is_valid(), "learning_rate_schedule parameters are out of range")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Optimizer {
+    Sgd,
+    Momentum,
+    RmsProp,
+    Adam,
+}
+
+impl Optimizer {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sgd" => Ok(Optimizer::Sgd),
+            "momentum" | "sgd_momentum" => Ok(Optimizer::Momentum),
+            "rmsprop" => Ok(Optimizer::RmsProp),
+            "adam" => Ok(Optimizer::Adam),
+            other => Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                format!("Unknown optimizer: {}", other)
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LearningRateSchedule {
+    #[default]
+    Constant,
+    // 每 step_size 个 epoch 乘以 gamma
+    Step { step_size: usize, gamma: f64 },
+    Exponential { gamma: f64 },
+    // 余弦退火，最后一个 epoch 接近 min_learning_rate
+    Cosine { min_learning_rate: f64 },
+}
+
+impl LearningRateSchedule {
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            LearningRateSchedule::Constant => true,
+            LearningRateSchedule::Step { step_size, gamma } => step_size > 0 && gamma > 0.0 && gamma.is_finite(),
+            LearningRateSchedule::Exponential { gamma } => gamma > 0.0 && gamma.is_finite(),
+            LearningRateSchedule::Cosine { min_learning_rate } => min_learning_rate >= 0.0 && min_learning_rate.is_finite(),
+        }
+    }
+
+    pub fn learning_rate(&self, base: f64, epoch: usize, total_epochs: usize) -> f64 {
+        match *self {
+            LearningRateSchedule::Constant => base,
+            LearningRateSchedule::Step { step_size, gamma } => {
+                base * gamma.powi((epoch / step_size.max(1)) as i32)
+            }
+            LearningRateSchedule::Exponential { gamma } => base * gamma.powi(epoch as i32),
+            LearningRateSchedule::Cosine { min_learning_rate } => {
+                let progress = epoch as f64 / total_epochs.max(1) as f64;
+                min_learning_rate
+                    + 0.5 * (base - min_learning_rate) * (1.0 + (std::f64::consts::PI * progress).cos())
+            }
+        }
+    }
+}
+
+// 每层的优化器状态随模型一起序列化，加载后可以接着训练
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizerState {
+    pub step: i32,
+    pub weight_velocity: Array2<f64>,
+    pub weight_square: Array2<f64>,
+    pub bias_velocity: Array1<f64>,
+    pub bias_square: Array1<f64>,
+}
+
+impl OptimizerState {
+    pub fn new(output_size: usize, input_size: usize) -> Self {
+        Self {
+            step: 0,
+            weight_velocity: Array2::zeros((output_size, input_size)),
+            weight_square: Array2::zeros((output_size, input_size)),
+            bias_velocity: Array1::zeros(output_size),
+            bias_square: Array1::zeros(output_size),
+        }
+    }
+}
+
+impl Default for OptimizerState {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+// 一次参数更新用到的优化器设置，MLP 与 LSTM 共用
+#[derive(Debug, Clone, Copy)]
+pub struct OptimizerSettings {
+    pub optimizer: Optimizer,
+    pub learning_rate: f64,
+    pub momentum: f64,
+    pub second_moment_decay: f64,
+}
+
+impl OptimizerState {
+    // 同时更新一组权重与偏置，step 计数只加一次
+    pub fn update(
+        &mut self,
+        weights: &mut Array2<f64>,
+        weight_grad: &Array2<f64>,
+        biases: &mut Array1<f64>,
+        bias_grad: &Array1<f64>,
+        settings: &OptimizerSettings,
+    ) {
+        // 旧版本保存的模型没有优化器状态
+        if self.weight_velocity.raw_dim() != weights.raw_dim() || self.bias_velocity.len() != biases.len() {
+            *self = OptimizerState::new(weights.nrows(), weights.ncols());
+        }
+
+        self.step += 1;
+        update_parameter(weights, weight_grad, &mut self.weight_velocity, &mut self.weight_square, settings, self.step);
+        update_parameter(biases, bias_grad, &mut self.bias_velocity, &mut self.bias_square, settings, self.step);
+    }
+}
+
+fn update_parameter<D: Dimension>(
+    param: &mut Array<f64, D>,
+    grad: &Array<f64, D>,
+    velocity: &mut Array<f64, D>,
+    square: &mut Array<f64, D>,
+    settings: &OptimizerSettings,
+    step: i32,
+) {
+    let OptimizerSettings { optimizer, learning_rate: lr, momentum, second_moment_decay: decay } = *settings;
+    let first_correction = 1.0 - momentum.powi(step);
+    let second_correction = 1.0 - decay.powi(step);
+
+    Zip::from(param).and(grad).and(velocity).and(square).for_each(|p, &g, v, s| match optimizer {
+        Optimizer::Sgd => *p -= lr * g,
+        Optimizer::Momentum => {
+            *v = momentum * *v + g;
+            *p -= lr * *v;
+        }
+        Optimizer::RmsProp => {
+            *s = decay * *s + (1.0 - decay) * g * g;
+            *p -= lr * g / (s.sqrt() + OPTIMIZER_EPSILON);
+        }
+        Optimizer::Adam => {
+            *v = momentum * *v + (1.0 - momentum) * g;
+            *s = decay * *s + (1.0 - decay) * g * g;
+            *p -= lr * (*v / first_correction) / ((*s / second_correction).sqrt() + OPTIMIZER_EPSILON);
+        }
+    });
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NeuralNetworkLayer {
     pub weights: Array2<f64>,
     pub biases: Array1<f64>,
     pub activation: String,
     pub dropout_rate: f64,
+    #[serde(default)]
+    pub optimizer_state: OptimizerState,
 }
 
 impl NeuralNetworkLayer {
@@ -61,11 +252,19 @@ impl NeuralNetworkLayer {
             biases,
             activation,
             dropout_rate,
+            optimizer_state: OptimizerState::new(output_size, input_size),
         }
     }
 
     pub fn forward(&self, input: &Array1<f64>, training: bool
     ) -> (Array1<f64>, Array1<f64>) {
+        let (a, z, _) = self.forward_with_mask(input, training);
+        (a, z)
+    }
+
+    // 训练时额外返回 dropout 掩码 (已按保留概率缩放)，反向传播时用到
+    pub fn forward_with_mask(&self, input: &Array1<f64>, training: bool
+    ) -> (Array1<f64>, Array1<f64>, Option<Array1<f64>>) {
         let z = self.weights.dot(input) + &self.biases;
         
         // Apply activation
@@ -79,60 +278,64 @@ impl NeuralNetworkLayer {
         };
 
         // Apply dropout
-        let a_final = if training && self.dropout_rate > 0.0 {
+        if training && self.dropout_rate > 0.0 {
             let mut rng = rand::thread_rng();
-            use rand::Rng;
-            a.mapv(|x| {
-                if rng.gen_range(0.0..1.0) < self.dropout_rate {
-                    0.0
-                } else {
-                    x / (1.0 - self.dropout_rate)
-                }
-            })
+            let keep = 1.0 - self.dropout_rate;
+            let mask = Array1::from_shape_fn(a.len(), |_| {
+                if rng.gen_range(0.0..1.0) < self.dropout_rate { 0.0 } else { 1.0 / keep }
+            });
+            (&a * &mask, z, Some(mask))
         } else {
-            a
-        };
-
-        (a_final, z)
+            (a, z, None)
+        }
     }
 
-    pub fn backward(&mut self, 
-        delta: &Array1<f64>,
-        z: &Array1<f64>,
-        input: &Array1<f64>,
-        learning_rate: f64,
-        regularization: f64
-    ) -> Array1<f64> {
-        // Compute gradient of activation
-        let grad_activation = match self.activation.as_str() {
+    pub fn activation_derivative(&self, z: &Array1<f64>) -> Array1<f64> {
+        match self.activation.as_str() {
             "relu" => z.mapv(|x| if x > 0.0 { 1.0 } else { 0.0 }),
-            "sigmoid" => {
-                let s = z.mapv(|x| 1.0 / (1.0 + (-x).exp()));
-                &s * (&s.mapv(|x| 1.0) - &s)
-            },
+            "sigmoid" => z.mapv(|x| {
+                let s = 1.0 / (1.0 + (-x).exp());
+                s * (1.0 - s)
+            }),
             "tanh" => z.mapv(|x| 1.0 - x.tanh().powi(2)),
             "leaky_relu" => z.mapv(|x| if x > 0.0 { 1.0 } else { 0.01 }),
             "elu" => z.mapv(|x| if x > 0.0 { 1.0 } else { x.exp() }),
             _ => Array1::ones(z.len()),
-        };
-
-        let delta_prev = delta * grad_activation;
-        
-        // Update weights and biases
-        let weight_grad = delta_prev.clone().insert_axis(Axis(1)).dot(&input.clone().insert_axis(Axis(0)));
-        let bias_grad = delta_prev.clone();
-
-        self.weights = &self.weights - learning_rate * (&weight_grad + regularization * &self.weights);
-        self.biases = &self.biases - learning_rate * &bias_grad;
+        }
+    }
 
-        // Return gradient w.r.t. input
-        self.weights.t().dot(&delta_prev)
+    // 梯度为 mini-batch 上的平均值，L2 正则只作用于权重
+    pub fn apply_gradients(
+        &mut self,
+        weight_grad: &Array2<f64>,
+        bias_grad: &Array1<f64>,
+        optimizer: Optimizer,
+        config: &NeuralNetworkConfig,
+        learning_rate: f64,
+    ) {
+        let weight_grad = weight_grad + &(config.regularization * &self.weights);
+        let settings = OptimizerSettings {
+            optimizer,
+            learning_rate,
+            momentum: config.momentum,
+            second_moment_decay: config.second_moment_decay,
+        };
+        self.optimizer_state.update(&mut self.weights, &weight_grad, &mut self.biases, bias_grad, &settings);
     }
 }
 
+// 一次前向传播中每层的输入、线性输出与 dropout 掩码
+struct LayerCache {
+    input: Array1<f64>,
+    z: Array1<f64>,
+    mask: Option<Array1<f64>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NeuralNetworkModel {
     pub config: NeuralNetworkConfig,
+    #[serde(default)]
+    pub base_config: NeuralNetworkConfig,
     pub layers: Vec<NeuralNetworkLayer>,
     pub input_size: usize,
     pub output_size: usize,
@@ -246,8 +449,25 @@ impl TargetEncoder {
 impl NeuralNetworkModel {
     pub fn new(config: NeuralNetworkConfig, lottery_type: LotteryType) -> Self {
         let input_size = 100; // 根据特征数量调整
-        let output_size = 49; // 最大号码范围
-        
+        let output_size = GameSpec::for_type(&lottery_type).main_pool_size();
+        let layers = Self::build_layers(&config, input_size, output_size);
+
+        Self {
+            base_config: config.clone(),
+            config,
+            layers,
+            input_size,
+            output_size,
+            is_trained: false,
+            lottery_type,
+            loss_history: Vec::new(),
+            validation_loss: Vec::new(),
+            feature_scaler: None,
+            target_encoder: None,
+        }
+    }
+
+    fn build_layers(config: &NeuralNetworkConfig, input_size: usize, output_size: usize) -> Vec<NeuralNetworkLayer> {
         let mut layers = Vec::new();
         let mut prev_size = input_size;
         
@@ -269,18 +489,7 @@ impl NeuralNetworkModel {
             0.0
         ));
 
-        Self {
-            config,
-            layers,
-            input_size,
-            output_size,
-            is_trained: false,
-            lottery_type,
-            loss_history: Vec::new(),
-            validation_loss: Vec::new(),
-            feature_scaler: None,
-            target_encoder: None,
-        }
+        layers
     }
 
     fn prepare_features(&self, features: &[Vec<f64>]) -> Array2<f64> {
@@ -290,48 +499,52 @@ impl NeuralNetworkModel {
         ).unwrap_or_else(|_| Array2::zeros((features.len(), features[0].len())))
     }
 
-    fn forward_pass(&self, input: &Array1<f64>, training: bool) -> Vec<(Array1<f64>, Array1<f64>)> {
-        let mut activations = vec![(input.clone(), Array1::zeros(0))];
+    fn forward_training(&self, input: &Array1<f64>) -> (Array1<f64>, Vec<LayerCache>) {
+        let mut caches = Vec::with_capacity(self.layers.len());
         let mut current_input = input.clone();
         
         for layer in &self.layers {
-            let (output, z) = layer.forward(&current_input, training);
-            activations.push((output.clone(), z));
+            let (output, z, mask) = layer.forward_with_mask(&current_input, true);
+            caches.push(LayerCache { input: current_input, z, mask });
             current_input = output;
         }
         
-        activations
+        (current_input, caches)
     }
 
-    fn backward_pass(
-        &mut self,
-        activations: &[(Array1<f64>, Array1<f64>)],
-        targets: &Array1<f64>,
-        learning_rate: f64,
-        regularization: f64
+    // 累加单个样本的梯度，返回该样本的交叉熵损失
+    fn accumulate_gradients(
+        &self,
+        input: &Array1<f64>,
+        target: &Array1<f64>,
+        gradients: &mut [(Array2<f64>, Array1<f64>)],
     ) -> f64 {
-        let _n_layers = self.layers.len();
-        let mut delta = activations.last().unwrap().0.clone() - targets;
-        
-        let mut total_loss = 0.0;
-        
-        for (layer_idx, layer) in self.layers.iter_mut().enumerate().rev() {
-            let (_a_prev, _) = &activations[layer_idx];
-            let (_, z) = &activations[layer_idx + 1];
-            
-            let input = if layer_idx == 0 {
-                &activations[0].0
-            } else {
-                &activations[layer_idx].0
-            };
-            
-            delta = layer.backward(&delta, z, input, learning_rate, regularization
-            );
-            
-            total_loss += delta.iter().map(|x| x * x).sum::<f64>();
+        let (output, caches) = self.forward_training(input);
+        let loss = self.binary_cross_entropy_loss(&output, target);
+
+        // 输出层为 sigmoid + 交叉熵，dL/dz = a - y
+        let output_layer = self.layers.len() - 1;
+        let mut delta = &output - target;
+
+        for (layer_idx, layer) in self.layers.iter().enumerate().rev() {
+            let cache = &caches[layer_idx];
+            if layer_idx != output_layer {
+                if let Some(mask) = &cache.mask {
+                    delta *= mask;
+                }
+                delta = delta * layer.activation_derivative(&cache.z);
+            }
+
+            let weight_grad = delta.view().insert_axis(Axis(1))
+                .dot(&cache.input.view().insert_axis(Axis(0)));
+            gradients[layer_idx].0 += &weight_grad;
+            gradients[layer_idx].1 += &delta;
+
+            // 用更新前的权重向前一层传播
+            delta = layer.weights.t().dot(&delta);
         }
-        
-        total_loss
+
+        loss
     }
 
     fn binary_cross_entropy_loss(&self, predictions: &Array1<f64>, targets: &Array1<f64>) -> f64 {
@@ -346,31 +559,119 @@ impl NeuralNetworkModel {
         loss / predictions.len() as f64
     }
 
-    fn train_epoch(&mut self, X: &Array2<f64>, y: &Array2<f64>) -> f64 {
+    fn train_epoch(
+        &mut self,
+        features: &Array2<f64>,
+        targets: &Array2<f64>,
+        indices: &[usize],
+        optimizer: Optimizer,
+        learning_rate: f64,
+        rng: &mut StdRng,
+    ) -> f64 {
+        let mut order = indices.to_vec();
+        order.shuffle(rng);
+
         let mut total_loss = 0.0;
-        let n_samples = X.nrows();
+        for batch in order.chunks(self.config.batch_size.max(1)) {
+            let mut gradients: Vec<(Array2<f64>, Array1<f64>)> = self.layers.iter()
+                .map(|layer| (Array2::zeros(layer.weights.raw_dim()), Array1::zeros(layer.biases.len())))
+                .collect();
+
+            for &i in batch {
+                total_loss += self.accumulate_gradients(
+                    &features.row(i).to_owned(),
+                    &targets.row(i).to_owned(),
+                    &mut gradients,
+                );
+            }
+
+            let scale = 1.0 / batch.len() as f64;
+            for (layer, (weight_grad, bias_grad)) in self.layers.iter_mut().zip(gradients) {
+                layer.apply_gradients(&(weight_grad * scale), &(bias_grad * scale), optimizer, &self.config, learning_rate);
+            }
+        }
         
-        for i in 0..n_samples {
-            let input = X.row(i).to_owned();
-            let target = y.row(i).to_owned();
+        total_loss / order.len() as f64
+    }
+
+    fn dataset_loss(&self, features: &Array2<f64>, targets: &Array2<f64>, indices: &[usize]) -> f64 {
+        let total: f64 = indices.iter()
+            .map(|&i| {
+                let output = self.predict_single(&features.row(i).to_owned());
+                self.binary_cross_entropy_loss(&output, &targets.row(i).to_owned())
+            })
+            .sum();
+        total / indices.len() as f64
+    }
+
+    // 按时间顺序留出最后 validation_split 的样本作验证集，早停依据验证集损失；
+    // 没有验证集时退回到训练损失
+    fn fit(&mut self, features: &Array2<f64>, targets: &Array2<f64>, optimizer: Optimizer) {
+        let n_samples = features.nrows();
+        let n_validation = (n_samples as f64 * self.config.validation_split.clamp(0.0, 0.5)).round() as usize;
+        let n_train = n_samples - n_validation;
+        let train_indices: Vec<usize> = (0..n_train).collect();
+        let validation_indices: Vec<usize> = (n_train..n_samples).collect();
+
+        self.loss_history.clear();
+        self.validation_loss.clear();
+
+        let mut rng = StdRng::from_entropy();
+        let mut best_loss = f64::INFINITY;
+        let mut best_layers = self.layers.clone();
+        let mut patience_counter = 0;
+        
+        for epoch in 0..self.config.epochs {
+            let learning_rate = self.config.learning_rate_schedule
+                .learning_rate(self.config.learning_rate, epoch, self.config.epochs);
+            let loss = self.train_epoch(features, targets, &train_indices, optimizer, learning_rate, &mut rng);
+            self.loss_history.push(loss);
+
+            let monitored = if validation_indices.is_empty() {
+                loss
+            } else {
+                let validation = self.dataset_loss(features, targets, &validation_indices);
+                self.validation_loss.push(validation);
+                validation
+            };
             
-            let activations = self.forward_pass(&input, true);
-            let loss = self.backward_pass(
-                &activations, 
-                &target, 
-                self.config.learning_rate, 
-                self.config.regularization
-            );
+            if monitored < best_loss {
+                best_loss = monitored;
+                best_layers = self.layers.clone();
+                patience_counter = 0;
+            } else {
+                patience_counter += 1;
+            }
             
-            total_loss += loss;
+            if self.config.early_stopping && patience_counter >= self.config.patience {
+                break;
+            }
+        }
+
+        if self.config.early_stopping {
+            self.layers = best_layers;
+        }
+    }
+
+    // 按主号码池多热编码，与输出层一一对应
+    fn encode_targets(&self, targets: &[Vec<u32>]) -> Array2<f64> {
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let mut encoded = Array2::zeros((targets.len(), self.output_size));
+        
+        for (i, target) in targets.iter().enumerate() {
+            for &number in target {
+                if spec.is_valid_main(number) {
+                    encoded[[i, (number - spec.main_min()) as usize]] = 1.0;
+                }
+            }
         }
         
-        total_loss / n_samples as f64
+        encoded
     }
 
     fn predict_single(&self, features: &Array1<f64>) -> Array1<f64> {
-        let activations = self.forward_pass(features, false);
-        activations.last().unwrap().0.clone()
+        self.layers.iter()
+            .fold(features.clone(), |input, layer| layer.forward(&input, false).0)
     }
 
     fn select_top_numbers(&self, probabilities: &Array1<f64>, count: usize) -> Vec<u32> {
@@ -381,9 +682,10 @@ impl NeuralNetworkModel {
         
         indexed_probs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
         
+        let min_number = GameSpec::for_type(&self.lottery_type).main_min();
         indexed_probs.into_iter()
             .take(count)
-            .map(|(i, _)| (i + 1) as u32)
+            .map(|(i, _)| i as u32 + min_number)
             .collect()
     }
 }
@@ -394,76 +696,67 @@ impl PredictionAlgorithm for NeuralNetworkModel {
         "Deep Neural Network".to_string()
     }
 
-    fn algorithm_type(&self) -> String {
-        "neural_network".to_string()
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::NeuralNetwork
     }
 
     async fn train(
         &mut self,
         training_data: &TrainingData,
-        _config: &AlgorithmConfig,
+        config: &AlgorithmConfig,
     ) -> Result<f64> {
+        let updated = config.apply(&self.base_config)?;
+        // 网络结构变化后从头初始化
+        if updated.hidden_layers != self.config.hidden_layers
+            || updated.activation != self.config.activation
+            || updated.dropout_rate != self.config.dropout_rate
+        {
+            let base_config = self.base_config.clone();
+            *self = Self::new(updated, self.lottery_type.clone());
+            self.base_config = base_config;
+        } else {
+            self.config = updated;
+        }
+
         if training_data.features.is_empty() || training_data.targets.is_empty() {
             return Err(crate::lottery::errors::LotteryError::InvalidParameter(
                 "No training data provided".to_string()
             ));
         }
 
+        let optimizer = Optimizer::from_name(&self.config.optimizer)?;
+
         // Prepare data
         let X = self.prepare_features(&training_data.features);
-        let _y = Array2::from_shape_vec(
-            (training_data.targets.len(), training_data.targets[0].len()),
-            training_data.targets.iter().flatten().map(|&x| x as f64).collect()
-        ).map_err(|_| crate::lottery::errors::LotteryError::AlgorithmError(
-            "Failed to create target array".to_string()
-        ))?;
 
         // Scale features
         let mut scaler = StandardScaler::new();
         scaler.fit(&X);
-        let X_scaled = scaler.transform(&X);
+        let scaled = scaler.transform(&X);
         self.feature_scaler = Some(scaler);
 
-        // Encode targets
         let mut encoder = TargetEncoder::new();
         encoder.fit(&training_data.targets);
-        let y_encoded = encoder.encode(&training_data.targets);
         self.target_encoder = Some(encoder);
+        let y = self.encode_targets(&training_data.targets);
 
-        if X_scaled.nrows() < 10 {
+        if scaled.nrows() < 10 {
             return Err(crate::lottery::errors::LotteryError::AlgorithmError(
                 "Insufficient data for neural network training".to_string()
             ));
         }
 
-        // Training loop
-        let mut best_loss = f64::INFINITY;
-        let mut patience_counter = 0;
-        
-        for _epoch in 0..self.config.epochs {
-            let loss = self.train_epoch(&X_scaled, &y_encoded
-            );
-            
-            self.loss_history.push(loss);
-            
-            if loss < best_loss {
-                best_loss = loss;
-                patience_counter = 0;
-            } else {
-                patience_counter += 1;
-            }
-            
-            if self.config.early_stopping && patience_counter >= self.config.patience {
-                break;
-            }
+        if scaled.ncols() != self.input_size {
+            self.input_size = scaled.ncols();
+            self.layers = Self::build_layers(&self.config, self.input_size, self.output_size);
         }
 
+        self.fit(&scaled, &y, optimizer);
+
         self.is_trained = true;
-        
-        // Calculate accuracy - use a simplified approach
-        let accuracy = 0.65 + (0.35 * (1.0 - best_loss.min(1.0))).max(0.0);
-        
-        Ok(accuracy)
+
+        let metrics = self.evaluate(training_data).await?;
+        Ok(metrics.accuracy)
     }
 
 
@@ -506,44 +799,20 @@ impl PredictionAlgorithm for NeuralNetworkModel {
         let predictions = self.predict_single(&features_scaled);
 
         // Determine number counts based on lottery type
-        let main_count = match self.lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
-
-        let special_count = match self.lottery_type {
-            LotteryType::Ssq => 1,
-            LotteryType::Dlt => 2,
-            _ => 0,
-        };
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let main_count = spec.pick_count;
 
         // Select top numbers
         let predicted_numbers = self.select_top_numbers(&predictions, main_count);
 
-        // Special numbers (simplified)
-        let predicted_special_numbers = if special_count > 0 {
-            let special_max = match self.lottery_type {
-                LotteryType::Ssq => 16,
-                LotteryType::Dlt => 12,
-                _ => 0,
-            };
-            
-            let mut rng = rand::thread_rng();
-            let mut candidates: Vec<u32> = (1..=special_max).collect();
-            use rand::seq::SliceRandom;
-            candidates.shuffle(&mut rng);
-            Some(candidates.into_iter().take(special_count).collect())
-        } else {
-            None
-        };
+        // 网络只输出主号码，特别号按历史出现次数
+        let special_scores = spec.has_special()
+            .then(|| calibration::history_special_scores(&spec, historical_data));
+        let predicted_special_numbers = special_scores.as_ref()
+            .map(|scores| calibration::top_special_numbers(&spec, scores));
 
-        let confidence_scores = predicted_numbers.iter()
-            .map(|&num| predictions[(num - 1) as usize])
-            .collect();
+        let probabilities = NumberProbabilities::from_scores(&spec, &predictions.to_vec(), special_scores.as_deref());
+        let confidence_scores = probabilities.confidence(&spec, &predicted_numbers);
 
         let computation_time = start_time.elapsed().as_millis() as u64;
 
@@ -552,12 +821,13 @@ impl PredictionAlgorithm for NeuralNetworkModel {
             predicted_special_numbers,
             confidence_scores,
             algorithm_metadata: HashMap::from_iter(vec![
-                ("algorithm".to_string(), serde_json::Value::String("neural_network".to_string())),
+                ("algorithm".to_string(), serde_json::Value::String(AlgorithmType::NeuralNetwork.to_string())),
                 ("hidden_layers".to_string(), serde_json::Value::Number(self.config.hidden_layers.len().into())),
                 ("activation".to_string(), serde_json::Value::String(self.config.activation.clone())),
                 ("epochs".to_string(), serde_json::Value::Number(self.config.epochs.into())),
             ]),
             computation_time_ms: computation_time,
+            probabilities: Some(probabilities),
         })
     }
 
@@ -569,27 +839,36 @@ impl PredictionAlgorithm for NeuralNetworkModel {
             return Ok(EvaluationMetrics::default());
         }
 
-        let X = self.prepare_features(&test_data.features);
-        let _y = Array2::from_shape_vec(
-            (test_data.targets.len(), test_data.targets[0].len()),
-            test_data.targets.iter().flatten().map(|&x| x as f64).collect()
-        ).map_err(|_| crate::lottery::errors::LotteryError::AlgorithmError(
-            "Failed to create target array".to_string()
-        ))?;
+        let scores = self.predict_scores(test_data).await?;
+        MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)
+    }
 
-        let scaler = self.feature_scaler.as_ref().unwrap();
-        let X_scaled = scaler.transform(&X);
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        let Some(scaler) = self.feature_scaler.as_ref().filter(|_| self.is_trained) else {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        };
 
-        // Simplified accuracy calculation
-        let accuracy = 0.65;
+        if test_data.features.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        let mut metrics = EvaluationMetrics::default();
-        metrics.accuracy = accuracy;
-        metrics.precision = accuracy * 0.95;
-        metrics.recall = accuracy * 0.9;
-        metrics.f1_score = 2.0 * (metrics.precision * metrics.recall) / (metrics.precision + metrics.recall + 1e-8);
+        let scaled = scaler.transform(&self.prepare_features(&test_data.features));
+        Ok(scaled.rows()
+            .into_iter()
+            .map(|row| self.predict_single(&row.to_owned()).to_vec())
+            .collect())
+    }
 
-        Ok(metrics)
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        calibration::frequency_special_scores(&self.lottery_type, test_data)
     }
 
     fn is_trained(&self) -> bool {
@@ -651,7 +930,7 @@ mod tests {
         let config = NeuralNetworkConfig::default();
         let model = NeuralNetworkModel::new(config, LotteryType::Ssq);
         assert_eq!(model.name(), "Deep Neural Network");
-        assert_eq!(model.algorithm_type(), "neural_network");
+        assert_eq!(model.algorithm_type(), AlgorithmType::NeuralNetwork);
         assert!(!model.is_trained());
     }
 
@@ -663,6 +942,100 @@ mod tests {
         assert_eq!(output.len(), 2);
     }
 
+    #[test]
+    fn test_learning_rate_schedules() {
+        let step = LearningRateSchedule::Step { step_size: 10, gamma: 0.5 };
+        assert_eq!(step.learning_rate(0.1, 9, 100), 0.1);
+        assert_eq!(step.learning_rate(0.1, 25, 100), 0.025);
+
+        let cosine = LearningRateSchedule::Cosine { min_learning_rate: 0.0 };
+        assert!((cosine.learning_rate(0.1, 0, 100) - 0.1).abs() < 1e-12);
+        assert!((cosine.learning_rate(0.1, 50, 100) - 0.05).abs() < 1e-12);
+
+        let schedule: LearningRateSchedule =
+            serde_json::from_value(serde_json::json!({"type": "exponential", "gamma": 0.9})).unwrap();
+        assert_eq!(schedule, LearningRateSchedule::Exponential { gamma: 0.9 });
+        assert!(Optimizer::from_name("nadam").is_err());
+    }
+
+    #[test]
+    fn test_optimizer_steps() {
+        let config = NeuralNetworkConfig { regularization: 0.0, ..Default::default() };
+        let weight_grad = Array2::from_elem((1, 1), 4.0);
+        let bias_grad = Array1::from_elem(1, 4.0);
+
+        for (optimizer, expected) in [
+            (Optimizer::Sgd, -0.4),
+            // 首步动量就是梯度本身
+            (Optimizer::Momentum, -0.4),
+            // 偏差修正后 Adam 首步约为 lr * sign(g)
+            (Optimizer::Adam, -0.1),
+        ] {
+            let mut layer = NeuralNetworkLayer::new(1, 1, "linear".to_string(), 0.0);
+            layer.weights.fill(0.0);
+            layer.apply_gradients(&weight_grad, &bias_grad, optimizer, &config, 0.1);
+            assert!((layer.weights[[0, 0]] - expected).abs() < 1e-6, "{:?}", optimizer);
+            assert_eq!(layer.optimizer_state.step, 1);
+        }
+
+        let mut layer = NeuralNetworkLayer::new(1, 1, "linear".to_string(), 0.0);
+        layer.weights.fill(0.0);
+        layer.apply_gradients(&weight_grad, &bias_grad, Optimizer::RmsProp, &config, 0.1);
+        assert!((layer.optimizer_state.weight_square[[0, 0]] - 0.016).abs() < 1e-12);
+        assert!(layer.weights[[0, 0]] < -1.0);
+    }
+
+    #[tokio::test]
+    async fn test_mini_batch_training_reduces_loss() {
+        let config = NeuralNetworkConfig {
+            hidden_layers: vec![8],
+            epochs: 60,
+            batch_size: 4,
+            learning_rate: 0.05,
+            dropout_rate: 0.0,
+            early_stopping: false,
+            validation_split: 0.25,
+            learning_rate_schedule: LearningRateSchedule::Exponential { gamma: 0.99 },
+            ..Default::default()
+        };
+        let mut model = NeuralNetworkModel::new(config, LotteryType::Ssq);
+
+        let mut training_data = TrainingData {
+            features: Vec::new(),
+            targets: Vec::new(),
+            special_targets: None,
+            weights: None,
+        };
+        for i in 0..24 {
+            let even = i % 2 == 0;
+            training_data.features.push(vec![if even { 1.0 } else { 0.0 }, if even { 0.0 } else { 1.0 }, 0.5]);
+            training_data.targets.push(if even { vec![1, 2, 3, 4, 5, 6] } else { vec![7, 8, 9, 10, 11, 12] });
+        }
+
+        let config = AlgorithmConfig {
+            lottery_type: LotteryType::Ssq,
+            parameters: HashMap::new(),
+            hyperparameters: HashMap::new(),
+            feature_config: HashMap::new(),
+        };
+
+        model.train(&training_data, &config).await.unwrap();
+        assert_eq!(model.input_size, 3);
+        assert_eq!(model.loss_history.len(), 60);
+        assert_eq!(model.validation_loss.len(), 60);
+        assert!(model.loss_history.last().unwrap() < model.loss_history.first().unwrap());
+        assert!(model.validation_loss.last().unwrap() < model.validation_loss.first().unwrap());
+
+        // 优化器状态随模型一起保存
+        let restored: NeuralNetworkModel =
+            serde_json::from_str(&serde_json::to_string(&model).unwrap()).unwrap();
+        assert_eq!(restored.layers[0].optimizer_state.step, model.layers[0].optimizer_state.step);
+        let velocity_diff = &restored.layers[0].optimizer_state.weight_velocity
+            - &model.layers[0].optimizer_state.weight_velocity;
+        // JSON 往返可能有末位误差
+        assert!(velocity_diff.iter().all(|d| d.abs() < 1e-12));
+    }
+
     #[tokio::test]
     async fn test_neural_network_training() {
         let config = NeuralNetworkConfig {
@@ -1,24 +1,39 @@
 use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::{self, NumberProbabilities};
 use crate::lottery::algorithms::feature_engineering::LotteryFeatureExtractor;
+use crate::lottery::algorithms::metrics::MetricsCalculator;
 use crate::lottery::errors::LotteryResult as Result;
-use crate::lottery::models::LotteryType;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryType};
 use async_trait::async_trait;
-use ndarray::Array2;
+use ndarray::{Array1, Array2, ArrayView1};
 use rand::prelude::*;
+use rand::seq::index;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::thread;
+
+// 预测元数据中列出的重要特征个数
+const REPORTED_FEATURES: usize = 10;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RandomForestConfig {
     pub n_estimators: usize,
     pub max_depth: Option<usize>,
     pub min_samples_split: usize,
     pub min_samples_leaf: usize,
+    // 每次分裂随机抽取的候选特征数，默认为特征总数的平方根
     pub max_features: Option<usize>,
     pub random_state: Option<u64>,
     pub bootstrap: bool,
+    // 每个估计器自助采样的样本比例，取值 (0, 1]，默认与样本数相同
     pub max_samples: Option<f64>,
+    // 用袋外样本计算得分和置换重要性，仅在 bootstrap 时生效
+    pub oob_score: bool,
+    // 并行训练的线程数，默认使用全部可用核心
+    pub n_jobs: Option<usize>,
 }
 
 impl Default for RandomForestConfig {
@@ -32,17 +47,34 @@ impl Default for RandomForestConfig {
             random_state: Some(42),
             bootstrap: true,
             max_samples: None,
+            oob_score: true,
+            n_jobs: None,
         }
     }
 }
 
+impl ModelConfig for RandomForestConfig {
+    fn validate(&self) -> Result<()> {
+        ensure_config(self.n_estimators > 0, "n_estimators must be positive")?;
+        ensure_config(self.max_depth != Some(0), "max_depth must be positive")?;
+        ensure_config(self.min_samples_split >= 2, "min_samples_split must be at least 2")?;
+        ensure_config(self.min_samples_leaf > 0, "min_samples_leaf must be positive")?;
+        ensure_config(self.max_features != Some(0), "max_features must be positive")?;
+        ensure_config(
+            self.max_samples.is_none_or(|fraction| fraction > 0.0 && fraction <= 1.0),
+            "max_samples must be in (0, 1]",
+        )?;
+        ensure_config(self.n_jobs != Some(0), "n_jobs must be positive")
+    }
+}
+
+// 单个号码的二分类树
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecisionTree {
     pub root: Option<Box<TreeNode>>,
     pub max_depth: usize,
     pub min_samples_split: usize,
     pub min_samples_leaf: usize,
-    pub feature_indices: Vec<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,260 +83,326 @@ pub struct TreeNode {
     pub threshold: Option<f64>,
     pub left: Option<Box<TreeNode>>,
     pub right: Option<Box<TreeNode>>,
-    pub value: Option<Vec<f64>>,
+    // 叶节点样本中该号码开出的比例
+    pub value: Option<f64>,
     pub samples: usize,
     pub gini: f64,
 }
 
+// 同一份自助样本上训练的一组树：主号码池和特别号码池中每个号码各一棵，按号码升序排列
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForestEstimator {
+    pub main_trees: Vec<DecisionTree>,
+    pub special_trees: Vec<DecisionTree>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RandomForestModel {
-    pub trees: Vec<DecisionTree>,
+    pub estimators: Vec<ForestEstimator>,
     pub config: RandomForestConfig,
+    #[serde(default)]
+    pub base_config: RandomForestConfig,
+    pub feature_names: Vec<String>,
+    // 平均不纯度下降 (Gini)，归一化到总和为 1
     pub feature_importance: Vec<f64>,
-    pub classes: Vec<u32>,
+    // 袋外样本上打乱单个特征后主号码 Brier 分数的上升量
+    pub permutation_importance: Vec<f64>,
+    pub oob_score: Option<f64>,
     pub is_trained: bool,
     pub lottery_type: LotteryType,
 }
 
+impl DecisionTree {
+    // 沿分裂走到叶节点，返回叶节点取值
+    pub fn predict(&self, features: ArrayView1<f64>) -> f64 {
+        let mut node = self.root.as_ref();
+
+        while let Some(current) = node {
+            match (current.feature_index, current.threshold) {
+                (Some(feature_idx), Some(threshold)) => {
+                    node = if features[feature_idx] <= threshold {
+                        current.left.as_ref()
+                    } else {
+                        current.right.as_ref()
+                    };
+                }
+                _ => return current.value.unwrap_or(0.0),
+            }
+        }
+
+        0.0
+    }
+}
+
+struct EstimatorFit {
+    estimator: ForestEstimator,
+    oob_indices: Vec<usize>,
+    importance: Vec<f64>,
+}
+
+struct Split {
+    feature_index: usize,
+    threshold: f64,
+    left_gini: f64,
+    right_gini: f64,
+}
+
 impl RandomForestModel {
     pub fn new(config: RandomForestConfig, lottery_type: LotteryType) -> Self {
         Self {
-            trees: Vec::new(),
+            estimators: Vec::new(),
+            base_config: config.clone(),
             config,
+            feature_names: Vec::new(),
             feature_importance: Vec::new(),
-            classes: Vec::new(),
+            permutation_importance: Vec::new(),
+            oob_score: None,
             is_trained: false,
             lottery_type,
         }
     }
 
-    fn get_classes(&self, targets: &[Vec<u32>]
-    ) -> Vec<u32> {
-        let mut classes = std::collections::HashSet::new();
-        for target in targets {
-            classes.extend(target.iter().copied());
+    pub fn get_permutation_importance(&self) -> Option<HashMap<String, f64>> {
+        self.named_importance(&self.permutation_importance)
+    }
+
+    fn named_importance(&self, values: &[f64]) -> Option<HashMap<String, f64>> {
+        if values.is_empty() {
+            return None;
         }
-        let mut classes: Vec<u32> = classes.into_iter().collect();
-        classes.sort();
-        classes
+
+        Some(self.feature_names.iter().cloned().zip(values.iter().copied()).collect())
     }
 
-    fn prepare_data(
-        &self,
-        training_data: &TrainingData,
-    ) -> Result<(Array2<f64>, Vec<Vec<u32>>)> {
-        let features = Array2::from_shape_vec(
+    // 按重要性降序取前 REPORTED_FEATURES 个特征
+    fn top_features(&self, values: &[f64]) -> Vec<(String, f64)> {
+        let mut ranked: Vec<(String, f64)> = self.feature_names.iter().cloned().zip(values.iter().copied()).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(REPORTED_FEATURES);
+        ranked
+    }
+
+    fn prepare_data(&self, training_data: &TrainingData) -> Result<Array2<f64>> {
+        if training_data.features.is_empty() {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "No training data provided".to_string()
+            ));
+        }
+
+        Array2::from_shape_vec(
             (training_data.features.len(), training_data.features[0].len()),
             training_data.features.iter().flatten().cloned().collect()
         ).map_err(|_| crate::lottery::errors::LotteryError::AlgorithmError(
             "Failed to create feature matrix".to_string()
-        ))?;
+        ))
+    }
+
+    fn check_feature_dimension(&self, len: usize) -> Result<()> {
+        if len != self.feature_names.len() {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(format!(
+                "Expected {} features, got {}",
+                self.feature_names.len(),
+                len
+            )));
+        }
+        Ok(())
+    }
+
+    fn has_special_trees(&self) -> bool {
+        let spec = GameSpec::for_type(&self.lottery_type);
+        spec.has_special() && self.estimators.first().is_some_and(|e| e.special_trees.len() == spec.special_pool_size())
+    }
+
+    fn max_features(&self, n_features: usize) -> usize {
+        self.config.max_features
+            .unwrap_or((n_features as f64).sqrt() as usize)
+            .clamp(1, n_features.max(1))
+    }
+
+    fn n_jobs(&self) -> usize {
+        self.config.n_jobs
+            .unwrap_or_else(|| thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+    }
+
+    // 返回 (袋内样本, 袋外样本)；不做自助采样时全部样本都在袋内
+    fn sample_indices(&self, n_samples: usize, rng: &mut StdRng) -> (Vec<usize>, Vec<usize>) {
+        if !self.config.bootstrap {
+            return ((0..n_samples).collect(), Vec::new());
+        }
+
+        let draws = self.config.max_samples
+            .map_or(n_samples, |fraction| (fraction * n_samples as f64).round() as usize)
+            .max(1);
+        let in_bag: Vec<usize> = (0..draws).map(|_| rng.gen_range(0..n_samples)).collect();
+
+        let mut drawn = vec![false; n_samples];
+        for &i in &in_bag {
+            drawn[i] = true;
+        }
+        let oob = (0..n_samples).filter(|&i| !drawn[i]).collect();
+
+        (in_bag, oob)
+    }
+
+    // 每个估计器使用独立的种子，结果与线程数无关
+    fn fit_estimator(
+        &self,
+        features: &Array2<f64>,
+        main_labels: &[Vec<bool>],
+        special_labels: &[Vec<bool>],
+        seed: u64,
+    ) -> EstimatorFit {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (in_bag, oob_indices) = self.sample_indices(features.nrows(), &mut rng);
+        let mut importance = vec![0.0; features.ncols()];
+
+        let mut train = |labels: &Vec<bool>| {
+            self.train_tree(features, labels, &in_bag, &mut importance, &mut rng)
+        };
+        let main_trees = main_labels.iter().map(&mut train).collect();
+        let special_trees = special_labels.iter().map(&mut train).collect();
 
-        Ok((features, training_data.targets.clone()))
+        EstimatorFit {
+            estimator: ForestEstimator { main_trees, special_trees },
+            oob_indices,
+            importance,
+        }
     }
 
     fn train_tree(
         &self,
         features: &Array2<f64>,
-        targets: &[Vec<u32>],
+        labels: &[bool],
         indices: &[usize],
-        classes: &[u32],
-    ) -> Result<DecisionTree> {
-        let mut rng = StdRng::seed_from_u64(self.config.random_state.unwrap_or(42));
-        
-        let n_features = features.ncols();
-        let max_features = self.config.max_features.unwrap_or((n_features as f64).sqrt() as usize);
-        
-        let mut feature_indices: Vec<usize> = (0..n_features).collect();
-        feature_indices.shuffle(&mut rng);
-        let feature_indices: Vec<usize> = feature_indices.into_iter().take(max_features).collect();
-
-        let root = self.build_tree_node(
-            features,
-            targets,
-            indices,
-            classes,
-            &feature_indices,
-            0,
-        )?;
+        importance: &mut [f64],
+        rng: &mut StdRng,
+    ) -> DecisionTree {
+        let root = self.build_tree_node(features, labels, indices, 0, importance, rng);
 
-        Ok(DecisionTree {
+        DecisionTree {
             root: Some(root),
             max_depth: self.config.max_depth.unwrap_or(10),
             min_samples_split: self.config.min_samples_split,
             min_samples_leaf: self.config.min_samples_leaf,
-            feature_indices,
-        })
+        }
     }
 
     fn build_tree_node(
         &self,
         features: &Array2<f64>,
-        targets: &[Vec<u32>],
+        labels: &[bool],
         indices: &[usize],
-        classes: &[u32],
-        feature_indices: &[usize],
         depth: usize,
-    ) -> Result<Box<TreeNode>> {
-        if indices.is_empty() {
-            return Ok(Box::new(TreeNode {
-                feature_index: None,
-                threshold: None,
-                left: None,
-                right: None,
-                value: Some(vec![0.0; classes.len()]),
-                samples: 0,
-                gini: 1.0,
-            }));
-        }
+        importance: &mut [f64],
+        rng: &mut StdRng,
+    ) -> Box<TreeNode> {
+        let samples = indices.len();
+        let positives = indices.iter().filter(|&&i| labels[i]).count();
+        let gini = binary_gini(positives, samples);
 
         let max_depth = self.config.max_depth.unwrap_or(10);
-        let min_samples_split = self.config.min_samples_split;
-        let min_samples_leaf = self.config.min_samples_leaf;
-
-        if depth >= max_depth || indices.len() < min_samples_split {
-            let value = self.calculate_class_distribution(targets, indices, classes);
-            let gini = self.calculate_gini(targets, indices, classes);
-            
-            return Ok(Box::new(TreeNode {
+        let min_samples_leaf = self.config.min_samples_leaf.max(1);
+        let splittable = depth < max_depth
+            && samples >= self.config.min_samples_split
+            && samples >= 2 * min_samples_leaf
+            && gini > 0.0;
+
+        let split = if splittable {
+            self.find_best_split(features, labels, indices, rng)
+        } else {
+            None
+        };
+
+        let Some(split) = split else {
+            return Box::new(TreeNode {
                 feature_index: None,
                 threshold: None,
                 left: None,
                 right: None,
-                value: Some(value),
-                samples: indices.len(),
+                value: Some(if samples > 0 { positives as f64 / samples as f64 } else { 0.0 }),
+                samples,
                 gini,
-            }));
-        }
+            });
+        };
 
-        let (best_feature, best_threshold, best_gini) = self.find_best_split(
+        let (left_indices, right_indices) = self.split_data(
             features,
-            targets,
             indices,
-            classes,
-            feature_indices,
-        )?;
-
-        if let (Some(feature_idx), Some(threshold)) = (best_feature, best_threshold) {
-            let (left_indices, right_indices) = self.split_data(
-                features,
-                indices,
-                feature_idx,
-                threshold,
-            );
-
-            if left_indices.len() < min_samples_leaf || right_indices.len() < min_samples_leaf {
-                let value = self.calculate_class_distribution(targets, indices, classes);
-                let gini = self.calculate_gini(targets, indices, classes);
-                
-                return Ok(Box::new(TreeNode {
-                    feature_index: None,
-                    threshold: None,
-                    left: None,
-                    right: None,
-                    value: Some(value),
-                    samples: indices.len(),
-                    gini,
-                }));
-            }
+            split.feature_index,
+            split.threshold,
+        );
 
-            let left_child = self.build_tree_node(
-                features,
-                targets,
-                &left_indices,
-                classes,
-                feature_indices,
-                depth + 1,
-            )?;
-
-            let right_child = self.build_tree_node(
-                features,
-                targets,
-                &right_indices,
-                classes,
-                feature_indices,
-                depth + 1,
-            )?;
-
-            Ok(Box::new(TreeNode {
-                feature_index: Some(feature_idx),
-                threshold: Some(threshold),
-                left: Some(left_child),
-                right: Some(right_child),
-                value: None,
-                samples: indices.len(),
-                gini: best_gini,
-            }))
-        } else {
-            let value = self.calculate_class_distribution(targets, indices, classes);
-            let gini = self.calculate_gini(targets, indices, classes);
-            
-            Ok(Box::new(TreeNode {
-                feature_index: None,
-                threshold: None,
-                left: None,
-                right: None,
-                value: Some(value),
-                samples: indices.len(),
-                gini,
-            }))
-        }
+        // 加权不纯度下降累加到分裂特征上
+        importance[split.feature_index] += samples as f64 * gini
+            - left_indices.len() as f64 * split.left_gini
+            - right_indices.len() as f64 * split.right_gini;
+
+        let left = self.build_tree_node(features, labels, &left_indices, depth + 1, importance, rng);
+        let right = self.build_tree_node(features, labels, &right_indices, depth + 1, importance, rng);
+
+        Box::new(TreeNode {
+            feature_index: Some(split.feature_index),
+            threshold: Some(split.threshold),
+            left: Some(left),
+            right: Some(right),
+            value: None,
+            samples,
+            gini,
+        })
     }
 
+    // 随机抽取 max_features 个特征，在排序后的相邻取值中点上穷举阈值，两侧都需满足 min_samples_leaf
     fn find_best_split(
         &self,
         features: &Array2<f64>,
-        targets: &[Vec<u32>],
+        labels: &[bool],
         indices: &[usize],
-        classes: &[u32],
-        feature_indices: &[usize],
-    ) -> Result<(Option<usize>, Option<f64>, f64)> {
-        let mut best_gini = 1.0;
-        let mut best_feature = None;
-        let mut best_threshold = None;
-
-        for &feature_idx in feature_indices {
-            let values: Vec<f64> = indices.iter().map(|&i| features[[i, feature_idx]]).collect();
-            let min_val = values.iter().cloned().fold(f64::INFINITY, f64::min);
-            let max_val = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-
-            if min_val >= max_val {
-                continue;
-            }
+        rng: &mut StdRng,
+    ) -> Option<Split> {
+        let samples = indices.len();
+        let positives = indices.iter().filter(|&&i| labels[i]).count();
+        let min_samples_leaf = self.config.min_samples_leaf.max(1);
+        let n_features = features.ncols();
+
+        let mut best = None;
+        let mut best_impurity = samples as f64 * binary_gini(positives, samples);
+
+        for feature_index in index::sample(rng, n_features, self.max_features(n_features)) {
+            let mut values: Vec<(f64, bool)> = indices.iter()
+                .map(|&i| (features[[i, feature_index]], labels[i]))
+                .collect();
+            values.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut left_positives = 0;
+            for left_len in 1..samples {
+                left_positives += values[left_len - 1].1 as usize;
 
-            let thresholds = vec![
-                (min_val + max_val) / 2.0,
-                min_val + (max_val - min_val) * 0.25,
-                min_val + (max_val - min_val) * 0.75,
-            ];
-
-            for &threshold in &thresholds {
-                let (left_indices, right_indices) = self.split_data(
-                    features,
-                    indices,
-                    feature_idx,
-                    threshold,
-                );
-
-                if left_indices.is_empty() || right_indices.is_empty() {
+                let right_len = samples - left_len;
+                if left_len < min_samples_leaf || right_len < min_samples_leaf {
+                    continue;
+                }
+                // 相同取值无法被阈值分开
+                if values[left_len].0 <= values[left_len - 1].0 {
                     continue;
                 }
 
-                let left_gini = self.calculate_gini(targets, &left_indices, classes);
-                let right_gini = self.calculate_gini(targets, &right_indices, classes);
-                let weighted_gini = (left_indices.len() as f64 * left_gini +
-                                   right_indices.len() as f64 * right_gini) /
-                                  indices.len() as f64;
-
-                if weighted_gini < best_gini {
-                    best_gini = weighted_gini;
-                    best_feature = Some(feature_idx);
-                    best_threshold = Some(threshold);
+                let left_gini = binary_gini(left_positives, left_len);
+                let right_gini = binary_gini(positives - left_positives, right_len);
+                let impurity = left_len as f64 * left_gini + right_len as f64 * right_gini;
+
+                if impurity < best_impurity - 1e-12 {
+                    best_impurity = impurity;
+                    best = Some(Split {
+                        feature_index,
+                        threshold: (values[left_len - 1].0 + values[left_len].0) / 2.0,
+                        left_gini,
+                        right_gini,
+                    });
                 }
             }
         }
 
-        Ok((best_feature, best_threshold, best_gini))
+        best
     }
 
     fn split_data(
@@ -329,104 +427,146 @@ impl RandomForestModel {
         (left_indices, right_indices)
     }
 
-    fn calculate_gini(
-        &self,
-        targets: &[Vec<u32>],
-        indices: &[usize],
-        classes: &[u32],
-    ) -> f64 {
-        let mut class_counts = vec![0.0; classes.len()];
-        
-        for &idx in indices {
-            for &target in &targets[idx] {
-                if let Some(pos) = classes.iter().position(|&c| c == target) {
-                    class_counts[pos] += 1.0;
-                }
+    // 各估计器中同一号码的树给出的开出概率取平均，special 为 true 时对应特别号码池
+    fn number_probabilities(&self, features: ArrayView1<f64>, special: bool) -> Vec<f64> {
+        let mut probabilities = Vec::new();
+
+        for estimator in &self.estimators {
+            let trees = if special { &estimator.special_trees } else { &estimator.main_trees };
+            probabilities.resize(trees.len(), 0.0);
+            for (probability, tree) in probabilities.iter_mut().zip(trees) {
+                *probability += tree.predict(features);
             }
         }
 
-        let total = class_counts.iter().sum::<f64>();
-        if total == 0.0 {
-            return 1.0;
+        let n_estimators = self.estimators.len().max(1) as f64;
+        for probability in &mut probabilities {
+            *probability /= n_estimators;
         }
 
-        let gini = 1.0 - class_counts.iter().map(|&count| {
-            let p = count / total;
-            p * p
-        }).sum::<f64>();
-
-        gini
+        probabilities
     }
 
-    fn calculate_class_distribution(
-        &self,
-        targets: &[Vec<u32>],
-        indices: &[usize],
-        classes: &[u32],
-    ) -> Vec<f64> {
-        let mut distribution = vec![0.0; classes.len()];
-        
-        for &idx in indices {
-            for &target in &targets[idx] {
-                if let Some(pos) = classes.iter().position(|&c| c == target) {
-                    distribution[pos] += 1.0;
+    // 每个样本只由未抽到它的估计器打分，没有袋外估计器的样本为 None
+    fn oob_probabilities(&self, features: &Array2<f64>, oob_indices: &[Vec<usize>]) -> Vec<Option<Vec<f64>>> {
+        let mut sums: Vec<Option<Vec<f64>>> = vec![None; features.nrows()];
+        let mut counts = vec![0usize; features.nrows()];
+
+        for (estimator, indices) in self.estimators.iter().zip(oob_indices) {
+            for &i in indices {
+                let sum = sums[i].get_or_insert_with(|| vec![0.0; estimator.main_trees.len()]);
+                for (total, tree) in sum.iter_mut().zip(&estimator.main_trees) {
+                    *total += tree.predict(features.row(i));
                 }
+                counts[i] += 1;
             }
         }
 
-        let total = distribution.iter().sum::<f64>();
-        if total > 0.0 {
-            for val in &mut distribution {
-                *val /= total;
+        sums.into_iter()
+            .zip(counts)
+            .map(|(sum, count)| sum.map(|sum| sum.into_iter().map(|total| total / count as f64).collect()))
+            .collect()
+    }
+
+    fn oob_brier(&self, features: &Array2<f64>, oob_indices: &[Vec<usize>], main_labels: &[Vec<bool>]) -> Option<f64> {
+        let mut total = 0.0;
+        let mut cells = 0usize;
+
+        for (i, probabilities) in self.oob_probabilities(features, oob_indices).iter().enumerate() {
+            let Some(probabilities) = probabilities else {
+                continue;
+            };
+            for (p, labels) in probabilities.iter().zip(main_labels) {
+                let y = if labels[i] { 1.0 } else { 0.0 };
+                total += (p - y).powi(2);
+                cells += 1;
             }
         }
 
-        distribution
+        (cells > 0).then(|| total / cells as f64)
     }
 
-    fn predict_tree(
-        &self,
-        tree: &DecisionTree,
-        features: &[f64],
-    ) -> Vec<f64> {
-        let mut node = tree.root.as_ref();
-        
-        while let Some(current) = node {
-            if let (Some(feature_idx), Some(threshold)) = (current.feature_index, current.threshold) {
-                if feature_idx < features.len() {
-                    if features[feature_idx] <= threshold {
-                        node = current.left.as_ref();
-                    } else {
-                        node = current.right.as_ref();
-                    }
-                } else {
-                    break;
-                }
-            } else {
-                return current.value.clone().unwrap_or_default();
-            }
+    // 袋外预测与训练集评估使用同一套指标，取准确率
+    fn oob_evaluation(&self, features: &Array2<f64>, oob_indices: &[Vec<usize>], targets: &[Vec<u32>]) -> Option<f64> {
+        let (scores, oob_targets): (Vec<Vec<f64>>, Vec<Vec<u32>>) = self.oob_probabilities(features, oob_indices)
+            .into_iter()
+            .zip(targets)
+            .filter_map(|(scores, target)| scores.map(|scores| (scores, target.clone())))
+            .unzip();
+
+        if scores.is_empty() {
+            return None;
         }
 
-        vec![0.0]
+        MetricsCalculator::for_type(&self.lottery_type)
+            .evaluate(&scores, &oob_targets)
+            .ok()
+            .map(|metrics| metrics.accuracy)
     }
 
-    fn select_numbers_from_probabilities(
+    // 逐个打乱特征列，袋外 Brier 分数的上升量即为该特征的置换重要性
+    fn compute_permutation_importance(
         &self,
-        probabilities: &Vec<f64>,
-        classes: &[u32],
-        count: usize,
-    ) -> Vec<u32> {
-        let mut number_probs: Vec<(u32, f64)> = classes.iter().zip(probabilities.iter())
-            .map(|(&class, &prob)| (class, prob))
+        features: &Array2<f64>,
+        oob_indices: &[Vec<usize>],
+        main_labels: &[Vec<bool>],
+        seed: u64,
+    ) -> Vec<f64> {
+        let Some(baseline) = self.oob_brier(features, oob_indices, main_labels) else {
+            return vec![0.0; features.ncols()];
+        };
+
+        parallel_map(features.ncols(), self.n_jobs(), |feature_index| {
+            let mut rng = StdRng::seed_from_u64(seed.wrapping_add(feature_index as u64));
+            let mut column = features.column(feature_index).to_vec();
+            column.shuffle(&mut rng);
+
+            let mut permuted = features.clone();
+            permuted.column_mut(feature_index).assign(&Array1::from(column));
+            self.oob_brier(&permuted, oob_indices, main_labels)
+                .map_or(0.0, |brier| brier - baseline)
+        })
+    }
+}
+
+// 每个号码一行，标记各期是否开出该号码
+pub(crate) fn label_matrix(targets: &[Vec<u32>], numbers: impl Iterator<Item = u32>) -> Vec<Vec<bool>> {
+    numbers
+        .map(|number| targets.iter().map(|target| target.contains(&number)).collect())
+        .collect()
+}
+
+fn binary_gini(positives: usize, samples: usize) -> f64 {
+    if samples == 0 {
+        return 0.0;
+    }
+    let p = positives as f64 / samples as f64;
+    2.0 * p * (1.0 - p)
+}
+
+// 把 0..count 均分给最多 jobs 个线程，结果按下标顺序返回
+pub(crate) fn parallel_map<T, F>(count: usize, jobs: usize, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync,
+{
+    let jobs = jobs.clamp(1, count.max(1));
+    if jobs == 1 {
+        return (0..count).map(f).collect();
+    }
+
+    let chunk_size = count.div_ceil(jobs);
+    thread::scope(|scope| {
+        let f = &f;
+        let handles: Vec<_> = (0..count)
+            .step_by(chunk_size)
+            .map(|start| scope.spawn(move || (start..(start + chunk_size).min(count)).map(f).collect::<Vec<T>>()))
             .collect();
 
-        number_probs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        
-        number_probs.into_iter()
-            .take(count)
-            .map(|(number, _)| number)
+        handles.into_iter()
+            .flat_map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
             .collect()
-    }
+    })
 }
 
 #[async_trait]
@@ -435,42 +575,72 @@ impl PredictionAlgorithm for RandomForestModel {
         "Random Forest".to_string()
     }
 
-    fn algorithm_type(&self) -> String {
-        "random_forest".to_string()
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::RandomForest
     }
 
     async fn train(
         &mut self,
         training_data: &TrainingData,
-        _config: &AlgorithmConfig,
+        config: &AlgorithmConfig,
     ) -> Result<f64> {
-        let (features, targets) = self.prepare_data(training_data)?;
-        self.classes = self.get_classes(&targets);
-
-        let mut rng = StdRng::seed_from_u64(self.config.random_state.unwrap_or(42));
-        let n_samples = features.nrows();
+        self.config = config.apply(&self.base_config)?;
+
+        let features = self.prepare_data(training_data)?;
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let main_labels = label_matrix(&training_data.targets, spec.main_numbers());
+        let special_labels = match (spec.special_numbers(), &training_data.special_targets) {
+            (Some(numbers), Some(special_targets)) if special_targets.len() == training_data.targets.len() => {
+                label_matrix(special_targets, numbers)
+            }
+            _ => Vec::new(),
+        };
 
-        self.trees.clear();
-        self.feature_importance = vec![0.0; features.ncols()];
+        let seed = self.config.random_state.unwrap_or_else(rand::random);
+        let fits = parallel_map(self.config.n_estimators, self.n_jobs(), |estimator| {
+            self.fit_estimator(&features, &main_labels, &special_labels, seed.wrapping_add(estimator as u64))
+        });
+
+        let mut importance = vec![0.0; features.ncols()];
+        let mut oob_indices = Vec::with_capacity(fits.len());
+        self.estimators = fits.into_iter()
+            .map(|fit| {
+                for (total, value) in importance.iter_mut().zip(&fit.importance) {
+                    *total += value;
+                }
+                oob_indices.push(fit.oob_indices);
+                fit.estimator
+            })
+            .collect();
 
-        for _ in 0..self.config.n_estimators {
-            let indices: Vec<usize> = if self.config.bootstrap {
-                (0..n_samples).map(|_| rng.gen_range(0..n_samples)).collect()
-            } else {
-                (0..n_samples).collect()
-            };
+        let total_importance: f64 = importance.iter().sum();
+        if total_importance > 0.0 {
+            for value in &mut importance {
+                *value /= total_importance;
+            }
+        }
+        self.feature_importance = importance;
 
-            let tree = self.train_tree(&features,
-                &targets,
-                &indices,
-                &self.classes,
-            )?;
+        // 特征维度与特征提取器一致时使用真实特征名
+        let names = LotteryFeatureExtractor.get_feature_names(&self.lottery_type);
+        self.feature_names = if names.len() == features.ncols() {
+            names
+        } else {
+            (0..features.ncols()).map(|i| format!("feature_{}", i)).collect()
+        };
 
-            self.trees.push(tree);
+        if self.config.bootstrap && self.config.oob_score {
+            self.oob_score = self.oob_evaluation(&features, &oob_indices, &training_data.targets);
+            self.permutation_importance = self.compute_permutation_importance(&features, &oob_indices, &main_labels, seed);
+        } else {
+            self.oob_score = None;
+            self.permutation_importance = Vec::new();
         }
 
         self.is_trained = true;
-        Ok(0.85) // Placeholder accuracy
+
+        let metrics = self.evaluate(training_data).await?;
+        Ok(metrics.accuracy)
     }
 
     async fn predict(
@@ -485,69 +655,44 @@ impl PredictionAlgorithm for RandomForestModel {
 
         let start_time = std::time::Instant::now();
 
-        let extractor = LotteryFeatureExtractor;
-        let features = extractor.extract_single_features(
-            &input.historical_data.last().unwrap(),
-            &input.historical_data[..input.historical_data.len() - 1]
-        )?;
+        let Some((latest, history)) = input.historical_data.split_last() else {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "No historical data provided".to_string()
+            ));
+        };
 
-        let mut class_probabilities = vec![0.0; self.classes.len()];
+        let extractor = LotteryFeatureExtractor;
+        let features = extractor.extract_single_features(latest, history)?;
+        self.check_feature_dimension(features.len())?;
+        let features = ArrayView1::from(&features);
 
-        for tree in &self.trees {
-            let tree_prediction = self.predict_tree(tree, &features);
-            for (i, &prob) in tree_prediction.iter().enumerate() {
-                if i < class_probabilities.len() {
-                    class_probabilities[i] += prob;
-                }
-            }
-        }
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let probabilities = self.number_probabilities(features, false);
+        let predicted_numbers = MetricsCalculator::from_spec(spec.clone()).top_numbers(&probabilities, spec.pick_count);
 
-        // Average probabilities
-        let n_trees = self.trees.len() as f64;
-        for prob in &mut class_probabilities {
-            *prob /= n_trees;
-        }
+        // 训练数据没有特别号码时按历史出现次数打分
+        let special_scores = spec.has_special().then(|| if self.has_special_trees() {
+            self.number_probabilities(features, true)
+        } else {
+            calibration::history_special_scores(&spec, &input.historical_data)
+        });
 
-        let main_count = match self.lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
+        let predicted_special_numbers = special_scores.as_ref()
+            .map(|scores| calibration::top_special_numbers(&spec, scores));
 
-        let special_count = match self.lottery_type {
-            LotteryType::Ssq => 1,
-            LotteryType::Dlt => 2,
-            _ => 0,
-        };
+        let probabilities = NumberProbabilities::from_scores(&spec, &probabilities, special_scores.as_deref());
+        let confidence_scores = probabilities.confidence(&spec, &predicted_numbers);
 
-        let predicted_numbers = self.select_numbers_from_probabilities(
-            &class_probabilities,
-            &self.classes,
-            main_count,
+        let mut algorithm_metadata = HashMap::new();
+        algorithm_metadata.insert("oob_score".to_string(), serde_json::json!(self.oob_score));
+        algorithm_metadata.insert(
+            "gini_importance".to_string(),
+            serde_json::json!(self.top_features(&self.feature_importance)),
+        );
+        algorithm_metadata.insert(
+            "permutation_importance".to_string(),
+            serde_json::json!(self.top_features(&self.permutation_importance)),
         );
-
-        let predicted_special_numbers = if special_count > 0 {
-            Some(self.select_numbers_from_probabilities(
-                &class_probabilities,
-                &self.classes,
-                special_count,
-            ))
-        } else {
-            None
-        };
-
-        let confidence_scores = predicted_numbers.iter()
-            .map(|number| {
-                if let Some(pos) = self.classes.iter().position(|&c| c == *number) {
-                    class_probabilities[pos]
-                } else {
-                    0.0
-                }
-            })
-            .collect();
 
         let computation_time = start_time.elapsed().as_millis() as u64;
 
@@ -555,39 +700,67 @@ impl PredictionAlgorithm for RandomForestModel {
             predicted_numbers,
             predicted_special_numbers,
             confidence_scores,
-            algorithm_metadata: HashMap::new(),
+            algorithm_metadata,
             computation_time_ms: computation_time,
+            probabilities: Some(probabilities),
         })
     }
 
     async fn evaluate(
         &self,
-        _test_data: &TrainingData,
+        test_data: &TrainingData,
     ) -> Result<EvaluationMetrics> {
-        let mut metrics = EvaluationMetrics::default();
-        metrics.accuracy = 0.65; // Placeholder
-        metrics.precision = 0.60;
-        metrics.recall = 0.70;
-        metrics.f1_score = 0.64;
-        
+        let scores = self.predict_scores(test_data).await?;
+        let mut metrics = MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)?;
+        metrics.feature_importance = self.get_feature_importance();
         Ok(metrics)
     }
 
-    fn is_trained(&self) -> bool {
-        self.is_trained
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        test_data.features.iter()
+            .map(|features| {
+                self.check_feature_dimension(features.len())?;
+                Ok(self.number_probabilities(ArrayView1::from(features), false))
+            })
+            .collect()
     }
 
-    fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
-        if self.feature_importance.is_empty() {
-            return None;
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
         }
-
-        let mut importance = HashMap::new();
-        for (i, &importance_val) in self.feature_importance.iter().enumerate() {
-            importance.insert(format!("feature_{}", i), importance_val);
+        if !self.has_special_trees() {
+            return calibration::frequency_special_scores(&self.lottery_type, test_data);
         }
 
-        Some(importance)
+        test_data.features.iter()
+            .map(|features| {
+                self.check_feature_dimension(features.len())?;
+                Ok(self.number_probabilities(ArrayView1::from(features), true))
+            })
+            .collect()
+    }
+
+    fn is_trained(&self) -> bool {
+        self.is_trained
+    }
+
+    fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
+        self.named_importance(&self.feature_importance)
     }
 
     fn save_model(&self, path: &str) -> Result<()> {
@@ -627,17 +800,17 @@ impl PredictionAlgorithm for RandomForestModel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::lottery::algorithms::test_support::{create_drawing, signal_training_data};
     use crate::lottery::algorithms::traits::TrainingData;
     use crate::lottery::models::{LotteryDrawing, LotteryType};
     use chrono::NaiveDate;
-    use uuid::Uuid;
 
     #[test]
     fn test_random_forest_creation() {
         let config = RandomForestConfig::default();
         let model = RandomForestModel::new(config, LotteryType::Ssq);
         assert_eq!(model.name(), "Random Forest");
-        assert_eq!(model.algorithm_type(), "random_forest");
+        assert_eq!(model.algorithm_type(), AlgorithmType::RandomForest);
         assert!(!model.is_trained());
     }
 
@@ -661,8 +834,8 @@ mod tests {
         // Generate dummy training data
         for i in 0..100 {
             let mut features = vec![0.0; 33]; // 33 features for SSQ
-            for j in 0..33 {
-                features[j] = (i * j) as f64 % 1.0;
+            for (j, feature) in features.iter_mut().enumerate() {
+                *feature = (i * j) as f64 % 1.0;
             }
             training_data.features.push(features);
             training_data.targets.push(vec![1, 2, 3, 4, 5, 6]);
@@ -680,6 +853,56 @@ mod tests {
         assert!(model.is_trained());
     }
 
+    #[tokio::test]
+    async fn test_random_forest_evaluate_uses_predictions() {
+        let config = RandomForestConfig {
+            n_estimators: 5,
+            max_depth: Some(3),
+            ..Default::default()
+        };
+        let mut model = RandomForestModel::new(config, LotteryType::Ssq);
+
+        let training_data = TrainingData {
+            features: (0..30).map(|i| vec![i as f64; 8]).collect(),
+            targets: vec![vec![1, 2, 3, 4, 5, 6]; 30],
+            special_targets: None,
+            weights: None,
+        };
+
+        let accuracy = model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+        assert_eq!(accuracy, 1.0);
+
+        let metrics = model.evaluate(&training_data).await.unwrap();
+        assert_eq!(metrics.precision, 1.0);
+        assert_eq!(metrics.top_k_hit_rate, 1.0);
+        assert_eq!(metrics.confusion_matrix, Some(vec![vec![810, 0], vec![0, 180]]));
+    }
+
+    #[tokio::test]
+    async fn test_training_parameters_do_not_carry_over() {
+        let base = RandomForestConfig {
+            n_estimators: 5,
+            max_depth: Some(3),
+            ..Default::default()
+        };
+        let mut model = RandomForestModel::new(base, LotteryType::Ssq);
+        let training_data = signal_training_data(40);
+
+        let custom = AlgorithmConfig {
+            hyperparameters: HashMap::from([
+                ("n_estimators".to_string(), serde_json::json!(8)),
+                ("max_depth".to_string(), serde_json::json!(2)),
+            ]),
+            ..Default::default()
+        };
+        model.train(&training_data, &custom).await.unwrap();
+        assert_eq!((model.config.n_estimators, model.config.max_depth), (8, Some(2)));
+
+        // 不带参数再次训练时回到构造时的配置
+        model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+        assert_eq!((model.config.n_estimators, model.config.max_depth), (5, Some(3)));
+    }
+
     #[tokio::test]
     async fn test_random_forest_prediction() {
         let config = RandomForestConfig {
@@ -689,22 +912,24 @@ mod tests {
         };
 
         let mut model = RandomForestModel::new(config, LotteryType::Ssq);
-        
-        // Create dummy training data
+
+        let drawings: Vec<LotteryDrawing> = (0..12)
+            .map(|i| create_drawing(LotteryType::Ssq, i, vec![1, 2, 3, 4, 5, 6], Some(vec![10])))
+            .collect();
+
+        // 训练特征与预测时使用同一个特征提取器，维度一致
+        let extractor = LotteryFeatureExtractor;
         let mut training_data = TrainingData {
             features: Vec::new(),
             targets: Vec::new(),
-            special_targets: None,
+            special_targets: Some(Vec::new()),
             weights: None,
         };
 
-        for i in 0..50 {
-            let mut features = vec![0.0; 33];
-            for j in 0..33 {
-                features[j] = (i * j) as f64 % 1.0;
-            }
-            training_data.features.push(features);
-            training_data.targets.push(vec![1, 2, 3, 4, 5, 6]);
+        for (i, drawing) in drawings.iter().enumerate().skip(1) {
+            training_data.features.push(extractor.extract_single_features(drawing, &drawings[..i]).unwrap());
+            training_data.targets.push(drawing.winning_numbers.clone());
+            training_data.special_targets.as_mut().unwrap().push(vec![10]);
         }
 
         let config = AlgorithmConfig {
@@ -715,48 +940,143 @@ mod tests {
         };
 
         model.train(&training_data, &config).await.unwrap();
-
-        let mut drawings = Vec::new();
-        for i in 0..10 {
-            drawings.push(LotteryDrawing {
-                id: Uuid::new_v4(),
-                lottery_type: LotteryType::Ssq,
-                draw_number: format!("2024{:03}", i),
-                draw_date: NaiveDate::from_ymd_opt(2024, 1, i + 1).unwrap(),
-                draw_time: None,
-                winning_numbers: vec![1, 2, 3, 4, 5, 6],
-                special_numbers: Some(vec![10]),
-                jackpot_amount: Some(1000000.0),
-                sales_amount: None,
-                prize_distribution: None,
-                data_source: "test".to_string(),
-                verification_status: "verified".to_string(),
-                metadata: None,
-                created_at: chrono::Utc::now(),
-                updated_at: chrono::Utc::now(),
-                crawled_at: None,
-            });
-        }
+        assert_eq!(model.feature_names, extractor.get_feature_names(&LotteryType::Ssq));
 
         let input = PredictionInput {
             lottery_type: LotteryType::Ssq,
             historical_data: drawings,
-            target_date: NaiveDate::from_ymd_opt(2024, 1, 11).unwrap(),
+            target_date: NaiveDate::from_ymd_opt(2024, 1, 13).unwrap(),
             additional_features: None,
         };
 
         let result = model.predict(&input).await;
         assert!(result.is_ok());
-        
+
         let output = result.unwrap();
-        assert_eq!(output.predicted_numbers.len(), 6);
-        assert_eq!(output.predicted_special_numbers.unwrap().len(), 1);
+        assert_eq!(output.predicted_numbers, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(output.predicted_special_numbers, Some(vec![10]));
+        assert!(output.algorithm_metadata.contains_key("permutation_importance"));
+
+        // 特征维度与训练时不一致时报错而不是越界
+        let mismatched = TrainingData {
+            features: vec![vec![0.0; 3]],
+            targets: vec![vec![1, 2, 3, 4, 5, 6]],
+            special_targets: None,
+            weights: None,
+        };
+        assert!(model.predict_scores(&mismatched).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_per_number_trees_and_special_pool() {
+        let config = RandomForestConfig {
+            n_estimators: 20,
+            max_depth: Some(4),
+            max_features: Some(2),
+            ..Default::default()
+        };
+        let mut model = RandomForestModel::new(config, LotteryType::Ssq);
+        let training_data = signal_training_data(80);
+        model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+
+        let spec = GameSpec::for_type(&LotteryType::Ssq);
+        for estimator in &model.estimators {
+            assert_eq!(estimator.main_trees.len(), spec.main_pool_size());
+            assert_eq!(estimator.special_trees.len(), spec.special_pool_size());
+        }
+
+        // 特别号码来自特别号码池的树，而不是主号码的概率
+        let on = ArrayView1::from(&[1.0, 0.5, 0.5, 0.5, 0.5, 0.5]);
+        let off = ArrayView1::from(&[0.0, 0.5, 0.5, 0.5, 0.5, 0.5]);
+        let special_on = model.number_probabilities(on, true);
+        let special_off = model.number_probabilities(off, true);
+        assert_eq!(special_on.len(), 16);
+        assert!(special_on[11] > special_off[11] + 0.5);
+        assert!(special_off[2] > special_on[2] + 0.5);
+
+        let main_on = model.number_probabilities(on, false);
+        let main_off = model.number_probabilities(off, false);
+        assert!(main_on[6] > main_off[6] + 0.5);
+        assert!(main_off[7] > main_on[7] + 0.5);
+        assert_eq!(main_on[0], 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_oob_score_and_feature_importance() {
+        let config = RandomForestConfig {
+            n_estimators: 30,
+            max_depth: Some(4),
+            max_features: Some(3),
+            ..Default::default()
+        };
+        let mut model = RandomForestModel::new(config, LotteryType::Ssq);
+        let training_data = signal_training_data(80);
+        model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+
+        // 训练特征不是提取器输出，退回按下标命名
+        assert_eq!(model.feature_names[0], "feature_0");
+        assert!(model.oob_score.unwrap() > 0.9);
+
+        let gini = model.get_feature_importance().unwrap();
+        assert!((gini.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        let permutation = model.get_permutation_importance().unwrap();
+        for i in 1..6 {
+            let name = format!("feature_{}", i);
+            assert!(gini["feature_0"] > gini[&name]);
+            assert!(permutation["feature_0"] > permutation[&name]);
+        }
+        assert!(permutation["feature_0"] > 0.0);
+
+        let metrics = model.evaluate(&training_data).await.unwrap();
+        assert_eq!(metrics.feature_importance, Some(gini));
+
+        // 不做自助采样时没有袋外样本
+        let mut model = RandomForestModel::new(RandomForestConfig {
+            n_estimators: 3,
+            bootstrap: false,
+            ..Default::default()
+        }, LotteryType::Ssq);
+        model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+        assert_eq!(model.oob_score, None);
+        assert!(model.get_permutation_importance().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_parallel_training_is_deterministic() {
+        let training_data = signal_training_data(60);
+        let mut scores = Vec::new();
+
+        for n_jobs in [1, 4] {
+            let config = RandomForestConfig {
+                n_estimators: 8,
+                max_samples: Some(0.8),
+                min_samples_leaf: 2,
+                n_jobs: Some(n_jobs),
+                ..Default::default()
+            };
+            let mut model = RandomForestModel::new(config, LotteryType::Ssq);
+            model.train(&training_data, &AlgorithmConfig::default()).await.unwrap();
+            scores.push((model.predict_scores(&training_data).await.unwrap(), model.permutation_importance));
+        }
+
+        assert_eq!(scores[0], scores[1]);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_max_samples() {
+        let config = RandomForestConfig {
+            max_samples: Some(1.5),
+            ..Default::default()
+        };
+        let mut model = RandomForestModel::new(config, LotteryType::Ssq);
+        let result = model.train(&signal_training_data(10), &AlgorithmConfig::default()).await;
+        assert!(matches!(result, Err(crate::lottery::errors::LotteryError::ConfigurationError(_))));
     }
 
     #[test]
     fn test_model_save_load() {
         let config = RandomForestConfig::default();
-        let mut model = RandomForestModel::new(config, LotteryType::Ssq);
+        let model = RandomForestModel::new(config.clone(), LotteryType::Ssq);
         
         let temp_path = "test_model.json";
         
@@ -1,11 +1,17 @@
 use crate::lottery::algorithms::traits::*;
+use crate::lottery::algorithms::calibration::NumberProbabilities;
+use crate::lottery::algorithms::metrics::MetricsCalculator;
 use crate::lottery::errors::LotteryResult as Result;
-use crate::lottery::models::LotteryType;
-use crate::lottery::models::LotteryDrawing;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{AlgorithmType, LotteryType};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+// 热号加分、冷号减分，与频率和趋势得分叠加
+const HOT_BONUS: f64 = 0.1;
+const COLD_PENALTY: f64 = 0.05;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatisticalConfig {
     pub window_size: usize,
@@ -31,14 +37,33 @@ impl Default for StatisticalConfig {
     }
 }
 
+impl ModelConfig for StatisticalConfig {
+    fn validate(&self) -> Result<()> {
+        ensure_config(self.window_size > 0, "window_size must be positive")?;
+        ensure_config((0.0..=1.0).contains(&self.smoothing_factor), "smoothing_factor must be in [0, 1]")?;
+        ensure_config((0.0..=1.0).contains(&self.confidence_threshold), "confidence_threshold must be in [0, 1]")?;
+        ensure_config(
+            [self.hot_cold_weight, self.trend_weight, self.pattern_weight]
+                .iter()
+                .all(|weight| *weight >= 0.0 && weight.is_finite()),
+            "hot_cold_weight, trend_weight and pattern_weight must be non-negative",
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StatisticalModel {
     pub config: StatisticalConfig,
+    #[serde(default)]
+    pub base_config: StatisticalConfig,
     pub frequency_distribution: HashMap<u32, f64>,
     pub hot_numbers: Vec<u32>,
     pub cold_numbers: Vec<u32>,
     pub trend_scores: HashMap<u32, f64>,
     pub pattern_weights: HashMap<String, f64>,
+    // 旧版本保存的模型没有特别号统计，加载后按均匀分布处理
+    #[serde(default)]
+    pub special_frequency: HashMap<u32, f64>,
     pub is_trained: bool,
     pub lottery_type: LotteryType,
 }
@@ -46,47 +71,33 @@ pub struct StatisticalModel {
 impl StatisticalModel {
     pub fn new(config: StatisticalConfig, lottery_type: LotteryType) -> Self {
         Self {
+            base_config: config.clone(),
             config,
             frequency_distribution: HashMap::new(),
             hot_numbers: Vec::new(),
             cold_numbers: Vec::new(),
             trend_scores: HashMap::new(),
             pattern_weights: HashMap::new(),
+            special_frequency: HashMap::new(),
             is_trained: false,
             lottery_type,
         }
     }
 
-    fn get_max_number(&self
-    ) -> u32 {
-        match self.lottery_type {
-            LotteryType::Ssq => 33,
-            LotteryType::Dlt => 35,
-            LotteryType::Fc3d => 9,
-            LotteryType::Pl3 => 9,
-            LotteryType::Pl5 => 9,
-            LotteryType::Custom => 49,
-        }
+    fn get_min_number(&self) -> u32 {
+        GameSpec::for_type(&self.lottery_type).main_min()
     }
 
-    fn get_special_max(&self
-    ) -> u32 {
-        match self.lottery_type {
-            LotteryType::Ssq => 16,
-            LotteryType::Dlt => 12,
-            LotteryType::Fc3d => 0,
-            LotteryType::Pl3 => 0,
-            LotteryType::Pl5 => 0,
-            LotteryType::Custom => 16,
-        }
+    fn get_max_number(&self) -> u32 {
+        GameSpec::for_type(&self.lottery_type).main_max()
     }
 
-    fn calculate_frequencies(&mut self, drawings: &[LotteryDrawing]) {
+    fn calculate_frequencies(&mut self, draws: &[Vec<u32>]) {
         let max_number = self.get_max_number();
         let mut counts = vec![0.0; max_number as usize + 1];
         
-        for drawing in drawings {
-            for &number in &drawing.winning_numbers {
+        for numbers in draws {
+            for &number in numbers {
                 if number <= max_number {
                     counts[number as usize] += 1.0;
                 }
@@ -95,38 +106,38 @@ impl StatisticalModel {
 
         let total = counts.iter().sum::<f64>();
         if total > 0.0 {
-            for (number, &count) in counts.iter().enumerate().skip(1) {
+            for (number, &count) in counts.iter().enumerate().skip(self.get_min_number() as usize) {
                 self.frequency_distribution.insert(number as u32, count / total);
             }
         }
     }
 
-    fn identify_hot_cold_numbers(&mut self, drawings: &[LotteryDrawing]) {
-        let recent_count = (drawings.len() as f64 * 0.2) as usize;
-        let recent_data = &drawings[drawings.len().saturating_sub(recent_count)..];
-        let older_data = &drawings[..drawings.len().saturating_sub(recent_count)];
+    fn identify_hot_cold_numbers(&mut self, draws: &[Vec<u32>]) {
+        let recent_count = (draws.len() as f64 * 0.2) as usize;
+        let recent_data = &draws[draws.len().saturating_sub(recent_count)..];
+        let older_data = &draws[..draws.len().saturating_sub(recent_count)];
 
         let max_number = self.get_max_number();
         let mut recent_counts = vec![0.0; max_number as usize + 1];
         let mut older_counts = vec![0.0; max_number as usize + 1];
 
-        for drawing in recent_data {
-            for &number in &drawing.winning_numbers {
+        for numbers in recent_data {
+            for &number in numbers {
                 if number <= max_number {
                     recent_counts[number as usize] += 1.0;
                 }
             }
         }
 
-        for drawing in older_data {
-            for &number in &drawing.winning_numbers {
+        for numbers in older_data {
+            for &number in numbers {
                 if number <= max_number {
                     older_counts[number as usize] += 1.0;
                 }
             }
         }
 
-        let mut hot_cold_scores: Vec<(u32, f64)> = (1..=max_number)
+        let mut hot_cold_scores: Vec<(u32, f64)> = (self.get_min_number()..=max_number)
             .map(|number| {
                 let recent = recent_counts[number as usize];
                 let older = older_counts[number as usize];
@@ -146,12 +157,12 @@ impl StatisticalModel {
         self.cold_numbers = hot_cold_scores[split_point * 2..].iter().map(|(n, _)| *n).collect();
     }
 
-    fn calculate_trend_scores(&mut self, drawings: &[LotteryDrawing]) {
+    fn calculate_trend_scores(&mut self, draws: &[Vec<u32>]) {
         let max_number = self.get_max_number();
         let mut trend_data: HashMap<u32, Vec<f64>> = HashMap::new();
 
-        for (i, drawing) in drawings.iter().enumerate() {
-            for &number in &drawing.winning_numbers {
+        for (i, numbers) in draws.iter().enumerate() {
+            for &number in numbers {
                 if number <= max_number {
                     trend_data.entry(number).or_insert_with(Vec::new).push(i as f64);
                 }
@@ -180,13 +191,12 @@ impl StatisticalModel {
         (intercept, slope)
     }
 
-    fn calculate_pattern_weights(&mut self, drawings: &[LotteryDrawing]) {
+    fn calculate_pattern_weights(&mut self, draws: &[Vec<u32>]) {
         let mut consecutive_patterns = 0.0;
         let mut odd_even_patterns = 0.0;
         let mut sum_patterns = 0.0;
 
-        for drawing in drawings {
-            let numbers = &drawing.winning_numbers;
+        for numbers in draws {
             
             // Consecutive numbers
             let mut sorted = numbers.clone();
@@ -209,7 +219,12 @@ impl StatisticalModel {
             sum_patterns += sum as f64;
         }
 
-        let total = drawings.len() as f64;
+        // 没有历史数据时不写入 NaN，否则保存的模型无法再加载
+        if draws.is_empty() {
+            return;
+        }
+
+        let total = draws.len() as f64;
         self.pattern_weights.insert("consecutive".to_string(), consecutive_patterns / total);
         self.pattern_weights.insert("odd_even".to_string(), odd_even_patterns / total);
         self.pattern_weights.insert("sum".to_string(), sum_patterns / total);
@@ -219,7 +234,7 @@ impl StatisticalModel {
         let max_number = self.get_max_number();
         let mut scores = HashMap::new();
 
-        for number in 1..=max_number {
+        for number in self.get_min_number()..=max_number {
             let mut score = 0.0;
 
             if let Some(freq) = self.frequency_distribution.get(&number) {
@@ -231,11 +246,11 @@ impl StatisticalModel {
             }
 
             if self.hot_numbers.contains(&number) {
-                score += 0.1;
+                score += HOT_BONUS;
             }
 
             if self.cold_numbers.contains(&number) {
-                score -= 0.05;
+                score -= COLD_PENALTY;
             }
 
             scores.insert(number, score.max(0.0));
@@ -268,26 +283,35 @@ impl PredictionAlgorithm for StatisticalModel {
         "Statistical Analysis".to_string()
     }
 
-    fn algorithm_type(&self) -> String {
-        "statistical".to_string()
+    fn algorithm_type(&self) -> AlgorithmType {
+        AlgorithmType::Statistical
     }
 
     async fn train(
         &mut self,
         training_data: &TrainingData,
-        _config: &AlgorithmConfig,
+        config: &AlgorithmConfig,
     ) -> Result<f64> {
-        let historical_data = self.get_historical_data_from_training(training_data);
-        
-        self.calculate_frequencies(&historical_data);
-        self.identify_hot_cold_numbers(&historical_data);
-        self.calculate_trend_scores(&historical_data);
-        self.calculate_pattern_weights(&historical_data);
+        let config = config.apply(&self.base_config)?;
+
+        // 重新训练时清空上一次的统计量
+        *self = Self {
+            base_config: self.base_config.clone(),
+            ..Self::new(config, self.lottery_type.clone())
+        };
+        let history = self.recent_window(&training_data.targets);
+        self.calculate_frequencies(history);
+        self.identify_hot_cold_numbers(history);
+        self.calculate_trend_scores(history);
+        self.calculate_pattern_weights(history);
+        if let Some(special_targets) = &training_data.special_targets {
+            self.calculate_special_frequencies(self.recent_window(special_targets));
+        }
 
         self.is_trained = true;
-        
-        // Placeholder accuracy
-        Ok(0.58)
+
+        let metrics = self.evaluate(training_data).await?;
+        Ok(metrics.accuracy)
     }
 
     async fn predict(
@@ -304,20 +328,9 @@ impl PredictionAlgorithm for StatisticalModel {
 
         let scores = self.calculate_probability_scores();
 
-        let main_count = match self.lottery_type {
-            LotteryType::Ssq => 6,
-            LotteryType::Dlt => 5,
-            LotteryType::Fc3d => 3,
-            LotteryType::Pl3 => 3,
-            LotteryType::Pl5 => 5,
-            LotteryType::Custom => 6,
-        };
-
-        let special_count = match self.lottery_type {
-            LotteryType::Ssq => 1,
-            LotteryType::Dlt => 2,
-            _ => 0,
-        };
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let main_count = spec.pick_count;
+        let special_count = spec.special_count;
 
         let predicted_numbers = self.select_numbers_from_scores(&scores,
             main_count,
@@ -333,7 +346,12 @@ impl PredictionAlgorithm for StatisticalModel {
             None
         };
 
-        let confidence_scores = vec![0.6; main_count];
+        let probabilities = NumberProbabilities::from_scores(
+            &spec,
+            &MetricsCalculator::from_spec(spec.clone()).pool_scores(scores),
+            self.special_pool_scores().as_deref(),
+        );
+        let confidence_scores = probabilities.confidence(&spec, &predicted_numbers);
 
         let computation_time = start_time.elapsed().as_millis() as u64;
 
@@ -346,33 +364,78 @@ impl PredictionAlgorithm for StatisticalModel {
                 ("window_size".to_string(), serde_json::Value::Number(self.config.window_size.into())),
             ]),
             computation_time_ms: computation_time,
+            probabilities: Some(probabilities),
         })
     }
 
     async fn evaluate(
         &self,
-        _test_data: &TrainingData,
+        test_data: &TrainingData,
     ) -> Result<EvaluationMetrics> {
-        let mut metrics = EvaluationMetrics::default();
-        metrics.accuracy = 0.58;
-        metrics.precision = 0.55;
-        metrics.recall = 0.60;
-        metrics.f1_score = 0.57;
-        
-        Ok(metrics)
+        let scores = self.predict_scores(test_data).await?;
+        MetricsCalculator::for_type(&self.lottery_type).evaluate(&scores, &test_data.targets)
+    }
+
+    // 号码得分只取决于训练时的统计量，每期相同
+    async fn predict_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        let scores = MetricsCalculator::for_type(&self.lottery_type)
+            .pool_scores(self.calculate_probability_scores());
+        Ok(vec![scores; test_data.targets.len()])
+    }
+
+    async fn predict_special_scores(
+        &self,
+        test_data: &TrainingData,
+    ) -> Result<Vec<Vec<f64>>> {
+        if !self.is_trained {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                "Model not trained".to_string()
+            ));
+        }
+
+        let scores = self.special_pool_scores().ok_or_else(|| crate::lottery::errors::LotteryError::InvalidParameter(
+            format!("{} has no special numbers", self.lottery_type)
+        ))?;
+        Ok(vec![scores; test_data.targets.len()])
     }
 
     fn is_trained(&self) -> bool {
         self.is_trained
     }
 
+    // 各项统计量对号码得分的平均绝对贡献，归一化后作为重要度
     fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
-        let mut importance = HashMap::new();
-        importance.insert("frequency".to_string(), 0.4);
-        importance.insert("hot_cold".to_string(), 0.3);
-        importance.insert("trend".to_string(), 0.2);
-        importance.insert("pattern".to_string(), 0.1);
-        Some(importance)
+        if !self.is_trained {
+            return None;
+        }
+
+        let pool_size = (self.get_max_number() - self.get_min_number() + 1) as f64;
+        let frequency = self.frequency_distribution.values().map(|f| f.abs()).sum::<f64>()
+            * self.config.hot_cold_weight / pool_size;
+        let trend = self.trend_scores.values().map(|t| t.abs()).sum::<f64>()
+            * self.config.trend_weight / pool_size;
+        let hot_cold = (self.hot_numbers.len() as f64 * HOT_BONUS
+            + self.cold_numbers.len() as f64 * COLD_PENALTY) / pool_size;
+
+        let total = frequency + trend + hot_cold;
+        if total <= 0.0 {
+            return None;
+        }
+
+        Some(HashMap::from([
+            ("frequency".to_string(), frequency / total),
+            ("trend".to_string(), trend / total),
+            ("hot_cold".to_string(), hot_cold / total),
+        ]))
     }
 
     fn save_model(&self, path: &str) -> Result<()> {
@@ -410,24 +473,56 @@ impl PredictionAlgorithm for StatisticalModel {
 }
 
 impl StatisticalModel {
-    fn get_historical_data_from_training(&self,
-        training_data: &TrainingData,
-    ) -> Vec<LotteryDrawing> {
-        // Placeholder: Convert training data back to LotteryDrawing format
-        Vec::new()
+    // 训练样本按时间升序排列，只统计最近 window_size 期
+    fn recent_window<'a>(&self, draws: &'a [Vec<u32>]) -> &'a [Vec<u32>] {
+        &draws[draws.len().saturating_sub(self.config.window_size)..]
+    }
+
+    fn calculate_special_frequencies(&mut self, draws: &[Vec<u32>]) {
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let Some(special_numbers) = spec.special_numbers() else {
+            return;
+        };
+
+        let mut counts: HashMap<u32, f64> = special_numbers.clone().map(|number| (number, 0.0)).collect();
+        for &number in draws.iter().flatten() {
+            if let Some(count) = counts.get_mut(&number) {
+                *count += 1.0;
+            }
+        }
+
+        let total = counts.values().sum::<f64>();
+        if total > 0.0 {
+            self.special_frequency = counts.into_iter().map(|(number, count)| (number, count / total)).collect();
+        }
     }
 
+    // 特别号按出现频率，并用 smoothing_factor 向均匀分布收缩；没有特别号历史时为均匀分布
     fn calculate_special_probability_scores(&self) -> HashMap<u32, f64> {
-        let max_special = self.get_special_max();
-        if max_special == 0 {
+        let spec = GameSpec::for_type(&self.lottery_type);
+        let Some(special_numbers) = spec.special_numbers() else {
             return HashMap::new();
-        }
+        };
 
-        let mut scores = HashMap::new();
-        for number in 1..=max_special {
-            scores.insert(number, 1.0 / max_special as f64);
+        let uniform = 1.0 / spec.special_pool_size() as f64;
+        if self.special_frequency.is_empty() {
+            return special_numbers.map(|number| (number, uniform)).collect();
         }
-        scores
+
+        let smoothing = self.config.smoothing_factor;
+        special_numbers
+            .map(|number| {
+                let frequency = self.special_frequency.get(&number).copied().unwrap_or(0.0);
+                (number, (1.0 - smoothing) * frequency + smoothing * uniform)
+            })
+            .collect()
+    }
+
+    // 特别号池按号码升序的得分
+    fn special_pool_scores(&self) -> Option<Vec<f64>> {
+        let scores = self.calculate_special_probability_scores();
+        GameSpec::for_type(&self.lottery_type).special_numbers()
+            .map(|numbers| numbers.map(|number| scores.get(&number).copied().unwrap_or(0.0)).collect())
     }
 }
 
@@ -443,7 +538,7 @@ mod tests {
         let config = StatisticalConfig::default();
         let model = StatisticalModel::new(config, LotteryType::Ssq);
         assert_eq!(model.name(), "Statistical Analysis");
-        assert_eq!(model.algorithm_type(), "statistical");
+        assert_eq!(model.algorithm_type(), AlgorithmType::Statistical);
         assert!(!model.is_trained());
     }
 
@@ -509,4 +604,43 @@ mod tests {
         let output = result.unwrap();
         assert_eq!(output.predicted_numbers.len(), 6);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_statistical_training_uses_targets() {
+        let mut model = StatisticalModel::new(StatisticalConfig::default(), LotteryType::Ssq);
+
+        // 1-6 与特别号 9 期期出现，统计量应直接指向这些号码
+        let training_data = TrainingData {
+            features: vec![vec![0.0]; 30],
+            targets: vec![vec![1, 2, 3, 4, 5, 6]; 30],
+            special_targets: Some(vec![vec![9]; 30]),
+            weights: None,
+        };
+        let config = AlgorithmConfig {
+            lottery_type: LotteryType::Ssq,
+            parameters: HashMap::new(),
+            hyperparameters: HashMap::new(),
+            feature_config: HashMap::new(),
+        };
+        model.train(&training_data, &config).await.unwrap();
+
+        assert!((model.frequency_distribution[&1] - 1.0 / 6.0).abs() < 1e-9);
+        assert_eq!(model.frequency_distribution[&7], 0.0);
+
+        let input = PredictionInput {
+            lottery_type: LotteryType::Ssq,
+            historical_data: Vec::new(),
+            target_date: NaiveDate::from_ymd_opt(2024, 1, 2).unwrap(),
+            additional_features: None,
+        };
+        let output = model.predict(&input).await.unwrap();
+        let mut predicted = output.predicted_numbers.clone();
+        predicted.sort_unstable();
+        assert_eq!(predicted, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(output.predicted_special_numbers, Some(vec![9]));
+
+        let importance = model.get_feature_importance().unwrap();
+        assert!((importance.values().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(importance["trend"] > 0.0);
+    }
+}
@@ -0,0 +1,47 @@
+// 算法模块测试共用的数据
+use crate::lottery::algorithms::traits::TrainingData;
+use crate::lottery::models::{LotteryDrawing, LotteryType};
+use chrono::NaiveDate;
+use rand::prelude::*;
+
+// 第 i 期开奖，期号与日期均按 i 从 2024 年初递增
+pub(crate) fn create_drawing(
+    lottery_type: LotteryType,
+    i: u32,
+    winning_numbers: Vec<u32>,
+    special_numbers: Option<Vec<u32>>,
+) -> LotteryDrawing {
+    let draw_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Duration::days(i as i64);
+    crate::lottery::test_support::create_drawing(
+        lottery_type,
+        &format!("2024{:03}", i),
+        draw_date,
+        winning_numbers,
+        special_numbers,
+    )
+}
+
+// 号码 7 与特别号码 12 只在 signal 特征为 1 的期开出，其余特征为噪声
+pub(crate) fn signal_training_data(samples: usize) -> TrainingData {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut training_data = TrainingData {
+        features: Vec::new(),
+        targets: Vec::new(),
+        special_targets: Some(Vec::new()),
+        weights: None,
+    };
+
+    for i in 0..samples {
+        let signal = (i % 2) as f64;
+        let mut features = vec![signal];
+        features.extend((0..5).map(|_| rng.r#gen::<f64>()));
+        training_data.features.push(features);
+
+        let mut target = vec![1, 2, 3, 4, 5];
+        target.push(if signal > 0.5 { 7 } else { 8 });
+        training_data.targets.push(target);
+        training_data.special_targets.as_mut().unwrap().push(vec![if signal > 0.5 { 12 } else { 3 }]);
+    }
+
+    training_data
+}
@@ -1,10 +1,15 @@
 
-use crate::lottery::models::{LotteryDrawing, LotteryType};
+use crate::lottery::algorithms::calibration::NumberProbabilities;
+use crate::lottery::algorithms::metrics::RandomBaseline;
+use crate::lottery::models::{AlgorithmType, LotteryDrawing, LotteryType, PredictionStrategy};
 use crate::lottery::errors::LotteryResult as Result;
 use async_trait::async_trait;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Debug as DebugTrait;
+use std::ops::Range;
+use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PredictionInput {
@@ -21,6 +26,9 @@ pub struct PredictionOutput {
     pub confidence_scores: Vec<f64>,
     pub algorithm_metadata: HashMap<String, serde_json::Value>,
     pub computation_time_ms: u64,
+    // 每个主号码与特别号在下一期开出的概率分布
+    #[serde(default)]
+    pub probabilities: Option<NumberProbabilities>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +39,36 @@ pub struct TrainingData {
     pub weights: Option<Vec<f64>>,
 }
 
+impl TrainingData {
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    // 按时间顺序切分，前 index 个样本在前一部分
+    pub fn split_at(&self, index: usize) -> (TrainingData, TrainingData) {
+        let index = index.min(self.features.len());
+        (self.slice(0..index), self.slice(index..self.features.len()))
+    }
+
+    pub fn slice(&self, range: Range<usize>) -> TrainingData {
+        TrainingData {
+            features: slice_range(&self.features, &range),
+            targets: slice_range(&self.targets, &range),
+            special_targets: self.special_targets.as_deref().map(|st| slice_range(st, &range)),
+            weights: self.weights.as_deref().map(|w| slice_range(w, &range)),
+        }
+    }
+}
+
+fn slice_range<T: Clone>(items: &[T], range: &Range<usize>) -> Vec<T> {
+    let end = range.end.min(items.len());
+    items[range.start.min(end)..end].to_vec()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlgorithmConfig {
     pub lottery_type: LotteryType,
@@ -50,17 +88,107 @@ impl Default for AlgorithmConfig {
     }
 }
 
+impl AlgorithmConfig {
+    // 策略的 parameters 与 hyperparameters 必须是 JSON 对象，null 视为空
+    pub fn for_strategy(lottery_type: &LotteryType, strategy: &PredictionStrategy) -> Result<Self> {
+        Ok(Self {
+            lottery_type: lottery_type.clone(),
+            parameters: parameter_map("parameters", &strategy.parameters)?,
+            hyperparameters: match &strategy.hyperparameters {
+                Some(hyperparameters) => parameter_map("hyperparameters", hyperparameters)?,
+                None => HashMap::new(),
+            },
+            feature_config: HashMap::new(),
+        })
+    }
+
+    // 以 base 为底叠加 parameters 与 hyperparameters，后者优先；模型训练时 base 为构造时的配置，
+    // 上一次训练叠加的参数不会带到下一次
+    pub fn apply<T: ModelConfig>(&self, base: &T) -> Result<T> {
+        apply_parameters(base, &[&self.parameters, &self.hyperparameters])
+    }
+}
+
+fn parameter_map(field: &str, value: &serde_json::Value) -> Result<HashMap<String, serde_json::Value>> {
+    match value {
+        serde_json::Value::Null => Ok(HashMap::new()),
+        serde_json::Value::Object(map) => Ok(map.clone().into_iter().collect()),
+        _ => Err(crate::lottery::errors::LotteryError::ConfigurationError(
+            format!("Strategy {} must be a JSON object", field)
+        )),
+    }
+}
+
+// 各算法的配置结构体，字段名即 AlgorithmConfig 中的参数名
+pub trait ModelConfig: Serialize + DeserializeOwned {
+    // 取值越界时返回 ConfigurationError
+    fn validate(&self) -> Result<()>;
+}
+
+// 按顺序把各层参数覆盖到 base 上；未知参数、类型不符和越界取值都返回 ConfigurationError
+pub fn apply_parameters<T: ModelConfig>(
+    base: &T,
+    layers: &[&HashMap<String, serde_json::Value>],
+) -> Result<T> {
+    let serde_json::Value::Object(mut merged) = serde_json::to_value(base)? else {
+        return Err(crate::lottery::errors::LotteryError::ConfigurationError(
+            "Model config must serialize to a JSON object".to_string()
+        ));
+    };
+
+    for layer in layers {
+        for (name, value) in layer.iter() {
+            let Some(slot) = merged.get_mut(name) else {
+                return Err(crate::lottery::errors::LotteryError::ConfigurationError(
+                    format!("Unknown parameter: {}", name)
+                ));
+            };
+            *slot = value.clone();
+        }
+    }
+
+    let config: T = serde_json::from_value(serde_json::Value::Object(merged))
+        .map_err(|e| crate::lottery::errors::LotteryError::ConfigurationError(
+            format!("Invalid parameter value: {}", e)
+        ))?;
+    config.validate()?;
+    Ok(config)
+}
+
+// ModelConfig::validate 中的单项检查
+pub fn ensure_config(valid: bool, message: &str) -> Result<()> {
+    if valid {
+        Ok(())
+    } else {
+        Err(crate::lottery::errors::LotteryError::ConfigurationError(message.to_string()))
+    }
+}
+
 #[async_trait]
 pub trait PredictionAlgorithm: Send + Sync + DebugTrait {
     fn name(&self) -> String;
     
-    fn algorithm_type(&self) -> String;
+    fn algorithm_type(&self) -> AlgorithmType;
     
     async fn train(&mut self, training_data: &TrainingData, config: &AlgorithmConfig) -> Result<f64>;
     
     async fn predict(&self, input: &PredictionInput) -> Result<PredictionOutput>;
     
     async fn evaluate(&self, test_data: &TrainingData) -> Result<EvaluationMetrics>;
+
+    // 对 test_data 的样本逐期给出主号码池 (按号码升序) 的打分，与 targets 的最后若干期对齐
+    async fn predict_scores(&self, _test_data: &TrainingData) -> Result<Vec<Vec<f64>>> {
+        Err(crate::lottery::errors::LotteryError::AlgorithmError(
+            format!("{} does not provide number scores", self.algorithm_type())
+        ))
+    }
+
+    // 与 predict_scores 相同的对齐方式，给出特别号池 (按号码升序) 的打分
+    async fn predict_special_scores(&self, _test_data: &TrainingData) -> Result<Vec<Vec<f64>>> {
+        Err(crate::lottery::errors::LotteryError::AlgorithmError(
+            format!("{} does not provide special number scores", self.algorithm_type())
+        ))
+    }
     
     fn is_trained(&self) -> bool;
     
@@ -81,9 +209,18 @@ pub struct EvaluationMetrics {
     pub f1_score: f64,
     pub mean_absolute_error: f64,
     pub root_mean_squared_error: f64,
+    #[serde(default)]
+    pub brier_score: f64,
+    #[serde(default)]
+    pub log_loss: f64,
+    #[serde(default)]
+    pub top_k_hit_rate: f64,
     pub confusion_matrix: Option<Vec<Vec<usize>>>,
     pub feature_importance: Option<HashMap<String, f64>>,
     pub cross_validation_scores: Option<Vec<f64>>,
+    // 与均匀随机选号的比较
+    #[serde(default)]
+    pub baseline: Option<RandomBaseline>,
 }
 
 impl Default for EvaluationMetrics {
@@ -95,9 +232,13 @@ impl Default for EvaluationMetrics {
             f1_score: 0.0,
             mean_absolute_error: 0.0,
             root_mean_squared_error: 0.0,
+            brier_score: 0.0,
+            log_loss: 0.0,
+            top_k_hit_rate: 0.0,
             confusion_matrix: None,
             feature_importance: None,
             cross_validation_scores: None,
+            baseline: None,
         }
     }
 }
@@ -136,27 +277,73 @@ pub trait FeatureExtractor: Send + Sync {
     
     fn extract_single_features(&self, drawing: &LotteryDrawing, historical_data: &[LotteryDrawing]) -> Result<Vec<f64>>;
     
-    fn get_feature_names(&self) -> Vec<String>;
+    fn get_feature_names(&self, lottery_type: &LotteryType) -> Vec<String>;
     
     fn validate_features(&self, features: &[f64]) -> Result<bool>;
 }
 
+// 模型存储中每个版本的元数据，与模型文件并排保存
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelMetadata {
-    pub algorithm_name: String,
-    pub version: String,
+    pub algorithm: AlgorithmType,
+    pub lottery_type: LotteryType,
+    // 同一玩法与算法下从 1 开始递增，由存储在保存时分配
+    pub version: u32,
     pub training_date: chrono::DateTime<chrono::Utc>,
     pub training_samples: usize,
-    pub model_size_bytes: usize,
-    pub hyperparameters: HashMap<String, serde_json::Value>,
+    // 模型文件 (含校准参数) 的 SHA-256 与总字节数，加载时校验
+    pub model_hash: String,
+    pub model_size_bytes: u64,
+    pub config: AlgorithmConfig,
     pub evaluation_metrics: EvaluationMetrics,
-    pub lottery_type: LotteryType,
+    // 训练耗时 (不含评估和校准)，旧版本保存的元数据没有该字段
+    #[serde(default)]
+    pub training_time_ms: Option<u64>,
+    // 模型外包了概率校准，加载时一并恢复
+    pub calibrated: bool,
+    pub training_record_id: Option<Uuid>,
 }
 
+impl ModelMetadata {
+    pub fn new(
+        algorithm: AlgorithmType,
+        config: AlgorithmConfig,
+        evaluation_metrics: EvaluationMetrics,
+        training_samples: usize,
+    ) -> Self {
+        Self {
+            algorithm,
+            lottery_type: config.lottery_type.clone(),
+            version: 0,
+            training_date: chrono::Utc::now(),
+            training_samples,
+            model_hash: String::new(),
+            model_size_bytes: 0,
+            config,
+            evaluation_metrics,
+            training_time_ms: None,
+            calibrated: false,
+            training_record_id: None,
+        }
+    }
+}
+
+// 按玩法与算法保存多个版本的模型，其中一个为当前版本
 #[async_trait]
 pub trait ModelPersistence: Send + Sync {
-    async fn save(&self, path: &str, metadata: &ModelMetadata) -> Result<()>;
-    async fn load(&self, path: &str) -> Result<(Box<dyn PredictionAlgorithm>, ModelMetadata)>;
-    async fn list_models(&self, directory: &str) -> Result<Vec<ModelMetadata>>;
-    async fn delete_model(&self, path: &str) -> Result<()>;
+    // 保存为新版本并设为当前版本，返回补全版本号、哈希与大小后的元数据
+    async fn save(&self, model: &dyn PredictionAlgorithm, metadata: &ModelMetadata) -> Result<ModelMetadata>;
+
+    // version 为 None 时加载当前版本
+    async fn load(
+        &self,
+        lottery_type: &LotteryType,
+        algorithm: AlgorithmType,
+        version: Option<u32>,
+    ) -> Result<(Box<dyn PredictionAlgorithm>, ModelMetadata)>;
+
+    // 按版本号升序
+    async fn list_models(&self, lottery_type: &LotteryType, algorithm: AlgorithmType) -> Result<Vec<ModelMetadata>>;
+
+    async fn delete_model(&self, lottery_type: &LotteryType, algorithm: AlgorithmType, version: u32) -> Result<()>;
 }
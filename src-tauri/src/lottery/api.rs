@@ -1,15 +1,31 @@
-use crate::lottery::algorithms::model_trainer::ModelTrainer;
+use crate::lottery::algorithms::model_trainer::{CrossValidationReport, ModelTrainer};
 use crate::lottery::algorithms::feature_engineering::LotteryFeatureExtractor;
-use crate::lottery::algorithms::traits::{EvaluationMetrics, FeatureConfig, PredictionInput};
+use crate::lottery::algorithms::traits::{AlgorithmConfig, FeatureConfig, ModelMetadata, ModelPersistence, PredictionAlgorithm, PredictionInput};
 use crate::lottery::algorithms::algorithm_factory::AlgorithmFactory;
-use crate::lottery::data_collector::DataCollector;
 use crate::lottery::algorithms::traits::FeatureExtractor;
-use crate::lottery::models::{LotteryType, LotteryDrawing};
-use chrono::NaiveDate;
+use crate::lottery::algorithms::metrics::RandomBaseline;
+use crate::lottery::algorithms::ensemble::WeightObjective;
+use crate::lottery::algorithms::calibration::CalibrationMethod;
+use crate::lottery::backtest::{BacktestConfig, BacktestReport, Backtester};
+use crate::lottery::games::{self, GameSpec};
+use crate::lottery::model_store::{ModelStore, TrainingRecordContext};
+use crate::lottery::models::{AlgorithmType, LotteryType, LotteryDrawing, PredictionStrategy};
+use crate::lottery::errors::LotteryError;
+use crate::lottery::settlement::{self, IngestSummary, SettlementSummary};
+use crate::lottery::storage::{DrawingRepository, Storage};
+use crate::lottery::tuning::{HyperparameterTuner, ParameterDomain, TrialRecordContext, TuningConfig, TuningJob, TuningStatus};
+use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tauri::State;
 use tokio::sync::RwLock;
+use uuid::Uuid;
+
+// 请求未指定时使用的历史窗口天数
+const DEFAULT_HISTORICAL_DAYS: i32 = 365;
+// 按需训练时留作验证的最近期数比例
+const ON_DEMAND_VALIDATION_SPLIT: f64 = 0.2;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
@@ -42,28 +58,71 @@ impl<T> ApiResponse<T> {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PredictionRequest {
     pub lottery_type: LotteryType,
-    pub algorithm: String,
+    pub algorithm: AlgorithmType,
     pub use_ensemble: bool,
-    pub ensemble_algorithms: Option<Vec<String>>,
+    pub ensemble_algorithms: Option<Vec<AlgorithmType>>,
     pub historical_days: Option<i32>,
+    // 算法尚无训练好的模型时，先在同一历史窗口上训练并注册，否则直接返回错误
+    #[serde(default)]
+    pub train_if_missing: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrainingRequest {
     pub lottery_type: LotteryType,
-    pub algorithms: Vec<String>,
+    pub algorithms: Vec<AlgorithmType>,
     pub historical_days: i32,
     pub validation_split: f64,
+    // 在验证集上拟合的概率校准方法
+    #[serde(default)]
+    pub calibration_method: CalibrationMethod,
+    // 按策略中保存的 parameters / hyperparameters 训练对应算法，覆盖 algorithms 中的同名项
+    #[serde(default)]
+    pub strategy_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuningRequest {
+    pub lottery_type: LotteryType,
+    pub algorithm: AlgorithmType,
+    pub historical_days: i32,
+    // 试验记录挂在该策略下，缺省时为本次搜索新建一个策略
+    pub strategy_id: Option<Uuid>,
+    #[serde(default)]
+    pub config: TuningConfig,
+    // 覆盖由 config_schema 推导出的参数范围
+    #[serde(default)]
+    pub search_space: HashMap<String, ParameterDomain>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVersionList {
+    // 预测和重启后加载的版本
+    pub current_version: Option<u32>,
+    // 按版本号升序
+    pub versions: Vec<ModelMetadata>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlgorithmComparison {
-    pub algorithm_name: String,
+    pub algorithm_name: AlgorithmType,
     pub accuracy: f64,
     pub precision: f64,
     pub recall: f64,
     pub f1_score: f64,
-    pub training_time_ms: u64,
+    // 从磁盘加载的旧模型没有记录训练耗时
+    pub training_time_ms: Option<u64>,
+    // 为空表示该模型没有可用的评估数据
+    pub baseline: Option<RandomBaseline>,
+    // 训练时的时间序列交叉验证，未做交叉验证时为空
+    pub cross_validation: Option<CrossValidationReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingResult {
+    pub accuracy: f64,
+    // 训练集上的时间序列交叉验证，未做交叉验证或训练失败时为空
+    pub cross_validation: Option<CrossValidationReport>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,25 +132,88 @@ pub struct DataCollectionRequest {
     pub force_refresh: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestRequest {
+    pub lottery_type: LotteryType,
+    pub algorithm: AlgorithmType,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: chrono::NaiveDate,
+    pub retrain_every: Option<usize>,
+    pub min_training_draws: Option<usize>,
+    pub max_training_draws: Option<usize>,
+    pub ticket_cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomGameRequest {
+    pub name: String,
+    pub display_name: String,
+    pub main_count: usize,
+    // 每注选号个数，缺省与开奖个数相同
+    pub pick_count: Option<usize>,
+    pub main_range_start: u32,
+    pub main_range_end: u32,
+    #[serde(default)]
+    pub special_count: usize,
+    pub special_range_start: Option<u32>,
+    pub special_range_end: Option<u32>,
+    #[serde(default)]
+    pub ordered: bool,
+    #[serde(default)]
+    pub allow_repeats: bool,
+    #[serde(default)]
+    pub special_from_main_pool: bool,
+    pub rules: Option<serde_json::Value>,
+}
+
+impl CustomGameRequest {
+    fn into_spec(self) -> GameSpec {
+        let special_range = if self.special_count == 0 {
+            None
+        } else if self.special_from_main_pool {
+            Some((self.main_range_start, self.main_range_end))
+        } else {
+            self.special_range_end
+                .map(|end| (self.special_range_start.unwrap_or(1), end))
+        };
+
+        GameSpec {
+            name: self.name,
+            display_name: self.display_name,
+            main_count: self.main_count,
+            pick_count: self.pick_count.unwrap_or(self.main_count),
+            main_range: (self.main_range_start, self.main_range_end),
+            special_count: self.special_count,
+            special_range,
+            ordered: self.ordered,
+            allow_repeats: self.allow_repeats,
+            special_from_main_pool: self.special_from_main_pool,
+            rules: self.rules.unwrap_or_else(|| serde_json::json!({})),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct LotteryAppState {
     pub factories: RwLock<HashMap<LotteryType, AlgorithmFactory>>,
     pub trainers: RwLock<HashMap<LotteryType, ModelTrainer>>,
-    pub data_collector: RwLock<DataCollector>,
+    pub storage: Storage,
+    // 训练好的模型按玩法和算法保存的版本
+    pub models: ModelStore,
+    // 超参数搜索任务，结束后保留以便查询结果
+    pub tuning_jobs: RwLock<HashMap<Uuid, TuningJob>>,
 }
 
 impl LotteryAppState {
-    pub fn new() -> Self {
+    pub fn new(storage: Storage, models: ModelStore) -> Self {
         let mut factories = HashMap::new();
         let mut trainers = HashMap::new();
         
-        let lottery_types = vec![
-            LotteryType::Ssq,
-            LotteryType::Dlt,
-            LotteryType::Fc3d,
-            LotteryType::Pl3,
-            LotteryType::Pl5,
-        ];
+        // 包含内置玩法以及启动时从 lottery_types 表加载的自定义玩法
+        let lottery_types = games::registry()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .lottery_types();
         
         for lottery_type in lottery_types {
             factories.insert(lottery_type.clone(), AlgorithmFactory::new(lottery_type.clone()));
@@ -101,7 +223,22 @@ impl LotteryAppState {
         Self {
             factories: RwLock::new(factories),
             trainers: RwLock::new(trainers),
-            data_collector: RwLock::new(DataCollector::new()),
+            storage,
+            models,
+            tuning_jobs: RwLock::new(HashMap::new()),
+        }
+    }
+
+    // 启动时把每个玩法、每个算法的当前版本注册到工厂，未保存过的算法跳过
+    pub async fn restore_models(&self) {
+        let mut factories = self.factories.write().await;
+        for factory in factories.values_mut() {
+            for algorithm in AlgorithmType::ALL {
+                match factory.load_model(&self.models, algorithm, None).await {
+                    Ok(_) | Err(LotteryError::NotFound(_)) => {}
+                    Err(e) => eprintln!("Failed to restore {} model for {}: {}", algorithm, factory.lottery_type, e),
+                }
+            }
         }
     }
 }
@@ -110,7 +247,7 @@ impl LotteryAppState {
 pub async fn get_available_algorithms(
     lottery_type: LotteryType,
     state: State<'_, LotteryAppState>
-) -> Result<ApiResponse<Vec<String>>, String> {
+) -> Result<ApiResponse<Vec<AlgorithmType>>, String> {
     let factories = state.factories.read().await;
     if let Some(factory) = factories.get(&lottery_type) {
         let algorithms = factory.list_available_algorithms().await;
@@ -123,12 +260,12 @@ pub async fn get_available_algorithms(
 #[tauri::command]
 pub async fn get_algorithm_metadata(
     lottery_type: LotteryType,
-    algorithm_name: String,
+    algorithm_name: AlgorithmType,
     state: State<'_, LotteryAppState>
 ) -> Result<ApiResponse<serde_json::Value>, String> {
     let factories = state.factories.read().await;
     if let Some(factory) = factories.get(&lottery_type) {
-        if let Some(metadata) = factory.get_algorithm_metadata(&algorithm_name) {
+        if let Some(metadata) = factory.get_algorithm_metadata(algorithm_name) {
             Ok(ApiResponse::success(serde_json::to_value(metadata).unwrap()))
         } else {
             Ok(ApiResponse::error(format!("Algorithm {} not found", algorithm_name)))
@@ -143,44 +280,40 @@ pub async fn predict_numbers(
     request: PredictionRequest,
     state: State<'_, LotteryAppState>
 ) -> Result<ApiResponse<serde_json::Value>, String> {
-    let factories = state.factories.read().await;
-    
-    if let Some(factory) = factories.get(&request.lottery_type) {
-        let result = if request.use_ensemble {
-            let algorithms = request.ensemble_algorithms
-                .as_ref()
-                .map(|v| v.iter().map(|s| s.as_str()).collect::<Vec<&str>>())
-                .unwrap_or_else(|| vec!["random_forest", "neural_network", "statistical"]);
-            
-            let prediction_input = create_prediction_input(&request).await?;
-            factory.ensemble_predict(&algorithms, &prediction_input).await
-        } else {
-            let prediction_input = create_prediction_input(&request).await?;
-            
-            if let Some(model) = factory.get_model(&request.algorithm).await {
-                model.predict(&prediction_input).await
-            } else {
-                let new_model = factory.create_algorithm(&request.algorithm, Default::default())
-                    .map_err(|e| e.to_string())?;
-                new_model.predict(&prediction_input).await
-            }
+    let result = if request.use_ensemble {
+        let algorithms = request.ensemble_algorithms
+            .clone()
+            .unwrap_or_else(|| vec![AlgorithmType::RandomForest, AlgorithmType::NeuralNetwork, AlgorithmType::Statistical]);
+
+        let factories = state.factories.read().await;
+        let Some(factory) = factories.get(&request.lottery_type) else {
+            return Ok(ApiResponse::error(format!("Lottery type {:?} not supported", request.lottery_type)));
+        };
+        let prediction_input = create_prediction_input(&request, state.storage.drawings.as_ref()).await?;
+        factory.ensemble_predict(&algorithms, &prediction_input).await
+    } else {
+        // 取出模型后即释放工厂的锁，预测期间完成的重新训练直接替换模型
+        let model = match trained_model(&request, state.inner()).await {
+            Ok(model) => model,
+            Err(e) => return Ok(ApiResponse::error(e)),
         };
+        let prediction_input = create_prediction_input(&request, state.storage.drawings.as_ref()).await?;
+        model.predict(&prediction_input).await
+    };
 
-        match result {
-            Ok(prediction) => {
-                let response = serde_json::json!({
-                    "predicted_numbers": prediction.predicted_numbers,
-                    "predicted_special_numbers": prediction.predicted_special_numbers,
-                    "confidence_scores": prediction.confidence_scores,
-                    "algorithm_metadata": prediction.algorithm_metadata,
-                    "computation_time_ms": prediction.computation_time_ms,
-                });
-                Ok(ApiResponse::success(response))
-            }
-            Err(e) => Ok(ApiResponse::error(e.to_string()))
+    match result {
+        Ok(prediction) => {
+            let response = serde_json::json!({
+                "predicted_numbers": prediction.predicted_numbers,
+                "predicted_special_numbers": prediction.predicted_special_numbers,
+                "confidence_scores": prediction.confidence_scores,
+                "algorithm_metadata": prediction.algorithm_metadata,
+                "computation_time_ms": prediction.computation_time_ms,
+                "probabilities": prediction.probabilities,
+            });
+            Ok(ApiResponse::success(response))
         }
-    } else {
-        Ok(ApiResponse::error(format!("Lottery type {:?} not supported", request.lottery_type)))
+        Err(e) => Ok(ApiResponse::error(e.to_string()))
     }
 }
 
@@ -188,59 +321,8 @@ pub async fn predict_numbers(
 pub async fn train_algorithms(
     request: TrainingRequest,
     state: State<'_, LotteryAppState>
-) -> Result<ApiResponse<HashMap<String, f64>>, String> {
-    let mut trainers = state.trainers.write().await;
-    
-    if let Some(trainer) = trainers.get_mut(&request.lottery_type) {
-        let historical_data = collect_training_data(&request).await?;
-        let training_data = prepare_training_data(&historical_data, &request.lottery_type).await?;
-        
-        let mut results = HashMap::new();
-        
-        for algorithm in &request.algorithms {
-            match trainer.train_algorithm(algorithm, &training_data, &Default::default()).await {
-                Ok(accuracy) => {
-                    results.insert(algorithm.clone(), accuracy);
-                }
-                Err(e) => {
-                    results.insert(algorithm.clone(), 0.0);
-                    eprintln!("Failed to train {}: {}", algorithm, e);
-                }
-            }
-        }
-        
-        // Update factory with trained models
-        let mut factories = state.factories.write().await;
-        if let Some(factory) = factories.get_mut(&request.lottery_type) {
-            for (algorithm, accuracy) in &results {
-                if *accuracy > 0.0 {
-                    let model = trainer.trained_models.get(algorithm).unwrap();
-                    let metrics = EvaluationMetrics {
-                        accuracy: *accuracy,
-                        precision: *accuracy * 0.95,
-                        recall: *accuracy * 0.98,
-                        f1_score: *accuracy * 0.96,
-                        mean_absolute_error: *accuracy * 0.05,
-                        root_mean_squared_error: *accuracy * 0.08,
-                        feature_importance: None,
-                        confusion_matrix: None,
-                        cross_validation_scores: Some(vec![*accuracy * 0.95, *accuracy * 0.97, *accuracy * 0.96]),
-                    };
-                    
-                    let _ = factory.register_model(
-                        algorithm.clone(),
-                        model.box_clone(),
-                        metrics,
-                        Default::default()
-                    ).await;
-                }
-            }
-        }
-        
-        Ok(ApiResponse::success(results))
-    } else {
-        Ok(ApiResponse::error(format!("Lottery type {:?} not supported", request.lottery_type)))
-    }
+) -> Result<ApiResponse<HashMap<AlgorithmType, TrainingResult>>, String> {
+    train_and_register(&request, state.inner()).await
 }
 
 #[tauri::command]
@@ -255,14 +337,17 @@ pub async fn compare_algorithms(
         let mut comparisons = Vec::new();
         
         for algorithm in trained_algorithms {
-            if let Some(metadata) = factory.get_algorithm_metadata(&algorithm) {
+            if let Some(info) = factory.model_registry.get(&algorithm) {
+                let metrics = &info.performance_metrics;
                 let comparison = AlgorithmComparison {
-                    algorithm_name: algorithm.clone(),
-                    accuracy: metadata.accuracy_range.1,
-                    precision: metadata.accuracy_range.1 * 0.95,
-                    recall: metadata.accuracy_range.1 * 0.98,
-                    f1_score: metadata.accuracy_range.1 * 0.96,
-                    training_time_ms: 1000, // Placeholder
+                    algorithm_name: algorithm,
+                    accuracy: metrics.accuracy,
+                    precision: metrics.precision,
+                    recall: metrics.recall,
+                    f1_score: metrics.f1_score,
+                    training_time_ms: info.training_time_ms,
+                    baseline: metrics.baseline.clone(),
+                    cross_validation: metrics.cross_validation_scores.clone().map(CrossValidationReport::from_scores),
                 };
                 comparisons.push(comparison);
             }
@@ -279,16 +364,18 @@ pub async fn collect_and_update_data(
     request: DataCollectionRequest,
     state: State<'_, LotteryAppState>
 ) -> Result<ApiResponse<HashMap<String, usize>>, String> {
-    let mut collector = state.data_collector.write().await;
+    // 开奖数据通过 import_drawings 写入存储，这里汇报窗口内可用的期数
+    let end_date = Utc::now().date_naive();
+    let start_date = end_date - Duration::days(request.days as i64);
     let mut results = HashMap::new();
     
     for lottery_type in &request.lottery_types {
-        match collector.collect_historical_data(lottery_type.clone(), request.days).await {
+        match state.storage.drawings.get_by_date_range(lottery_type, start_date, end_date).await {
             Ok(drawings) => {
                 results.insert(format!("{:?}", lottery_type), drawings.len());
             }
             Err(e) => {
-                eprintln!("Failed to collect data for {:?}: {}", lottery_type, e);
+                eprintln!("Failed to load data for {:?}: {}", lottery_type, e);
                 results.insert(format!("{:?}", lottery_type), 0);
             }
         }
@@ -303,9 +390,7 @@ pub async fn get_recent_drawings(
     count: i32,
     state: State<'_, LotteryAppState>
 ) -> Result<ApiResponse<Vec<LotteryDrawing>>, String> {
-    let collector = state.data_collector.read().await;
-    
-    match collector.get_recent_drawings(lottery_type, count).await {
+    match state.storage.drawings.get_latest(&lottery_type, count.max(0) as usize).await {
         Ok(drawings) => Ok(ApiResponse::success(drawings)),
         Err(e) => Ok(ApiResponse::error(e.to_string()))
     }
@@ -315,7 +400,7 @@ pub async fn get_recent_drawings(
 pub async fn get_algorithm_rankings(
     lottery_type: LotteryType,
     state: State<'_, LotteryAppState>
-) -> Result<ApiResponse<Vec<(String, f64)>>, String> {
+) -> Result<ApiResponse<Vec<(AlgorithmType, f64)>>, String> {
     let factories = state.factories.read().await;
     
     if let Some(factory) = factories.get(&lottery_type) {
@@ -332,7 +417,7 @@ pub async fn recommend_algorithms(
     data_size: usize,
     target_accuracy: f64,
     state: State<'_, LotteryAppState>
-) -> Result<ApiResponse<Vec<String>>, String> {
+) -> Result<ApiResponse<Vec<AlgorithmType>>, String> {
     let factories = state.factories.read().await;
     
     if let Some(factory) = factories.get(&lottery_type) {
@@ -343,27 +428,543 @@ pub async fn recommend_algorithms(
     }
 }
 
-async fn create_prediction_input(request: &PredictionRequest) -> Result<PredictionInput, String> {
-    let collector = DataCollector::new();
-    let days = request.historical_days.unwrap_or(365);
+#[tauri::command]
+pub async fn run_backtest(
+    request: BacktestRequest,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<BacktestReport>, String> {
+    let algorithm_config = AlgorithmConfig {
+        lottery_type: request.lottery_type.clone(),
+        ..Default::default()
+    };
+
+    let mut algorithm = {
+        let factories = state.factories.read().await;
+        let Some(factory) = factories.get(&request.lottery_type) else {
+            return Ok(ApiResponse::error(format!("Lottery type {:?} not supported", request.lottery_type)));
+        };
+        match factory.create_algorithm(request.algorithm, algorithm_config.clone()) {
+            Ok(algorithm) => algorithm,
+            Err(e) => return Ok(ApiResponse::error(e.to_string())),
+        }
+    };
+
+    let mut config = BacktestConfig::new(request.start_date, request.end_date);
+    config.retrain_every = request.retrain_every.unwrap_or(config.retrain_every);
+    config.min_training_draws = request.min_training_draws.unwrap_or(config.min_training_draws);
+    config.max_training_draws = request.max_training_draws;
+    config.ticket_cost = request.ticket_cost.unwrap_or(config.ticket_cost);
+
+    let drawings = match state.storage.drawings.get_by_type(&request.lottery_type).await {
+        Ok(drawings) => drawings,
+        Err(e) => return Ok(ApiResponse::error(e.to_string())),
+    };
+
+    match Backtester::new(config).run(algorithm.as_mut(), &algorithm_config, &drawings).await {
+        Ok(report) => Ok(ApiResponse::success(report)),
+        Err(e) => Ok(ApiResponse::error(e.to_string())),
+    }
+}
+
+// 导入开奖数据，入库后自动核验对应期次的预测
+#[tauri::command]
+pub async fn import_drawings(
+    drawings: Vec<LotteryDrawing>,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<IngestSummary>, String> {
+    match settlement::ingest_drawings(&state.storage, &drawings).await {
+        Ok(summary) => Ok(ApiResponse::success(summary)),
+        Err(e) => Ok(ApiResponse::error(e.to_string())),
+    }
+}
+
+// 核验目标日期不晚于今天且已有开奖结果的预测，开奖数据由其他途径导入时手动触发
+#[tauri::command]
+pub async fn settle_predictions(
+    lottery_types: Vec<LotteryType>,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<SettlementSummary>, String> {
+    let today = Utc::now().date_naive();
+    let mut summary = SettlementSummary::default();
+
+    for lottery_type in &lottery_types {
+        match settlement::settle_pending(&state.storage, lottery_type, today).await {
+            Ok(result) => summary.merge(result),
+            Err(e) => return Ok(ApiResponse::error(e.to_string())),
+        }
+    }
+
+    Ok(ApiResponse::success(summary))
+}
+
+// 在后台逐个评估候选参数，立即返回任务状态；进度通过 get_hyperparameter_search 查询
+#[tauri::command]
+pub async fn start_hyperparameter_search(
+    request: TuningRequest,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<TuningStatus>, String> {
+    let mut tuner = match HyperparameterTuner::new(request.algorithm, request.lottery_type.clone(), request.config.clone()) {
+        Ok(tuner) => tuner,
+        Err(e) => return Ok(ApiResponse::error(e.to_string())),
+    };
+    for (name, domain) in request.search_space.clone() {
+        tuner = match tuner.with_parameter(&name, domain) {
+            Ok(tuner) => tuner,
+            Err(e) => return Ok(ApiResponse::error(e.to_string())),
+        };
+    }
+    if tuner.space.parameters.is_empty() {
+        return Ok(ApiResponse::error(format!("{} has no tunable parameters", request.algorithm)));
+    }
+
+    let end_date = Utc::now().date_naive();
+    let start_date = end_date - Duration::days(request.historical_days as i64);
+    let drawings = match state.storage.drawings.get_by_date_range(&request.lottery_type, start_date, end_date).await {
+        Ok(drawings) => drawings,
+        Err(e) => return Ok(ApiResponse::error(e.to_string())),
+    };
+    let (Some(first), Some(last)) = (drawings.first(), drawings.last()) else {
+        return Ok(ApiResponse::error(format!(
+            "No drawings stored for {} in the last {} days",
+            request.lottery_type, request.historical_days
+        )));
+    };
+    let data = prepare_training_data(&drawings, &request.lottery_type).await?;
+
+    let strategy_id = match tuning_strategy(&state.storage, &request).await {
+        Ok(strategy_id) => strategy_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let context = TrialRecordContext {
+        strategy_id,
+        training_data_start: first.draw_date,
+        training_data_end: last.draw_date,
+    };
+
+    let job = TuningJob::new(&tuner, strategy_id);
+    let status = job.status();
+    state.tuning_jobs.write().await.insert(status.job_id, job.clone());
+
+    let storage = state.storage.clone();
+    tauri::async_runtime::spawn(async move {
+        let status = job.run(&tuner, &data, storage.training_records.as_ref(), &context).await;
+        // 最优参数写回策略的 hyperparameters
+        if let Some(parameters) = status.best_parameters()
+            && let Err(e) = save_best_parameters(&storage, strategy_id, tuner.full_parameters(parameters)).await
+        {
+            eprintln!("Failed to save tuned parameters for {}: {}", strategy_id, e);
+        }
+    });
+
+    Ok(ApiResponse::success(status))
+}
+
+#[tauri::command]
+pub async fn get_hyperparameter_search(
+    job_id: Uuid,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<TuningStatus>, String> {
+    match state.tuning_jobs.read().await.get(&job_id) {
+        Some(job) => Ok(ApiResponse::success(job.status())),
+        None => Ok(ApiResponse::error(format!("Tuning job {} not found", job_id))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_hyperparameter_searches(
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<Vec<TuningStatus>>, String> {
+    let mut statuses: Vec<TuningStatus> = state.tuning_jobs.read().await
+        .values()
+        .map(TuningJob::status)
+        .collect();
+    statuses.sort_by_key(|status| std::cmp::Reverse(status.started_at));
+
+    Ok(ApiResponse::success(statuses))
+}
+
+// 正在运行的试验在当前折结束后停止，返回的状态可能仍为 running
+#[tauri::command]
+pub async fn cancel_hyperparameter_search(
+    job_id: Uuid,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<TuningStatus>, String> {
+    match state.tuning_jobs.read().await.get(&job_id) {
+        Some(job) => {
+            job.cancel();
+            Ok(ApiResponse::success(job.status()))
+        }
+        None => Ok(ApiResponse::error(format!("Tuning job {} not found", job_id))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_model_versions(
+    lottery_type: LotteryType,
+    algorithm: AlgorithmType,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<ModelVersionList>, String> {
+    let versions = match state.models.list_models(&lottery_type, algorithm).await {
+        Ok(versions) => versions,
+        Err(e) => return Ok(ApiResponse::error(e.to_string())),
+    };
+    match state.models.current_version(&lottery_type, algorithm).await {
+        Ok(current_version) => Ok(ApiResponse::success(ModelVersionList { current_version, versions })),
+        Err(e) => Ok(ApiResponse::error(e.to_string())),
+    }
+}
+
+#[tauri::command]
+pub async fn rollback_model(
+    lottery_type: LotteryType,
+    algorithm: AlgorithmType,
+    version: u32,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<ModelMetadata>, String> {
+    let mut factories = state.factories.write().await;
+    let Some(factory) = factories.get_mut(&lottery_type) else {
+        return Ok(ApiResponse::error(format!("Lottery type {:?} not supported", lottery_type)));
+    };
+
+    // 先确认该版本能加载并替换工厂中的模型，再切换当前版本
+    if let Err(e) = factory.load_model(&state.models, algorithm, Some(version)).await {
+        return Ok(ApiResponse::error(e.to_string()));
+    }
+    match state.models.rollback(&lottery_type, algorithm, version).await {
+        Ok(metadata) => Ok(ApiResponse::success(metadata)),
+        Err(e) => Ok(ApiResponse::error(e.to_string())),
+    }
+}
+
+#[tauri::command]
+pub async fn prune_model_versions(
+    lottery_type: LotteryType,
+    algorithm: AlgorithmType,
+    keep: usize,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<Vec<u32>>, String> {
+    match state.models.gc(&lottery_type, algorithm, keep).await {
+        Ok(removed) => Ok(ApiResponse::success(removed)),
+        Err(e) => Ok(ApiResponse::error(e.to_string())),
+    }
+}
+
+#[tauri::command]
+pub async fn list_games() -> Result<ApiResponse<Vec<GameSpec>>, String> {
+    let registry = games::registry().read().unwrap_or_else(|e| e.into_inner());
+    let specs = registry
+        .names()
+        .iter()
+        .filter_map(|name| registry.get(name))
+        .map(|spec| spec.as_ref().clone())
+        .collect();
+
+    Ok(ApiResponse::success(specs))
+}
+
+#[tauri::command]
+pub async fn register_custom_game(
+    request: CustomGameRequest,
+    state: State<'_, LotteryAppState>
+) -> Result<ApiResponse<GameSpec>, String> {
+    let spec = request.into_spec();
+
+    if let Err(e) = spec.validate() {
+        return Ok(ApiResponse::error(e.to_string()));
+    }
+    if games::registry().read().unwrap_or_else(|e| e.into_inner()).is_builtin(&spec.name) {
+        return Ok(ApiResponse::error(format!("{} is a built-in lottery type", spec.name)));
+    }
+
+    // 先落库再注册，保证重启后仍可用
+    if let Err(e) = state.storage.lottery_types.save_lottery_type(&spec.to_config()).await {
+        return Ok(ApiResponse::error(e.to_string()));
+    }
+
+    let lottery_type = match games::register_custom(spec.clone()) {
+        Ok(lottery_type) => lottery_type,
+        Err(e) => return Ok(ApiResponse::error(e.to_string())),
+    };
+
+    // 号码范围可能已改变，重新注册同名玩法时丢弃旧的已训练模型
+    state.factories.write().await.insert(lottery_type.clone(), AlgorithmFactory::new(lottery_type.clone()));
+    state.trainers.write().await.insert(lottery_type.clone(), ModelTrainer::new(lottery_type));
+
+    Ok(ApiResponse::success(spec))
+}
+
+async fn create_prediction_input(
+    request: &PredictionRequest,
+    drawings: &dyn DrawingRepository,
+) -> Result<PredictionInput, String> {
+    let days = request.historical_days.unwrap_or(DEFAULT_HISTORICAL_DAYS);
+    let target_date = Utc::now().date_naive();
     
-    let historical_data = collector
-        .get_recent_drawings(request.lottery_type.clone(), days)
+    let historical_data = drawings
+        .get_by_date_range(&request.lottery_type, target_date - Duration::days(days as i64), target_date)
         .await
         .map_err(|e| e.to_string())?;
     
+    if historical_data.is_empty() {
+        return Err(format!(
+            "No drawings stored for {} in the last {} days ({} backend)",
+            request.lottery_type, days, drawings.backend_name()
+        ));
+    }
+    
     Ok(PredictionInput {
         historical_data,
         lottery_type: request.lottery_type.clone(),
-        target_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        target_date,
         additional_features: None,
     })
 }
 
-async fn collect_training_data(request: &TrainingRequest) -> Result<Vec<LotteryDrawing>, String> {
-    let collector = DataCollector::new();
-    collector
-        .get_recent_drawings(request.lottery_type.clone(), request.historical_days)
+// 训练请求中的算法并把验证集上评估过的模型注册到工厂；predict_numbers 按需训练时同样走这里
+async fn train_and_register(
+    request: &TrainingRequest,
+    state: &LotteryAppState,
+) -> Result<ApiResponse<HashMap<AlgorithmType, TrainingResult>>, String> {
+    let mut trainers = state.trainers.write().await;
+
+    if let Some(trainer) = trainers.get_mut(&request.lottery_type) {
+        let historical_data = collect_training_data(request, state.storage.drawings.as_ref()).await?;
+        let training_data = prepare_training_data(&historical_data, &request.lottery_type).await?;
+        let configs = training_configs(request, &state.storage).await?;
+
+        // 按时间顺序留出最近的若干期做验证，注册到工厂的是验证集上的真实指标
+        let validation_split = request.validation_split.clamp(0.0, 1.0);
+        let split_index = ((1.0 - validation_split) * training_data.len() as f64) as usize;
+        let (train_set, validation_set) = training_data.split_at(split_index);
+        
+        let mut results = HashMap::new();
+        let mut performance = HashMap::new();
+        let mut started = HashMap::new();
+        let mut training_times = HashMap::new();
+        
+        for (algorithm, config, _) in &configs {
+            let algorithm = *algorithm;
+            started.insert(algorithm, Utc::now());
+            let timer = std::time::Instant::now();
+            match trainer.train_algorithm(algorithm, &train_set, config).await {
+                Ok(accuracy) => {
+                    training_times.insert(algorithm, timer.elapsed().as_millis() as u64);
+                    results.insert(algorithm, TrainingResult {
+                        accuracy,
+                        cross_validation: trainer.get_cross_validation_report(algorithm).cloned(),
+                    });
+                    match trainer.evaluate_algorithm(algorithm, &validation_set).await {
+                        Ok(metrics) => {
+                            performance.insert(algorithm, metrics);
+                        }
+                        Err(e) => eprintln!("Failed to evaluate {}: {}", algorithm, e),
+                    }
+                    if let Err(e) = trainer.calibrate_algorithm(algorithm, &validation_set, request.calibration_method).await {
+                        eprintln!("Failed to calibrate {}: {}", algorithm, e);
+                    }
+                }
+                Err(e) => {
+                    results.insert(algorithm, TrainingResult { accuracy: 0.0, cross_validation: None });
+                    eprintln!("Failed to train {}: {}", algorithm, e);
+                }
+            }
+        }
+        
+        // 训练好的模型先保存为新版本再移交给工厂，之后的预测共享这一份；替换前已取出的旧模型在其预测结束后释放
+        let mut factories = state.factories.write().await;
+        if let Some(factory) = factories.get_mut(&request.lottery_type) {
+            let mut registered = Vec::new();
+            for (algorithm, config, strategy_id) in &configs {
+                let Some(metrics) = performance.remove(algorithm) else {
+                    continue;
+                };
+                let Some(model) = trainer.trained_models.remove(algorithm) else {
+                    continue;
+                };
+                let model: Arc<dyn PredictionAlgorithm> = Arc::from(model);
+                let metadata = ModelMetadata {
+                    training_time_ms: training_times.get(algorithm).copied(),
+                    ..ModelMetadata::new(*algorithm, config.clone(), metrics, train_set.len())
+                };
+
+                // 有对应策略时同时写入 model_training_records
+                let stored = match strategy_id {
+                    Some(strategy_id) => {
+                        let context = TrainingRecordContext {
+                            strategy_id: *strategy_id,
+                            training_data_start: historical_data.iter().map(|d| d.draw_date).min().unwrap_or_default(),
+                            training_data_end: historical_data.iter().map(|d| d.draw_date).max().unwrap_or_default(),
+                            validation_samples: validation_set.len(),
+                            training_accuracy: results.get(algorithm).map(|result| result.accuracy),
+                            started_at: started[algorithm],
+                        };
+                        state.models.save_trained(model.as_ref(), &metadata, state.storage.training_records.as_ref(), &context).await
+                    }
+                    None => state.models.save(model.as_ref(), &metadata).await,
+                };
+
+                // 保存失败不影响本次训练结果，模型仍然注册，只是重启后需要重新训练
+                let _ = match stored {
+                    Ok(stored) => factory.register_stored_model(model, &stored).await,
+                    Err(e) => {
+                        eprintln!("Failed to store {} model: {}", algorithm, e);
+                        factory.register_model(*algorithm, model, metadata.evaluation_metrics, config.clone()).await
+                    }
+                };
+                registered.push(*algorithm);
+            }
+
+            // 集成权重同样只在验证集上学习
+            if registered.len() > 1
+                && !validation_set.is_empty()
+                && let Err(e) = factory.fit_ensemble_weights(&registered, &validation_set, WeightObjective::default()).await
+            {
+                eprintln!("Failed to fit ensemble weights: {}", e);
+            }
+        }
+        
+        Ok(ApiResponse::success(results))
+    } else {
+        Ok(ApiResponse::error(format!("Lottery type {:?} not supported", request.lottery_type)))
+    }
+}
+
+// 工厂中已注册的训练模型；没有时按请求在同一历史窗口上现场训练，否则提示先训练
+async fn trained_model(
+    request: &PredictionRequest,
+    state: &LotteryAppState,
+) -> Result<Arc<dyn PredictionAlgorithm>, String> {
+    let registered = registered_model(state, &request.lottery_type, request.algorithm).await?;
+    if let Some(model) = registered {
+        return Ok(model);
+    }
+    if !request.train_if_missing {
+        return Err(format!(
+            "No trained {} model for {}; train it with train_algorithms first or set train_if_missing",
+            request.algorithm, request.lottery_type
+        ));
+    }
+
+    let training_request = TrainingRequest {
+        lottery_type: request.lottery_type.clone(),
+        algorithms: vec![request.algorithm],
+        historical_days: request.historical_days.unwrap_or(DEFAULT_HISTORICAL_DAYS),
+        validation_split: ON_DEMAND_VALIDATION_SPLIT,
+        calibration_method: CalibrationMethod::default(),
+        strategy_ids: Vec::new(),
+    };
+    let response = train_and_register(&training_request, state).await?;
+    if !response.success {
+        return Err(response.message);
+    }
+
+    registered_model(state, &request.lottery_type, request.algorithm).await?
+        .ok_or_else(|| format!("Training {} on demand for {} produced no model", request.algorithm, request.lottery_type))
+}
+
+async fn registered_model(
+    state: &LotteryAppState,
+    lottery_type: &LotteryType,
+    algorithm: AlgorithmType,
+) -> Result<Option<Arc<dyn PredictionAlgorithm>>, String> {
+    let factories = state.factories.read().await;
+    let factory = factories.get(lottery_type)
+        .ok_or_else(|| format!("Lottery type {:?} not supported", lottery_type))?;
+    Ok(factory.get_model(algorithm).await)
+}
+
+// 每个算法的训练配置及训练记录所属的策略；策略的参数来自数据库，同名算法以策略为准，
+// 使用默认配置的算法记录到同一算法的系统策略下，没有系统策略时不写训练记录
+async fn training_configs(
+    request: &TrainingRequest,
+    storage: &Storage,
+) -> Result<Vec<(AlgorithmType, AlgorithmConfig, Option<Uuid>)>, String> {
+    let strategies = storage.strategies.list_strategies().await.map_err(|e| e.to_string())?;
+    let mut configs: Vec<(AlgorithmType, AlgorithmConfig, Option<Uuid>)> = request.algorithms.iter()
+        .map(|&algorithm| {
+            let system_strategy = strategies.iter()
+                .find(|strategy| strategy.is_system && strategy.algorithm_type == algorithm)
+                .map(|strategy| strategy.id);
+            (algorithm, AlgorithmConfig {
+                lottery_type: request.lottery_type.clone(),
+                ..Default::default()
+            }, system_strategy)
+        })
+        .collect();
+
+    for &strategy_id in &request.strategy_ids {
+        let strategy = storage.strategies.get_strategy(strategy_id).await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Strategy {} not found", strategy_id))?;
+        let config = AlgorithmConfig::for_strategy(&request.lottery_type, &strategy)
+            .map_err(|e| e.to_string())?;
+        configs.retain(|(algorithm, _, _)| *algorithm != strategy.algorithm_type);
+        configs.push((strategy.algorithm_type, config, Some(strategy_id)));
+    }
+
+    Ok(configs)
+}
+
+// 指定的策略必须使用同一算法；未指定时新建一个策略承载试验记录
+async fn tuning_strategy(storage: &Storage, request: &TuningRequest) -> Result<Uuid, String> {
+    if let Some(strategy_id) = request.strategy_id {
+        let strategy = storage.strategies.get_strategy(strategy_id).await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Strategy {} not found", strategy_id))?;
+        if strategy.algorithm_type != request.algorithm {
+            return Err(format!("Strategy {} uses {}, not {}", strategy_id, strategy.algorithm_type, request.algorithm));
+        }
+        return Ok(strategy_id);
+    }
+
+    let now = Utc::now();
+    let strategy = PredictionStrategy {
+        id: Uuid::new_v4(),
+        name: format!("{} hyperparameter search", request.algorithm),
+        algorithm_type: request.algorithm,
+        description: Some(format!("Created by hyperparameter search on {}", now.date_naive())),
+        parameters: serde_json::json!({}),
+        hyperparameters: None,
+        accuracy_rate: None,
+        precision_rate: None,
+        recall_rate: None,
+        f1_score: None,
+        total_predictions: 0,
+        successful_predictions: 0,
+        is_active: true,
+        is_public: false,
+        is_system: false,
+        owner_id: None,
+        version: "1.0.0".to_string(),
+        created_at: now,
+        updated_at: now,
+    };
+    storage.strategies.save_strategy(&strategy).await.map_err(|e| e.to_string())?;
+
+    Ok(strategy.id)
+}
+
+async fn save_best_parameters(
+    storage: &Storage,
+    strategy_id: Uuid,
+    parameters: HashMap<String, serde_json::Value>,
+) -> crate::lottery::errors::LotteryResult<()> {
+    let Some(mut strategy) = storage.strategies.get_strategy(strategy_id).await? else {
+        return Ok(());
+    };
+    strategy.hyperparameters = Some(serde_json::json!(parameters));
+    strategy.updated_at = Utc::now();
+    storage.strategies.save_strategy(&strategy).await
+}
+
+async fn collect_training_data(
+    request: &TrainingRequest,
+    drawings: &dyn DrawingRepository,
+) -> Result<Vec<LotteryDrawing>, String> {
+    let end_date = Utc::now().date_naive();
+    let start_date = end_date - Duration::days(request.historical_days as i64);
+    drawings
+        .get_by_date_range(&request.lottery_type, start_date, end_date)
         .await
         .map_err(|e| e.to_string())
 }
@@ -0,0 +1,361 @@
+use crate::lottery::algorithms::feature_engineering::LotteryFeatureExtractor;
+use crate::lottery::algorithms::traits::{
+    AlgorithmConfig, FeatureConfig, FeatureExtractor, PredictionAlgorithm, PredictionInput,
+};
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::errors::LotteryError;
+use crate::lottery::models::{LotteryDrawing, LotteryType};
+use crate::lottery::prize::{PrizeEvaluator, Ticket};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    // 回测的目标期次范围 (含首尾)
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    // 每回测多少期重新训练一次，0 表示只在第一期前训练
+    pub retrain_every: usize,
+    // 训练集少于该期数时跳过该期
+    pub min_training_draws: usize,
+    // 只用最近 N 期训练，None 表示使用全部历史
+    pub max_training_draws: Option<usize>,
+    // 每注成本 (元)
+    pub ticket_cost: f64,
+    pub feature_config: FeatureConfig,
+}
+
+impl BacktestConfig {
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate) -> Self {
+        Self {
+            start_date,
+            end_date,
+            retrain_every: 10,
+            min_training_draws: 100,
+            max_training_draws: None,
+            ticket_cost: 2.0,
+            feature_config: FeatureConfig::default(),
+        }
+    }
+}
+
+// 账本中的一期；cumulative_* 字段依次连起来即为命中率、奖金与 ROI 曲线
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestEntry {
+    pub drawing_id: Uuid,
+    pub draw_number: String,
+    pub draw_date: NaiveDate,
+    pub training_draws: usize,
+    pub retrained: bool,
+    pub predicted_numbers: Vec<u32>,
+    pub predicted_special_numbers: Option<Vec<u32>>,
+    pub winning_numbers: Vec<u32>,
+    pub special_numbers: Option<Vec<u32>>,
+    pub match_count: u32,
+    pub special_match_count: u32,
+    pub prize_tier: Option<u32>,
+    pub prize_amount: f64,
+    pub cumulative_hit_rate: f64,
+    pub cumulative_prize: f64,
+    pub cumulative_roi: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestSummary {
+    pub draws: usize,
+    pub skipped_draws: usize,
+    pub winning_draws: usize,
+    pub hit_rate: f64,
+    pub average_match_count: f64,
+    pub total_cost: f64,
+    pub total_prize: f64,
+    pub roi: f64,
+    pub retrain_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub algorithm: String,
+    pub lottery_type: LotteryType,
+    pub config: BacktestConfig,
+    pub ledger: Vec<BacktestEntry>,
+    pub summary: BacktestSummary,
+}
+
+impl BacktestReport {
+    pub fn hit_rate_curve(&self) -> Vec<(NaiveDate, f64)> {
+        self.ledger.iter().map(|e| (e.draw_date, e.cumulative_hit_rate)).collect()
+    }
+
+    pub fn prize_curve(&self) -> Vec<(NaiveDate, f64)> {
+        self.ledger.iter().map(|e| (e.draw_date, e.cumulative_prize)).collect()
+    }
+
+    pub fn roi_curve(&self) -> Vec<(NaiveDate, f64)> {
+        self.ledger.iter().map(|e| (e.draw_date, e.cumulative_roi)).collect()
+    }
+}
+
+// 逐期向前回测：每一期只用开奖日期早于该期的数据训练和预测，杜绝未来数据泄漏
+#[derive(Debug, Clone)]
+pub struct Backtester {
+    pub config: BacktestConfig,
+}
+
+impl Backtester {
+    pub fn new(config: BacktestConfig) -> Self {
+        Self { config }
+    }
+
+    pub async fn run(
+        &self,
+        algorithm: &mut dyn PredictionAlgorithm,
+        algorithm_config: &AlgorithmConfig,
+        drawings: &[LotteryDrawing],
+    ) -> Result<BacktestReport> {
+        if self.config.start_date > self.config.end_date {
+            return Err(LotteryError::InvalidParameter(
+                "Backtest start date is after end date".to_string(),
+            ));
+        }
+
+        let mut history = drawings.to_vec();
+        history.sort_by_key(|d| d.draw_date);
+
+        let lottery_type = algorithm_config.lottery_type.clone();
+        if let Some(other) = history.iter().find(|d| d.lottery_type != lottery_type) {
+            return Err(LotteryError::InvalidParameter(format!(
+                "Backtest for {} received a {} drawing", lottery_type, other.lottery_type
+            )));
+        }
+
+        let evaluator = PrizeEvaluator::for_type(&lottery_type)?;
+        let extractor = LotteryFeatureExtractor;
+        let mut ledger = Vec::new();
+        let mut summary = BacktestSummary::default();
+        let mut total_matches = 0u64;
+        let mut steps_since_training: Option<usize> = None;
+
+        for target in history.iter().filter(|d| {
+            d.draw_date >= self.config.start_date && d.draw_date <= self.config.end_date
+        }) {
+            // 同一天可能有多条记录，严格小于保证目标期及之后的数据都不可见
+            let visible = history.partition_point(|d| d.draw_date < target.draw_date);
+            let window_start = self.config.max_training_draws
+                .map(|n| visible.saturating_sub(n))
+                .unwrap_or(0);
+            let training_window = &history[window_start..visible];
+
+            if training_window.len() < self.config.min_training_draws.max(1) {
+                summary.skipped_draws += 1;
+                continue;
+            }
+
+            let retrain = match steps_since_training {
+                None => true,
+                Some(steps) => self.config.retrain_every > 0 && steps >= self.config.retrain_every,
+            };
+            if retrain {
+                let training_data = extractor.extract_features(training_window, &self.config.feature_config)?;
+                algorithm.train(&training_data, algorithm_config).await?;
+                steps_since_training = Some(0);
+                summary.retrain_count += 1;
+            }
+
+            let input = PredictionInput {
+                lottery_type: lottery_type.clone(),
+                historical_data: training_window.to_vec(),
+                target_date: target.draw_date,
+                additional_features: None,
+            };
+            let output = algorithm.predict(&input).await?;
+            steps_since_training = steps_since_training.map(|steps| steps + 1);
+
+            let ticket = Ticket::new(
+                output.predicted_numbers.clone(),
+                output.predicted_special_numbers.clone().unwrap_or_default(),
+            );
+            let outcome = evaluator.evaluate(&ticket, target);
+            let prize_amount = outcome.prize_amount.unwrap_or(0.0);
+
+            summary.draws += 1;
+            if outcome.is_winner() {
+                summary.winning_draws += 1;
+            }
+            total_matches += outcome.match_count as u64;
+            summary.total_cost += self.config.ticket_cost;
+            summary.total_prize += prize_amount;
+
+            ledger.push(BacktestEntry {
+                drawing_id: target.id,
+                draw_number: target.draw_number.clone(),
+                draw_date: target.draw_date,
+                training_draws: training_window.len(),
+                retrained: retrain,
+                predicted_numbers: output.predicted_numbers,
+                predicted_special_numbers: output.predicted_special_numbers,
+                winning_numbers: target.winning_numbers.clone(),
+                special_numbers: target.special_numbers.clone(),
+                match_count: outcome.match_count,
+                special_match_count: outcome.special_match_count,
+                prize_tier: outcome.prize_tier,
+                prize_amount,
+                cumulative_hit_rate: summary.winning_draws as f64 / summary.draws as f64,
+                cumulative_prize: summary.total_prize,
+                cumulative_roi: Self::roi(summary.total_prize, summary.total_cost),
+            });
+        }
+
+        if summary.draws > 0 {
+            summary.hit_rate = summary.winning_draws as f64 / summary.draws as f64;
+            summary.average_match_count = total_matches as f64 / summary.draws as f64;
+            summary.roi = Self::roi(summary.total_prize, summary.total_cost);
+        }
+
+        Ok(BacktestReport {
+            algorithm: algorithm.name(),
+            lottery_type,
+            config: self.config.clone(),
+            ledger,
+            summary,
+        })
+    }
+
+    fn roi(prize: f64, cost: f64) -> f64 {
+        if cost > 0.0 {
+            (prize - cost) / cost
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::algorithms::traits::{EvaluationMetrics, PredictionOutput, TrainingData};
+    use crate::lottery::models::AlgorithmType;
+    use crate::lottery::storage::{DemoDrawingRepository, DrawingRepository};
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+
+    // 复读上一期号码；任何一次看到目标期及之后的数据都会报错
+    #[derive(Debug, Clone, Default)]
+    struct RepeatLastDraw {
+        trained: bool,
+        train_calls: usize,
+    }
+
+    #[async_trait]
+    impl PredictionAlgorithm for RepeatLastDraw {
+        fn name(&self) -> String {
+            "repeat_last".to_string()
+        }
+
+        fn algorithm_type(&self) -> AlgorithmType {
+            AlgorithmType::Statistical
+        }
+
+        async fn train(&mut self, _training_data: &TrainingData, _config: &AlgorithmConfig) -> Result<f64> {
+            self.trained = true;
+            self.train_calls += 1;
+            Ok(0.0)
+        }
+
+        async fn predict(&self, input: &PredictionInput) -> Result<PredictionOutput> {
+            if input.historical_data.iter().any(|d| d.draw_date >= input.target_date) {
+                return Err(LotteryError::PredictionFailed("look-ahead data in input".to_string()));
+            }
+
+            let last = input.historical_data.last().unwrap();
+            Ok(PredictionOutput {
+                predicted_numbers: last.winning_numbers.clone(),
+                predicted_special_numbers: last.special_numbers.clone(),
+                confidence_scores: vec![],
+                algorithm_metadata: HashMap::new(),
+                computation_time_ms: 0,
+                probabilities: None,
+            })
+        }
+
+        async fn evaluate(&self, _test_data: &TrainingData) -> Result<EvaluationMetrics> {
+            Ok(EvaluationMetrics::default())
+        }
+
+        fn is_trained(&self) -> bool {
+            self.trained
+        }
+
+        fn get_feature_importance(&self) -> Option<HashMap<String, f64>> {
+            None
+        }
+
+        fn save_model(&self, _path: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn load_model(&mut self, _path: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn box_clone(&self) -> Box<dyn PredictionAlgorithm> {
+            Box::new(self.clone())
+        }
+    }
+
+    async fn demo_drawings() -> Vec<LotteryDrawing> {
+        DemoDrawingRepository::with_history(80, 11)
+            .get_by_type(&LotteryType::Ssq)
+            .await
+            .unwrap()
+    }
+
+    fn test_config(drawings: &[LotteryDrawing]) -> BacktestConfig {
+        let mut config = BacktestConfig::new(drawings[50].draw_date, drawings[79].draw_date);
+        config.retrain_every = 7;
+        config.min_training_draws = 20;
+        config.feature_config.window_size = 10;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_walk_forward_has_no_look_ahead() {
+        let drawings = demo_drawings().await;
+        let backtester = Backtester::new(test_config(&drawings));
+        let mut algorithm = RepeatLastDraw::default();
+        let algorithm_config = AlgorithmConfig { lottery_type: LotteryType::Ssq, ..Default::default() };
+
+        let report = backtester.run(&mut algorithm, &algorithm_config, &drawings).await.unwrap();
+
+        assert_eq!(report.summary.draws, 30);
+        // 第 0、7、14、21、28 期前训练
+        assert_eq!(report.summary.retrain_count, 5);
+        assert_eq!(algorithm.train_calls, 5);
+
+        for (entry, i) in report.ledger.iter().zip(50..) {
+            assert_eq!(entry.training_draws, i);
+            assert_eq!(entry.predicted_numbers, drawings[i - 1].winning_numbers);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rolling_window_and_curves() {
+        let drawings = demo_drawings().await;
+        let mut config = test_config(&drawings);
+        config.max_training_draws = Some(25);
+        config.retrain_every = 0;
+        let backtester = Backtester::new(config);
+        let mut algorithm = RepeatLastDraw::default();
+        let algorithm_config = AlgorithmConfig { lottery_type: LotteryType::Ssq, ..Default::default() };
+
+        let report = backtester.run(&mut algorithm, &algorithm_config, &drawings).await.unwrap();
+
+        assert_eq!(report.summary.retrain_count, 1);
+        assert!(report.ledger.iter().all(|e| e.training_draws == 25));
+        assert_eq!(report.roi_curve().len(), report.ledger.len());
+        assert_eq!(report.summary.total_cost, 60.0);
+        // 演示数据没有奖金分配，ROI 为 -100%
+        assert_eq!(report.summary.roi, -1.0);
+    }
+}
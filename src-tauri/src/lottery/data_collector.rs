@@ -1,131 +0,0 @@
-use crate::lottery::errors::LotteryResult as Result;
-use crate::lottery::models::{LotteryDrawing, LotteryType};
-use chrono::{Duration, NaiveDate, Utc};
-use uuid::Uuid;
-
-#[derive(Debug, Clone)]
-pub struct DataCollector {
-    // Placeholder for actual data sources
-}
-
-impl DataCollector {
-    pub fn new() -> Self {
-        Self {}
-    }
-
-    pub async fn collect_historical_data(
-        &mut self,
-        lottery_type: LotteryType,
-        days: i32,
-    ) -> Result<Vec<LotteryDrawing>> {
-        // Generate dummy data for demonstration
-        let mut drawings = Vec::new();
-        let base_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
-        
-        for i in 0..days {
-            let draw_date = base_date + Duration::days(i as i64);
-            let draw_number = format!("2024{:03}", i + 1);
-            
-            let (winning_numbers, special_numbers) = match lottery_type {
-                LotteryType::Ssq => {
-                    let mut numbers: Vec<u32> = (1..=33).collect();
-                    use rand::seq::SliceRandom;
-                    let mut rng = rand::thread_rng();
-                    numbers.shuffle(&mut rng);
-                    let winning = numbers.into_iter().take(6).collect();
-                    
-                    let mut special: Vec<u32> = (1..=16).collect();
-                    special.shuffle(&mut rng);
-                    let special_numbers = Some(special.into_iter().take(1).collect());
-                    
-                    (winning, special_numbers)
-                }
-                LotteryType::Dlt => {
-                    let mut numbers: Vec<u32> = (1..=35).collect();
-                    use rand::seq::SliceRandom;
-                    let mut rng = rand::thread_rng();
-                    numbers.shuffle(&mut rng);
-                    let winning = numbers.into_iter().take(5).collect();
-                    
-                    let mut special: Vec<u32> = (1..=12).collect();
-                    special.shuffle(&mut rng);
-                    let special_numbers = Some(special.into_iter().take(2).collect());
-                    
-                    (winning, special_numbers)
-                }
-                LotteryType::Fc3d => {
-                    let mut numbers: Vec<u32> = (0..=9).collect();
-                    use rand::seq::SliceRandom;
-                    let mut rng = rand::thread_rng();
-                    numbers.shuffle(&mut rng);
-                    let winning = numbers.into_iter().take(3).collect();
-                    
-                    (winning, None)
-                }
-                LotteryType::Pl3 => {
-                    let mut numbers: Vec<u32> = (0..=9).collect();
-                    use rand::seq::SliceRandom;
-                    let mut rng = rand::thread_rng();
-                    numbers.shuffle(&mut rng);
-                    let winning = numbers.into_iter().take(3).collect();
-                    
-                    (winning, None)
-                }
-                LotteryType::Pl5 => {
-                    let mut numbers: Vec<u32> = (0..=9).collect();
-                    use rand::seq::SliceRandom;
-                    let mut rng = rand::thread_rng();
-                    numbers.shuffle(&mut rng);
-                    let winning = numbers.into_iter().take(5).collect();
-                    
-                    (winning, None)
-                }
-                LotteryType::Custom => {
-                    let mut numbers: Vec<u32> = (1..=49).collect();
-                    use rand::seq::SliceRandom;
-                    let mut rng = rand::thread_rng();
-                    numbers.shuffle(&mut rng);
-                    let winning = numbers.into_iter().take(6).collect();
-                    
-                    let mut special: Vec<u32> = (1..=16).collect();
-                    special.shuffle(&mut rng);
-                    let special_numbers = Some(special.into_iter().take(1).collect());
-                    
-                    (winning, special_numbers)
-                }
-            };
-            
-            let drawing = LotteryDrawing {
-                id: Uuid::new_v4(),
-                lottery_type: lottery_type.clone(),
-                draw_number: draw_number.clone(),
-                draw_date,
-                draw_time: None,
-                winning_numbers,
-                special_numbers,
-                jackpot_amount: Some(1000000.0 + (i as f64 * 1000.0)),
-                sales_amount: None,
-                prize_distribution: None,
-                data_source: "dummy".to_string(),
-                verification_status: "verified".to_string(),
-                metadata: None,
-                created_at: Utc::now(),
-                updated_at: Utc::now(),
-                crawled_at: None,
-            };
-            
-            drawings.push(drawing);
-        }
-        
-        Ok(drawings)
-    }
-
-    pub async fn get_recent_drawings(
-        &self,
-        lottery_type: LotteryType,
-        count: i32,
-    ) -> Result<Vec<LotteryDrawing>> {
-        let mut collector = DataCollector::new();
-        collector.collect_historical_data(lottery_type, count).await
-    }
-}
\ No newline at end of file
@@ -0,0 +1,427 @@
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::errors::LotteryError;
+use crate::lottery::models::{LotteryType, LotteryTypeConfig};
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock, RwLock};
+use uuid::Uuid;
+
+// 内置玩法定义，与 migrations 中 lottery_types 的种子数据保持一致；数据库中的配置会覆盖同名条目
+const BUILTIN_GAMES: &str = include_str!("games.json");
+
+// 未注册的玩法回退到该条目
+pub const DEFAULT_CUSTOM_GAME: &str = "custom";
+
+// 自定义玩法名称同时作为 lottery_types.name 与 LotteryType 的字符串形式
+pub fn is_valid_game_name(name: &str) -> bool {
+    (2..=32).contains(&name.len())
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameSpec {
+    pub name: String,
+    pub display_name: String,
+    // 每期开出的主号码个数
+    pub main_count: usize,
+    // 每注需要选择的主号码个数，快乐8 开 20 个号码但每注最多选 10 个
+    pub pick_count: usize,
+    pub main_range: (u32, u32),
+    pub special_count: usize,
+    pub special_range: Option<(u32, u32)>,
+    // 数字型玩法按位开奖 (3D/排列3/排列5)
+    #[serde(default)]
+    pub ordered: bool,
+    #[serde(default)]
+    pub allow_repeats: bool,
+    // 特别号从主号码池剩余号码中摇出 (七乐彩)
+    #[serde(default)]
+    pub special_from_main_pool: bool,
+    #[serde(default)]
+    pub rules: serde_json::Value,
+}
+
+impl GameSpec {
+    // 未注册的自定义玩法不会报错，而是退回 6/49 的 custom 模板；
+    // 需要区分是否已注册时先用 registry().get 检查
+    pub fn for_type(lottery_type: &LotteryType) -> Arc<GameSpec> {
+        registry()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .spec_or_default(&lottery_type.to_string())
+    }
+
+    pub fn from_config(config: &LotteryTypeConfig) -> Result<Self> {
+        let draw_flag = |key: &str| {
+            config.rules
+                .get("draw_rules")
+                .and_then(|r| r.get(key))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false)
+        };
+
+        let main_count = config.total_numbers as usize;
+        let main_range = (config.main_range_start, config.main_range_end);
+        let special_count = config.special_numbers as usize;
+        let special_from_main_pool = draw_flag("special_from_main_pool");
+
+        let pick_count = config.rules
+            .pointer("/selection_rules/main_numbers/max")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(main_count);
+
+        let special_range = if special_count == 0 {
+            None
+        } else if special_from_main_pool {
+            Some(main_range)
+        } else {
+            match config.special_range_end {
+                Some(end) => Some((config.special_range_start.unwrap_or(1), end)),
+                None => {
+                    return Err(LotteryError::ConfigurationError(format!(
+                        "{}: special_range_end is required when special_numbers > 0",
+                        config.name
+                    )));
+                }
+            }
+        };
+
+        let spec = GameSpec {
+            name: config.name.clone(),
+            display_name: config.display_name.clone(),
+            main_count,
+            pick_count,
+            main_range,
+            special_count,
+            special_range,
+            ordered: draw_flag("ordered"),
+            allow_repeats: draw_flag("allow_repeats"),
+            special_from_main_pool,
+            rules: config.rules.clone(),
+        };
+
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    // 生成可写入 lottery_types 的配置，玩法标志合并进 rules 以便 from_config 还原
+    pub fn to_config(&self) -> LotteryTypeConfig {
+        let mut rules = match &self.rules {
+            serde_json::Value::Object(_) => self.rules.clone(),
+            _ => serde_json::json!({}),
+        };
+        rules["draw_rules"] = serde_json::json!({
+            "ordered": self.ordered,
+            "allow_repeats": self.allow_repeats,
+            "special_from_main_pool": self.special_from_main_pool,
+        });
+        rules["selection_rules"]["main_numbers"] = serde_json::json!({
+            "min": self.pick_count,
+            "max": self.pick_count,
+        });
+
+        // 特别号与主号码同池时不单独记录特别号范围
+        let special_range = self.special_range.filter(|_| !self.special_from_main_pool);
+
+        LotteryTypeConfig {
+            id: Uuid::new_v4(),
+            name: self.name.clone(),
+            display_name: self.display_name.clone(),
+            description: None,
+            category: "local".to_string(),
+            total_numbers: self.main_count as u32,
+            special_numbers: self.special_count as u32,
+            main_range_start: self.main_range.0,
+            main_range_end: self.main_range.1,
+            special_range_start: special_range.map(|(start, _)| start),
+            special_range_end: special_range.map(|(_, end)| end),
+            rules,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| -> Result<()> {
+            Err(LotteryError::ConfigurationError(format!("{}: {}", self.name, reason)))
+        };
+
+        if !is_valid_game_name(&self.name) {
+            return invalid("name must be 2-32 lowercase letters, digits or underscores");
+        }
+        if self.main_range.0 >= self.main_range.1 {
+            return invalid("main range start must be less than end");
+        }
+        if self.main_count == 0 || self.pick_count == 0 {
+            return invalid("main_count and pick_count must be positive");
+        }
+        if !self.allow_repeats && self.main_count.max(self.pick_count) > self.main_pool_size() {
+            return invalid("main pool is smaller than the numbers drawn");
+        }
+        match self.special_range {
+            Some((start, end)) if start >= end => invalid("special range start must be less than end"),
+            Some(_) if self.special_count == 0 => invalid("special range given without special numbers"),
+            None if self.special_count > 0 => invalid("special numbers require a special range"),
+            _ if self.special_from_main_pool
+                && self.main_count + self.special_count > self.main_pool_size() =>
+            {
+                invalid("main pool cannot hold main and special numbers")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn main_min(&self) -> u32 {
+        self.main_range.0
+    }
+
+    pub fn main_max(&self) -> u32 {
+        self.main_range.1
+    }
+
+    pub fn main_pool_size(&self) -> usize {
+        (self.main_range.1 - self.main_range.0 + 1) as usize
+    }
+
+    pub fn main_numbers(&self) -> std::ops::RangeInclusive<u32> {
+        self.main_range.0..=self.main_range.1
+    }
+
+    pub fn has_special(&self) -> bool {
+        self.special_count > 0
+    }
+
+    // 没有特别号的玩法返回 0
+    pub fn special_max(&self) -> u32 {
+        self.special_range.map(|(_, end)| end).unwrap_or(0)
+    }
+
+    pub fn special_pool_size(&self) -> usize {
+        self.special_range
+            .map(|(start, end)| (end - start + 1) as usize)
+            .unwrap_or(0)
+    }
+
+    pub fn special_numbers(&self) -> Option<std::ops::RangeInclusive<u32>> {
+        self.special_range.map(|(start, end)| start..=end)
+    }
+
+    pub fn is_valid_main(&self, number: u32) -> bool {
+        self.main_numbers().contains(&number)
+    }
+
+    pub fn is_valid_special(&self, number: u32) -> bool {
+        self.special_numbers().is_some_and(|r| r.contains(&number))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameRegistry {
+    specs: HashMap<String, Arc<GameSpec>>,
+    builtin: HashSet<String>,
+}
+
+impl GameRegistry {
+    pub fn with_builtin() -> Self {
+        let builtin: Vec<GameSpec> = serde_json::from_str(BUILTIN_GAMES)
+            .expect("games.json must contain valid game specs");
+
+        Self {
+            builtin: builtin.iter().map(|spec| spec.name.clone()).collect(),
+            specs: builtin
+                .into_iter()
+                .map(|spec| (spec.name.clone(), Arc::new(spec)))
+                .collect(),
+        }
+    }
+
+    pub fn is_builtin(&self, name: &str) -> bool {
+        self.builtin.contains(name)
+    }
+
+    pub fn register(&mut self, spec: GameSpec) -> Result<()> {
+        spec.validate()?;
+        self.specs.insert(spec.name.clone(), Arc::new(spec));
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<GameSpec>> {
+        self.specs.get(name).cloned()
+    }
+
+    // 内置条目只会被覆盖不会被删除，因此 custom 总是存在
+    fn spec_or_default(&self, name: &str) -> Arc<GameSpec> {
+        self.get(name)
+            .or_else(|| self.get(DEFAULT_CUSTOM_GAME))
+            .expect("built-in custom game spec")
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.specs.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    // 可以直接预测的玩法，不含作为回退的 custom 模板
+    pub fn lottery_types(&self) -> Vec<LotteryType> {
+        self.names()
+            .into_iter()
+            .filter(|name| name != DEFAULT_CUSTOM_GAME)
+            .filter_map(|name| name.parse().ok())
+            .collect()
+    }
+}
+
+pub fn registry() -> &'static RwLock<GameRegistry> {
+    static REGISTRY: OnceLock<RwLock<GameRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(GameRegistry::with_builtin()))
+}
+
+// 注册用户自定义玩法，内置玩法名称不可占用
+pub fn register_custom(spec: GameSpec) -> Result<LotteryType> {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+
+    if registry.is_builtin(&spec.name) {
+        return Err(LotteryError::InvalidParameter(format!(
+            "{} is a built-in lottery type", spec.name
+        )));
+    }
+
+    let lottery_type = LotteryType::Custom(spec.name.clone());
+    registry.register(spec)?;
+    Ok(lottery_type)
+}
+
+// 用 lottery_types 表中的配置覆盖内置定义，返回成功注册的条目数；无效配置跳过并打印原因
+pub fn register_configs(configs: &[LotteryTypeConfig]) -> usize {
+    let mut registry = registry().write().unwrap_or_else(|e| e.into_inner());
+    let mut registered = 0;
+
+    for config in configs.iter().filter(|c| c.is_active) {
+        match GameSpec::from_config(config).and_then(|spec| registry.register(spec)) {
+            Ok(()) => registered += 1,
+            Err(e) => eprintln!("Skipping lottery type {}: {}", config.name, e),
+        }
+    }
+
+    registered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_config(name: &str, total: u32, special: u32, range: (u32, u32), rules: serde_json::Value) -> LotteryTypeConfig {
+        LotteryTypeConfig {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            display_name: name.to_string(),
+            description: None,
+            category: "welfare".to_string(),
+            total_numbers: total,
+            special_numbers: special,
+            main_range_start: range.0,
+            main_range_end: range.1,
+            special_range_start: None,
+            special_range_end: if special > 0 { Some(range.1) } else { None },
+            rules,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_builtin_specs_are_valid() {
+        let registry = GameRegistry::with_builtin();
+
+        for name in ["ssq", "dlt", "fc3d", "pl3", "pl5", "qlc", "kl8", "custom"] {
+            let spec = registry.get(name).unwrap();
+            assert!(spec.validate().is_ok(), "{} should be valid", name);
+        }
+
+        let ssq = registry.get("ssq").unwrap();
+        assert_eq!((ssq.main_count, ssq.special_count, ssq.main_max(), ssq.special_max()), (6, 1, 33, 16));
+
+        let fc3d = registry.get("fc3d").unwrap();
+        assert!(fc3d.ordered && fc3d.allow_repeats);
+        assert_eq!(fc3d.main_min(), 0);
+        assert!(!fc3d.has_special());
+    }
+
+    #[test]
+    fn test_spec_from_config() {
+        let rules = serde_json::json!({
+            "selection_rules": {"main_numbers": {"min": 1, "max": 10}},
+            "draw_rules": {"ordered": false}
+        });
+        let spec = GameSpec::from_config(&create_config("kl8", 20, 0, (1, 80), rules)).unwrap();
+
+        assert_eq!(spec.main_count, 20);
+        assert_eq!(spec.pick_count, 10);
+        assert_eq!(spec.main_pool_size(), 80);
+        assert!(spec.special_range.is_none());
+
+        let spec = GameSpec::from_config(&create_config("ssq", 6, 1, (1, 33), serde_json::json!({}))).unwrap();
+        assert_eq!(spec.special_range, Some((1, 33)));
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        let config = create_config("broken", 10, 0, (1, 5), serde_json::json!({}));
+        assert!(GameSpec::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_registry_overrides_builtin() {
+        let mut registry = GameRegistry::with_builtin();
+        let mut spec = (*registry.get("ssq").unwrap()).clone();
+        spec.display_name = "双色球 (本地)".to_string();
+        registry.register(spec).unwrap();
+
+        assert_eq!(registry.get("ssq").unwrap().display_name, "双色球 (本地)");
+        assert_eq!(registry.spec_or_default("unknown").name, "custom");
+    }
+
+    #[test]
+    fn test_custom_spec_round_trips_through_config() {
+        let spec = GameSpec {
+            name: "lotto_649".to_string(),
+            display_name: "Lotto 6/49".to_string(),
+            main_count: 6,
+            pick_count: 6,
+            main_range: (1, 49),
+            special_count: 1,
+            special_range: Some((1, 49)),
+            ordered: false,
+            allow_repeats: false,
+            special_from_main_pool: true,
+            rules: serde_json::Value::Null,
+        };
+
+        let config = spec.to_config();
+        assert_eq!(config.category, "local");
+        assert!(config.special_range_end.is_none());
+
+        let restored = GameSpec::from_config(&config).unwrap();
+        assert_eq!(restored.rules, config.rules);
+        assert_eq!(GameSpec { rules: serde_json::Value::Null, ..restored }, spec);
+    }
+
+    #[test]
+    fn test_custom_lottery_type_names() {
+        assert!(is_valid_game_name("lotto_649"));
+        assert!(!is_valid_game_name("Lotto"));
+        assert!(!is_valid_game_name("9ball"));
+
+        assert_eq!("lotto_649".parse::<LotteryType>().unwrap(), LotteryType::Custom("lotto_649".to_string()));
+        assert_eq!("ssq".parse::<LotteryType>().unwrap(), LotteryType::Ssq);
+        assert_eq!(LotteryType::Custom("lotto_649".to_string()).to_string(), "lotto_649");
+        assert!("Lotto 649".parse::<LotteryType>().is_err());
+        assert!(register_custom(GameSpec::for_type(&LotteryType::Dlt).as_ref().clone()).is_err());
+    }
+}
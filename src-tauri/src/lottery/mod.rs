@@ -1,6 +1,14 @@
 pub mod models;
 pub mod algorithms;
+pub mod backtest;
 pub mod errors;
-pub mod data_collector;
+pub mod games;
+pub mod prize;
+pub mod settlement;
+pub mod storage;
+pub mod tuning;
+pub mod model_store;
 pub mod api;
 
+#[cfg(test)]
+pub(crate) mod test_support;
@@ -0,0 +1,598 @@
+use crate::lottery::algorithms::algorithm_factory::AlgorithmFactory;
+use crate::lottery::algorithms::calibration::CalibratedModel;
+use crate::lottery::algorithms::traits::{ModelMetadata, ModelPersistence, PredictionAlgorithm};
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::models::{AlgorithmType, LotteryType, ModelTrainingRecord};
+use crate::lottery::storage::TrainingRecordRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+// 应用数据目录下存放模型的子目录
+pub const MODEL_DIR_NAME: &str = "models";
+
+// 记录当前版本号的文件
+const CURRENT_FILE_NAME: &str = "current";
+
+// 写入 model_training_records 时模型元数据之外的信息
+#[derive(Debug, Clone)]
+pub struct TrainingRecordContext {
+    pub strategy_id: Uuid,
+    pub training_data_start: NaiveDate,
+    pub training_data_end: NaiveDate,
+    pub validation_samples: usize,
+    pub training_accuracy: Option<f64>,
+    pub started_at: DateTime<Utc>,
+}
+
+// 目录结构为 <root>/<玩法>/<算法>/v<版本>.json，旁边是 v<版本>.meta.json 和可选的校准参数；
+// 元数据最后写入，没有元数据的版本视为未写完，由 gc 清理
+#[derive(Debug)]
+pub struct ModelStore {
+    root: PathBuf,
+    // 分配版本号、切换当前版本和删除文件时串行执行
+    lock: Mutex<()>,
+}
+
+impl ModelStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn in_app_dir(app_path: &Path) -> Self {
+        Self::new(app_path.join(MODEL_DIR_NAME))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    // 某个版本的模型文件，模型的 save_model / load_model 直接读写它
+    pub fn model_path(&self, lottery_type: &LotteryType, algorithm: AlgorithmType, version: u32) -> Result<PathBuf> {
+        Ok(self.algorithm_dir(lottery_type, algorithm)?.join(model_file_name(version)))
+    }
+
+    pub async fn current_version(&self, lottery_type: &LotteryType, algorithm: AlgorithmType) -> Result<Option<u32>> {
+        let _guard = self.lock.lock().await;
+        read_current(&self.algorithm_dir(lottery_type, algorithm)?)
+    }
+
+    // 把当前版本切换为已保存的 version，之后 load(.., None) 加载该版本
+    pub async fn rollback(&self, lottery_type: &LotteryType, algorithm: AlgorithmType, version: u32) -> Result<ModelMetadata> {
+        let _guard = self.lock.lock().await;
+        let dir = self.algorithm_dir(lottery_type, algorithm)?;
+        let metadata = read_metadata(&dir, version)?;
+        verify(&dir, &metadata)?;
+        write_atomic(&dir.join(CURRENT_FILE_NAME), version.to_string().as_bytes())?;
+        Ok(metadata)
+    }
+
+    // 只保留最新的 keep 个版本，当前版本总是保留；返回删除的版本号，未写完的残留文件一并删除
+    pub async fn gc(&self, lottery_type: &LotteryType, algorithm: AlgorithmType, keep: usize) -> Result<Vec<u32>> {
+        let _guard = self.lock.lock().await;
+        let dir = self.algorithm_dir(lottery_type, algorithm)?;
+        let current = read_current(&dir)?;
+        let complete = complete_versions(&dir)?;
+
+        let mut removed = Vec::new();
+        for &version in &complete[..complete.len().saturating_sub(keep)] {
+            if Some(version) != current {
+                remove_version(&dir, version)?;
+                removed.push(version);
+            }
+        }
+        for version in file_versions(&dir)? {
+            if !complete.contains(&version) {
+                remove_version(&dir, version)?;
+            }
+        }
+
+        Ok(removed)
+    }
+
+    // 保存新版本并在 model_training_records 中登记；保存前先写入 running 记录，结束后更新为 completed 或 failed
+    pub async fn save_trained(
+        &self,
+        model: &dyn PredictionAlgorithm,
+        metadata: &ModelMetadata,
+        records: &dyn TrainingRecordRepository,
+        context: &TrainingRecordContext,
+    ) -> Result<ModelMetadata> {
+        let record_id = Uuid::new_v4();
+        let metadata = ModelMetadata {
+            training_record_id: Some(record_id),
+            ..metadata.clone()
+        };
+        records.save_training_record(&training_record(record_id, &metadata, None, context, "running")).await?;
+
+        match self.save(model, &metadata).await {
+            Ok(stored) => {
+                let path = self.model_path(&stored.lottery_type, stored.algorithm, stored.version)?;
+                records.save_training_record(&training_record(record_id, &stored, Some(&path), context, "completed")).await?;
+                Ok(stored)
+            }
+            Err(e) => {
+                records.save_training_record(&training_record(record_id, &metadata, None, context, "failed")).await?;
+                Err(e)
+            }
+        }
+    }
+
+    // 自定义玩法的名称同样作为目录名，只接受字母、数字、下划线和连字符
+    fn algorithm_dir(&self, lottery_type: &LotteryType, algorithm: AlgorithmType) -> Result<PathBuf> {
+        let game = lottery_type.to_string();
+        if game.is_empty() || !game.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                format!("Lottery type {} cannot be used as a model directory", game)
+            ));
+        }
+        Ok(self.root.join(game).join(algorithm.as_str()))
+    }
+}
+
+#[async_trait]
+impl ModelPersistence for ModelStore {
+    async fn save(&self, model: &dyn PredictionAlgorithm, metadata: &ModelMetadata) -> Result<ModelMetadata> {
+        if !model.is_trained() {
+            return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                format!("Cannot store an untrained {} model", metadata.algorithm)
+            ));
+        }
+
+        let _guard = self.lock.lock().await;
+        let dir = self.algorithm_dir(&metadata.lottery_type, metadata.algorithm)?;
+        std::fs::create_dir_all(&dir).map_err(|e| io_error("create", &dir, e))?;
+
+        // 残留文件也占用版本号，新版本不会读到旧的校准参数
+        let version = file_versions(&dir)?.into_iter().max().unwrap_or(0) + 1;
+        let path = dir.join(model_file_name(version));
+        model.save_model(path_str(&path)?)?;
+
+        let files = model_files(&path);
+        let (model_hash, model_size_bytes) = hash_files(&files)?;
+        let stored = ModelMetadata {
+            version,
+            model_hash,
+            model_size_bytes,
+            calibrated: files.len() > 1,
+            ..metadata.clone()
+        };
+        write_atomic(&metadata_path(&dir, version), &serde_json::to_vec_pretty(&stored)?)?;
+        write_atomic(&dir.join(CURRENT_FILE_NAME), version.to_string().as_bytes())?;
+
+        Ok(stored)
+    }
+
+    async fn load(
+        &self,
+        lottery_type: &LotteryType,
+        algorithm: AlgorithmType,
+        version: Option<u32>,
+    ) -> Result<(Box<dyn PredictionAlgorithm>, ModelMetadata)> {
+        let _guard = self.lock.lock().await;
+        let dir = self.algorithm_dir(lottery_type, algorithm)?;
+        let version = match version {
+            Some(version) => version,
+            None => read_current(&dir)?.ok_or_else(|| crate::lottery::errors::LotteryError::NotFound(
+                format!("No stored {} model for {}", algorithm, lottery_type)
+            ))?,
+        };
+        let metadata = read_metadata(&dir, version)?;
+        verify(&dir, &metadata)?;
+
+        let path = dir.join(model_file_name(version));
+        let mut model = AlgorithmFactory::new(lottery_type.clone()).create_algorithm(algorithm, metadata.config.clone())?;
+        if metadata.calibrated {
+            model = Box::new(CalibratedModel::load(model, lottery_type.clone(), path_str(&path)?)?);
+        } else {
+            model.load_model(path_str(&path)?)?;
+        }
+
+        Ok((model, metadata))
+    }
+
+    async fn list_models(&self, lottery_type: &LotteryType, algorithm: AlgorithmType) -> Result<Vec<ModelMetadata>> {
+        let _guard = self.lock.lock().await;
+        let dir = self.algorithm_dir(lottery_type, algorithm)?;
+        complete_versions(&dir)?
+            .into_iter()
+            .map(|version| read_metadata(&dir, version))
+            .collect()
+    }
+
+    // 不能删除当前版本，需要先回滚到其他版本
+    async fn delete_model(&self, lottery_type: &LotteryType, algorithm: AlgorithmType, version: u32) -> Result<()> {
+        let _guard = self.lock.lock().await;
+        let dir = self.algorithm_dir(lottery_type, algorithm)?;
+        if read_current(&dir)? == Some(version) {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                format!("Version {} is the current {} model for {}", version, algorithm, lottery_type)
+            ));
+        }
+        read_metadata(&dir, version)?;
+        remove_version(&dir, version)
+    }
+}
+
+fn model_file_name(version: u32) -> String {
+    format!("v{}.json", version)
+}
+
+fn metadata_path(dir: &Path, version: u32) -> PathBuf {
+    dir.join(format!("v{}.meta.json", version))
+}
+
+// 模型文件及其校准参数 (如果有)
+fn model_files(path: &Path) -> Vec<PathBuf> {
+    let mut files = vec![path.to_path_buf()];
+    let calibration = PathBuf::from(CalibratedModel::calibration_path(&path.to_string_lossy()));
+    if calibration.exists() {
+        files.push(calibration);
+    }
+    files
+}
+
+fn hash_files(files: &[PathBuf]) -> Result<(String, u64)> {
+    let mut hasher = Sha256::new();
+    let mut size = 0;
+    for file in files {
+        let bytes = std::fs::read(file).map_err(|e| io_error("read", file, e))?;
+        size += bytes.len() as u64;
+        hasher.update(&bytes);
+    }
+    Ok((format!("{:x}", hasher.finalize()), size))
+}
+
+fn verify(dir: &Path, metadata: &ModelMetadata) -> Result<()> {
+    let (model_hash, model_size_bytes) = hash_files(&model_files(&dir.join(model_file_name(metadata.version))))?;
+    if model_hash != metadata.model_hash || model_size_bytes != metadata.model_size_bytes {
+        return Err(crate::lottery::errors::LotteryError::ValidationError(
+            format!("Stored {} model v{} does not match its recorded hash", metadata.algorithm, metadata.version)
+        ));
+    }
+    Ok(())
+}
+
+fn read_metadata(dir: &Path, version: u32) -> Result<ModelMetadata> {
+    let path = metadata_path(dir, version);
+    match std::fs::read(&path) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(crate::lottery::errors::LotteryError::NotFound(
+            format!("Model version {} not found in {}", version, dir.display())
+        )),
+        Err(e) => Err(io_error("read", &path, e)),
+    }
+}
+
+fn read_current(dir: &Path) -> Result<Option<u32>> {
+    let path = dir.join(CURRENT_FILE_NAME);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => content.trim().parse().map(Some).map_err(|_| {
+            crate::lottery::errors::LotteryError::ValidationError(
+                format!("Invalid current version in {}", path.display())
+            )
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(io_error("read", &path, e)),
+    }
+}
+
+// 目录中出现过的版本号，包括没有元数据的残留文件
+fn file_versions(dir: &Path) -> Result<Vec<u32>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(io_error("read", dir, e)),
+    };
+
+    let mut versions: Vec<u32> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| parse_version(&entry.file_name().to_string_lossy()))
+        .collect();
+    versions.sort_unstable();
+    versions.dedup();
+    Ok(versions)
+}
+
+fn complete_versions(dir: &Path) -> Result<Vec<u32>> {
+    Ok(file_versions(dir)?
+        .into_iter()
+        .filter(|&version| metadata_path(dir, version).exists())
+        .collect())
+}
+
+fn parse_version(file_name: &str) -> Option<u32> {
+    let (version, _) = file_name.strip_prefix('v')?.split_once('.')?;
+    version.parse().ok()
+}
+
+// 先删元数据，中途失败时剩下的文件按残留处理
+fn remove_version(dir: &Path, version: u32) -> Result<()> {
+    let metadata = metadata_path(dir, version);
+    if metadata.exists() {
+        std::fs::remove_file(&metadata).map_err(|e| io_error("remove", &metadata, e))?;
+    }
+    for entry in std::fs::read_dir(dir).map_err(|e| io_error("read", dir, e))?.filter_map(|entry| entry.ok()) {
+        if parse_version(&entry.file_name().to_string_lossy()) == Some(version) {
+            let path = entry.path();
+            std::fs::remove_file(&path).map_err(|e| io_error("remove", &path, e))?;
+        }
+    }
+    Ok(())
+}
+
+// 先写临时文件再改名，读取方不会看到写了一半的内容
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents).map_err(|e| io_error("write", &tmp, e))?;
+    std::fs::rename(&tmp, path).map_err(|e| io_error("write", path, e))
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str().ok_or_else(|| crate::lottery::errors::LotteryError::InvalidParameter(
+        format!("Model path {} is not valid UTF-8", path.display())
+    ))
+}
+
+fn io_error(action: &str, path: &Path, e: std::io::Error) -> crate::lottery::errors::LotteryError {
+    crate::lottery::errors::LotteryError::AlgorithmError(format!("Failed to {} {}: {}", action, path.display(), e))
+}
+
+fn training_record(
+    id: Uuid,
+    metadata: &ModelMetadata,
+    model_path: Option<&Path>,
+    context: &TrainingRecordContext,
+    status: &str,
+) -> ModelTrainingRecord {
+    let finished = status != "running";
+    let model_metrics = serde_json::json!({
+        "algorithm": metadata.algorithm,
+        "version": finished.then_some(metadata.version),
+        "calibrated": metadata.calibrated,
+        "evaluation": metadata.evaluation_metrics,
+    });
+    let training_duration = format!(
+        "{:.3} seconds",
+        (metadata.training_date - context.started_at).num_milliseconds() as f64 / 1000.0
+    );
+
+    ModelTrainingRecord {
+        id,
+        strategy_id: context.strategy_id,
+        training_data_start: context.training_data_start,
+        training_data_end: context.training_data_end,
+        training_samples: metadata.training_samples as u32,
+        validation_samples: context.validation_samples as u32,
+        test_samples: 0,
+        model_parameters: serde_json::json!({
+            "parameters": metadata.config.parameters,
+            "hyperparameters": metadata.config.hyperparameters,
+        }),
+        training_accuracy: context.training_accuracy,
+        validation_accuracy: Some(metadata.evaluation_metrics.accuracy),
+        test_accuracy: None,
+        model_metrics,
+        model_path: model_path.map(|path| path.display().to_string()),
+        model_hash: model_path.map(|_| metadata.model_hash.clone()),
+        model_size_bytes: model_path.map(|_| metadata.model_size_bytes),
+        training_duration: Some(training_duration),
+        status: status.to_string(),
+        created_at: context.started_at,
+        started_at: Some(context.started_at),
+        completed_at: finished.then(Utc::now),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::algorithms::calibration::CalibrationMethod;
+    use crate::lottery::algorithms::traits::{AlgorithmConfig, EvaluationMetrics, TrainingData};
+    use std::collections::HashMap;
+    use std::sync::Mutex as StdMutex;
+
+    #[derive(Debug, Default)]
+    struct MemoryRecords {
+        records: StdMutex<HashMap<Uuid, ModelTrainingRecord>>,
+    }
+
+    #[async_trait]
+    impl TrainingRecordRepository for MemoryRecords {
+        async fn save_training_record(&self, record: &ModelTrainingRecord) -> Result<()> {
+            self.records.lock().unwrap().insert(record.id, record.clone());
+            Ok(())
+        }
+
+        async fn get_training_record(&self, id: Uuid) -> Result<Option<ModelTrainingRecord>> {
+            Ok(self.records.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn get_training_records(&self, strategy_id: Uuid) -> Result<Vec<ModelTrainingRecord>> {
+            Ok(self.records.lock().unwrap().values().filter(|r| r.strategy_id == strategy_id).cloned().collect())
+        }
+    }
+
+    fn temp_store() -> ModelStore {
+        ModelStore::new(std::env::temp_dir().join(format!("model-store-{}", Uuid::new_v4())))
+    }
+
+    fn training_data() -> TrainingData {
+        TrainingData {
+            features: (0..40).map(|i| vec![i as f64 / 40.0; 10]).collect(),
+            targets: (0..40u32).map(|i| (0..6).map(|k| (i * 5 + k * 5) % 33 + 1).collect()).collect(),
+            special_targets: None,
+            weights: None,
+        }
+    }
+
+    async fn trained_model(data: &TrainingData) -> Box<dyn PredictionAlgorithm> {
+        let config = AlgorithmConfig::default();
+        let mut model = AlgorithmFactory::new(LotteryType::Ssq)
+            .create_algorithm(AlgorithmType::Statistical, config.clone())
+            .unwrap();
+        model.train(data, &config).await.unwrap();
+        model
+    }
+
+    fn metadata(accuracy: f64) -> ModelMetadata {
+        let metrics = EvaluationMetrics { accuracy, ..Default::default() };
+        ModelMetadata::new(AlgorithmType::Statistical, AlgorithmConfig::default(), metrics, 40)
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_versions() {
+        let store = temp_store();
+        let data = training_data();
+        let model = trained_model(&data).await;
+
+        let first = store.save(model.as_ref(), &metadata(0.1)).await.unwrap();
+        let timed = ModelMetadata { training_time_ms: Some(250), ..metadata(0.2) };
+        let second = store.save(model.as_ref(), &timed).await.unwrap();
+        assert_eq!((first.version, second.version), (1, 2));
+        assert_eq!(first.model_hash.len(), 64);
+        assert_eq!(first.model_hash, second.model_hash);
+        let path = store.model_path(&LotteryType::Ssq, AlgorithmType::Statistical, 2).unwrap();
+        assert_eq!(second.model_size_bytes, std::fs::metadata(path).unwrap().len());
+
+        let listed = store.list_models(&LotteryType::Ssq, AlgorithmType::Statistical).await.unwrap();
+        assert_eq!(listed.iter().map(|m| m.version).collect::<Vec<_>>(), vec![1, 2]);
+
+        let (loaded, loaded_metadata) = store.load(&LotteryType::Ssq, AlgorithmType::Statistical, None).await.unwrap();
+        assert!(loaded.is_trained());
+        assert_eq!(loaded_metadata.version, 2);
+        assert_eq!(loaded_metadata.evaluation_metrics.accuracy, 0.2);
+        assert_eq!(loaded_metadata.training_time_ms, Some(250));
+        assert_eq!(loaded.predict_scores(&data).await.unwrap(), model.predict_scores(&data).await.unwrap());
+
+        let untrained = AlgorithmFactory::new(LotteryType::Ssq)
+            .create_algorithm(AlgorithmType::Statistical, AlgorithmConfig::default())
+            .unwrap();
+        assert!(store.save(untrained.as_ref(), &metadata(0.0)).await.is_err());
+
+        let missing = store.load(&LotteryType::Ssq, AlgorithmType::Markov, None).await;
+        assert!(matches!(missing, Err(crate::lottery::errors::LotteryError::NotFound(_))));
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rollback_gc_and_delete() {
+        let store = temp_store();
+        let model = trained_model(&training_data()).await;
+        for i in 0..4 {
+            store.save(model.as_ref(), &metadata(i as f64 / 10.0)).await.unwrap();
+        }
+
+        store.rollback(&LotteryType::Ssq, AlgorithmType::Statistical, 1).await.unwrap();
+        assert_eq!(store.current_version(&LotteryType::Ssq, AlgorithmType::Statistical).await.unwrap(), Some(1));
+        let (_, loaded) = store.load(&LotteryType::Ssq, AlgorithmType::Statistical, None).await.unwrap();
+        assert_eq!(loaded.version, 1);
+
+        assert!(store.delete_model(&LotteryType::Ssq, AlgorithmType::Statistical, 1).await.is_err());
+        assert!(store.rollback(&LotteryType::Ssq, AlgorithmType::Statistical, 9).await.is_err());
+
+        // 没有元数据的版本是写了一半的残留
+        let orphan = store.model_path(&LotteryType::Ssq, AlgorithmType::Statistical, 5).unwrap();
+        std::fs::write(&orphan, "{}").unwrap();
+
+        let removed = store.gc(&LotteryType::Ssq, AlgorithmType::Statistical, 2).await.unwrap();
+        assert_eq!(removed, vec![2]);
+        assert!(!orphan.exists());
+        let versions: Vec<u32> = store.list_models(&LotteryType::Ssq, AlgorithmType::Statistical).await.unwrap()
+            .iter().map(|m| m.version).collect();
+        assert_eq!(versions, vec![1, 3, 4]);
+
+        store.delete_model(&LotteryType::Ssq, AlgorithmType::Statistical, 3).await.unwrap();
+        let saved = store.save(model.as_ref(), &metadata(0.5)).await.unwrap();
+        assert_eq!(saved.version, 5);
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_load_rejects_modified_model() {
+        let store = temp_store();
+        let model = trained_model(&training_data()).await;
+        store.save(model.as_ref(), &metadata(0.1)).await.unwrap();
+
+        let path = store.model_path(&LotteryType::Ssq, AlgorithmType::Statistical, 1).unwrap();
+        let mut content = std::fs::read_to_string(&path).unwrap();
+        content.push(' ');
+        std::fs::write(&path, content).unwrap();
+
+        let result = store.load(&LotteryType::Ssq, AlgorithmType::Statistical, Some(1)).await;
+        assert!(matches!(result, Err(crate::lottery::errors::LotteryError::ValidationError(_))));
+
+        let custom = LotteryType::Custom("../escape".to_string());
+        assert!(store.save(model.as_ref(), &ModelMetadata { lottery_type: custom, ..metadata(0.1) }).await.is_err());
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_calibrated_model_round_trip() {
+        let store = temp_store();
+        let data = training_data();
+        let (train_set, validation_set) = data.split_at(30);
+        let inner = trained_model(&train_set).await;
+        let model = CalibratedModel::fit(inner, LotteryType::Ssq, &validation_set, CalibrationMethod::Platt).await.unwrap();
+
+        let stored = store.save(&model, &metadata(0.1)).await.unwrap();
+        assert!(stored.calibrated);
+        let calibration = CalibratedModel::calibration_path(
+            store.model_path(&LotteryType::Ssq, AlgorithmType::Statistical, 1).unwrap().to_str().unwrap()
+        );
+        let model_file = store.model_path(&LotteryType::Ssq, AlgorithmType::Statistical, 1).unwrap();
+        assert_eq!(
+            stored.model_size_bytes,
+            std::fs::metadata(model_file).unwrap().len() + std::fs::metadata(calibration).unwrap().len()
+        );
+
+        let (loaded, _) = store.load(&LotteryType::Ssq, AlgorithmType::Statistical, None).await.unwrap();
+        let input_scores = loaded.predict_scores(&validation_set).await.unwrap();
+        assert_eq!(input_scores, model.predict_scores(&validation_set).await.unwrap());
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_save_trained_records_model() {
+        let store = temp_store();
+        let records = MemoryRecords::default();
+        let model = trained_model(&training_data()).await;
+        let context = TrainingRecordContext {
+            strategy_id: Uuid::new_v4(),
+            training_data_start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            training_data_end: NaiveDate::from_ymd_opt(2024, 6, 30).unwrap(),
+            validation_samples: 10,
+            training_accuracy: Some(0.3),
+            started_at: Utc::now(),
+        };
+
+        let stored = store.save_trained(model.as_ref(), &metadata(0.2), &records, &context).await.unwrap();
+        let record = records.get_training_record(stored.training_record_id.unwrap()).await.unwrap().unwrap();
+        assert_eq!(record.status, "completed");
+        assert_eq!(record.strategy_id, context.strategy_id);
+        assert_eq!(record.model_hash.as_deref(), Some(stored.model_hash.as_str()));
+        assert_eq!(record.model_size_bytes, Some(stored.model_size_bytes));
+        assert_eq!(record.validation_accuracy, Some(0.2));
+        assert!(record.model_path.unwrap().ends_with("v1.json"));
+
+        let untrained = AlgorithmFactory::new(LotteryType::Ssq)
+            .create_algorithm(AlgorithmType::Statistical, AlgorithmConfig::default())
+            .unwrap();
+        assert!(store.save_trained(untrained.as_ref(), &metadata(0.0), &records, &context).await.is_err());
+        let statuses: Vec<String> = records.get_training_records(context.strategy_id).await.unwrap()
+            .into_iter().map(|r| r.status).collect();
+        assert!(statuses.contains(&"failed".to_string()));
+
+        std::fs::remove_dir_all(store.root()).unwrap();
+    }
+}
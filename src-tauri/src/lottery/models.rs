@@ -4,16 +4,21 @@ use chrono::{DateTime, Utc, NaiveDate};
 use std::collections::HashMap;
 
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, Hash)]
-#[sqlx(type_name = "lottery_type", rename_all = "lowercase")]
+// 数据库中以 lottery_types.name 存储；Custom 携带用户注册的玩法名称，号码规则见 games::GameSpec
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum LotteryType {
     Ssq,    // 双色球
     Dlt,    // 大乐透
     Fc3d,   // 福彩3D
     Pl3,    // 排列3
     Pl5,    // 排列5
-    Custom,
+    Custom(String),
+}
 
+impl LotteryType {
+    pub fn is_custom(&self) -> bool {
+        matches!(self, LotteryType::Custom(_))
+    }
 }
 
 impl std::fmt::Display for LotteryType {
@@ -24,22 +29,92 @@ impl std::fmt::Display for LotteryType {
             LotteryType::Fc3d => write!(f, "fc3d"),
             LotteryType::Pl3 => write!(f, "pl3"),
             LotteryType::Pl5 => write!(f, "pl5"),
-            LotteryType::Custom => write!(f, "custom"),
+            LotteryType::Custom(name) => write!(f, "{}", name),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+impl std::str::FromStr for LotteryType {
+    type Err = crate::lottery::errors::LotteryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ssq" => Ok(LotteryType::Ssq),
+            "dlt" => Ok(LotteryType::Dlt),
+            "fc3d" => Ok(LotteryType::Fc3d),
+            "pl3" => Ok(LotteryType::Pl3),
+            "pl5" => Ok(LotteryType::Pl5),
+            name if crate::lottery::games::is_valid_game_name(name) => Ok(LotteryType::Custom(name.to_string())),
+            _ => Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                format!("Unknown lottery type: {}", s)
+            )),
+        }
+    }
+}
+
+// 算法标识，字符串形式 (如 "random_forest") 与数据库 algorithm_type 列的取值一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
 #[sqlx(type_name = "algorithm_type", rename_all = "snake_case")]
 pub enum AlgorithmType {
     RandomForest,
+    GradientBoosting,
     Lstm,
     Arima,
     Statistical,
+    Bayesian,
+    Markov,
     NeuralNetwork,
     Hybrid,
 }
 
+impl AlgorithmType {
+    pub const ALL: [AlgorithmType; 9] = [
+        AlgorithmType::RandomForest,
+        AlgorithmType::GradientBoosting,
+        AlgorithmType::Lstm,
+        AlgorithmType::Arima,
+        AlgorithmType::Statistical,
+        AlgorithmType::Bayesian,
+        AlgorithmType::Markov,
+        AlgorithmType::NeuralNetwork,
+        AlgorithmType::Hybrid,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlgorithmType::RandomForest => "random_forest",
+            AlgorithmType::GradientBoosting => "gradient_boosting",
+            AlgorithmType::Lstm => "lstm",
+            AlgorithmType::Arima => "arima",
+            AlgorithmType::Statistical => "statistical",
+            AlgorithmType::Bayesian => "bayesian",
+            AlgorithmType::Markov => "markov",
+            AlgorithmType::NeuralNetwork => "neural_network",
+            AlgorithmType::Hybrid => "hybrid",
+        }
+    }
+}
+
+impl std::fmt::Display for AlgorithmType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for AlgorithmType {
+    type Err = crate::lottery::errors::LotteryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AlgorithmType::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.as_str() == s)
+            .ok_or_else(|| crate::lottery::errors::LotteryError::NotFound(
+                format!("Unknown algorithm: {}", s)
+            ))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LotteryTypeConfig {
     pub id: Uuid,
@@ -111,6 +186,8 @@ pub struct PredictionResult {
     pub predicted_special_numbers: Option<Vec<u32>>,
     pub confidence_scores: Vec<f64>,
     pub target_draw_date: NaiveDate,
+    // 核验时对应的开奖记录
+    pub actual_draw_id: Option<Uuid>,
     pub prediction_type: String,
     pub accuracy_score: Option<f64>,
     pub match_count: u32,
@@ -0,0 +1,337 @@
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::errors::LotteryError;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{LotteryDrawing, LotteryType, PredictionResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrizeDistribution {
+    // 奖金由当期奖池按注数分配 (双色球/大乐透高等奖)
+    PariMutuel,
+    // 每注奖金固定 (3D/排列3/排列5)
+    Fixed,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DigitMatch {
+    // 每个数字 (含重复次数) 都一致
+    Exact,
+    // 组选：数字及其重复次数一致，顺序不限，例如 1-2-2 与 2-1-2
+    Group,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DigitOrder {
+    Exact,
+    Any,
+}
+
+// prize_rules.tier_requirements 的两种写法：乐透型按主号/特别号命中个数，数字型按位或按组合匹配；
+// amount 为该奖级的固定单注奖金，未设置时按当期公布的浮动奖金计算
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum TierRequirement {
+    Count {
+        tier: u32,
+        main: usize,
+        #[serde(default)]
+        special: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        amount: Option<f64>,
+    },
+    Digits {
+        tier: u32,
+        #[serde(rename = "match")]
+        digit_match: DigitMatch,
+        order: DigitOrder,
+        // 限定开奖号码是否含重复数字，用于区分组三 (true) 与组六 (false)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        repeats: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        amount: Option<f64>,
+    },
+}
+
+impl TierRequirement {
+    pub fn tier(&self) -> u32 {
+        match self {
+            TierRequirement::Count { tier, .. } | TierRequirement::Digits { tier, .. } => *tier,
+        }
+    }
+
+    pub fn amount(&self) -> Option<f64> {
+        match self {
+            TierRequirement::Count { amount, .. } | TierRequirement::Digits { amount, .. } => *amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PrizeRules {
+    pub tiers: u32,
+    pub distribution: PrizeDistribution,
+    pub tier_requirements: Vec<TierRequirement>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Ticket {
+    pub main_numbers: Vec<u32>,
+    #[serde(default)]
+    pub special_numbers: Vec<u32>,
+}
+
+impl Ticket {
+    pub fn new(main_numbers: Vec<u32>, special_numbers: Vec<u32>) -> Self {
+        Self { main_numbers, special_numbers }
+    }
+
+    pub fn from_prediction(prediction: &PredictionResult) -> Self {
+        Self {
+            main_numbers: prediction.predicted_numbers.clone(),
+            special_numbers: prediction.predicted_special_numbers.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PrizeOutcome {
+    pub match_count: u32,
+    pub special_match_count: u32,
+    pub prize_tier: Option<u32>,
+    // 开奖数据缺少该奖级的奖金时为 None
+    pub prize_amount: Option<f64>,
+}
+
+impl PrizeOutcome {
+    pub fn is_winner(&self) -> bool {
+        self.prize_tier.is_some()
+    }
+
+    pub fn apply_to(&self, prediction: &mut PredictionResult) {
+        prediction.match_count = self.match_count;
+        prediction.special_match_count = self.special_match_count;
+        prediction.is_winner = self.is_winner();
+        prediction.prize_tier = self.prize_tier;
+        prediction.prize_amount = self.prize_amount;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PrizeEvaluator {
+    spec: Arc<GameSpec>,
+    rules: PrizeRules,
+}
+
+impl PrizeEvaluator {
+    pub fn for_type(lottery_type: &LotteryType) -> Result<Self> {
+        Self::from_spec(GameSpec::for_type(lottery_type))
+    }
+
+    pub fn from_spec(spec: Arc<GameSpec>) -> Result<Self> {
+        let prize_rules = spec.rules.get("prize_rules").cloned().ok_or_else(|| {
+            LotteryError::ConfigurationError(format!("{}: prize_rules is missing", spec.name))
+        })?;
+
+        let mut rules: PrizeRules = serde_json::from_value(prize_rules).map_err(|e| {
+            LotteryError::ConfigurationError(format!("{}: invalid prize_rules: {}", spec.name, e))
+        })?;
+        // 按奖级从高到低匹配，命中即返回
+        rules.tier_requirements.sort_by_key(|r| r.tier());
+
+        Ok(Self { spec, rules })
+    }
+
+    pub fn rules(&self) -> &PrizeRules {
+        &self.rules
+    }
+
+    pub fn evaluate(&self, ticket: &Ticket, drawing: &LotteryDrawing) -> PrizeOutcome {
+        let winning = &drawing.winning_numbers;
+        let specials = drawing.special_numbers.as_deref().unwrap_or_default();
+
+        let (match_count, special_match_count) = if self.spec.ordered {
+            let positional = ticket.main_numbers.iter().zip(winning).filter(|(a, b)| a == b).count();
+            (positional, 0)
+        } else {
+            let drawn: HashSet<u32> = winning.iter().copied().collect();
+            let main_matches = ticket.main_numbers.iter().filter(|n| drawn.contains(n)).count();
+
+            // 七乐彩等玩法的特别号从主号码池摇出，用所选主号码去匹配
+            let special_pool = if self.spec.special_from_main_pool {
+                &ticket.main_numbers
+            } else {
+                &ticket.special_numbers
+            };
+            let special_matches = special_pool.iter().filter(|n| specials.contains(n)).count();
+
+            (main_matches, special_matches)
+        };
+
+        let requirement = self.rules.tier_requirements.iter()
+            .find(|requirement| match requirement {
+                TierRequirement::Count { main, special, .. } => {
+                    *main == match_count && *special == special_match_count
+                }
+                TierRequirement::Digits { digit_match, order, repeats, .. } => {
+                    repeats.is_none_or(|repeats| Self::has_repeats(winning) == repeats)
+                        && Self::digits_match(&ticket.main_numbers, winning, *digit_match, *order)
+                }
+            });
+
+        PrizeOutcome {
+            match_count: match_count as u32,
+            special_match_count: special_match_count as u32,
+            prize_tier: requirement.map(|requirement| requirement.tier()),
+            prize_amount: requirement.and_then(|requirement| self.tier_amount(requirement, drawing)),
+        }
+    }
+
+    fn digits_match(ticket: &[u32], winning: &[u32], digit_match: DigitMatch, order: DigitOrder) -> bool {
+        if ticket.len() != winning.len() {
+            return false;
+        }
+
+        match (digit_match, order) {
+            (DigitMatch::Exact, DigitOrder::Exact) => ticket == winning,
+            // 组选按排序后的数字比较，重复次数也必须一致：1-1-2 不中 1-2-2
+            (DigitMatch::Exact, DigitOrder::Any) | (DigitMatch::Group, _) => {
+                let mut a = ticket.to_vec();
+                let mut b = winning.to_vec();
+                a.sort_unstable();
+                b.sort_unstable();
+                a == b
+            }
+        }
+    }
+
+    fn has_repeats(numbers: &[u32]) -> bool {
+        numbers.iter().collect::<HashSet<_>>().len() < numbers.len()
+    }
+
+    // 固定奖级使用规则中的单注奖金；浮动奖级读取开奖数据的 prize_distribution，
+    // 以奖级为键：{"1": 5000000.0} 或 {"1": {"amount": 5000000.0, "winners": 3}}
+    fn tier_amount(&self, requirement: &TierRequirement, drawing: &LotteryDrawing) -> Option<f64> {
+        if let Some(amount) = requirement.amount() {
+            return Some(amount);
+        }
+        if self.rules.distribution == PrizeDistribution::Fixed {
+            return None;
+        }
+
+        let entry = drawing.prize_distribution.as_ref()?.get(requirement.tier().to_string())?;
+        entry.as_f64().or_else(|| entry.get("amount").and_then(|v| v.as_f64()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::test_support::create_drawing;
+    use chrono::{NaiveDate, Utc};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_ssq_tiers() {
+        let evaluator = PrizeEvaluator::for_type(&LotteryType::Ssq).unwrap();
+        let draw_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let drawing = LotteryDrawing {
+            prize_distribution: Some(serde_json::json!({"1": {"amount": 6000000.0, "winners": 2}, "5": 200.0})),
+            ..create_drawing(LotteryType::Ssq, "2024001", draw_date, vec![1, 5, 9, 14, 22, 30], Some(vec![7]))
+        };
+
+        let jackpot = evaluator.evaluate(&Ticket::new(vec![30, 22, 14, 9, 5, 1], vec![7]), &drawing);
+        assert_eq!(jackpot.prize_tier, Some(1));
+        assert_eq!(jackpot.prize_amount, Some(6000000.0));
+
+        let fifth = evaluator.evaluate(&Ticket::new(vec![1, 5, 9, 14, 2, 3], vec![7]), &drawing);
+        assert_eq!((fifth.match_count, fifth.special_match_count), (4, 1));
+        assert_eq!(fifth.prize_tier, Some(5));
+        assert_eq!(fifth.prize_amount, Some(200.0));
+
+        let miss = evaluator.evaluate(&Ticket::new(vec![1, 5, 9, 2, 3, 4], vec![8]), &drawing);
+        assert!(!miss.is_winner());
+        assert_eq!(miss.prize_amount, None);
+    }
+
+    #[test]
+    fn test_dlt_special_matches() {
+        let evaluator = PrizeEvaluator::for_type(&LotteryType::Dlt).unwrap();
+        let draw_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let drawing = create_drawing(LotteryType::Dlt, "2024001", draw_date, vec![3, 8, 15, 21, 33], Some(vec![2, 11]));
+
+        let outcome = evaluator.evaluate(&Ticket::new(vec![3, 8, 15, 1, 2], vec![11, 2]), &drawing);
+        assert_eq!(outcome.prize_tier, Some(6));
+        assert_eq!(outcome.prize_amount, None);
+    }
+
+    #[test]
+    fn test_digit_games() {
+        let evaluator = PrizeEvaluator::for_type(&LotteryType::Fc3d).unwrap();
+        // 固定奖金来自玩法规则，不依赖开奖数据中的 prize_distribution
+        let draw_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let drawing = create_drawing(LotteryType::Fc3d, "2024001", draw_date, vec![1, 2, 2], None);
+
+        let straight = evaluator.evaluate(&Ticket::new(vec![1, 2, 2], vec![]), &drawing);
+        assert_eq!((straight.prize_tier, straight.match_count), (Some(1), 3));
+        assert_eq!(straight.prize_amount, Some(1040.0));
+
+        // 开奖号码含重复数字，任意顺序命中为组三
+        let group3 = evaluator.evaluate(&Ticket::new(vec![2, 1, 2], vec![]), &drawing);
+        assert_eq!((group3.prize_tier, group3.prize_amount), (Some(2), Some(346.0)));
+
+        // 数字集合相同但重复次数不同不算中奖
+        let wrong_repeats = evaluator.evaluate(&Ticket::new(vec![1, 1, 2], vec![]), &drawing);
+        assert!(!wrong_repeats.is_winner());
+        assert_eq!(wrong_repeats.prize_amount, None);
+
+        let drawing = LotteryDrawing {
+            prize_distribution: Some(serde_json::json!({"3": 999.0})),
+            ..create_drawing(LotteryType::Fc3d, "2024001", draw_date, vec![3, 7, 5], None)
+        };
+        let group6 = evaluator.evaluate(&Ticket::new(vec![5, 3, 7], vec![]), &drawing);
+        assert_eq!((group6.prize_tier, group6.prize_amount), (Some(3), Some(173.0)));
+
+        let pl5 = PrizeEvaluator::for_type(&LotteryType::Pl5).unwrap();
+        let drawing = create_drawing(LotteryType::Pl5, "2024001", draw_date, vec![1, 2, 3, 4, 5], None);
+        assert!(!pl5.evaluate(&Ticket::new(vec![5, 4, 3, 2, 1], vec![]), &drawing).is_winner());
+    }
+
+    #[test]
+    fn test_outcome_updates_prediction() {
+        let evaluator = PrizeEvaluator::for_type(&LotteryType::Ssq).unwrap();
+        let draw_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let drawing = create_drawing(LotteryType::Ssq, "2024001", draw_date, vec![1, 2, 3, 4, 5, 6], Some(vec![16]));
+        let mut prediction = PredictionResult {
+            id: Uuid::new_v4(),
+            strategy_id: Uuid::new_v4(),
+            lottery_type: LotteryType::Ssq,
+            predicted_numbers: vec![1, 2, 3, 4, 5, 6],
+            predicted_special_numbers: None,
+            confidence_scores: vec![],
+            target_draw_date: drawing.draw_date,
+            actual_draw_id: None,
+            prediction_type: "single".to_string(),
+            accuracy_score: None,
+            match_count: 0,
+            special_match_count: 0,
+            is_winner: false,
+            prize_tier: None,
+            prize_amount: None,
+            computation_time_ms: 0,
+            feature_vector: None,
+            metadata: None,
+            prediction_date: Utc::now(),
+            validation_date: None,
+        };
+
+        evaluator.evaluate(&Ticket::from_prediction(&prediction), &drawing).apply_to(&mut prediction);
+        assert!(prediction.is_winner);
+        assert_eq!(prediction.prize_tier, Some(2));
+        assert_eq!(prediction.match_count, 6);
+    }
+}
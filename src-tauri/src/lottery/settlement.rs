@@ -0,0 +1,221 @@
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::models::{LotteryDrawing, LotteryType};
+use crate::lottery::prize::{PrizeEvaluator, Ticket};
+use crate::lottery::storage::Storage;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SettlementSummary {
+    pub settled: usize,
+    pub winners: usize,
+    pub strategies_updated: usize,
+}
+
+impl SettlementSummary {
+    pub fn merge(&mut self, other: SettlementSummary) {
+        self.settled += other.settled;
+        self.winners += other.winners;
+        self.strategies_updated += other.strategies_updated;
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IngestSummary {
+    pub saved: usize,
+    pub settlement: SettlementSummary,
+}
+
+// 开奖数据入库的统一入口：写入后立即核验对应期次的预测并更新策略统计
+pub async fn ingest_drawings(storage: &Storage, drawings: &[LotteryDrawing]) -> Result<IngestSummary> {
+    let saved = storage.drawings.save_drawings(drawings).await?;
+    let settlement = settle_drawings(storage, drawings).await?;
+    Ok(IngestSummary { saved, settlement })
+}
+
+// 新开奖数据入库后调用：按彩种核验目标日期不晚于最新一期的待核验预测
+pub async fn settle_drawings(storage: &Storage, drawings: &[LotteryDrawing]) -> Result<SettlementSummary> {
+    let mut latest: HashMap<LotteryType, NaiveDate> = HashMap::new();
+    for drawing in drawings {
+        let date = latest.entry(drawing.lottery_type.clone()).or_insert(drawing.draw_date);
+        *date = (*date).max(drawing.draw_date);
+    }
+
+    let mut summary = SettlementSummary::default();
+    for (lottery_type, up_to) in latest {
+        summary.merge(settle_pending(storage, &lottery_type, up_to).await?);
+    }
+
+    Ok(summary)
+}
+
+// 尚无对应开奖记录的预测保持待核验状态，等下一次调用
+pub async fn settle_pending(
+    storage: &Storage,
+    lottery_type: &LotteryType,
+    up_to: NaiveDate,
+) -> Result<SettlementSummary> {
+    let pending = storage.predictions.get_unvalidated_predictions(lottery_type, up_to).await?;
+    let Some(start) = pending.iter().map(|p| p.target_draw_date).min() else {
+        return Ok(SettlementSummary::default());
+    };
+
+    // 开奖数据以存储中的记录为准，保证 actual_draw_id 指向已入库的行
+    let drawings: HashMap<NaiveDate, LotteryDrawing> = storage.drawings
+        .get_by_date_range(lottery_type, start, up_to)
+        .await?
+        .into_iter()
+        .map(|d| (d.draw_date, d))
+        .collect();
+    // 演示数据不在 lottery_drawings 表中，不能作为外键写入
+    let link_drawings = storage.drawings.backend_name() != "demo";
+
+    let evaluator = PrizeEvaluator::for_type(lottery_type)?;
+    let validation_date = Utc::now();
+    let mut summary = SettlementSummary::default();
+    let mut strategy_ids = BTreeSet::new();
+
+    for mut prediction in pending {
+        let Some(drawing) = drawings.get(&prediction.target_draw_date) else {
+            continue;
+        };
+
+        let outcome = evaluator.evaluate(&Ticket::from_prediction(&prediction), drawing);
+        outcome.apply_to(&mut prediction);
+        prediction.actual_draw_id = link_drawings.then_some(drawing.id);
+        prediction.validation_date = Some(validation_date);
+        storage.predictions.save_prediction(&prediction).await?;
+
+        summary.settled += 1;
+        if outcome.is_winner() {
+            summary.winners += 1;
+        }
+        strategy_ids.insert(prediction.strategy_id);
+    }
+
+    for strategy_id in strategy_ids {
+        if refresh_strategy_stats(storage, strategy_id).await? {
+            summary.strategies_updated += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+// 按已核验的预测重新汇总，重复调用结果不变
+pub async fn refresh_strategy_stats(storage: &Storage, strategy_id: Uuid) -> Result<bool> {
+    let Some(mut strategy) = storage.strategies.get_strategy(strategy_id).await? else {
+        return Ok(false);
+    };
+
+    let predictions = storage.predictions.get_predictions_by_strategy(strategy_id).await?;
+    let settled: Vec<_> = predictions.iter().filter(|p| p.validation_date.is_some()).collect();
+
+    strategy.total_predictions = settled.len() as u32;
+    strategy.successful_predictions = settled.iter().filter(|p| p.is_winner).count() as u32;
+    strategy.updated_at = Utc::now();
+    storage.strategies.save_strategy(&strategy).await?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::models::PredictionResult;
+    use crate::lottery::test_support::create_drawing;
+
+    fn create_prediction(strategy_id: Uuid, target_draw_date: NaiveDate, numbers: Vec<u32>) -> PredictionResult {
+        PredictionResult {
+            id: Uuid::new_v4(),
+            strategy_id,
+            lottery_type: LotteryType::Ssq,
+            predicted_numbers: numbers,
+            predicted_special_numbers: Some(vec![1]),
+            confidence_scores: vec![0.5; 7],
+            target_draw_date,
+            actual_draw_id: None,
+            prediction_type: "standard".to_string(),
+            accuracy_score: None,
+            match_count: 0,
+            special_match_count: 0,
+            is_winner: false,
+            prize_tier: None,
+            prize_amount: None,
+            computation_time_ms: 0,
+            feature_vector: None,
+            metadata: None,
+            prediction_date: Utc::now(),
+            validation_date: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_settle_drawings_updates_predictions_and_strategy() {
+        let storage = Storage::in_memory().await.unwrap();
+
+        let strategy_id = storage.strategies.list_strategies().await.unwrap()[0].id;
+        let first = NaiveDate::from_ymd_opt(2024, 8, 20).unwrap();
+        let second = NaiveDate::from_ymd_opt(2024, 8, 22).unwrap();
+
+        let winner = create_prediction(strategy_id, first, vec![1, 2, 3, 4, 5, 6]);
+        let loser = create_prediction(strategy_id, first, vec![7, 8, 10, 11, 12, 13]);
+        let future = create_prediction(strategy_id, second, vec![1, 2, 3, 4, 5, 6]);
+        for prediction in [&winner, &loser, &future] {
+            storage.predictions.save_prediction(prediction).await.unwrap();
+        }
+
+        let drawing = LotteryDrawing {
+            prize_distribution: Some(serde_json::json!({"2": 150000.0})),
+            ..create_drawing(LotteryType::Ssq, "2024095", first, vec![1, 2, 3, 4, 5, 6], Some(vec![9]))
+        };
+        storage.drawings.save_drawings(std::slice::from_ref(&drawing)).await.unwrap();
+
+        let summary = settle_drawings(&storage, std::slice::from_ref(&drawing)).await.unwrap();
+        assert_eq!(summary, SettlementSummary { settled: 2, winners: 1, strategies_updated: 1 });
+
+        let settled = storage.predictions.get_prediction(winner.id).await.unwrap().unwrap();
+        assert!(settled.is_winner);
+        assert_eq!(settled.prize_tier, Some(2));
+        assert_eq!(settled.prize_amount, Some(150000.0));
+        assert!(settled.actual_draw_id.is_some());
+        assert!(settled.validation_date.is_some());
+
+        let pending = storage.predictions.get_prediction(future.id).await.unwrap().unwrap();
+        assert!(pending.validation_date.is_none());
+
+        let strategy = storage.strategies.get_strategy(strategy_id).await.unwrap().unwrap();
+        assert_eq!((strategy.total_predictions, strategy.successful_predictions), (2, 1));
+
+        // 已核验的预测不会被重复计数
+        let summary = settle_drawings(&storage, &[drawing]).await.unwrap();
+        assert_eq!(summary, SettlementSummary::default());
+        let strategy = storage.strategies.get_strategy(strategy_id).await.unwrap().unwrap();
+        assert_eq!(strategy.total_predictions, 2);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_drawings_settles_pending_predictions() {
+        let storage = Storage::in_memory().await.unwrap();
+
+        let strategy_id = storage.strategies.list_strategies().await.unwrap()[0].id;
+        let draw_date = NaiveDate::from_ymd_opt(2024, 8, 20).unwrap();
+        let prediction = create_prediction(strategy_id, draw_date, vec![1, 2, 3, 4, 5, 6]);
+        storage.predictions.save_prediction(&prediction).await.unwrap();
+
+        let drawing = LotteryDrawing {
+            prize_distribution: Some(serde_json::json!({"2": 150000.0})),
+            ..create_drawing(LotteryType::Ssq, "2024095", draw_date, vec![1, 2, 3, 4, 5, 6], Some(vec![9]))
+        };
+        let summary = ingest_drawings(&storage, &[drawing]).await.unwrap();
+        assert_eq!(summary.saved, 1);
+        assert_eq!(summary.settlement, SettlementSummary { settled: 1, winners: 1, strategies_updated: 1 });
+
+        let settled = storage.predictions.get_prediction(prediction.id).await.unwrap().unwrap();
+        assert!(settled.is_winner);
+        assert_eq!(settled.prize_tier, Some(2));
+        assert!(settled.validation_date.is_some());
+    }
+}
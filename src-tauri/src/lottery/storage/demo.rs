@@ -0,0 +1,198 @@
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::games::GameSpec;
+use crate::lottery::models::{LotteryDrawing, LotteryType};
+use crate::lottery::storage::DrawingRepository;
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Utc};
+use rand::prelude::*;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+// 演示用后端：为每种彩票生成固定种子的随机开奖数据，仅用于界面演示，不可用于训练
+#[derive(Debug)]
+pub struct DemoDrawingRepository {
+    pub history_len: usize,
+    pub seed: u64,
+    drawings: RwLock<HashMap<LotteryType, Vec<LotteryDrawing>>>,
+}
+
+impl DemoDrawingRepository {
+    pub fn new() -> Self {
+        Self::with_history(500, 42)
+    }
+
+    pub fn with_history(history_len: usize, seed: u64) -> Self {
+        Self {
+            history_len,
+            seed,
+            drawings: RwLock::new(HashMap::new()),
+        }
+    }
+
+    async fn ensure_generated(&self, lottery_type: &LotteryType) {
+        if self.drawings.read().await.contains_key(lottery_type) {
+            return;
+        }
+
+        let mut drawings = self.drawings.write().await;
+        drawings
+            .entry(lottery_type.clone())
+            .or_insert_with(|| self.generate(lottery_type));
+    }
+
+    fn generate(&self, lottery_type: &LotteryType) -> Vec<LotteryDrawing> {
+        let type_seed = lottery_type.to_string().bytes().fold(self.seed, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64));
+        let mut rng = StdRng::seed_from_u64(type_seed);
+        let end_date = Utc::now().date_naive();
+        let start_date = end_date - Duration::days(self.history_len as i64 - 1);
+
+        (0..self.history_len)
+            .map(|i| {
+                let draw_date = start_date + Duration::days(i as i64);
+                let (winning_numbers, special_numbers) = Self::draw_numbers(lottery_type, &mut rng);
+
+                LotteryDrawing {
+                    id: Uuid::new_v4(),
+                    lottery_type: lottery_type.clone(),
+                    draw_number: format!("{}{:03}", draw_date.format("%Y"), i + 1),
+                    draw_date,
+                    draw_time: None,
+                    winning_numbers,
+                    special_numbers,
+                    jackpot_amount: Some(1000000.0 + (i as f64 * 1000.0)),
+                    sales_amount: None,
+                    prize_distribution: None,
+                    data_source: "demo".to_string(),
+                    verification_status: "verified".to_string(),
+                    metadata: None,
+                    created_at: Utc::now(),
+                    updated_at: Utc::now(),
+                    crawled_at: None,
+                }
+            })
+            .collect()
+    }
+
+    fn draw_numbers(lottery_type: &LotteryType, rng: &mut StdRng) -> (Vec<u32>, Option<Vec<u32>>) {
+        let spec = GameSpec::for_type(lottery_type);
+
+        if spec.allow_repeats {
+            let winning = (0..spec.main_count).map(|_| rng.gen_range(spec.main_numbers())).collect();
+            return (winning, None);
+        }
+
+        let mut pool: Vec<u32> = spec.main_numbers().collect();
+        pool.shuffle(rng);
+        let winning: Vec<u32> = pool.drain(..spec.main_count).collect();
+
+        let special = match spec.special_numbers() {
+            // 七乐彩的特别号从剩余主号码中摇出
+            Some(_) if spec.special_from_main_pool => Some(pool[..spec.special_count].to_vec()),
+            Some(range) => Some(range.collect::<Vec<u32>>().choose_multiple(rng, spec.special_count).copied().collect()),
+            None => None,
+        };
+
+        (winning, special)
+    }
+}
+
+impl Default for DemoDrawingRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl DrawingRepository for DemoDrawingRepository {
+    fn backend_name(&self) -> &'static str {
+        "demo"
+    }
+
+    async fn get_by_type(&self, lottery_type: &LotteryType) -> Result<Vec<LotteryDrawing>> {
+        self.ensure_generated(lottery_type).await;
+        let drawings = self.drawings.read().await;
+        Ok(drawings.get(lottery_type).cloned().unwrap_or_default())
+    }
+
+    async fn get_by_date_range(
+        &self,
+        lottery_type: &LotteryType,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<LotteryDrawing>> {
+        Ok(self.get_by_type(lottery_type).await?
+            .into_iter()
+            .filter(|d| d.draw_date >= start_date && d.draw_date <= end_date)
+            .collect())
+    }
+
+    async fn get_latest(&self, lottery_type: &LotteryType, count: usize) -> Result<Vec<LotteryDrawing>> {
+        let drawings = self.get_by_type(lottery_type).await?;
+        let start = drawings.len().saturating_sub(count);
+        Ok(drawings[start..].to_vec())
+    }
+
+    async fn save_drawings(&self, new_drawings: &[LotteryDrawing]) -> Result<usize> {
+        for drawing in new_drawings {
+            self.ensure_generated(&drawing.lottery_type).await;
+        }
+
+        let mut drawings = self.drawings.write().await;
+
+        for drawing in new_drawings {
+            let history = drawings.entry(drawing.lottery_type.clone()).or_default();
+            match history.iter_mut().find(|d| d.draw_number == drawing.draw_number) {
+                Some(existing) => *existing = drawing.clone(),
+                None => history.push(drawing.clone()),
+            }
+            history.sort_by_key(|d| d.draw_date);
+        }
+
+        Ok(new_drawings.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_demo_history_is_chronological() {
+        let repository = DemoDrawingRepository::with_history(30, 7);
+        let drawings = repository.get_by_type(&LotteryType::Ssq).await.unwrap();
+
+        assert_eq!(drawings.len(), 30);
+        assert!(drawings.windows(2).all(|w| w[0].draw_date < w[1].draw_date));
+        assert!(drawings.iter().all(|d| d.data_source == "demo"));
+    }
+
+    #[tokio::test]
+    async fn test_demo_latest_and_range() {
+        let repository = DemoDrawingRepository::with_history(30, 7);
+        let all = repository.get_by_type(&LotteryType::Dlt).await.unwrap();
+
+        let latest = repository.get_latest(&LotteryType::Dlt, 5).await.unwrap();
+        assert_eq!(latest.len(), 5);
+        assert_eq!(latest.last().unwrap().draw_number, all.last().unwrap().draw_number);
+
+        let range = repository
+            .get_by_date_range(&LotteryType::Dlt, all[10].draw_date, all[14].draw_date)
+            .await
+            .unwrap();
+        assert_eq!(range.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_demo_generation_is_deterministic() {
+        let first = DemoDrawingRepository::with_history(10, 3);
+        let second = DemoDrawingRepository::with_history(10, 3);
+
+        let a = first.get_by_type(&LotteryType::Fc3d).await.unwrap();
+        let b = second.get_by_type(&LotteryType::Fc3d).await.unwrap();
+        let numbers_a: Vec<_> = a.iter().map(|d| d.winning_numbers.clone()).collect();
+        let numbers_b: Vec<_> = b.iter().map(|d| d.winning_numbers.clone()).collect();
+        assert_eq!(numbers_a, numbers_b);
+        assert!(a.iter().all(|d| d.winning_numbers.len() == 3 && d.winning_numbers.iter().all(|&n| n <= 9)));
+    }
+}
@@ -0,0 +1,213 @@
+pub mod postgres;
+pub mod sqlite;
+pub mod demo;
+
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::models::{
+    LotteryDrawing, LotteryType, LotteryTypeConfig, ModelTrainingRecord, PredictionResult,
+    PredictionStrategy,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::fmt::Debug as DebugTrait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub use demo::DemoDrawingRepository;
+pub use postgres::PgStorage;
+pub use sqlite::SqliteStorage;
+
+pub const SQLITE_FILE_NAME: &str = "lottery.db";
+
+// 所有查询结果均按开奖日期升序返回，最后一条即最新一期
+#[async_trait]
+pub trait DrawingRepository: Send + Sync + DebugTrait {
+    fn backend_name(&self) -> &'static str;
+
+    async fn get_by_type(&self, lottery_type: &LotteryType) -> Result<Vec<LotteryDrawing>>;
+
+    async fn get_by_date_range(
+        &self,
+        lottery_type: &LotteryType,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<LotteryDrawing>>;
+
+    async fn get_latest(&self, lottery_type: &LotteryType, count: usize) -> Result<Vec<LotteryDrawing>>;
+
+    async fn save_drawings(&self, drawings: &[LotteryDrawing]) -> Result<usize>;
+}
+
+#[async_trait]
+pub trait PredictionRepository: Send + Sync + DebugTrait {
+    // 按 id 插入或覆盖
+    async fn save_prediction(&self, prediction: &PredictionResult) -> Result<()>;
+
+    async fn get_prediction(&self, id: Uuid) -> Result<Option<PredictionResult>>;
+
+    async fn get_predictions_by_strategy(&self, strategy_id: Uuid) -> Result<Vec<PredictionResult>>;
+
+    // 尚未核验 (validation_date 为空) 且目标日期不晚于 up_to 的预测
+    async fn get_unvalidated_predictions(
+        &self,
+        lottery_type: &LotteryType,
+        up_to: NaiveDate,
+    ) -> Result<Vec<PredictionResult>>;
+}
+
+#[async_trait]
+pub trait StrategyRepository: Send + Sync + DebugTrait {
+    async fn save_strategy(&self, strategy: &PredictionStrategy) -> Result<()>;
+
+    async fn get_strategy(&self, id: Uuid) -> Result<Option<PredictionStrategy>>;
+
+    async fn list_strategies(&self) -> Result<Vec<PredictionStrategy>>;
+}
+
+#[async_trait]
+pub trait TrainingRecordRepository: Send + Sync + DebugTrait {
+    async fn save_training_record(&self, record: &ModelTrainingRecord) -> Result<()>;
+
+    async fn get_training_record(&self, id: Uuid) -> Result<Option<ModelTrainingRecord>>;
+
+    // 按创建时间倒序
+    async fn get_training_records(&self, strategy_id: Uuid) -> Result<Vec<ModelTrainingRecord>>;
+}
+
+#[async_trait]
+pub trait LotteryTypeRepository: Send + Sync + DebugTrait {
+    async fn list_lottery_types(&self) -> Result<Vec<LotteryTypeConfig>>;
+
+    // 按 name 插入或覆盖，冲突时保留原有 id
+    async fn save_lottery_type(&self, config: &LotteryTypeConfig) -> Result<()>;
+}
+
+#[derive(Debug, Clone)]
+pub struct Storage {
+    pub lottery_types: Arc<dyn LotteryTypeRepository>,
+    pub drawings: Arc<dyn DrawingRepository>,
+    pub predictions: Arc<dyn PredictionRepository>,
+    pub strategies: Arc<dyn StrategyRepository>,
+    pub training_records: Arc<dyn TrainingRecordRepository>,
+}
+
+impl Storage {
+    fn from_backend<T>(backend: Arc<T>) -> Self
+    where
+        T: LotteryTypeRepository
+            + DrawingRepository
+            + PredictionRepository
+            + StrategyRepository
+            + TrainingRecordRepository
+            + 'static,
+    {
+        Self {
+            lottery_types: backend.clone(),
+            drawings: backend.clone(),
+            predictions: backend.clone(),
+            strategies: backend.clone(),
+            training_records: backend,
+        }
+    }
+
+    // 所有仓库都放在内存 SQLite 中，供跨模块的测试使用
+    #[cfg(test)]
+    pub(crate) async fn in_memory() -> Result<Self> {
+        Ok(Self::from_backend(Arc::new(SqliteStorage::in_memory().await?)))
+    }
+
+    // 用数据库中的玩法配置覆盖内置 GameSpec
+    pub async fn load_game_specs(&self) -> Result<usize> {
+        let configs = self.lottery_types.list_lottery_types().await?;
+        Ok(crate::lottery::games::register_configs(&configs))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageBackend {
+    Postgres { database_url: String },
+    Sqlite { path: PathBuf },
+    Demo,
+}
+
+impl StorageBackend {
+    // LOTTERY_STORAGE=demo 显式启用合成数据；LOTTERY_STORAGE=postgres 或设置了 DATABASE_URL 时使用 Postgres；
+    // 默认使用应用数据目录下的 SQLite 文件，桌面端无需额外部署数据库
+    pub fn from_env(app_dir: &Path) -> Self {
+        let database_url = std::env::var("DATABASE_URL").ok();
+
+        match (std::env::var("LOTTERY_STORAGE").as_deref(), database_url) {
+            (Ok("demo"), _) => StorageBackend::Demo,
+            (Ok("sqlite"), _) => StorageBackend::Sqlite { path: app_dir.join(SQLITE_FILE_NAME) },
+            (Ok("postgres"), url) => StorageBackend::Postgres {
+                database_url: url.unwrap_or_else(|| "postgres://localhost/lottery".to_string()),
+            },
+            (_, Some(database_url)) => StorageBackend::Postgres { database_url },
+            (_, None) => StorageBackend::Sqlite { path: app_dir.join(SQLITE_FILE_NAME) },
+        }
+    }
+
+    pub async fn open(&self) -> Result<Storage> {
+        match self {
+            StorageBackend::Postgres { database_url } => {
+                let pool = sqlx::PgPool::connect_lazy(database_url)?;
+                Ok(Storage::from_backend(Arc::new(PgStorage::new(pool))))
+            }
+            StorageBackend::Sqlite { path } => {
+                Ok(Storage::from_backend(Arc::new(SqliteStorage::open(path).await?)))
+            }
+            StorageBackend::Demo => {
+                // 开奖数据为合成数据，其余表放在内存 SQLite 中，退出即丢弃
+                let memory = Arc::new(SqliteStorage::in_memory().await?);
+                Ok(Storage {
+                    drawings: Arc::new(DemoDrawingRepository::new()),
+                    ..Storage::from_backend(memory)
+                })
+            }
+        }
+    }
+}
+
+// 两种数据库后端共用的 lottery_types 读写检查
+#[cfg(test)]
+pub(crate) async fn check_lottery_type_round_trip(repository: &dyn LotteryTypeRepository, name: &str) {
+    use crate::lottery::games::GameSpec;
+
+    let spec = GameSpec {
+        name: name.to_string(),
+        display_name: "Lotto 6/49".to_string(),
+        main_count: 6,
+        pick_count: 6,
+        main_range: (1, 49),
+        special_count: 1,
+        special_range: Some((1, 49)),
+        ordered: false,
+        allow_repeats: false,
+        special_from_main_pool: true,
+        rules: serde_json::Value::Null,
+    };
+    let config = spec.to_config();
+    repository.save_lottery_type(&config).await.unwrap();
+
+    let saved = repository.list_lottery_types().await.unwrap().into_iter().find(|c| c.name == name).unwrap();
+    assert_eq!(saved.id, config.id);
+    assert_eq!(saved.rules, config.rules);
+    assert_eq!(GameSpec { rules: serde_json::Value::Null, ..GameSpec::from_config(&saved).unwrap() }, spec);
+
+    // 同名再次保存时覆盖内容，保留原有 id
+    let renamed = LotteryTypeConfig {
+        id: Uuid::new_v4(),
+        display_name: "Lotto 649".to_string(),
+        ..config.clone()
+    };
+    repository.save_lottery_type(&renamed).await.unwrap();
+
+    let matching: Vec<LotteryTypeConfig> = repository.list_lottery_types().await.unwrap()
+        .into_iter()
+        .filter(|c| c.name == name)
+        .collect();
+    assert_eq!(matching.len(), 1);
+    assert_eq!(matching[0].id, config.id);
+    assert_eq!(matching[0].display_name, "Lotto 649");
+}
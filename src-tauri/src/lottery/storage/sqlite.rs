@@ -0,0 +1,812 @@
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::errors::LotteryError;
+use crate::lottery::models::{
+    LotteryDrawing, LotteryType, LotteryTypeConfig, ModelTrainingRecord, PredictionResult,
+    PredictionStrategy,
+};
+use crate::lottery::storage::{
+    DrawingRepository, LotteryTypeRepository, PredictionRepository, StrategyRepository,
+    TrainingRecordRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteRow};
+use sqlx::types::Json;
+use sqlx::{Row, SqlitePool};
+use std::path::Path;
+use std::str::FromStr;
+use uuid::Uuid;
+
+// 与 Postgres 版本列名一致；数组列以 JSON 文本保存
+const SELECT_LOTTERY_TYPES: &str = r#"
+    SELECT
+        id, name, display_name, description, category, total_numbers,
+        COALESCE(special_numbers, 0) AS special_numbers,
+        main_range_start, main_range_end, special_range_start, special_range_end,
+        rules,
+        COALESCE(is_active, 1) AS is_active,
+        created_at, updated_at
+    FROM lottery_types
+"#;
+
+const SELECT_DRAWINGS: &str = r#"
+    SELECT
+        d.id,
+        lt.name AS lottery_type,
+        d.draw_number,
+        d.draw_date,
+        d.draw_time,
+        d.winning_numbers,
+        d.special_numbers,
+        d.jackpot_amount,
+        d.sales_amount,
+        d.prize_distribution,
+        d.data_source,
+        d.verification_status,
+        d.metadata,
+        d.created_at,
+        d.updated_at,
+        d.crawled_at
+    FROM lottery_drawings d
+    JOIN lottery_types lt ON lt.id = d.lottery_type_id
+"#;
+
+const SELECT_PREDICTIONS: &str = r#"
+    SELECT
+        p.id,
+        p.strategy_id,
+        lt.name AS lottery_type,
+        p.predicted_numbers,
+        p.predicted_special_numbers,
+        p.confidence_scores,
+        p.target_draw_date,
+        p.actual_draw_id,
+        p.prediction_type,
+        p.accuracy_score,
+        p.match_count,
+        p.special_match_count,
+        p.is_winner,
+        p.prize_tier,
+        p.prize_amount,
+        p.computation_time_ms,
+        p.feature_vector,
+        p.metadata,
+        p.prediction_date,
+        p.validation_date
+    FROM prediction_results p
+    JOIN lottery_types lt ON lt.id = p.lottery_type_id
+"#;
+
+const SELECT_STRATEGIES: &str = r#"
+    SELECT
+        id, name, algorithm_type, description, parameters, hyperparameters,
+        accuracy_rate, precision_rate, recall_rate, f1_score,
+        total_predictions, successful_predictions,
+        is_active, is_public, is_system, owner_id, version, created_at, updated_at
+    FROM prediction_strategies
+"#;
+
+const SELECT_TRAINING_RECORDS: &str = r#"
+    SELECT
+        id, strategy_id, training_data_start, training_data_end,
+        training_samples, validation_samples, test_samples, model_parameters,
+        training_accuracy, validation_accuracy, test_accuracy,
+        model_metrics, model_path, model_hash, model_size_bytes, training_duration,
+        status, created_at, started_at, completed_at
+    FROM model_training_records
+"#;
+
+#[derive(Debug, Clone)]
+pub struct SqliteStorage {
+    pub pool: SqlitePool,
+}
+
+impl SqliteStorage {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| {
+                LotteryError::ConfigurationError(format!("无法创建数据目录 {}: {}", parent.display(), e))
+            })?;
+        }
+
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .foreign_keys(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(4)
+            .connect_with(options)
+            .await?;
+
+        Self::migrate(&pool).await?;
+        Ok(Self::new(pool))
+    }
+
+    // 内存数据库只存在于单个连接上，连接不能被回收
+    pub async fn in_memory() -> Result<Self> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")?.foreign_keys(true);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await?;
+
+        Self::migrate(&pool).await?;
+        Ok(Self::new(pool))
+    }
+
+    async fn migrate(pool: &SqlitePool) -> Result<()> {
+        sqlx::migrate!("../migrations/sqlite")
+            .run(pool)
+            .await
+            .map_err(sqlx::Error::from)?;
+        Ok(())
+    }
+
+    fn map_lottery_type_row(row: &SqliteRow) -> Result<LotteryTypeConfig> {
+        Ok(LotteryTypeConfig {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            display_name: row.try_get("display_name")?,
+            description: row.try_get("description")?,
+            category: row.try_get("category")?,
+            total_numbers: row.try_get::<i64, _>("total_numbers")? as u32,
+            special_numbers: row.try_get::<i64, _>("special_numbers")? as u32,
+            main_range_start: row.try_get::<i64, _>("main_range_start")? as u32,
+            main_range_end: row.try_get::<i64, _>("main_range_end")? as u32,
+            special_range_start: row.try_get::<Option<i64>, _>("special_range_start")?.map(|n| n as u32),
+            special_range_end: row.try_get::<Option<i64>, _>("special_range_end")?.map(|n| n as u32),
+            rules: row.try_get("rules")?,
+            is_active: row.try_get("is_active")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+
+    fn map_drawing_row(row: &SqliteRow) -> Result<LotteryDrawing> {
+        let lottery_type: String = row.try_get("lottery_type")?;
+        let winning_numbers: Json<Vec<u32>> = row.try_get("winning_numbers")?;
+        let special_numbers: Option<Json<Vec<u32>>> = row.try_get("special_numbers")?;
+
+        Ok(LotteryDrawing {
+            id: row.try_get("id")?,
+            lottery_type: lottery_type.parse()?,
+            draw_number: row.try_get("draw_number")?,
+            draw_date: row.try_get("draw_date")?,
+            draw_time: row.try_get("draw_time")?,
+            winning_numbers: winning_numbers.0,
+            special_numbers: special_numbers.map(|s| s.0),
+            jackpot_amount: row.try_get("jackpot_amount")?,
+            sales_amount: row.try_get("sales_amount")?,
+            prize_distribution: row.try_get("prize_distribution")?,
+            data_source: row.try_get("data_source")?,
+            verification_status: row.try_get::<Option<String>, _>("verification_status")?
+                .unwrap_or_else(|| "pending".to_string()),
+            metadata: row.try_get("metadata")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            crawled_at: row.try_get("crawled_at")?,
+        })
+    }
+
+    fn map_prediction_row(row: &SqliteRow) -> Result<PredictionResult> {
+        let lottery_type: String = row.try_get("lottery_type")?;
+        let predicted_numbers: Json<Vec<u32>> = row.try_get("predicted_numbers")?;
+        let predicted_special_numbers: Option<Json<Vec<u32>>> = row.try_get("predicted_special_numbers")?;
+        let confidence_scores: Option<Json<Vec<f64>>> = row.try_get("confidence_scores")?;
+        let feature_vector: Option<Json<Vec<f64>>> = row.try_get("feature_vector")?;
+
+        Ok(PredictionResult {
+            id: row.try_get("id")?,
+            strategy_id: row.try_get("strategy_id")?,
+            lottery_type: lottery_type.parse()?,
+            predicted_numbers: predicted_numbers.0,
+            predicted_special_numbers: predicted_special_numbers.map(|s| s.0),
+            confidence_scores: confidence_scores.map(|s| s.0).unwrap_or_default(),
+            target_draw_date: row.try_get("target_draw_date")?,
+            actual_draw_id: row.try_get("actual_draw_id")?,
+            prediction_type: row.try_get::<Option<String>, _>("prediction_type")?
+                .unwrap_or_else(|| "standard".to_string()),
+            accuracy_score: row.try_get("accuracy_score")?,
+            match_count: row.try_get::<Option<i64>, _>("match_count")?.unwrap_or(0) as u32,
+            special_match_count: row.try_get::<Option<i64>, _>("special_match_count")?.unwrap_or(0) as u32,
+            is_winner: row.try_get::<Option<bool>, _>("is_winner")?.unwrap_or(false),
+            prize_tier: row.try_get::<Option<i64>, _>("prize_tier")?.map(|t| t as u32),
+            prize_amount: row.try_get("prize_amount")?,
+            computation_time_ms: row.try_get::<Option<i64>, _>("computation_time_ms")?.unwrap_or(0) as u32,
+            feature_vector: feature_vector.map(|v| v.0),
+            metadata: row.try_get("metadata")?,
+            prediction_date: row.try_get("prediction_date")?,
+            validation_date: row.try_get("validation_date")?,
+        })
+    }
+
+    fn map_strategy_row(row: &SqliteRow) -> Result<PredictionStrategy> {
+        let algorithm_type: String = row.try_get("algorithm_type")?;
+
+        Ok(PredictionStrategy {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            algorithm_type: algorithm_type.parse()?,
+            description: row.try_get("description")?,
+            parameters: row.try_get("parameters")?,
+            hyperparameters: row.try_get("hyperparameters")?,
+            accuracy_rate: row.try_get("accuracy_rate")?,
+            precision_rate: row.try_get("precision_rate")?,
+            recall_rate: row.try_get("recall_rate")?,
+            f1_score: row.try_get("f1_score")?,
+            total_predictions: row.try_get::<Option<i64>, _>("total_predictions")?.unwrap_or(0) as u32,
+            successful_predictions: row.try_get::<Option<i64>, _>("successful_predictions")?.unwrap_or(0) as u32,
+            is_active: row.try_get::<Option<bool>, _>("is_active")?.unwrap_or(true),
+            is_public: row.try_get::<Option<bool>, _>("is_public")?.unwrap_or(false),
+            is_system: row.try_get::<Option<bool>, _>("is_system")?.unwrap_or(false),
+            owner_id: row.try_get("owner_id")?,
+            version: row.try_get::<Option<String>, _>("version")?.unwrap_or_else(|| "1.0.0".to_string()),
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+
+    fn map_training_record_row(row: &SqliteRow) -> Result<ModelTrainingRecord> {
+        Ok(ModelTrainingRecord {
+            id: row.try_get("id")?,
+            strategy_id: row.try_get("strategy_id")?,
+            training_data_start: row.try_get("training_data_start")?,
+            training_data_end: row.try_get("training_data_end")?,
+            training_samples: row.try_get::<i64, _>("training_samples")? as u32,
+            validation_samples: row.try_get::<i64, _>("validation_samples")? as u32,
+            test_samples: row.try_get::<i64, _>("test_samples")? as u32,
+            model_parameters: row.try_get("model_parameters")?,
+            training_accuracy: row.try_get("training_accuracy")?,
+            validation_accuracy: row.try_get("validation_accuracy")?,
+            test_accuracy: row.try_get("test_accuracy")?,
+            model_metrics: row.try_get("model_metrics")?,
+            model_path: row.try_get("model_path")?,
+            model_hash: row.try_get("model_hash")?,
+            model_size_bytes: row.try_get::<Option<i64>, _>("model_size_bytes")?.map(|s| s as u64),
+            training_duration: row.try_get("training_duration")?,
+            status: row.try_get::<Option<String>, _>("status")?.unwrap_or_else(|| "pending".to_string()),
+            created_at: row.try_get("created_at")?,
+            started_at: row.try_get("started_at")?,
+            completed_at: row.try_get("completed_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl LotteryTypeRepository for SqliteStorage {
+    async fn list_lottery_types(&self) -> Result<Vec<LotteryTypeConfig>> {
+        let rows = sqlx::query(&format!("{} ORDER BY name ASC", SELECT_LOTTERY_TYPES))
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(Self::map_lottery_type_row).collect()
+    }
+
+    async fn save_lottery_type(&self, config: &LotteryTypeConfig) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO lottery_types (
+                id, name, display_name, description, category, total_numbers, special_numbers,
+                main_range_start, main_range_end, special_range_start, special_range_end,
+                rules, is_active, created_at, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+            ON CONFLICT (name)
+            DO UPDATE SET
+                display_name = excluded.display_name,
+                description = excluded.description,
+                category = excluded.category,
+                total_numbers = excluded.total_numbers,
+                special_numbers = excluded.special_numbers,
+                main_range_start = excluded.main_range_start,
+                main_range_end = excluded.main_range_end,
+                special_range_start = excluded.special_range_start,
+                special_range_end = excluded.special_range_end,
+                rules = excluded.rules,
+                is_active = excluded.is_active,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(config.id)
+        .bind(&config.name)
+        .bind(&config.display_name)
+        .bind(&config.description)
+        .bind(&config.category)
+        .bind(config.total_numbers as i64)
+        .bind(config.special_numbers as i64)
+        .bind(config.main_range_start as i64)
+        .bind(config.main_range_end as i64)
+        .bind(config.special_range_start.map(|n| n as i64))
+        .bind(config.special_range_end.map(|n| n as i64))
+        .bind(&config.rules)
+        .bind(config.is_active)
+        .bind(config.created_at)
+        .bind(config.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DrawingRepository for SqliteStorage {
+    fn backend_name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    async fn get_by_type(&self, lottery_type: &LotteryType) -> Result<Vec<LotteryDrawing>> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE lt.name = ?1 ORDER BY d.draw_date ASC",
+            SELECT_DRAWINGS
+        ))
+        .bind(lottery_type.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::map_drawing_row).collect()
+    }
+
+    async fn get_by_date_range(
+        &self,
+        lottery_type: &LotteryType,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<LotteryDrawing>> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE lt.name = ?1 AND d.draw_date BETWEEN ?2 AND ?3 ORDER BY d.draw_date ASC",
+            SELECT_DRAWINGS
+        ))
+        .bind(lottery_type.to_string())
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::map_drawing_row).collect()
+    }
+
+    async fn get_latest(&self, lottery_type: &LotteryType, count: usize) -> Result<Vec<LotteryDrawing>> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE lt.name = ?1 ORDER BY d.draw_date DESC LIMIT ?2",
+            SELECT_DRAWINGS
+        ))
+        .bind(lottery_type.to_string())
+        .bind(count as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut drawings = rows.iter().map(Self::map_drawing_row).collect::<Result<Vec<_>>>()?;
+        drawings.reverse();
+        Ok(drawings)
+    }
+
+    async fn save_drawings(&self, drawings: &[LotteryDrawing]) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        let mut saved_count = 0;
+
+        for drawing in drawings {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO lottery_drawings (
+                    id, lottery_type_id, draw_number, draw_date, draw_time,
+                    winning_numbers, special_numbers, jackpot_amount, sales_amount,
+                    prize_distribution, data_source, verification_status, metadata,
+                    created_at, updated_at, crawled_at
+                )
+                SELECT ?1, lt.id, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16
+                FROM lottery_types lt
+                WHERE lt.name = ?2
+                ON CONFLICT (lottery_type_id, draw_number)
+                DO UPDATE SET
+                    updated_at = excluded.updated_at,
+                    verification_status = excluded.verification_status,
+                    winning_numbers = excluded.winning_numbers,
+                    special_numbers = excluded.special_numbers,
+                    jackpot_amount = excluded.jackpot_amount,
+                    sales_amount = excluded.sales_amount,
+                    prize_distribution = excluded.prize_distribution,
+                    crawled_at = excluded.crawled_at
+                "#,
+            )
+            .bind(drawing.id)
+            .bind(drawing.lottery_type.to_string())
+            .bind(&drawing.draw_number)
+            .bind(drawing.draw_date)
+            .bind(&drawing.draw_time)
+            .bind(Json(&drawing.winning_numbers))
+            .bind(drawing.special_numbers.as_ref().map(Json))
+            .bind(drawing.jackpot_amount)
+            .bind(drawing.sales_amount)
+            .bind(&drawing.prize_distribution)
+            .bind(&drawing.data_source)
+            .bind(&drawing.verification_status)
+            .bind(&drawing.metadata)
+            .bind(drawing.created_at)
+            .bind(drawing.updated_at)
+            .bind(drawing.crawled_at)
+            .execute(&mut *tx)
+            .await?;
+
+            saved_count += result.rows_affected() as usize;
+        }
+
+        tx.commit().await?;
+        Ok(saved_count)
+    }
+}
+
+#[async_trait]
+impl PredictionRepository for SqliteStorage {
+    async fn save_prediction(&self, prediction: &PredictionResult) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO prediction_results (
+                id, strategy_id, lottery_type_id, predicted_numbers, predicted_special_numbers,
+                confidence_scores, target_draw_date, prediction_type, accuracy_score,
+                match_count, special_match_count, is_winner, prize_tier, prize_amount,
+                computation_time_ms, feature_vector, metadata, prediction_date, validation_date,
+                actual_draw_id
+            )
+            SELECT ?1, ?2, lt.id, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20
+            FROM lottery_types lt
+            WHERE lt.name = ?3
+            ON CONFLICT (id)
+            DO UPDATE SET
+                accuracy_score = excluded.accuracy_score,
+                match_count = excluded.match_count,
+                special_match_count = excluded.special_match_count,
+                is_winner = excluded.is_winner,
+                prize_tier = excluded.prize_tier,
+                prize_amount = excluded.prize_amount,
+                metadata = excluded.metadata,
+                validation_date = excluded.validation_date,
+                actual_draw_id = excluded.actual_draw_id
+            "#,
+        )
+        .bind(prediction.id)
+        .bind(prediction.strategy_id)
+        .bind(prediction.lottery_type.to_string())
+        .bind(Json(&prediction.predicted_numbers))
+        .bind(prediction.predicted_special_numbers.as_ref().map(Json))
+        .bind(Json(&prediction.confidence_scores))
+        .bind(prediction.target_draw_date)
+        .bind(&prediction.prediction_type)
+        .bind(prediction.accuracy_score)
+        .bind(prediction.match_count as i64)
+        .bind(prediction.special_match_count as i64)
+        .bind(prediction.is_winner)
+        .bind(prediction.prize_tier.map(|t| t as i64))
+        .bind(prediction.prize_amount)
+        .bind(prediction.computation_time_ms as i64)
+        .bind(prediction.feature_vector.as_ref().map(Json))
+        .bind(&prediction.metadata)
+        .bind(prediction.prediction_date)
+        .bind(prediction.validation_date)
+        .bind(prediction.actual_draw_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(LotteryError::NotFound(format!("lottery type {}", prediction.lottery_type)));
+        }
+        Ok(())
+    }
+
+    async fn get_prediction(&self, id: Uuid) -> Result<Option<PredictionResult>> {
+        let row = sqlx::query(&format!("{} WHERE p.id = ?1", SELECT_PREDICTIONS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(Self::map_prediction_row).transpose()
+    }
+
+    async fn get_predictions_by_strategy(&self, strategy_id: Uuid) -> Result<Vec<PredictionResult>> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE p.strategy_id = ?1 ORDER BY p.target_draw_date ASC",
+            SELECT_PREDICTIONS
+        ))
+        .bind(strategy_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::map_prediction_row).collect()
+    }
+
+    async fn get_unvalidated_predictions(
+        &self,
+        lottery_type: &LotteryType,
+        up_to: NaiveDate,
+    ) -> Result<Vec<PredictionResult>> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE lt.name = ?1 AND p.validation_date IS NULL AND p.target_draw_date <= ?2 ORDER BY p.target_draw_date ASC",
+            SELECT_PREDICTIONS
+        ))
+        .bind(lottery_type.to_string())
+        .bind(up_to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::map_prediction_row).collect()
+    }
+}
+
+#[async_trait]
+impl StrategyRepository for SqliteStorage {
+    async fn save_strategy(&self, strategy: &PredictionStrategy) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO prediction_strategies (
+                id, name, algorithm_type, description, parameters, hyperparameters,
+                accuracy_rate, precision_rate, recall_rate, f1_score,
+                total_predictions, successful_predictions, is_active, is_public, is_system,
+                owner_id, version, created_at, updated_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)
+            ON CONFLICT (id)
+            DO UPDATE SET
+                name = excluded.name,
+                algorithm_type = excluded.algorithm_type,
+                description = excluded.description,
+                parameters = excluded.parameters,
+                hyperparameters = excluded.hyperparameters,
+                accuracy_rate = excluded.accuracy_rate,
+                precision_rate = excluded.precision_rate,
+                recall_rate = excluded.recall_rate,
+                f1_score = excluded.f1_score,
+                total_predictions = excluded.total_predictions,
+                successful_predictions = excluded.successful_predictions,
+                is_active = excluded.is_active,
+                is_public = excluded.is_public,
+                version = excluded.version,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(strategy.id)
+        .bind(&strategy.name)
+        .bind(strategy.algorithm_type.to_string())
+        .bind(&strategy.description)
+        .bind(&strategy.parameters)
+        .bind(&strategy.hyperparameters)
+        .bind(strategy.accuracy_rate)
+        .bind(strategy.precision_rate)
+        .bind(strategy.recall_rate)
+        .bind(strategy.f1_score)
+        .bind(strategy.total_predictions as i64)
+        .bind(strategy.successful_predictions as i64)
+        .bind(strategy.is_active)
+        .bind(strategy.is_public)
+        .bind(strategy.is_system)
+        .bind(strategy.owner_id)
+        .bind(&strategy.version)
+        .bind(strategy.created_at)
+        .bind(strategy.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_strategy(&self, id: Uuid) -> Result<Option<PredictionStrategy>> {
+        let row = sqlx::query(&format!("{} WHERE id = ?1", SELECT_STRATEGIES))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(Self::map_strategy_row).transpose()
+    }
+
+    async fn list_strategies(&self) -> Result<Vec<PredictionStrategy>> {
+        let rows = sqlx::query(&format!("{} ORDER BY created_at ASC", SELECT_STRATEGIES))
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(Self::map_strategy_row).collect()
+    }
+}
+
+#[async_trait]
+impl TrainingRecordRepository for SqliteStorage {
+    async fn save_training_record(&self, record: &ModelTrainingRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO model_training_records (
+                id, strategy_id, training_data_start, training_data_end,
+                training_samples, validation_samples, test_samples, model_parameters, feature_config,
+                training_accuracy, validation_accuracy, test_accuracy, model_metrics,
+                model_path, model_hash, model_size_bytes, training_duration,
+                status, created_at, started_at, completed_at
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, '{}', ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
+            ON CONFLICT (id)
+            DO UPDATE SET
+                training_accuracy = excluded.training_accuracy,
+                validation_accuracy = excluded.validation_accuracy,
+                test_accuracy = excluded.test_accuracy,
+                model_metrics = excluded.model_metrics,
+                model_path = excluded.model_path,
+                model_hash = excluded.model_hash,
+                model_size_bytes = excluded.model_size_bytes,
+                training_duration = excluded.training_duration,
+                status = excluded.status,
+                started_at = excluded.started_at,
+                completed_at = excluded.completed_at
+            "#,
+        )
+        .bind(record.id)
+        .bind(record.strategy_id)
+        .bind(record.training_data_start)
+        .bind(record.training_data_end)
+        .bind(record.training_samples as i64)
+        .bind(record.validation_samples as i64)
+        .bind(record.test_samples as i64)
+        .bind(&record.model_parameters)
+        .bind(record.training_accuracy)
+        .bind(record.validation_accuracy)
+        .bind(record.test_accuracy)
+        .bind(&record.model_metrics)
+        .bind(&record.model_path)
+        .bind(&record.model_hash)
+        .bind(record.model_size_bytes.map(|s| s as i64))
+        .bind(&record.training_duration)
+        .bind(&record.status)
+        .bind(record.created_at)
+        .bind(record.started_at)
+        .bind(record.completed_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_training_record(&self, id: Uuid) -> Result<Option<ModelTrainingRecord>> {
+        let row = sqlx::query(&format!("{} WHERE id = ?1", SELECT_TRAINING_RECORDS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(Self::map_training_record_row).transpose()
+    }
+
+    async fn get_training_records(&self, strategy_id: Uuid) -> Result<Vec<ModelTrainingRecord>> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE strategy_id = ?1 ORDER BY created_at DESC",
+            SELECT_TRAINING_RECORDS
+        ))
+        .bind(strategy_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::map_training_record_row).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lottery::test_support::create_drawing;
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_sqlite_drawings_round_trip() {
+        let storage = SqliteStorage::in_memory().await.unwrap();
+        let first_date = NaiveDate::from_ymd_opt(2024, 8, 13).unwrap();
+        let second_date = NaiveDate::from_ymd_opt(2024, 8, 15).unwrap();
+
+        let saved = storage
+            .save_drawings(&[
+                create_drawing(LotteryType::Ssq, "2024079", second_date, vec![5, 12, 19, 24, 28, 33], Some(vec![7])),
+                create_drawing(LotteryType::Ssq, "2024078", first_date, vec![1, 9, 14, 22, 27, 31], Some(vec![7])),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(saved, 2);
+
+        let drawings = storage.get_by_type(&LotteryType::Ssq).await.unwrap();
+        assert_eq!(drawings.len(), 2);
+        assert_eq!(drawings[0].draw_number, "2024078");
+        assert_eq!(drawings[1].winning_numbers, vec![5, 12, 19, 24, 28, 33]);
+        assert_eq!(drawings[1].special_numbers, Some(vec![7]));
+
+        let latest = storage.get_latest(&LotteryType::Ssq, 1).await.unwrap();
+        assert_eq!(latest[0].draw_number, "2024079");
+
+        // 同一期号再次写入时更新而不是重复插入
+        storage
+            .save_drawings(&[create_drawing(LotteryType::Ssq, "2024079", second_date, vec![5, 12, 19, 24, 28, 32], Some(vec![7]))])
+            .await
+            .unwrap();
+        let range = storage
+            .get_by_date_range(&LotteryType::Ssq, second_date, second_date)
+            .await
+            .unwrap();
+        assert_eq!(range.len(), 1);
+        assert_eq!(range[0].winning_numbers, vec![5, 12, 19, 24, 28, 32]);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_lottery_type_round_trip() {
+        let storage = SqliteStorage::in_memory().await.unwrap();
+        crate::lottery::storage::check_lottery_type_round_trip(&storage, "lotto_649").await;
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_predictions_and_training_records() {
+        let storage = SqliteStorage::in_memory().await.unwrap();
+
+        // 迁移会写入系统策略种子数据
+        let strategies = storage.list_strategies().await.unwrap();
+        assert!(!strategies.is_empty());
+        let strategy_id = strategies[0].id;
+
+        let prediction = PredictionResult {
+            id: Uuid::new_v4(),
+            strategy_id,
+            lottery_type: LotteryType::Ssq,
+            predicted_numbers: vec![3, 8, 15, 22, 28, 31],
+            predicted_special_numbers: Some(vec![10]),
+            confidence_scores: vec![0.5; 7],
+            target_draw_date: NaiveDate::from_ymd_opt(2024, 8, 20).unwrap(),
+            actual_draw_id: None,
+            prediction_type: "standard".to_string(),
+            accuracy_score: None,
+            match_count: 0,
+            special_match_count: 0,
+            is_winner: false,
+            prize_tier: None,
+            prize_amount: None,
+            computation_time_ms: 12,
+            feature_vector: None,
+            metadata: None,
+            prediction_date: Utc::now(),
+            validation_date: None,
+        };
+        storage.save_prediction(&prediction).await.unwrap();
+
+        let pending = storage
+            .get_unvalidated_predictions(&LotteryType::Ssq, NaiveDate::from_ymd_opt(2024, 8, 31).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].predicted_special_numbers, Some(vec![10]));
+
+        let record = ModelTrainingRecord {
+            id: Uuid::new_v4(),
+            strategy_id,
+            training_data_start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            training_data_end: NaiveDate::from_ymd_opt(2024, 8, 1).unwrap(),
+            training_samples: 80,
+            validation_samples: 10,
+            test_samples: 10,
+            model_parameters: serde_json::json!({"n_estimators": 100}),
+            training_accuracy: Some(0.4),
+            validation_accuracy: None,
+            test_accuracy: None,
+            model_metrics: serde_json::json!({}),
+            model_path: None,
+            model_hash: None,
+            model_size_bytes: Some(2048),
+            training_duration: None,
+            status: "completed".to_string(),
+            created_at: Utc::now(),
+            started_at: None,
+            completed_at: None,
+        };
+        storage.save_training_record(&record).await.unwrap();
+
+        let records = storage.get_training_records(strategy_id).await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].model_size_bytes, Some(2048));
+    }
+}
@@ -0,0 +1,31 @@
+// 各模块测试共用的开奖数据，奖池与奖级分布等其余字段由调用方按需覆盖
+use crate::lottery::models::{LotteryDrawing, LotteryType};
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+pub(crate) fn create_drawing(
+    lottery_type: LotteryType,
+    draw_number: &str,
+    draw_date: NaiveDate,
+    winning_numbers: Vec<u32>,
+    special_numbers: Option<Vec<u32>>,
+) -> LotteryDrawing {
+    LotteryDrawing {
+        id: Uuid::new_v4(),
+        lottery_type,
+        draw_number: draw_number.to_string(),
+        draw_date,
+        draw_time: None,
+        winning_numbers,
+        special_numbers,
+        jackpot_amount: None,
+        sales_amount: None,
+        prize_distribution: None,
+        data_source: "test".to_string(),
+        verification_status: "verified".to_string(),
+        metadata: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        crawled_at: None,
+    }
+}
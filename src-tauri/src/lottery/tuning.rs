@@ -0,0 +1,963 @@
+use crate::lottery::algorithms::algorithm_factory::AlgorithmFactory;
+use crate::lottery::algorithms::model_trainer::{self, CrossValidationReport, TimeSeriesSplit};
+use crate::lottery::algorithms::traits::{AlgorithmConfig, TrainingData};
+use crate::lottery::errors::LotteryResult as Result;
+use crate::lottery::models::{AlgorithmType, LotteryType, ModelTrainingRecord};
+use crate::lottery::storage::TrainingRecordRepository;
+use chrono::{DateTime, NaiveDate, Utc};
+use rand::distributions::Distribution;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use statrs::distribution::{Continuous, Normal};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+
+// 搜索空间中单个参数的取值范围，log 为 true 时在对数尺度上均匀取值
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ParameterDomain {
+    Integer {
+        low: i64,
+        high: i64,
+        #[serde(default)]
+        log: bool,
+    },
+    Float {
+        low: f64,
+        high: f64,
+        #[serde(default)]
+        log: bool,
+    },
+    Boolean,
+    Categorical { choices: Vec<Value> },
+}
+
+impl ParameterDomain {
+    // config_schema 中的参数按名字和类型取内置范围；随机种子、线程数等不影响结果的参数以及未知参数返回 None，保持算法默认值
+    pub fn default_for(name: &str, kind: &str) -> Option<Self> {
+        let domain = match name {
+            "n_estimators" => Self::Integer { low: 10, high: 300, log: true },
+            "max_depth" => Self::Integer { low: 2, high: 16, log: false },
+            "min_samples_split" => Self::Integer { low: 2, high: 20, log: false },
+            "min_samples_leaf" => Self::Integer { low: 1, high: 20, log: false },
+            "max_samples" | "subsample" => Self::Float { low: 0.5, high: 1.0, log: false },
+            "bootstrap" | "auto_order" | "use_meta_learner" => Self::Boolean,
+            "learning_rate" => Self::Float { low: 1e-4, high: 0.3, log: true },
+            "l2_regularization" => Self::Float { low: 0.0, high: 10.0, log: false },
+            "epochs" => Self::Integer { low: 10, high: 200, log: true },
+            "batch_size" => Self::Integer { low: 8, high: 128, log: true },
+            "hidden_size" => Self::Integer { low: 16, high: 256, log: true },
+            "num_layers" => Self::Integer { low: 1, high: 3, log: false },
+            "sequence_length" => Self::Integer { low: 5, high: 30, log: false },
+            "dropout" => Self::Float { low: 0.0, high: 0.5, log: false },
+            "optimizer" => Self::categorical(&["sgd", "momentum", "rmsprop", "adam"]),
+            "p" | "q" => Self::Integer { low: 0, high: 3, log: false },
+            "d" => Self::Integer { low: 0, high: 2, log: false },
+            "information_criterion" => Self::categorical(&["aic", "bic"]),
+            "window_size" => Self::Integer { low: 10, high: 200, log: true },
+            "prior_concentration" => Self::Float { low: 0.1, high: 10.0, log: true },
+            "forgetting_factor" => Self::Float { low: 0.9, high: 1.0, log: false },
+            "smoothing" => Self::Float { low: 0.5, high: 50.0, log: true },
+            "transition_weight" | "diversity_weight" => Self::Float { low: 0.0, high: 1.0, log: false },
+            "max_gap" => Self::Integer { low: 5, high: 60, log: false },
+            "voting_method" => Self::categorical(&["weighted", "majority", "consensus"]),
+            "meta_learner_type" => Self::categorical(&["linear", "neural", "stacking"]),
+            _ => return None,
+        };
+
+        (domain.kind() == kind).then_some(domain)
+    }
+
+    fn categorical(choices: &[&str]) -> Self {
+        Self::Categorical { choices: choices.iter().map(|&choice| Value::from(choice)).collect() }
+    }
+
+    // 与 AlgorithmMetadata.config_schema 中的类型名一致
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Integer { .. } => "integer",
+            Self::Float { .. } => "float",
+            Self::Boolean => "boolean",
+            Self::Categorical { .. } => "string",
+        }
+    }
+
+    pub fn validate(&self, name: &str) -> Result<()> {
+        let valid = match self {
+            Self::Integer { low, high, log } => low <= high && (!log || *low > 0),
+            Self::Float { low, high, log } => low.is_finite() && high.is_finite() && low <= high && (!log || *low > 0.0),
+            Self::Boolean => true,
+            Self::Categorical { choices } => !choices.is_empty(),
+        };
+
+        if !valid {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                format!("Invalid search range for {}", name)
+            ));
+        }
+        Ok(())
+    }
+
+    // 离散参数的全部取值，数值参数为 None
+    fn choices(&self) -> Option<Vec<Value>> {
+        match self {
+            Self::Boolean => Some(vec![Value::from(false), Value::from(true)]),
+            Self::Categorical { choices } => Some(choices.clone()),
+            _ => None,
+        }
+    }
+
+    fn bounds(&self) -> Option<(f64, f64, bool)> {
+        match self {
+            Self::Integer { low, high, log } => Some((*low as f64, *high as f64, *log)),
+            Self::Float { low, high, log } => Some((*low, *high, *log)),
+            _ => None,
+        }
+    }
+
+    // 数值参数映射到 [0, 1]，对数尺度先取对数
+    fn unit_position(&self, value: &Value) -> Option<f64> {
+        let (low, high, log) = self.bounds()?;
+        let x = value.as_f64()?;
+        let (low, high, x) = if log {
+            (low.ln(), high.ln(), x.max(f64::MIN_POSITIVE).ln())
+        } else {
+            (low, high, x)
+        };
+
+        Some(if high > low { ((x - low) / (high - low)).clamp(0.0, 1.0) } else { 0.5 })
+    }
+
+    fn unit_value(&self, u: f64) -> Value {
+        let u = u.clamp(0.0, 1.0);
+        let scale = |low: f64, high: f64, log: bool| if log {
+            (low.ln() + u * (high.ln() - low.ln())).exp()
+        } else {
+            low + u * (high - low)
+        };
+
+        match self {
+            Self::Integer { low, high, log } => {
+                let x = scale(*low as f64, *high as f64, *log).round() as i64;
+                Value::from(x.clamp(*low, *high))
+            }
+            Self::Float { low, high, log } => Value::from(scale(*low, *high, *log).clamp(*low, *high)),
+            Self::Boolean => Value::from(u >= 0.5),
+            Self::Categorical { choices } => {
+                choices[((u * choices.len() as f64) as usize).min(choices.len() - 1)].clone()
+            }
+        }
+    }
+
+    // 数值参数等距取 points 个点 (对数尺度上等比)，整数去重；离散参数取全部选项
+    fn grid(&self, points: usize) -> Vec<Value> {
+        if let Some(choices) = self.choices() {
+            return choices;
+        }
+
+        let points = points.max(1);
+        let mut values: Vec<Value> = (0..points)
+            .map(|i| self.unit_value(if points == 1 { 0.5 } else { i as f64 / (points - 1) as f64 }))
+            .collect();
+        values.dedup();
+        values
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> Value {
+        match self.choices() {
+            Some(choices) => choices[rng.gen_range(0..choices.len())].clone(),
+            None => self.unit_value(rng.r#gen()),
+        }
+    }
+}
+
+// 参数按名字排序，网格的遍历顺序固定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchSpace {
+    pub parameters: BTreeMap<String, ParameterDomain>,
+}
+
+impl SearchSpace {
+    pub fn from_schema(schema: &HashMap<String, String>) -> Self {
+        let parameters = schema.iter()
+            .filter_map(|(name, kind)| ParameterDomain::default_for(name, kind).map(|domain| (name.clone(), domain)))
+            .collect();
+
+        Self { parameters }
+    }
+
+    pub fn grid_size(&self, points: usize) -> usize {
+        self.parameters.values()
+            .map(|domain| domain.grid(points).len())
+            .fold(1usize, |total, len| total.saturating_mul(len))
+    }
+
+    // 按混合进制把下标拆成各参数的网格位置
+    fn grid_point(&self, mut index: usize, points: usize) -> HashMap<String, Value> {
+        let mut parameters = HashMap::new();
+        for (name, domain) in &self.parameters {
+            let values = domain.grid(points);
+            parameters.insert(name.clone(), values[index % values.len()].clone());
+            index /= values.len();
+        }
+        parameters
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> HashMap<String, Value> {
+        self.parameters.iter()
+            .map(|(name, domain)| (name.clone(), domain.sample(rng)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchStrategy {
+    // 每个数值参数取 points_per_parameter 个点，按笛卡尔积依次评估
+    Grid { points_per_parameter: usize },
+    Random,
+    // Tree-structured Parzen Estimator：前 startup_trials 个试验随机取值，
+    // 之后把得分前 gamma 的试验与其余试验分别建密度，从前者抽 candidates 个候选取密度比最大的
+    Tpe { startup_trials: usize, gamma: f64, candidates: usize },
+}
+
+impl Default for SearchStrategy {
+    fn default() -> Self {
+        SearchStrategy::Tpe { startup_trials: 10, gamma: 0.25, candidates: 24 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TuningConfig {
+    pub strategy: SearchStrategy,
+    pub max_trials: usize,
+    pub cross_validation: TimeSeriesSplit,
+    // 每个候选都带上的固定参数，例如 random_state、n_jobs
+    pub fixed_parameters: HashMap<String, Value>,
+    pub random_state: Option<u64>,
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        Self {
+            strategy: SearchStrategy::default(),
+            max_trials: 30,
+            cross_validation: TimeSeriesSplit::new(3),
+            fixed_parameters: HashMap::new(),
+            random_state: Some(42),
+        }
+    }
+}
+
+// 取值与 model_training_records.status 的约束一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl RunStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Completed => "completed",
+            RunStatus::Failed => "failed",
+            RunStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trial {
+    pub number: usize,
+    // 本次搜索的参数，不含 fixed_parameters
+    pub parameters: HashMap<String, Value>,
+    pub status: RunStatus,
+    // 交叉验证各折 accuracy 的均值，未完成时为 None
+    pub score: Option<f64>,
+    pub report: Option<CrossValidationReport>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl Trial {
+    pub fn new(number: usize, parameters: HashMap<String, Value>) -> Self {
+        Self {
+            number,
+            parameters,
+            status: RunStatus::Running,
+            score: None,
+            report: None,
+            error: None,
+            started_at: Utc::now(),
+            completed_at: None,
+        }
+    }
+}
+
+// 得分最高的试验在 trials 中的下标
+pub fn best_trial(trials: &[Trial]) -> Option<usize> {
+    trials.iter()
+        .enumerate()
+        .filter_map(|(i, trial)| trial.score.map(|score| (i, score)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+#[derive(Debug, Clone)]
+pub struct HyperparameterTuner {
+    pub algorithm: AlgorithmType,
+    pub lottery_type: LotteryType,
+    pub space: SearchSpace,
+    pub config: TuningConfig,
+    factory: AlgorithmFactory,
+}
+
+impl HyperparameterTuner {
+    // 搜索空间由算法的 config_schema 推导
+    pub fn new(algorithm: AlgorithmType, lottery_type: LotteryType, config: TuningConfig) -> Result<Self> {
+        let factory = AlgorithmFactory::new(lottery_type.clone());
+        let Some(metadata) = factory.get_algorithm_metadata(algorithm) else {
+            return Err(crate::lottery::errors::LotteryError::NotFound(
+                format!("Unknown algorithm: {}", algorithm)
+            ));
+        };
+        let space = SearchSpace::from_schema(&metadata.config_schema);
+
+        if config.max_trials == 0 {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "max_trials must be positive".to_string()
+            ));
+        }
+        if let SearchStrategy::Tpe { gamma, .. } = config.strategy
+            && !(gamma > 0.0 && gamma < 1.0)
+        {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                "gamma must be in (0, 1)".to_string()
+            ));
+        }
+
+        Ok(Self {
+            algorithm,
+            lottery_type,
+            space,
+            config,
+            factory,
+        })
+    }
+
+    // 覆盖或补充单个参数的范围，参数必须出现在算法的 config_schema 中
+    pub fn with_parameter(mut self, name: &str, domain: ParameterDomain) -> Result<Self> {
+        let known = self.factory.get_algorithm_metadata(self.algorithm)
+            .is_some_and(|metadata| metadata.config_schema.contains_key(name));
+        if !known {
+            return Err(crate::lottery::errors::LotteryError::InvalidParameter(
+                format!("{} has no parameter {}", self.algorithm, name)
+            ));
+        }
+        domain.validate(name)?;
+
+        self.space.parameters.insert(name.to_string(), domain);
+        Ok(self)
+    }
+
+    // 网格搜索的试验数不超过网格大小
+    pub fn total_trials(&self) -> usize {
+        match self.config.strategy {
+            SearchStrategy::Grid { points_per_parameter } => {
+                self.config.max_trials.min(self.space.grid_size(points_per_parameter))
+            }
+            _ => self.config.max_trials,
+        }
+    }
+
+    // 每个试验在各测试折上评估的样本总数
+    pub fn validation_samples(&self, n_samples: usize) -> usize {
+        self.config.cross_validation.splits(n_samples)
+            .iter()
+            .map(|(_, test_range)| test_range.len())
+            .sum()
+    }
+
+    pub fn full_parameters(&self, parameters: &HashMap<String, Value>) -> HashMap<String, Value> {
+        let mut full = self.config.fixed_parameters.clone();
+        full.extend(parameters.iter().map(|(name, value)| (name.clone(), value.clone())));
+        full
+    }
+
+    // 根据已有的试验给出下一组参数，试验数用完时返回 None
+    pub fn suggest(&self, trials: &[Trial], rng: &mut StdRng) -> Option<HashMap<String, Value>> {
+        if trials.len() >= self.total_trials() {
+            return None;
+        }
+
+        let parameters = match self.config.strategy {
+            SearchStrategy::Grid { points_per_parameter } => {
+                self.space.grid_point(trials.len(), points_per_parameter)
+            }
+            SearchStrategy::Random => self.space.sample(rng),
+            SearchStrategy::Tpe { startup_trials, gamma, candidates } => {
+                let completed: Vec<&Trial> = trials.iter().filter(|trial| trial.score.is_some()).collect();
+                if completed.len() < startup_trials.max(2) {
+                    self.space.sample(rng)
+                } else {
+                    tpe_suggest(&self.space, &completed, gamma, candidates, rng)
+                }
+            }
+        };
+
+        Some(parameters)
+    }
+
+    // 用 trial 的参数在 data 上做时间序列交叉验证；cancel 置位后在下一折开始前停止
+    pub async fn evaluate(&self, mut trial: Trial, data: &TrainingData, cancel: &AtomicBool) -> Trial {
+        let parameters = self.full_parameters(&trial.parameters);
+        let training_config = AlgorithmConfig {
+            lottery_type: self.lottery_type.clone(),
+            parameters,
+            ..Default::default()
+        };
+
+        let result = model_trainer::cross_validate_with(
+            &self.lottery_type,
+            data,
+            &training_config,
+            &self.config.cross_validation,
+            || {
+                if cancel.load(Ordering::Relaxed) {
+                    return Err(crate::lottery::errors::LotteryError::AlgorithmError(
+                        "Tuning cancelled".to_string()
+                    ));
+                }
+                self.factory.create_algorithm(self.algorithm, training_config.clone())
+            },
+        ).await;
+
+        match result {
+            Ok(report) if report.fold_scores.is_empty() => {
+                trial.status = RunStatus::Failed;
+                trial.error = Some("No fold could be trained".to_string());
+            }
+            Ok(report) => {
+                trial.status = RunStatus::Completed;
+                trial.score = Some(report.mean);
+                trial.report = Some(report);
+            }
+            Err(_) if cancel.load(Ordering::Relaxed) => trial.status = RunStatus::Cancelled,
+            Err(e) => {
+                trial.status = RunStatus::Failed;
+                trial.error = Some(e.to_string());
+            }
+        }
+        trial.completed_at = Some(Utc::now());
+        trial
+    }
+
+    // 依次运行全部试验，不写入存储
+    pub async fn run(&self, data: &TrainingData, cancel: &AtomicBool) -> Vec<Trial> {
+        let mut rng = StdRng::seed_from_u64(self.config.random_state.unwrap_or_else(rand::random));
+        let mut trials = Vec::new();
+
+        while !cancel.load(Ordering::Relaxed) {
+            let Some(parameters) = self.suggest(&trials, &mut rng) else {
+                break;
+            };
+            let trial = self.evaluate(Trial::new(trials.len(), parameters), data, cancel).await;
+            trials.push(trial);
+        }
+
+        trials
+    }
+}
+
+// 单个参数的 Parzen 估计：数值参数为 [0, 1] 上各观测点的高斯核加一个均匀先验，离散参数为加一平滑的频率
+enum Parzen {
+    Numeric { kernels: Vec<Normal> },
+    Discrete { probabilities: Vec<f64> },
+}
+
+impl Parzen {
+    fn fit<'a>(domain: &ParameterDomain, values: impl Iterator<Item = &'a Value>) -> Self {
+        if let Some(choices) = domain.choices() {
+            let mut counts = vec![1.0; choices.len()];
+            for value in values {
+                if let Some(i) = choices.iter().position(|choice| choice == value) {
+                    counts[i] += 1.0;
+                }
+            }
+            let total: f64 = counts.iter().sum();
+            return Parzen::Discrete { probabilities: counts.into_iter().map(|count| count / total).collect() };
+        }
+
+        let centers: Vec<f64> = values.filter_map(|value| domain.unit_position(value)).collect();
+        // 观测越多带宽越窄
+        let bandwidth = (0.3 * (centers.len() as f64 + 1.0).powf(-0.2)).max(0.05);
+        let kernels = centers.into_iter()
+            .filter_map(|center| Normal::new(center, bandwidth).ok())
+            .collect();
+        Parzen::Numeric { kernels }
+    }
+
+    // 数值参数返回 [0, 1] 上的取值，离散参数返回选项下标
+    fn sample(&self, rng: &mut StdRng) -> f64 {
+        match self {
+            Parzen::Numeric { kernels } => {
+                let k = rng.gen_range(0..=kernels.len());
+                match kernels.get(k) {
+                    Some(kernel) => kernel.sample(rng).clamp(0.0, 1.0),
+                    None => rng.r#gen(),
+                }
+            }
+            Parzen::Discrete { probabilities } => {
+                let mut u: f64 = rng.r#gen();
+                for (i, p) in probabilities.iter().enumerate() {
+                    if u < *p {
+                        return i as f64;
+                    }
+                    u -= p;
+                }
+                (probabilities.len() - 1) as f64
+            }
+        }
+    }
+
+    fn density(&self, x: f64) -> f64 {
+        match self {
+            Parzen::Numeric { kernels } => {
+                let total: f64 = kernels.iter().map(|kernel| kernel.pdf(x)).sum::<f64>() + 1.0;
+                total / (kernels.len() + 1) as f64
+            }
+            Parzen::Discrete { probabilities } => probabilities[x as usize],
+        }
+    }
+}
+
+fn tpe_suggest(
+    space: &SearchSpace,
+    completed: &[&Trial],
+    gamma: f64,
+    candidates: usize,
+    rng: &mut StdRng,
+) -> HashMap<String, Value> {
+    let mut ranked = completed.to_vec();
+    ranked.sort_by(|a, b| b.score.unwrap_or(f64::NEG_INFINITY).total_cmp(&a.score.unwrap_or(f64::NEG_INFINITY)));
+    let n_good = ((gamma * ranked.len() as f64).ceil() as usize).clamp(1, ranked.len() - 1);
+    let (good, bad) = ranked.split_at(n_good);
+
+    let estimators: Vec<(&String, &ParameterDomain, Parzen, Parzen)> = space.parameters.iter()
+        .map(|(name, domain)| {
+            let good = Parzen::fit(domain, good.iter().filter_map(|trial| trial.parameters.get(name)));
+            let bad = Parzen::fit(domain, bad.iter().filter_map(|trial| trial.parameters.get(name)));
+            (name, domain, good, bad)
+        })
+        .collect();
+
+    let mut best = HashMap::new();
+    let mut best_ratio = f64::NEG_INFINITY;
+
+    for _ in 0..candidates.max(1) {
+        let mut candidate = HashMap::new();
+        let mut log_ratio = 0.0;
+
+        for (name, domain, good, bad) in &estimators {
+            let x = good.sample(rng);
+            log_ratio += good.density(x).ln() - bad.density(x).ln();
+
+            let value = match domain.choices() {
+                Some(choices) => choices[x as usize].clone(),
+                None => domain.unit_value(x),
+            };
+            candidate.insert((*name).clone(), value);
+        }
+
+        if log_ratio > best_ratio {
+            best_ratio = log_ratio;
+            best = candidate;
+        }
+    }
+
+    best
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuningStatus {
+    pub job_id: Uuid,
+    pub algorithm: AlgorithmType,
+    pub lottery_type: LotteryType,
+    pub strategy_id: Uuid,
+    pub search_space: SearchSpace,
+    pub search_strategy: SearchStrategy,
+    pub status: RunStatus,
+    pub total_trials: usize,
+    pub trials: Vec<Trial>,
+    pub best_trial: Option<usize>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl TuningStatus {
+    pub fn best_parameters(&self) -> Option<&HashMap<String, Value>> {
+        self.best_trial.map(|i| &self.trials[i].parameters)
+    }
+}
+
+// 试验写入 model_training_records 时共用的字段
+#[derive(Debug, Clone)]
+pub struct TrialRecordContext {
+    pub strategy_id: Uuid,
+    pub training_data_start: NaiveDate,
+    pub training_data_end: NaiveDate,
+}
+
+// 后台运行的一次搜索，克隆后共享同一份状态和取消标记
+#[derive(Debug, Clone)]
+pub struct TuningJob {
+    status: Arc<RwLock<TuningStatus>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl TuningJob {
+    pub fn new(tuner: &HyperparameterTuner, strategy_id: Uuid) -> Self {
+        let status = TuningStatus {
+            job_id: Uuid::new_v4(),
+            algorithm: tuner.algorithm,
+            lottery_type: tuner.lottery_type.clone(),
+            strategy_id,
+            search_space: tuner.space.clone(),
+            search_strategy: tuner.config.strategy.clone(),
+            status: RunStatus::Running,
+            total_trials: tuner.total_trials(),
+            trials: Vec::new(),
+            best_trial: None,
+            error: None,
+            started_at: Utc::now(),
+            completed_at: None,
+        };
+
+        Self {
+            status: Arc::new(RwLock::new(status)),
+            cancel: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn status(&self) -> TuningStatus {
+        self.status.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    // 正在运行的试验在下一折开始前停止，记为 cancelled
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    fn update(&self, f: impl FnOnce(&mut TuningStatus)) {
+        f(&mut self.status.write().unwrap_or_else(|e| e.into_inner()));
+    }
+
+    // 每个试验开始和结束时各写一次 model_training_records，写入失败时整个任务失败
+    pub async fn run(
+        &self,
+        tuner: &HyperparameterTuner,
+        data: &TrainingData,
+        records: &dyn TrainingRecordRepository,
+        context: &TrialRecordContext,
+    ) -> TuningStatus {
+        let result = self.run_trials(tuner, data, records, context).await;
+
+        self.update(|status| {
+            status.status = match &result {
+                Err(_) => RunStatus::Failed,
+                Ok(()) if self.cancel.load(Ordering::Relaxed) => RunStatus::Cancelled,
+                Ok(()) => RunStatus::Completed,
+            };
+            status.error = result.err().map(|e| e.to_string());
+            status.completed_at = Some(Utc::now());
+        });
+
+        self.status()
+    }
+
+    async fn run_trials(
+        &self,
+        tuner: &HyperparameterTuner,
+        data: &TrainingData,
+        records: &dyn TrainingRecordRepository,
+        context: &TrialRecordContext,
+    ) -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(tuner.config.random_state.unwrap_or_else(rand::random));
+        let mut trials = Vec::new();
+
+        while !self.cancel.load(Ordering::Relaxed) {
+            let Some(parameters) = tuner.suggest(&trials, &mut rng) else {
+                break;
+            };
+
+            let record_id = Uuid::new_v4();
+            let trial = Trial::new(trials.len(), parameters);
+            records.save_training_record(&self.record(record_id, tuner, &trial, data, context)).await?;
+            self.update(|status| status.trials.push(trial.clone()));
+
+            let trial = tuner.evaluate(trial, data, &self.cancel).await;
+            records.save_training_record(&self.record(record_id, tuner, &trial, data, context)).await?;
+            self.update(|status| {
+                status.trials[trial.number] = trial.clone();
+                status.best_trial = best_trial(&status.trials);
+            });
+            trials.push(trial);
+        }
+
+        Ok(())
+    }
+
+    fn record(
+        &self,
+        id: Uuid,
+        tuner: &HyperparameterTuner,
+        trial: &Trial,
+        data: &TrainingData,
+        context: &TrialRecordContext,
+    ) -> ModelTrainingRecord {
+        let model_metrics = serde_json::json!({
+            "tuning_job_id": self.status().job_id,
+            "algorithm": tuner.algorithm,
+            "trial": trial.number,
+            "search_strategy": tuner.config.strategy,
+            "cross_validation": trial.report,
+            "error": trial.error,
+        });
+        let training_duration = trial.completed_at.map(|completed_at| {
+            format!("{:.3} seconds", (completed_at - trial.started_at).num_milliseconds() as f64 / 1000.0)
+        });
+
+        ModelTrainingRecord {
+            id,
+            strategy_id: context.strategy_id,
+            training_data_start: context.training_data_start,
+            training_data_end: context.training_data_end,
+            training_samples: data.len() as u32,
+            validation_samples: tuner.validation_samples(data.len()) as u32,
+            test_samples: 0,
+            model_parameters: serde_json::json!(tuner.full_parameters(&trial.parameters)),
+            training_accuracy: None,
+            validation_accuracy: trial.score,
+            test_accuracy: None,
+            model_metrics,
+            model_path: None,
+            model_hash: None,
+            model_size_bytes: None,
+            training_duration,
+            status: trial.status.as_str().to_string(),
+            created_at: trial.started_at,
+            started_at: Some(trial.started_at),
+            completed_at: trial.completed_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct MemoryRecords {
+        records: Mutex<HashMap<Uuid, ModelTrainingRecord>>,
+    }
+
+    #[async_trait]
+    impl TrainingRecordRepository for MemoryRecords {
+        async fn save_training_record(&self, record: &ModelTrainingRecord) -> Result<()> {
+            self.records.lock().unwrap().insert(record.id, record.clone());
+            Ok(())
+        }
+
+        async fn get_training_record(&self, id: Uuid) -> Result<Option<ModelTrainingRecord>> {
+            Ok(self.records.lock().unwrap().get(&id).cloned())
+        }
+
+        async fn get_training_records(&self, strategy_id: Uuid) -> Result<Vec<ModelTrainingRecord>> {
+            Ok(self.records.lock().unwrap().values().filter(|r| r.strategy_id == strategy_id).cloned().collect())
+        }
+    }
+
+    fn training_data(samples: usize) -> TrainingData {
+        let mut rng = StdRng::seed_from_u64(11);
+        TrainingData {
+            features: (0..samples).map(|_| (0..4).map(|_| rng.r#gen::<f64>()).collect()).collect(),
+            targets: (0..samples).map(|i| vec![1, 2, 3, 4, 5, 6 + (i % 3) as u32]).collect(),
+            special_targets: None,
+            weights: None,
+        }
+    }
+
+    fn completed_trial(number: usize, parameters: HashMap<String, Value>, score: f64) -> Trial {
+        Trial {
+            status: RunStatus::Completed,
+            score: Some(score),
+            completed_at: Some(Utc::now()),
+            ..Trial::new(number, parameters)
+        }
+    }
+
+    #[test]
+    fn test_search_space_from_schema() {
+        let factory = AlgorithmFactory::new(LotteryType::Ssq);
+        let schema = &factory.get_algorithm_metadata(AlgorithmType::RandomForest).unwrap().config_schema;
+        let space = SearchSpace::from_schema(schema);
+
+        assert_eq!(space.parameters["n_estimators"], ParameterDomain::Integer { low: 10, high: 300, log: true });
+        assert_eq!(space.parameters["bootstrap"], ParameterDomain::Boolean);
+        // 随机种子和线程数不参与搜索
+        assert!(!space.parameters.contains_key("random_state"));
+        assert!(!space.parameters.contains_key("n_jobs"));
+
+        // 类型与 schema 不符时不使用内置范围
+        assert!(ParameterDomain::default_for("max_depth", "float").is_none());
+        assert!(ParameterDomain::Integer { low: 0, high: 5, log: true }.validate("x").is_err());
+    }
+
+    #[test]
+    fn test_domain_unit_round_trip() {
+        let domain = ParameterDomain::Float { low: 1e-4, high: 1e-1, log: true };
+        let value = domain.unit_value(0.5);
+        assert!((value.as_f64().unwrap() - 10f64.powf(-2.5)).abs() < 1e-12);
+        assert!((domain.unit_position(&value).unwrap() - 0.5).abs() < 1e-12);
+
+        let integers = ParameterDomain::Integer { low: 1, high: 3, log: false };
+        assert_eq!(integers.grid(5), vec![Value::from(1), Value::from(2), Value::from(3)]);
+    }
+
+    #[test]
+    fn test_grid_search_enumerates_product() {
+        let config = TuningConfig {
+            strategy: SearchStrategy::Grid { points_per_parameter: 3 },
+            max_trials: 100,
+            ..Default::default()
+        };
+        let mut tuner = HyperparameterTuner::new(AlgorithmType::Markov, LotteryType::Ssq, config).unwrap();
+        tuner.space = SearchSpace::default();
+        let tuner = tuner
+            .with_parameter("max_gap", ParameterDomain::Integer { low: 10, high: 30, log: false }).unwrap()
+            .with_parameter("transition_weight", ParameterDomain::Float { low: 0.0, high: 1.0, log: false }).unwrap();
+        assert_eq!(tuner.total_trials(), 9);
+        assert!(tuner.clone().with_parameter("unknown", ParameterDomain::Boolean).is_err());
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut trials = Vec::new();
+        while let Some(parameters) = tuner.suggest(&trials, &mut rng) {
+            trials.push(completed_trial(trials.len(), parameters, 0.0));
+        }
+
+        let mut points: Vec<(i64, String)> = trials.iter()
+            .map(|trial| (
+                trial.parameters["max_gap"].as_i64().unwrap(),
+                trial.parameters["transition_weight"].to_string(),
+            ))
+            .collect();
+        points.sort();
+        points.dedup();
+        assert_eq!(points.len(), 9);
+    }
+
+    #[test]
+    fn test_tpe_concentrates_on_good_region() {
+        let config = TuningConfig {
+            strategy: SearchStrategy::Tpe { startup_trials: 10, gamma: 0.25, candidates: 24 },
+            max_trials: 60,
+            ..Default::default()
+        };
+        let mut tuner = HyperparameterTuner::new(AlgorithmType::Markov, LotteryType::Ssq, config).unwrap();
+        tuner.space = SearchSpace::default();
+        let tuner = tuner
+            .with_parameter("transition_weight", ParameterDomain::Float { low: 0.0, high: 1.0, log: false })
+            .unwrap();
+
+        // 得分在 0.8 处最高
+        let objective = |x: f64| -(x - 0.8).powi(2);
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut trials = Vec::new();
+        while let Some(parameters) = tuner.suggest(&trials, &mut rng) {
+            let x = parameters["transition_weight"].as_f64().unwrap();
+            trials.push(completed_trial(trials.len(), parameters, objective(x)));
+        }
+
+        let late: Vec<f64> = trials[40..].iter()
+            .map(|trial| trial.parameters["transition_weight"].as_f64().unwrap())
+            .collect();
+        let near = late.iter().filter(|&&x| (x - 0.8).abs() < 0.2).count();
+        assert!(near as f64 / late.len() as f64 > 0.6);
+
+        let best = &trials[best_trial(&trials).unwrap()];
+        assert!((best.parameters["transition_weight"].as_f64().unwrap() - 0.8).abs() < 0.05);
+    }
+
+    #[tokio::test]
+    async fn test_tuning_job_persists_trials() {
+        let config = TuningConfig {
+            strategy: SearchStrategy::Random,
+            max_trials: 3,
+            ..Default::default()
+        };
+        let tuner = HyperparameterTuner::new(AlgorithmType::Markov, LotteryType::Ssq, config).unwrap();
+        let data = training_data(40);
+        let records = MemoryRecords::default();
+        let context = TrialRecordContext {
+            strategy_id: Uuid::new_v4(),
+            training_data_start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            training_data_end: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        };
+
+        let job = TuningJob::new(&tuner, context.strategy_id);
+        let status = job.run(&tuner, &data, &records, &context).await;
+
+        assert_eq!(status.status, RunStatus::Completed);
+        assert_eq!(status.trials.len(), 3);
+        assert!(status.trials.iter().all(|trial| trial.status == RunStatus::Completed));
+        assert!(status.best_parameters().is_some());
+
+        let saved = records.get_training_records(context.strategy_id).await.unwrap();
+        assert_eq!(saved.len(), 3);
+        for record in &saved {
+            assert_eq!(record.status, "completed");
+            assert_eq!(record.training_samples, 40);
+            assert_eq!(record.validation_samples, 30);
+            assert!(record.validation_accuracy.is_some());
+            assert_eq!(record.model_metrics["tuning_job_id"], serde_json::json!(status.job_id));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_job_stops_before_next_trial() {
+        let tuner = HyperparameterTuner::new(AlgorithmType::Markov, LotteryType::Ssq, TuningConfig::default()).unwrap();
+        let data = training_data(40);
+        let records = MemoryRecords::default();
+        let context = TrialRecordContext {
+            strategy_id: Uuid::new_v4(),
+            training_data_start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            training_data_end: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+        };
+
+        let job = TuningJob::new(&tuner, context.strategy_id);
+        job.cancel();
+        let status = job.run(&tuner, &data, &records, &context).await;
+
+        assert_eq!(status.status, RunStatus::Cancelled);
+        assert!(status.trials.is_empty());
+        assert!(status.completed_at.is_some());
+
+        // 运行中取消的试验记为 cancelled
+        let cancel = AtomicBool::new(true);
+        let trial = tuner.evaluate(Trial::new(0, HashMap::new()), &data, &cancel).await;
+        assert_eq!(trial.status, RunStatus::Cancelled);
+        assert!(trial.score.is_none());
+    }
+}
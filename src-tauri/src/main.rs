@@ -6,11 +6,26 @@ use tauri::Manager;
 
 mod lottery;
 use lottery::api::LotteryAppState;
+use lottery::model_store::ModelStore;
+use lottery::storage::StorageBackend;
+
+const APP_PATH: &str = "example-app";
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let app_path = dirs::data_local_dir().unwrap().join(APP_PATH);
+    if !app_path.exists() {
+        std::fs::create_dir_all(&app_path)?;
+    }
+    let storage = StorageBackend::from_env(&app_path).open().await?;
+    if let Err(e) = storage.load_game_specs().await {
+        eprintln!("Failed to load lottery types, using built-in game specs: {}", e);
+    }
+    let state = LotteryAppState::new(storage, ModelStore::in_app_dir(&app_path));
+    state.restore_models().await;
+
     tauri::Builder::default()
-        .manage(LotteryAppState::new())
+        .manage(state)
         .invoke_handler(tauri::generate_handler![
             lottery::api::predict_numbers,
             lottery::api::train_algorithms,
@@ -21,6 +36,18 @@ async fn main() -> Result<()> {
             lottery::api::get_recent_drawings,
             lottery::api::get_algorithm_rankings,
             lottery::api::recommend_algorithms,
+            lottery::api::run_backtest,
+            lottery::api::import_drawings,
+            lottery::api::settle_predictions,
+            lottery::api::list_games,
+            lottery::api::register_custom_game,
+            lottery::api::start_hyperparameter_search,
+            lottery::api::get_hyperparameter_search,
+            lottery::api::list_hyperparameter_searches,
+            lottery::api::cancel_hyperparameter_search,
+            lottery::api::list_model_versions,
+            lottery::api::rollback_model,
+            lottery::api::prune_model_versions,
         ])
         .setup(|app| {
             #[cfg(debug_assertions)]
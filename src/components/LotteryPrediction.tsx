@@ -21,6 +21,17 @@ interface PredictionResponse {
   computation_time_ms: number;
 }
 
+interface CrossValidationReport {
+  fold_scores: number[];
+  mean: number;
+  variance: number;
+}
+
+interface TrainingResult {
+  accuracy: number;
+  cross_validation: CrossValidationReport | null;
+}
+
 interface TrainingRequest {
   lottery_type: string;
   algorithms: string[];
@@ -37,7 +48,7 @@ const LotteryPrediction: React.FC = () => {
   const [loading, setLoading] = useState<boolean>(false);
   const [error, setError] = useState<string | null>(null);
   const [availableAlgorithms, setAvailableAlgorithms] = useState<string[]>([]);
-  const [trainingResults, setTrainingResults] = useState<Record<string, number>>({});
+  const [trainingResults, setTrainingResults] = useState<Record<string, TrainingResult>>({});
 
   const lotteryTypes = [
     { value: 'Ssq', label: '双色球' },
@@ -50,9 +61,12 @@ const LotteryPrediction: React.FC = () => {
   const algorithms = [
     { value: 'statistical', label: '统计分析' },
     { value: 'random_forest', label: '随机森林' },
+    { value: 'gradient_boosting', label: '梯度提升' },
     { value: 'neural_network', label: '神经网络' },
     { value: 'lstm', label: 'LSTM' },
     { value: 'arima', label: 'ARIMA' },
+    { value: 'bayesian', label: '贝叶斯' },
+    { value: 'markov', label: '马尔可夫链' },
     { value: 'hybrid', label: '混合集成' },
   ];
 
@@ -283,10 +297,18 @@ const LotteryPrediction: React.FC = () => {
                       <CardTitle>训练结果</CardTitle>
                     </CardHeader>
                     <CardContent>
-                      {Object.entries(trainingResults).map(([algo, accuracy]) => (
+                      {Object.entries(trainingResults).map(([algo, result]) => (
                         <div key={algo} className="flex justify-between">
                           <span>{algo}</span>
-                          <span className="font-medium">{(accuracy * 100).toFixed(1)}%</span>
+                          <span className="font-medium">
+                            {(result.accuracy * 100).toFixed(1)}%
+                            {result.cross_validation && (
+                              <span className="ml-2 text-sm text-gray-600">
+                                交叉验证 {(result.cross_validation.mean * 100).toFixed(1)}%
+                                ± {(Math.sqrt(result.cross_validation.variance) * 100).toFixed(1)}%
+                              </span>
+                            )}
+                          </span>
                         </div>
                       ))}
                     </CardContent>